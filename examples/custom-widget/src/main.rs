@@ -69,7 +69,7 @@ mod ui {
 
 use alloc::boxed::Box;
 use timer_no_std::MonoClock;
-use tuifw_screen::{Error, Key, Vector};
+use tuifw_screen::{Error, Key, Mods, Vector};
 use tuifw_window::{Event, EventHandler, Window, WindowTree, App};
 use tuifw::Canvas;
 
@@ -92,18 +92,18 @@ impl EventHandler for RootEventHandler {
         state: &mut dyn App
     ) -> bool {
         let state = state.downcast_mut::<State>().unwrap();
-        if let Event::Key(Key::Escape) = event {
+        if let Event::Key(Key::Escape, Mods::NONE) = event {
             tree.quit();
             return true;
         }
         let offset = match event {
-            Event::Key(Key::Left) | Event::Key(Key::Char('h')) =>
+            Event::Key(Key::Left, Mods::NONE) | Event::Key(Key::Char('h'), Mods::NONE) =>
                 Some(Vector { x: -2, y: 0 }),
-            Event::Key(Key::Right) | Event::Key(Key::Char('l')) =>
+            Event::Key(Key::Right, Mods::NONE) | Event::Key(Key::Char('l'), Mods::NONE) =>
                 Some(Vector { x: 2, y: 0 }),
-            Event::Key(Key::Up) | Event::Key(Key::Char('k')) =>
+            Event::Key(Key::Up, Mods::NONE) | Event::Key(Key::Char('k'), Mods::NONE) =>
                 Some(Vector { x: 0, y: -1 }),
-            Event::Key(Key::Down) | Event::Key(Key::Char('j')) =>
+            Event::Key(Key::Down, Mods::NONE) | Event::Key(Key::Char('j'), Mods::NONE) =>
                 Some(Vector { x: 0, y: 1 }),
             _ => None
         };
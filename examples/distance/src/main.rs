@@ -46,7 +46,7 @@ use alloc::string::ToString;
 use core::mem::replace;
 use core::str::FromStr;
 use timer_no_std::MonoClock;
-use tuifw_screen::{Error, Key};
+use tuifw_screen::{Error, Key, Mods};
 use tuifw_window::{Event, EventHandler, Window, WindowTree, App};
 use tuifw::{Button, InputLine, StaticText, CMD_INPUT_LINE_IS_VALID_CHANGED};
 
@@ -77,7 +77,7 @@ impl EventHandler for RootEventHandler {
     ) -> bool {
         let state = state.downcast_mut::<State>().unwrap();
         match event {
-            Event::Key(Key::Escape) => {
+            Event::Key(Key::Escape, Mods::NONE) => {
                 tree.quit();
                 true
             },
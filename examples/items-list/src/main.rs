@@ -44,7 +44,7 @@ mod ui {
 use alloc::boxed::Box;
 use alloc::string::{String, ToString};
 use timer_no_std::MonoClock;
-use tuifw_screen::{Error, Key};
+use tuifw_screen::{Error, Key, Mods};
 use tuifw_window::{Data, Event, EventHandler, Window, WindowTree, App};
 use tuifw::{CheckBox, ItemsPresenter, CMD_ITEMS_PRESENTER_BIND, ScrollViewer};
 
@@ -71,7 +71,7 @@ impl EventHandler for RootEventHandler {
         _event_source: Window,
         _state: &mut dyn App,
     ) -> bool {
-        if event == Event::Key(Key::Escape) {
+        if event == Event::Key(Key::Escape, Mods::NONE) {
             tree.quit();
             true
         } else {
@@ -66,7 +66,7 @@ mod ui {
 
 use alloc::boxed::Box;
 use timer_no_std::MonoClock;
-use tuifw_screen::{Error, Key};
+use tuifw_screen::{Error, Key, Mods};
 use tuifw_window::{Event, EventHandler, Window, WindowTree, App};
 
 struct State;
@@ -86,7 +86,7 @@ impl EventHandler for RootEventHandler {
         _state: &mut dyn App,
     ) -> bool {
         match event {
-            Event::Key(Key::Escape) => {
+            Event::Key(Key::Escape, Mods::NONE) => {
                 tree.quit();
                 true
             },
@@ -0,0 +1,402 @@
+use alloc::string::String;
+use core::cmp::min;
+use core::num::NonZeroU16;
+use core::str::{self};
+use tuifw_screen_base::*;
+
+const ONCE: NonZeroU16 = NonZeroU16::new(1).unwrap();
+
+const ESC: u8 = 0x1B;
+
+const PASTE_END: &[u8] = b"\x1B[201~";
+
+pub enum Decoded {
+    Incomplete,
+    Consumed(usize, Option<Event>),
+}
+
+pub struct Decoder {
+    paste: Option<String>,
+    paste_cr: bool,
+}
+
+struct Csi {
+    private: u8,
+    params: [u16; 4],
+    final_byte: u8,
+}
+
+impl Csi {
+    fn param(&self, i: usize) -> u16 { self.params[i] }
+
+    fn mods(&self, i: usize) -> Mods { Mods::from_xterm_param(min(self.params[i], 0xFF) as u8) }
+}
+
+impl Decoder {
+    pub const fn new() -> Self {
+        Decoder { paste: None, paste_cr: false }
+    }
+
+    /// Decodes the first event from `bytes`.
+    ///
+    /// If `last` is set, no more input is expected soon,
+    /// so an unfinished sequence is resolved as is (e.g. a lone `ESC` becomes `Key::Escape`).
+    pub fn decode(&mut self, bytes: &[u8], last: bool) -> Decoded {
+        if bytes.is_empty() { return Decoded::Incomplete; }
+        let res = if self.paste.is_some() {
+            self.decode_paste(bytes, last)
+        } else if bytes[0] == ESC {
+            self.decode_esc(bytes)
+        } else {
+            decode_utf8(bytes).map(|(n, c)| (n, c.and_then(char_key)))
+        };
+        match res {
+            Some((n, e)) => Decoded::Consumed(n, e),
+            None if !last => Decoded::Incomplete,
+            None if bytes[0] == ESC && (bytes.len() == 1 || bytes[1] == ESC) =>
+                Decoded::Consumed(1, Some(key(Key::Escape, Mods::NONE))),
+            None if bytes[0] == ESC && bytes.len() == 2 && (b' ' .. 0x7F).contains(&bytes[1]) =>
+                Decoded::Consumed(2, Some(key(Key::Alt(char::from(bytes[1])), Mods::NONE))),
+            None => Decoded::Consumed(bytes.len(), None),
+        }
+    }
+
+    /// Collects the pasted text up to the bracketed paste end, then reports it as a single [`Event::Paste`].
+    fn decode_paste(&mut self, bytes: &[u8], last: bool) -> Option<(usize, Option<Event>)> {
+        if bytes[0] == ESC {
+            if bytes.starts_with(PASTE_END) {
+                let text = self.paste.take().unwrap();
+                return Some((PASTE_END.len(), Some(Event::Paste(text))));
+            }
+            if !last && PASTE_END.starts_with(bytes) { return None; }
+            return Some((1, None));
+        }
+        let (n, c) = decode_utf8(bytes)?;
+        let paste_cr = self.paste_cr;
+        self.paste_cr = c == Some('\r');
+        let text = self.paste.as_mut().unwrap();
+        match c {
+            Some('\r') => text.push('\n'),
+            Some('\n') if paste_cr => { },
+            Some(c @ ('\n' | '\t')) => text.push(c),
+            Some(c) if c < ' ' || c == '\x7F' => { },
+            Some(c) => text.push(c),
+            None => { },
+        }
+        Some((n, None))
+    }
+
+    fn decode_esc(&mut self, bytes: &[u8]) -> Option<(usize, Option<Event>)> {
+        match *bytes.get(1)? {
+            b'[' => {
+                let (n, csi) = parse_csi(&bytes[2 ..])?;
+                if let Some(csi) = csi {
+                    if csi.private == 0 && csi.final_byte == b'M' && n == 1 {
+                        // X10 mouse report, never requested, skip it
+                        return if bytes.len() >= 6 { Some((6, None)) } else { None };
+                    }
+                    Some((2 + n, self.csi_event(&csi)))
+                } else {
+                    Some((2 + n, None))
+                }
+            },
+            b'O' => {
+                let e = match *bytes.get(2)? {
+                    b'A' => Some(key(Key::Up, Mods::NONE)),
+                    b'B' => Some(key(Key::Down, Mods::NONE)),
+                    b'C' => Some(key(Key::Right, Mods::NONE)),
+                    b'D' => Some(key(Key::Left, Mods::NONE)),
+                    b'H' => Some(key(Key::Home, Mods::NONE)),
+                    b'F' => Some(key(Key::End, Mods::NONE)),
+                    b'E' => Some(key(Key::KeypadCenter, Mods::NONE)),
+                    b'M' => Some(key(Key::Enter, Mods::NONE)),
+                    b'P' => Some(key(Key::F1, Mods::NONE)),
+                    b'Q' => Some(key(Key::F2, Mods::NONE)),
+                    b'R' => Some(key(Key::F3, Mods::NONE)),
+                    b'S' => Some(key(Key::F4, Mods::NONE)),
+                    _ => None,
+                };
+                Some((3, e))
+            },
+            ESC => {
+                let (n, e) = self.decode_esc(&bytes[1 ..])?;
+                Some((1 + n, e.map(with_alt)))
+            },
+            _ => {
+                let (n, c) = decode_utf8(&bytes[1 ..])?;
+                let e = c.and_then(|c| if c < ' ' || c == '\x7F' {
+                    char_key(c).filter(|e| !matches!(e, Event::Key(_, Key::Ctrl(_), _))).map(with_alt)
+                } else {
+                    Some(key(Key::Alt(c), Mods::NONE))
+                });
+                Some((1 + n, e))
+            },
+        }
+    }
+
+    fn csi_event(&mut self, csi: &Csi) -> Option<Event> {
+        match (csi.private, csi.final_byte) {
+            (b'<', b'M') | (b'<', b'm') => sgr_mouse(csi),
+            (0, b'A') => Some(key(Key::Up, csi.mods(1))),
+            (0, b'B') => Some(key(Key::Down, csi.mods(1))),
+            (0, b'C') => Some(key(Key::Right, csi.mods(1))),
+            (0, b'D') => Some(key(Key::Left, csi.mods(1))),
+            (0, b'H') => Some(key(Key::Home, csi.mods(1))),
+            (0, b'F') => Some(key(Key::End, csi.mods(1))),
+            (0, b'E') => Some(key(Key::KeypadCenter, csi.mods(1))),
+            (0, b'P') => Some(key(Key::F1, csi.mods(1))),
+            (0, b'Q') => Some(key(Key::F2, csi.mods(1))),
+            (0, b'R') => Some(key(Key::F3, csi.mods(1))),
+            (0, b'S') => Some(key(Key::F4, csi.mods(1))),
+            (0, b'Z') => Some(key(Key::Tab, csi.mods(1) | Mods::SHIFT)),
+            (0, b'~') => match csi.param(0) {
+                200 => {
+                    self.paste = Some(String::new());
+                    self.paste_cr = false;
+                    None
+                },
+                n => tilde_key(n).map(|k| key(k, csi.mods(1))),
+            },
+            _ => None,
+        }
+    }
+}
+
+fn key(key: Key, mods: Mods) -> Event {
+    Event::Key(ONCE, key, mods)
+}
+
+fn with_alt(e: Event) -> Event {
+    match e {
+        Event::Key(n, Key::Char(c), mods) => Event::Key(n, Key::Alt(c), mods),
+        Event::Key(n, key, mods) => Event::Key(n, key, mods | Mods::ALT),
+        e => e
+    }
+}
+
+fn decode_utf8(bytes: &[u8]) -> Option<(usize, Option<char>)> {
+    let n = match bytes[0] {
+        0x00 ..= 0x7F => 1,
+        0xC2 ..= 0xDF => 2,
+        0xE0 ..= 0xEF => 3,
+        0xF0 ..= 0xF4 => 4,
+        _ => return Some((1, None)),
+    };
+    if bytes.len() < n {
+        if bytes[1 ..].iter().any(|&b| b & 0xC0 != 0x80) { return Some((1, None)); }
+        return None;
+    }
+    match str::from_utf8(&bytes[.. n]) {
+        Ok(s) => Some((n, s.chars().next())),
+        Err(_) => Some((1, None)),
+    }
+}
+
+fn parse_csi(bytes: &[u8]) -> Option<(usize, Option<Csi>)> {
+    let mut csi = Csi { private: 0, params: [0; 4], final_byte: 0 };
+    let mut param = 0;
+    let mut i = 0;
+    if let Some(&b) = bytes.first() {
+        if (0x3C ..= 0x3F).contains(&b) {
+            csi.private = b;
+            i += 1;
+        }
+    }
+    loop {
+        let b = *bytes.get(i)?;
+        i += 1;
+        match b {
+            b'0' ..= b'9' => if let Some(p) = csi.params.get_mut(param) {
+                *p = p.saturating_mul(10).saturating_add(u16::from(b - b'0'));
+            },
+            b';' | b':' => param += 1,
+            0x20 ..= 0x2F => { },
+            0x40 ..= 0x7E => {
+                csi.final_byte = b;
+                return Some((i, Some(csi)));
+            },
+            _ => return Some((i, None)),
+        }
+    }
+}
+
+fn tilde_key(n: u16) -> Option<Key> {
+    Some(match n {
+        1 | 7 => Key::Home,
+        2 => Key::Insert,
+        3 => Key::Delete,
+        4 | 8 => Key::End,
+        5 => Key::PageUp,
+        6 => Key::PageDown,
+        11 ..= 15 => Key::function((n - 10) as u8)?,
+        17 ..= 21 => Key::function((n - 11) as u8)?,
+        23 ..= 26 => Key::function((n - 12) as u8)?,
+        28 | 29 => Key::function((n - 13) as u8)?,
+        31 ..= 34 => Key::function((n - 14) as u8)?,
+        _ => return None,
+    })
+}
+
+fn sgr_mouse(csi: &Csi) -> Option<Event> {
+    let b = csi.param(0);
+    let point = Point {
+        x: min(csi.param(1).saturating_sub(1), i16::MAX as u16) as i16,
+        y: min(csi.param(2).saturating_sub(1), i16::MAX as u16) as i16,
+    };
+    let mut mods = Mods::NONE;
+    if b & 4 != 0 { mods |= Mods::SHIFT; }
+    if b & 8 != 0 { mods |= Mods::ALT; }
+    if b & 16 != 0 { mods |= Mods::CTRL; }
+    if b & 64 != 0 {
+        return match b & 3 {
+            0 => Some(Event::Wheel(point, -1, mods)),
+            1 => Some(Event::Wheel(point, 1, mods)),
+            _ => None,
+        };
+    }
+    let button = match b & 3 {
+        0 => Some(MouseButton::Left),
+        1 => Some(MouseButton::Middle),
+        2 => Some(MouseButton::Right),
+        _ => None,
+    };
+    if b & 32 != 0 {
+        Some(Event::MouseMove(point, button, mods))
+    } else if csi.final_byte == b'M' {
+        button.map(|button| Event::MouseDown(point, button, mods))
+    } else {
+        button.map(|button| Event::MouseUp(point, button, mods))
+    }
+}
+
+fn char_key(c: char) -> Option<Event> {
+    Some(match c {
+        '\0' => key(Key::Ctrl(Ctrl::At), Mods::NONE),
+        '\x01' => key(Key::Ctrl(Ctrl::A), Mods::NONE),
+        '\x02' => key(Key::Ctrl(Ctrl::B), Mods::NONE),
+        '\x03' => key(Key::Ctrl(Ctrl::C), Mods::NONE),
+        '\x04' => key(Key::Ctrl(Ctrl::D), Mods::NONE),
+        '\x05' => key(Key::Ctrl(Ctrl::E), Mods::NONE),
+        '\x06' => key(Key::Ctrl(Ctrl::F), Mods::NONE),
+        '\x07' => key(Key::Ctrl(Ctrl::G), Mods::NONE),
+        '\x08' => key(Key::Backspace, Mods::NONE),
+        '\t' => key(Key::Tab, Mods::NONE),
+        '\x0A' => key(Key::Ctrl(Ctrl::J), Mods::NONE),
+        '\x0B' => key(Key::Ctrl(Ctrl::K), Mods::NONE),
+        '\x0C' => key(Key::Ctrl(Ctrl::L), Mods::NONE),
+        '\r' => key(Key::Enter, Mods::NONE),
+        '\x0E' => key(Key::Ctrl(Ctrl::N), Mods::NONE),
+        '\x0F' => key(Key::Ctrl(Ctrl::O), Mods::NONE),
+        '\x10' => key(Key::Ctrl(Ctrl::P), Mods::NONE),
+        '\x11' => key(Key::Ctrl(Ctrl::Q), Mods::NONE),
+        '\x12' => key(Key::Ctrl(Ctrl::R), Mods::NONE),
+        '\x13' => key(Key::Ctrl(Ctrl::S), Mods::NONE),
+        '\x14' => key(Key::Ctrl(Ctrl::T), Mods::NONE),
+        '\x15' => key(Key::Ctrl(Ctrl::U), Mods::NONE),
+        '\x16' => key(Key::Ctrl(Ctrl::V), Mods::NONE),
+        '\x17' => key(Key::Ctrl(Ctrl::W), Mods::NONE),
+        '\x18' => key(Key::Ctrl(Ctrl::X), Mods::NONE),
+        '\x19' => key(Key::Ctrl(Ctrl::Y), Mods::NONE),
+        '\x1A' => key(Key::Ctrl(Ctrl::Z), Mods::NONE),
+        '\x1B' => key(Key::Escape, Mods::NONE),
+        '\x1C' => key(Key::Ctrl(Ctrl::Backslash), Mods::NONE),
+        '\x1D' => key(Key::Ctrl(Ctrl::Bracket), Mods::NONE),
+        '\x1E' => key(Key::Ctrl(Ctrl::Caret), Mods::NONE),
+        '\x1F' => key(Key::Ctrl(Ctrl::Underscore), Mods::NONE),
+        '\x7F' => key(Key::Backspace, Mods::NONE),
+        c if (('\u{80}' ..= '\u{9F}').contains(&c)) => return None,
+        c => key(Key::Char(c), Mods::NONE),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    fn decode_all(bytes: &[u8]) -> Vec<Event> {
+        let mut decoder = Decoder::new();
+        let mut events = Vec::new();
+        let mut bytes = bytes;
+        while !bytes.is_empty() {
+            let Decoded::Consumed(n, e) = decoder.decode(bytes, true) else { panic!() };
+            events.extend(e);
+            bytes = &bytes[n ..];
+        }
+        events
+    }
+
+    fn is_incomplete(bytes: &[u8]) -> bool {
+        matches!(Decoder::new().decode(bytes, false), Decoded::Incomplete)
+    }
+
+    #[test]
+    fn chars() {
+        assert_eq!(decode_all("aЖ\r\x7F\x01".as_bytes()), [
+            key(Key::Char('a'), Mods::NONE),
+            key(Key::Char('Ж'), Mods::NONE),
+            key(Key::Enter, Mods::NONE),
+            key(Key::Backspace, Mods::NONE),
+            key(Key::Ctrl(Ctrl::A), Mods::NONE),
+        ]);
+        assert!(is_incomplete(&"Ж".as_bytes()[.. 1]));
+        assert_eq!(decode_all(b"\xFFa"), [key(Key::Char('a'), Mods::NONE)]);
+    }
+
+    #[test]
+    fn csi_keys() {
+        assert_eq!(decode_all(b"\x1B[A\x1B[1;5C\x1B[3~\x1B[15;2~\x1B[Z\x1B[H"), [
+            key(Key::Up, Mods::NONE),
+            key(Key::Right, Mods::CTRL),
+            key(Key::Delete, Mods::NONE),
+            key(Key::F5, Mods::SHIFT),
+            key(Key::Tab, Mods::SHIFT),
+            key(Key::Home, Mods::NONE),
+        ]);
+        assert!(is_incomplete(b"\x1B[1;5"));
+        assert_eq!(decode_all(b"\x1B[99~x"), [key(Key::Char('x'), Mods::NONE)]);
+    }
+
+    #[test]
+    fn ss3_keys() {
+        assert_eq!(decode_all(b"\x1BOP\x1BOA\x1BOM"), [
+            key(Key::F1, Mods::NONE),
+            key(Key::Up, Mods::NONE),
+            key(Key::Enter, Mods::NONE),
+        ]);
+    }
+
+    #[test]
+    fn escape_and_alt() {
+        assert!(is_incomplete(b"\x1B"));
+        assert_eq!(decode_all(b"\x1B"), [key(Key::Escape, Mods::NONE)]);
+        assert_eq!(decode_all(b"\x1B\x1B"), [key(Key::Escape, Mods::NONE), key(Key::Escape, Mods::NONE)]);
+        assert_eq!(decode_all(b"\x1Bx"), [key(Key::Alt('x'), Mods::NONE)]);
+        assert_eq!(decode_all(b"\x1B\x1B[A"), [key(Key::Up, Mods::ALT)]);
+        assert_eq!(decode_all(b"\x1B\x7F"), [key(Key::Backspace, Mods::ALT)]);
+    }
+
+    #[test]
+    fn sgr_mouse() {
+        let p = Point { x: 9, y: 4 };
+        assert_eq!(decode_all(b"\x1B[<0;10;5M\x1B[<0;10;5m\x1B[<34;10;5M\x1B[<64;10;5M\x1B[<20;10;5M"), [
+            Event::MouseDown(p, MouseButton::Left, Mods::NONE),
+            Event::MouseUp(p, MouseButton::Left, Mods::NONE),
+            Event::MouseMove(p, Some(MouseButton::Right), Mods::NONE),
+            Event::Wheel(p, -1, Mods::NONE),
+            Event::MouseDown(p, MouseButton::Left, Mods::SHIFT | Mods::CTRL),
+        ]);
+    }
+
+    #[test]
+    fn bracketed_paste() {
+        assert_eq!(decode_all(b"\x1B[200~a\r\nb\tc\x1B\x1B[201~d"), [
+            Event::Paste("a\nb\tc".into()),
+            key(Key::Char('d'), Mods::NONE),
+        ]);
+        assert_eq!(decode_all(b"\x1B[200~a\rb\x07\x1B[201~"), [Event::Paste("a\nb".into())]);
+        let mut decoder = Decoder::new();
+        assert!(matches!(decoder.decode(b"\x1B[200~", true), Decoded::Consumed(6, None)));
+        assert!(matches!(decoder.decode(b"\x1B[20", false), Decoded::Incomplete));
+    }
+}
@@ -0,0 +1,420 @@
+#![cfg(not(windows))]
+
+#![feature(allocator_api)]
+#![feature(negative_impls)]
+
+#![deny(warnings)]
+#![doc(test(attr(deny(warnings))))]
+#![doc(test(attr(allow(dead_code))))]
+#![doc(test(attr(allow(unused_variables))))]
+#![allow(clippy::collapsible_if)]
+
+#![no_std]
+
+extern crate alloc;
+
+mod input;
+
+mod term;
+
+use alloc::alloc::Global;
+use alloc::vec::Vec;
+use core::alloc::Allocator;
+use core::cmp::{max, min};
+use core::fmt::{self, Write};
+use core::ops::Range;
+use input::*;
+use panicking::panicking;
+use term::*;
+use tuifw_screen_base::*;
+use tuifw_screen_base::Screen as base_Screen;
+use unicode_width::UnicodeWidthChar;
+
+const GLOBAL: composable_allocators::Global = composable_allocators::Global;
+
+const ESC_DELAY_MS: i32 = 25;
+
+const START: &str = concat!(
+    "\x1B[?1049h", // alternate screen
+    "\x1B[?7l", // no autowrap
+    "\x1B[?25l", // hide cursor
+    "\x1B[?1000h\x1B[?1002h\x1B[?1003h\x1B[?1006h", // SGR any-motion mouse tracking
+    "\x1B[?2004h", // bracketed paste
+);
+
+const STOP: &str = concat!(
+    "\x1B[?2004l",
+    "\x1B[?1006l\x1B[?1003l\x1B[?1002l\x1B[?1000l",
+    "\x1B[0m",
+    "\x1B[?25h",
+    "\x1B[?7h",
+    "\x1B[?1049l",
+);
+
+type Cell = (char, ExtFg, ExtBg, Attr);
+
+const SPACE: Cell = (' ', ExtFg::Cga(Fg::LightGray), ExtBg::Cga(Bg::None), Attr::NONE);
+
+struct Line {
+    dirty: bool,
+    data: Range<i16>,
+}
+
+struct Out<'a, A: Allocator>(&'a mut Vec<u8, A>);
+
+impl<'a, A: Allocator> Write for Out<'a, A> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0.extend_from_slice(s.as_bytes());
+        Ok(())
+    }
+}
+
+pub struct Screen<A: Allocator + Clone = Global> {
+    error_alloc: &'static dyn Allocator,
+    max_size: Option<(u16, u16)>,
+    tty: Tty,
+    colors: ColorDepth,
+    size: Vector,
+    buf: Vec<Cell, A>,
+    shown: Vec<Cell, A>,
+    lines: Vec<Line, A>,
+    full_redraw: bool,
+    cursor_is_visible: bool,
+    out: Vec<u8, A>,
+    input: Vec<u8, A>,
+    decoder: Decoder,
+    double_click: DoubleClickDetector,
+    clipboard: Osc52Clipboard,
+}
+
+impl<A: Allocator + Clone> !Sync for Screen<A> { }
+impl<A: Allocator + Clone> !Send for Screen<A> { }
+
+impl Screen {
+    /// # Safety
+    ///
+    /// This function switches the controlling terminal to raw mode and installs a `SIGWINCH` handler.
+    /// It is safe iff no other code in application changes terminal settings or `SIGWINCH` disposition
+    /// while `Screen` instance is alive. This rule also applies to another `Screen` instance:
+    /// it is not safe to call `new`/`new_in` again until `Screen` created by previous call is dropped.
+    ///
+    /// It is impossible to garantee this conditions on a library level.
+    /// So this unsafity should be propagated through all wrappers to the final application.
+    pub unsafe fn new(max_size: Option<(u16, u16)>, error_alloc: Option<&'static dyn Allocator>) -> Result<Self, Error> {
+        Self::new_in(max_size, error_alloc, Global)
+    }
+}
+
+impl<A: Allocator + Clone> Screen<A> {
+    /// # Safety
+    ///
+    /// This function switches the controlling terminal to raw mode and installs a `SIGWINCH` handler.
+    /// It is safe iff no other code in application changes terminal settings or `SIGWINCH` disposition
+    /// while `Screen` instance is alive. This rule also applies to another `Screen` instance:
+    /// it is not safe to call `new`/`new_in` again until `Screen` created by previous call is dropped.
+    ///
+    /// It is impossible to garantee this conditions on a library level.
+    /// So this unsafity should be propagated through all wrappers to the final application.
+    pub unsafe fn new_in(
+        max_size: Option<(u16, u16)>,
+        error_alloc: Option<&'static dyn Allocator>,
+        alloc: A
+    ) -> Result<Self, Error> {
+        let error_alloc = error_alloc.unwrap_or(&GLOBAL);
+        let mut s = Screen {
+            error_alloc,
+            max_size,
+            tty: Tty::open(error_alloc)?,
+            colors: ColorDepth::from_env(),
+            size: Vector::null(),
+            buf: Vec::new_in(alloc.clone()),
+            shown: Vec::new_in(alloc.clone()),
+            lines: Vec::new_in(alloc.clone()),
+            full_redraw: true,
+            cursor_is_visible: false,
+            out: Vec::new_in(alloc.clone()),
+            input: Vec::new_in(alloc),
+            decoder: Decoder::new(),
+            double_click: DoubleClickDetector::new(),
+            clipboard: Osc52Clipboard::new(),
+        };
+        s.tty.start(START, error_alloc)?;
+        s.resize()?;
+        Ok(s)
+    }
+
+    fn resize(&mut self) -> Result<(), Error> {
+        let (x, y) = self.tty.size(self.error_alloc)?;
+        let (x, y) = if let Some(max_size) = self.max_size {
+            (min(max_size.0, x), min(max_size.1, y))
+        } else {
+            (x, y)
+        };
+        let size = Vector { x: min(x, i16::MAX as u16) as i16, y: min(y, i16::MAX as u16) as i16 };
+        let area = usize::from(size.x as u16) * usize::from(size.y as u16);
+        self.buf.try_reserve(area.saturating_sub(self.buf.len())).map_err(|_| Error::Oom)?;
+        self.shown.try_reserve(area.saturating_sub(self.shown.len())).map_err(|_| Error::Oom)?;
+        self.lines.try_reserve(usize::from(size.y as u16).saturating_sub(self.lines.len())).map_err(|_| Error::Oom)?;
+        self.buf.clear();
+        self.buf.resize(area, SPACE);
+        self.shown.clear();
+        self.shown.resize(area, SPACE);
+        self.lines.clear();
+        self.lines.extend((0 .. size.y).map(|_| Line { dirty: true, data: 0 .. size.x }));
+        self.size = size;
+        self.full_redraw = true;
+        Ok(())
+    }
+
+    fn start_text(line: &mut [Cell], x: i16) {
+        if x <= 0 { return; }
+        let mut x = x as u16;
+        if let Some(col) = line.get(x as usize) {
+            if col.0 != '\0' { return; }
+        } else {
+            return;
+        }
+        loop {
+            debug_assert!(x > 0);
+            x -= 1;
+            let col = &mut line[x as usize];
+            let stop = col.0 != '\0';
+            col.0 = ' ';
+            if stop { break; }
+        }
+    }
+
+    fn end_text(line: &mut [Cell], mut x: i16) {
+        if x <= 0 { return; }
+        while let Some(col) = line.get_mut(x as u16 as usize) {
+            if col.0 != '\0' { break; }
+            col.0 = ' ';
+            x += 1;
+        }
+    }
+
+    fn render(&mut self, cursor: Option<Point>) {
+        let cols = usize::from(self.size.x as u16);
+        let mut out = Out(&mut self.out);
+        if self.full_redraw {
+            out.0.extend_from_slice(b"\x1B[0m\x1B[2J");
+        }
+        let mut pos = None;
+        let mut sgr = None;
+        for (y, line) in self.lines.iter_mut().enumerate() {
+            if !line.dirty && !self.full_redraw { continue; }
+            line.dirty = false;
+            let row = y * cols;
+            let mut x = 0;
+            while x < cols {
+                let cell = self.buf[row + x];
+                if cell.0 == '\0' {
+                    x += 1;
+                    continue;
+                }
+                let w = min(cell.0.width().unwrap_or(1).max(1), cols - x);
+                let cells = row + x .. row + x + w;
+                if self.full_redraw || self.buf[cells.clone()] != self.shown[cells.clone()] {
+                    if pos != Some((x, y)) {
+                        let _ = write!(out, "\x1B[{};{}H", y + 1, x + 1);
+                    }
+                    if sgr != Some((cell.1, cell.2, cell.3)) {
+                        write_sgr(&mut out, self.colors, cell.1, cell.2, cell.3);
+                        sgr = Some((cell.1, cell.2, cell.3));
+                    }
+                    let _ = out.write_char(cell.0);
+                    self.shown[cells.clone()].copy_from_slice(&self.buf[cells]);
+                    pos = if x + w < cols { Some((x + w, y)) } else { None };
+                }
+                x += w;
+            }
+        }
+        self.full_redraw = false;
+        let cursor = cursor.filter(|&cursor| (Rect { tl: Point { x: 0, y: 0 }, size: self.size }).contains(cursor));
+        if let Some(cursor) = cursor {
+            let _ = write!(out, "\x1B[{};{}H", cursor.y + 1, cursor.x + 1);
+        }
+        if cursor.is_some() != self.cursor_is_visible {
+            self.cursor_is_visible = cursor.is_some();
+            out.0.extend_from_slice(if self.cursor_is_visible { b"\x1B[?25h" } else { b"\x1B[?25l" });
+        }
+    }
+
+    fn next_event(&mut self, last: bool) -> Option<Event> {
+        while !self.input.is_empty() {
+            match self.decoder.decode(&self.input, last) {
+                Decoded::Incomplete => return None,
+                Decoded::Consumed(n, e) => {
+                    self.input.drain(.. n);
+                    if let Some(e) = e { return Some(self.double_click(e)); }
+                },
+            }
+        }
+        None
+    }
+
+    fn double_click(&mut self, e: Event) -> Event {
+        if let Event::MouseDown(point, button, mods) = e {
+            if self.double_click.press(point, button, monotonic_ms()) {
+                return Event::DoubleClick(point, button, mods);
+            }
+        }
+        e
+    }
+
+    fn read_event(&mut self, wait: bool) -> Result<Option<Event>, Error> {
+        loop {
+            if let Some(e) = self.next_event(false) { return Ok(Some(e)); }
+            let pending = !self.input.is_empty();
+            let timeout = if pending { ESC_DELAY_MS } else if wait { -1 } else { 0 };
+            match self.tty.poll(timeout, self.error_alloc)? {
+                Ready::Resize => {
+                    self.resize()?;
+                    return Ok(Some(Event::Resize));
+                },
+                Ready::Input => {
+                    self.tty.read(&mut self.input, self.error_alloc)?;
+                },
+                Ready::Interrupted => { },
+                Ready::Timeout => {
+                    if pending {
+                        if let Some(e) = self.next_event(true) { return Ok(Some(e)); }
+                    } else {
+                        return Ok(None);
+                    }
+                },
+            }
+        }
+    }
+
+    fn update_raw(&mut self, cursor: Option<Point>, wait: bool) -> Result<Option<Event>, Error> {
+        self.render(cursor);
+        if let Some(sequence) = self.clipboard.take_sequence() {
+            self.out.extend_from_slice(sequence.as_bytes());
+        }
+        if !self.out.is_empty() {
+            let res = self.tty.write(&self.out, self.error_alloc);
+            self.out.clear();
+            res?;
+        }
+        let e = self.read_event(wait)?;
+        if let Some(Event::Key(_, Key::Ctrl(Ctrl::L), _)) = e {
+            self.full_redraw = true;
+        }
+        Ok(e)
+    }
+}
+
+impl<A: Allocator + Clone> Drop for Screen<A> {
+    #[allow(clippy::panicking_unwrap)]
+    fn drop(&mut self) {
+        let e = self.tty.stop(STOP, self.error_alloc);
+        if e.is_err() && !panicking() { e.unwrap(); }
+    }
+}
+
+fn write_sgr<A: Allocator>(out: &mut Out<A>, colors: ColorDepth, fg: ExtFg, bg: ExtBg, attr: Attr) {
+    out.0.extend_from_slice(b"\x1B[0");
+    if attr.contains(Attr::BOLD) { out.0.extend_from_slice(b";1"); }
+    if attr.contains(Attr::ITALIC) { out.0.extend_from_slice(b";3"); }
+    if attr.contains(Attr::UNDERLINE) { out.0.extend_from_slice(b";4"); }
+    if attr.contains(Attr::REVERSE) { out.0.extend_from_slice(b";7"); }
+    if attr.contains(Attr::STRIKETHROUGH) { out.0.extend_from_slice(b";9"); }
+    let _ = match colors.fg(fg) {
+        ExtFg::Cga(fg) if (fg as u8) < 8 => write!(out, ";{}", 30 + fg as u8),
+        ExtFg::Cga(fg) => write!(out, ";{}", 90 + fg as u8 - 8),
+        ExtFg::Indexed(i) => write!(out, ";38;5;{i}"),
+        ExtFg::Rgb(r, g, b) => write!(out, ";38;2;{r};{g};{b}"),
+    };
+    let _ = match colors.bg(bg) {
+        ExtBg::Cga(Bg::None) => write!(out, ";49"),
+        ExtBg::Cga(bg) => write!(out, ";{}", 40 + bg as u8 - 1),
+        ExtBg::Indexed(i) => write!(out, ";48;5;{i}"),
+        ExtBg::Rgb(r, g, b) => write!(out, ";48;2;{r};{g};{b}"),
+    };
+    out.0.push(b'm');
+}
+
+impl<A: Allocator + Clone> base_Screen for Screen<A> {
+    fn size(&self) -> Vector { self.size }
+
+    fn out(
+        &mut self,
+        p: Point,
+        fg: ExtFg,
+        bg: ExtBg,
+        attr: Attr,
+        text: &str,
+        hard: Range<i16>,
+        soft: Range<i16>
+    ) -> Range<i16> {
+        debug_assert!(p.y >= 0 && p.y < self.size().y);
+        debug_assert!(hard.start >= 0 && hard.end > hard.start && hard.end <= self.size().x);
+        debug_assert!(soft.start >= 0 && soft.end > soft.start && soft.end <= self.size().x);
+        let text_end = if soft.end <= p.x { return 0 .. 0 } else { soft.end.saturating_sub(p.x) };
+        let text_start = if soft.start <= p.x { 0 } else { soft.start.saturating_sub(p.x) };
+        let cols = usize::from(self.size.x as u16);
+        let line = &mut self.buf[usize::from(p.y as u16) * cols .. (usize::from(p.y as u16) + 1) * cols];
+        self.lines[p.y as u16 as usize].dirty = true;
+        let chars = text.chars().filter_map(|c| c.width().filter(|&w| w != 0).map(|w| (c, w)));
+        let mut x0 = None;
+        let mut x = p.x;
+        let mut n = 0i16;
+        for (c, w) in chars {
+            if x >= hard.end { break; }
+            if n >= text_end { break; }
+            let w = min(w, i16::MAX as u16 as usize) as u16 as i16;
+            n = n.saturating_add(w);
+            let before_text_start = n <= text_start;
+            if before_text_start {
+                x = min(hard.end, x.saturating_add(w));
+                continue;
+            }
+            if x < hard.start {
+                x = min(hard.end, x.saturating_add(w));
+                if x > hard.start {
+                    debug_assert!(x0.is_none());
+                    Self::start_text(line, hard.start);
+                    x0 = Some(hard.start);
+                    for i in hard.start .. x {
+                        line[i as u16 as usize] = (' ', fg, bg, attr);
+                    }
+                }
+                continue;
+            }
+            if x0.is_none() {
+                Self::start_text(line, x);
+                x0 = Some(x);
+            }
+            let next_x = min(hard.end, x.saturating_add(w));
+            if next_x - x < w {
+                for i in x .. next_x {
+                    line[i as u16 as usize] = (' ', fg, bg, attr);
+                }
+                x = next_x;
+                break;
+            }
+            line[x as u16 as usize] = (c, fg, bg, attr);
+            for i in x + 1 .. next_x {
+                line[i as u16 as usize] = ('\0', fg, bg, attr);
+            }
+            x = next_x;
+        }
+        if let Some(x0) = x0 {
+            Self::end_text(line, x);
+            max(x0, hard.start) .. x
+        } else {
+            0 .. 0
+        }
+    }
+
+    fn update(&mut self, cursor: Option<Point>, wait: bool) -> Result<Option<Event>, Error> {
+        self.update_raw(cursor, wait)
+    }
+
+    fn line_invalidated_range(&self, line: i16) -> &Range<i16> { &self.lines[usize::from(line as u16)].data }
+
+    fn line_invalidated_range_mut(&mut self, line: i16) -> &mut Range<i16> { &mut self.lines[usize::from(line as u16)].data }
+
+    fn clipboard(&mut self) -> Option<&mut dyn Clipboard> { Some(&mut self.clipboard) }
+}
@@ -0,0 +1,206 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::alloc::Allocator;
+use core::ffi::CStr;
+use core::mem::MaybeUninit;
+use core::sync::atomic::{AtomicI32, Ordering};
+use errno_no_std::{Errno, errno, set_errno};
+use libc::*;
+use tuifw_screen_base::*;
+
+static SIGWINCH_PIPE: AtomicI32 = AtomicI32::new(-1);
+
+extern "C" fn on_sigwinch(_: c_int) {
+    let fd = SIGWINCH_PIPE.load(Ordering::Relaxed);
+    if fd < 0 { return; }
+    let e = errno();
+    let b = 0u8;
+    unsafe { write(fd, &b as *const _ as *const c_void, 1); }
+    set_errno(e);
+}
+
+fn system_error(error_alloc: &'static dyn Allocator) -> Error {
+    Error::System(Box::new_in(errno(), error_alloc))
+}
+
+fn non_neg(r: c_int, error_alloc: &'static dyn Allocator) -> Result<c_int, Error> {
+    if r < 0 { Err(system_error(error_alloc)) } else { Ok(r) }
+}
+
+pub fn monotonic_ms() -> u32 {
+    let mut t = timespec { tv_sec: 0, tv_nsec: 0 };
+    unsafe { clock_gettime(CLOCK_MONOTONIC, &mut t as *mut _); }
+    (t.tv_sec as u32).wrapping_mul(1000).wrapping_add((t.tv_nsec / 1_000_000) as u32)
+}
+
+pub enum Ready {
+    Input,
+    Resize,
+    Interrupted,
+    Timeout,
+}
+
+pub struct Tty {
+    fd: c_int,
+    pipe: [c_int; 2],
+    original_termios: Option<termios>,
+    original_sigwinch: Option<sigaction>,
+    started: bool,
+}
+
+impl Tty {
+    pub fn open(error_alloc: &'static dyn Allocator) -> Result<Tty, Error> {
+        let fd = non_neg(unsafe { open(c"/dev/tty".as_ptr(), O_RDWR | O_NOCTTY | O_CLOEXEC) }, error_alloc)?;
+        Ok(Tty { fd, pipe: [-1, -1], original_termios: None, original_sigwinch: None, started: false })
+    }
+
+    pub unsafe fn start(&mut self, seq: &str, error_alloc: &'static dyn Allocator) -> Result<(), Error> {
+        let mut original = MaybeUninit::<termios>::uninit();
+        non_neg(tcgetattr(self.fd, original.as_mut_ptr()), error_alloc)?;
+        let original = original.assume_init();
+        let mut raw = original;
+        cfmakeraw(&mut raw as *mut _);
+        raw.c_cc[VMIN] = 1;
+        raw.c_cc[VTIME] = 0;
+        non_neg(tcsetattr(self.fd, TCSAFLUSH, &raw as *const _), error_alloc)?;
+        self.original_termios = Some(original);
+        non_neg(pipe(self.pipe.as_mut_ptr()), error_alloc)?;
+        for fd in self.pipe {
+            non_neg(fcntl(fd, F_SETFL, fcntl(fd, F_GETFL) | O_NONBLOCK), error_alloc)?;
+            non_neg(fcntl(fd, F_SETFD, FD_CLOEXEC), error_alloc)?;
+        }
+        SIGWINCH_PIPE.store(self.pipe[1], Ordering::Relaxed);
+        let mut action = MaybeUninit::<sigaction>::zeroed().assume_init();
+        action.sa_sigaction = on_sigwinch as extern "C" fn(c_int) as sighandler_t;
+        action.sa_flags = SA_RESTART;
+        sigemptyset(&mut action.sa_mask as *mut _);
+        let mut original_sigwinch = MaybeUninit::<sigaction>::uninit();
+        non_neg(sigaction(SIGWINCH, &action as *const _, original_sigwinch.as_mut_ptr()), error_alloc)?;
+        self.original_sigwinch = Some(original_sigwinch.assume_init());
+        self.started = true;
+        self.write(seq.as_bytes(), error_alloc)
+    }
+
+    pub fn stop(&mut self, seq: &str, error_alloc: &'static dyn Allocator) -> Result<(), Error> {
+        let mut res = Ok(());
+        if self.started {
+            res = res.and(self.write(seq.as_bytes(), error_alloc));
+        }
+        if let Some(original) = self.original_sigwinch.take() {
+            res = res.and(non_neg(unsafe { sigaction(SIGWINCH, &original as *const _, core::ptr::null_mut()) }, error_alloc).map(|_| ()));
+        }
+        SIGWINCH_PIPE.store(-1, Ordering::Relaxed);
+        for fd in self.pipe {
+            if fd >= 0 { unsafe { close(fd); } }
+        }
+        self.pipe = [-1, -1];
+        if let Some(original) = self.original_termios.take() {
+            res = res.and(non_neg(unsafe { tcsetattr(self.fd, TCSAFLUSH, &original as *const _) }, error_alloc).map(|_| ()));
+        }
+        if self.fd >= 0 {
+            unsafe { close(self.fd); }
+            self.fd = -1;
+        }
+        res
+    }
+
+    pub fn size(&self, error_alloc: &'static dyn Allocator) -> Result<(u16, u16), Error> {
+        let mut size = winsize { ws_row: 0, ws_col: 0, ws_xpixel: 0, ws_ypixel: 0 };
+        non_neg(unsafe { ioctl(self.fd, TIOCGWINSZ, &mut size as *mut _) }, error_alloc)?;
+        Ok((size.ws_col, size.ws_row))
+    }
+
+    pub fn write(&self, mut bytes: &[u8], error_alloc: &'static dyn Allocator) -> Result<(), Error> {
+        while !bytes.is_empty() {
+            let n = unsafe { write(self.fd, bytes.as_ptr() as *const c_void, bytes.len()) };
+            if n < 0 {
+                if errno() == Errno(EINTR) { continue; }
+                return Err(system_error(error_alloc));
+            }
+            bytes = &bytes[n as usize ..];
+        }
+        Ok(())
+    }
+
+    pub fn read<A: Allocator>(&self, input: &mut Vec<u8, A>, error_alloc: &'static dyn Allocator) -> Result<(), Error> {
+        let mut buf = [0u8; 256];
+        let n = unsafe { read(self.fd, buf.as_mut_ptr() as *mut c_void, buf.len()) };
+        if n < 0 {
+            if errno() == Errno(EINTR) || errno() == Errno(EAGAIN) { return Ok(()); }
+            return Err(system_error(error_alloc));
+        }
+        if n == 0 {
+            return Err(Error::System(Box::new_in("terminal closed", error_alloc)));
+        }
+        input.try_reserve(n as usize).map_err(|_| Error::Oom)?;
+        input.extend_from_slice(&buf[.. n as usize]);
+        Ok(())
+    }
+
+    pub fn poll(&self, timeout_ms: c_int, error_alloc: &'static dyn Allocator) -> Result<Ready, Error> {
+        let mut fds = [
+            pollfd { fd: self.fd, events: POLLIN, revents: 0 },
+            pollfd { fd: self.pipe[0], events: POLLIN, revents: 0 },
+        ];
+        let n = unsafe { poll(fds.as_mut_ptr(), fds.len() as nfds_t, timeout_ms) };
+        if n < 0 {
+            if errno() == Errno(EINTR) { return Ok(Ready::Interrupted); }
+            return Err(system_error(error_alloc));
+        }
+        if fds[1].revents != 0 {
+            let mut buf = [0u8; 16];
+            while unsafe { read(self.pipe[0], buf.as_mut_ptr() as *mut c_void, buf.len()) } > 0 { }
+            return Ok(Ready::Resize);
+        }
+        if fds[0].revents != 0 { return Ok(Ready::Input); }
+        Ok(Ready::Timeout)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ColorDepth {
+    Cga,
+    Indexed,
+    Rgb,
+}
+
+fn env(name: &CStr) -> Option<&'static [u8]> {
+    let value = unsafe { getenv(name.as_ptr()) };
+    if value.is_null() { return None; }
+    Some(unsafe { CStr::from_ptr(value) }.to_bytes())
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack.windows(needle.len()).any(|x| x == needle)
+}
+
+impl ColorDepth {
+    pub fn from_env() -> ColorDepth {
+        let colorterm = env(c"COLORTERM");
+        if colorterm.is_some_and(|x| contains(x, b"truecolor") || contains(x, b"24bit")) {
+            return ColorDepth::Rgb;
+        }
+        if colorterm.is_some() || env(c"TERM").is_some_and(|x| contains(x, b"256color")) {
+            return ColorDepth::Indexed;
+        }
+        ColorDepth::Cga
+    }
+
+    pub fn fg(self, fg: ExtFg) -> ExtFg {
+        match (self, fg) {
+            (ColorDepth::Rgb, fg) => fg,
+            (ColorDepth::Indexed, ExtFg::Rgb(..)) => ExtFg::Indexed(fg.to_indexed()),
+            (ColorDepth::Indexed, fg) => fg,
+            (ColorDepth::Cga, fg) => ExtFg::Cga(fg.to_cga()),
+        }
+    }
+
+    pub fn bg(self, bg: ExtBg) -> ExtBg {
+        match (self, bg) {
+            (ColorDepth::Rgb, bg) => bg,
+            (ColorDepth::Indexed, ExtBg::Rgb(..)) => bg.to_indexed().map_or(ExtBg::Cga(Bg::None), ExtBg::Indexed),
+            (ColorDepth::Indexed, bg) => bg,
+            (ColorDepth::Cga, bg) => ExtBg::Cga(bg.to_cga()),
+        }
+    }
+}
@@ -19,10 +19,13 @@
 extern crate alloc;
 
 use alloc::boxed::Box;
+use alloc::string::String;
 use core::alloc::Allocator;
+use core::cmp::min;
 use core::fmt::{self, Debug, Display, Formatter};
 use core::num::NonZeroU16;
-use core::ops::Range;
+use core::ops::{BitOr, BitOrAssign, Range};
+use core::str::FromStr;
 use enum_derive_2018::{EnumDisplay, EnumFromStr, IterVariants};
 use macro_attr_2018::macro_attr;
 use unicode_width::UnicodeWidthChar;
@@ -130,6 +133,265 @@ impl TryFrom<Fg> for Bg {
     }
 }
 
+const CGA_RGB: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (170, 0, 0),
+    (0, 170, 0),
+    (170, 85, 0),
+    (0, 0, 170),
+    (170, 0, 170),
+    (0, 170, 170),
+    (170, 170, 170),
+    (85, 85, 85),
+    (255, 85, 85),
+    (85, 255, 85),
+    (255, 255, 85),
+    (85, 85, 255),
+    (255, 85, 255),
+    (85, 255, 255),
+    (255, 255, 255),
+];
+
+fn indexed_rgb(i: u8) -> (u8, u8, u8) {
+    if i < 16 {
+        CGA_RGB[usize::from(i)]
+    } else if i < 232 {
+        let i = i - 16;
+        let c = |x: u8| if x == 0 { 0 } else { 55 + 40 * x };
+        (c(i / 36), c(i / 6 % 6), c(i % 6))
+    } else {
+        let v = 8 + 10 * (i - 232);
+        (v, v, v)
+    }
+}
+
+fn rgb_distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let d = |x: u8, y: u8| { let d = u32::from(x.abs_diff(y)); d * d };
+    d(a.0, b.0) + d(a.1, b.1) + d(a.2, b.2)
+}
+
+fn nearest_cga(rgb: (u8, u8, u8), count: usize) -> usize {
+    CGA_RGB[.. count].iter().enumerate().min_by_key(|&(_, &c)| rgb_distance(rgb, c)).unwrap().0
+}
+
+fn rgb_indexed(rgb: (u8, u8, u8)) -> u8 {
+    let q = |x: u8| if x < 48 { 0 } else if x < 115 { 1 } else { (x - 35) / 40 };
+    let cube = 16 + 36 * q(rgb.0) + 6 * q(rgb.1) + q(rgb.2);
+    let avg = ((u16::from(rgb.0) + u16::from(rgb.1) + u16::from(rgb.2)) / 3) as u8;
+    let gray = if avg < 8 { 232 } else { 232 + min(23, (avg - 8 + 5) / 10) };
+    if rgb_distance(rgb, indexed_rgb(gray)) < rgb_distance(rgb, indexed_rgb(cube)) { gray } else { cube }
+}
+
+fn parse_rgb(s: &str) -> Option<(u8, u8, u8)> {
+    let s = s.strip_prefix('#')?;
+    if s.len() != 6 || !s.is_ascii() { return None; }
+    let r = u8::from_str_radix(&s[0 .. 2], 16).ok()?;
+    let g = u8::from_str_radix(&s[2 .. 4], 16).ok()?;
+    let b = u8::from_str_radix(&s[4 .. 6], 16).ok()?;
+    Some((r, g, b))
+}
+
+#[derive(Eq, PartialEq, Debug, Hash, Clone, Copy, Ord, PartialOrd)]
+pub enum ExtFg {
+    Cga(Fg),
+    Indexed(u8),
+    Rgb(u8, u8, u8),
+}
+
+impl From<Fg> for ExtFg {
+    fn from(fg: Fg) -> ExtFg {
+        ExtFg::Cga(fg)
+    }
+}
+
+impl ExtFg {
+    pub fn rgb(self) -> (u8, u8, u8) {
+        match self {
+            ExtFg::Cga(fg) => CGA_RGB[fg as usize],
+            ExtFg::Indexed(i) => indexed_rgb(i),
+            ExtFg::Rgb(r, g, b) => (r, g, b),
+        }
+    }
+
+    pub fn to_indexed(self) -> u8 {
+        match self {
+            ExtFg::Cga(fg) => fg as u8,
+            ExtFg::Indexed(i) => i,
+            ExtFg::Rgb(r, g, b) => rgb_indexed((r, g, b)),
+        }
+    }
+
+    pub fn to_cga(self) -> Fg {
+        match self {
+            ExtFg::Cga(fg) => fg,
+            ExtFg::Indexed(i) if i < 16 => Fg::iter_variants().nth(usize::from(i)).unwrap(),
+            x => Fg::iter_variants().nth(nearest_cga(x.rgb(), 16)).unwrap(),
+        }
+    }
+}
+
+impl Display for ExtFg {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            ExtFg::Cga(fg) => write!(f, "{fg}"),
+            ExtFg::Indexed(i) => write!(f, "{i}"),
+            ExtFg::Rgb(r, g, b) => write!(f, "#{r:02X}{g:02X}{b:02X}"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ParseExtColorError;
+
+impl FromStr for ExtFg {
+    type Err = ParseExtColorError;
+
+    fn from_str(s: &str) -> Result<ExtFg, Self::Err> {
+        if let Ok(fg) = Fg::from_str(s) {
+            Ok(ExtFg::Cga(fg))
+        } else if let Ok(i) = u8::from_str(s) {
+            Ok(ExtFg::Indexed(i))
+        } else if let Some((r, g, b)) = parse_rgb(s) {
+            Ok(ExtFg::Rgb(r, g, b))
+        } else {
+            Err(ParseExtColorError)
+        }
+    }
+}
+
+#[derive(Eq, PartialEq, Debug, Hash, Clone, Copy, Ord, PartialOrd)]
+pub enum ExtBg {
+    Cga(Bg),
+    Indexed(u8),
+    Rgb(u8, u8, u8),
+}
+
+impl From<Bg> for ExtBg {
+    fn from(bg: Bg) -> ExtBg {
+        ExtBg::Cga(bg)
+    }
+}
+
+impl ExtBg {
+    pub fn rgb(self) -> Option<(u8, u8, u8)> {
+        match self {
+            ExtBg::Cga(Bg::None) => None,
+            ExtBg::Cga(bg) => Some(CGA_RGB[bg as usize - 1]),
+            ExtBg::Indexed(i) => Some(indexed_rgb(i)),
+            ExtBg::Rgb(r, g, b) => Some((r, g, b)),
+        }
+    }
+
+    pub fn to_indexed(self) -> Option<u8> {
+        match self {
+            ExtBg::Cga(Bg::None) => None,
+            ExtBg::Cga(bg) => Some(bg as u8 - 1),
+            ExtBg::Indexed(i) => Some(i),
+            ExtBg::Rgb(r, g, b) => Some(rgb_indexed((r, g, b))),
+        }
+    }
+
+    pub fn to_cga(self) -> Bg {
+        match self {
+            ExtBg::Cga(bg) => bg,
+            ExtBg::Indexed(i) if i < 8 => Bg::iter_variants().nth(usize::from(i) + 1).unwrap(),
+            x => Bg::iter_variants().nth(nearest_cga(x.rgb().unwrap(), 8) + 1).unwrap(),
+        }
+    }
+}
+
+impl Display for ExtBg {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            ExtBg::Cga(bg) => write!(f, "{bg}"),
+            ExtBg::Indexed(i) => write!(f, "{i}"),
+            ExtBg::Rgb(r, g, b) => write!(f, "#{r:02X}{g:02X}{b:02X}"),
+        }
+    }
+}
+
+impl FromStr for ExtBg {
+    type Err = ParseExtColorError;
+
+    fn from_str(s: &str) -> Result<ExtBg, Self::Err> {
+        if let Ok(bg) = Bg::from_str(s) {
+            Ok(ExtBg::Cga(bg))
+        } else if let Ok(i) = u8::from_str(s) {
+            Ok(ExtBg::Indexed(i))
+        } else if let Some((r, g, b)) = parse_rgb(s) {
+            Ok(ExtBg::Rgb(r, g, b))
+        } else {
+            Err(ParseExtColorError)
+        }
+    }
+}
+
+#[derive(Eq, PartialEq, Debug, Hash, Clone, Copy, Ord, PartialOrd, Default)]
+pub struct Attr(u8);
+
+impl Attr {
+    pub const NONE: Attr = Attr(0);
+    pub const BOLD: Attr = Attr(1 << 0);
+    pub const UNDERLINE: Attr = Attr(1 << 1);
+    pub const ITALIC: Attr = Attr(1 << 2);
+    pub const REVERSE: Attr = Attr(1 << 3);
+    pub const STRIKETHROUGH: Attr = Attr(1 << 4);
+
+    const NAMES: [(Attr, &'static str); 5] = [
+        (Attr::BOLD, "Bold"),
+        (Attr::UNDERLINE, "Underline"),
+        (Attr::ITALIC, "Italic"),
+        (Attr::REVERSE, "Reverse"),
+        (Attr::STRIKETHROUGH, "Strikethrough"),
+    ];
+
+    pub fn is_empty(self) -> bool { self.0 == 0 }
+
+    pub fn contains(self, other: Attr) -> bool { self.0 & other.0 == other.0 }
+
+    pub fn without(self, other: Attr) -> Attr { Attr(self.0 & !other.0) }
+}
+
+impl BitOr for Attr {
+    type Output = Attr;
+
+    fn bitor(self, rhs: Attr) -> Attr { Attr(self.0 | rhs.0) }
+}
+
+impl BitOrAssign for Attr {
+    fn bitor_assign(&mut self, rhs: Attr) { self.0 |= rhs.0; }
+}
+
+impl Display for Attr {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        if self.is_empty() { return write!(f, "None"); }
+        let mut first = true;
+        for (attr, name) in Attr::NAMES {
+            if !self.contains(attr) { continue; }
+            if !first { write!(f, "+")?; }
+            write!(f, "{name}")?;
+            first = false;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct ParseAttrError;
+
+impl FromStr for Attr {
+    type Err = ParseAttrError;
+
+    fn from_str(s: &str) -> Result<Attr, Self::Err> {
+        if s == "None" { return Ok(Attr::NONE); }
+        let mut res = Attr::NONE;
+        for part in s.split('+') {
+            res |= Attr::NAMES.iter().find(|&&(_, name)| name == part).ok_or(ParseAttrError)?.0;
+        }
+        Ok(res)
+    }
+}
+
 #[derive(Eq, PartialEq, Debug, Hash, Clone, Copy, Ord, PartialOrd)]
 pub enum Ctrl {
     At, A, B, C, D, E, F, G, J, K, L, N,
@@ -137,12 +399,48 @@ pub enum Ctrl {
     Backslash, Bracket, Caret, Underscore
 }
 
+#[derive(Eq, PartialEq, Debug, Hash, Clone, Copy, Ord, PartialOrd, Default)]
+pub struct Mods(u8);
+
+impl Mods {
+    pub const NONE: Mods = Mods(0);
+    pub const SHIFT: Mods = Mods(1 << 0);
+    pub const ALT: Mods = Mods(1 << 1);
+    pub const CTRL: Mods = Mods(1 << 2);
+
+    pub fn is_empty(self) -> bool { self.0 == 0 }
+
+    pub fn contains(self, other: Mods) -> bool { self.0 & other.0 == other.0 }
+
+    pub fn without(self, other: Mods) -> Mods { Mods(self.0 & !other.0) }
+
+    pub fn from_xterm_param(n: u8) -> Mods {
+        Mods(n.saturating_sub(1) & 0x07)
+    }
+}
+
+impl BitOr for Mods {
+    type Output = Mods;
+
+    fn bitor(self, rhs: Mods) -> Mods { Mods(self.0 | rhs.0) }
+}
+
+impl BitOrAssign for Mods {
+    fn bitor_assign(&mut self, rhs: Mods) { self.0 |= rhs.0; }
+}
+
 #[derive(Eq, PartialEq, Debug, Hash, Clone, Copy, Ord, PartialOrd)]
 #[non_exhaustive]
 pub enum Key {
+    /// A printable character, with Shift already applied, so it always comes with `Mods::NONE`.
     Char(char),
+    /// A printable character typed with Alt held.
+    ///
+    /// All backends report Alt this way for printable characters, never as `Char` with [`Mods::ALT`],
+    /// which marks Alt held with any other key.
     Alt(char),
     Ctrl(Ctrl),
+    /// The main or the keypad Enter key.
     Enter,
     Escape,
     Down,
@@ -169,15 +467,99 @@ pub enum Key {
     F10,
     F11,
     F12,
+    F13,
+    F14,
+    F15,
+    F16,
+    F17,
+    F18,
+    F19,
+    F20,
+    F21,
+    F22,
+    F23,
+    F24,
+    KeypadCenter,
+}
+
+impl Key {
+    pub fn function(n: u8) -> Option<Key> {
+        Some(match n {
+            1 => Key::F1,
+            2 => Key::F2,
+            3 => Key::F3,
+            4 => Key::F4,
+            5 => Key::F5,
+            6 => Key::F6,
+            7 => Key::F7,
+            8 => Key::F8,
+            9 => Key::F9,
+            10 => Key::F10,
+            11 => Key::F11,
+            12 => Key::F12,
+            13 => Key::F13,
+            14 => Key::F14,
+            15 => Key::F15,
+            16 => Key::F16,
+            17 => Key::F17,
+            18 => Key::F18,
+            19 => Key::F19,
+            20 => Key::F20,
+            21 => Key::F21,
+            22 => Key::F22,
+            23 => Key::F23,
+            24 => Key::F24,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Eq, PartialEq, Debug, Hash, Clone, Copy, Ord, PartialOrd)]
+pub enum MouseButton {
+    Left,
+    Right,
+    Middle,
+}
+
+pub const DOUBLE_CLICK_MS: u32 = 400;
+
+/// Recognizes double clicks in a stream of button presses.
+///
+/// Backends feed every press with a monotonic (possibly wrapping) millisecond timestamp.
+#[derive(Debug, Clone, Default)]
+pub struct DoubleClickDetector {
+    last_press: Option<(Point, MouseButton, u32)>,
+}
+
+impl DoubleClickDetector {
+    pub const fn new() -> Self {
+        DoubleClickDetector { last_press: None }
+    }
+
+    pub fn press(&mut self, point: Point, button: MouseButton, time_ms: u32) -> bool {
+        let double = self.last_press.is_some_and(|(last_point, last_button, last_time_ms)|
+            last_point == point && last_button == button && time_ms.wrapping_sub(last_time_ms) <= DOUBLE_CLICK_MS
+        );
+        self.last_press = if double { None } else { Some((point, button, time_ms)) };
+        double
+    }
 }
 
-#[derive(Eq, PartialEq, Debug, Hash, Clone, Copy)]
+#[derive(Eq, PartialEq, Debug, Hash, Clone)]
 #[non_exhaustive]
 pub enum Event {
     Resize,
-    Key(NonZeroU16, Key),
-    LmbDown(Point),
-    LmbUp(Point),
+    Key(NonZeroU16, Key, Mods),
+    MouseDown(Point, MouseButton, Mods),
+    MouseUp(Point, MouseButton, Mods),
+    /// Second press of a double click, reported instead of `MouseDown`.
+    DoubleClick(Point, MouseButton, Mods),
+    /// Pointer motion, with the button held down if it is a drag.
+    MouseMove(Point, Option<MouseButton>, Mods),
+    /// Wheel rotation, negative is up (away from the user).
+    Wheel(Point, i16, Mods),
+    /// Text pasted into the terminal, with `\n` line breaks. It should be inserted as is, not treated as typed keys.
+    Paste(String),
 }
 
 pub enum Error {
@@ -206,8 +588,9 @@ pub trait Screen {
     fn out(
         &mut self,
         p: Point,
-        fg: Fg,
-        bg: Bg,
+        fg: ExtFg,
+        bg: ExtBg,
+        attr: Attr,
         text: &str,
         hard: Range<i16>,
         soft: Range<i16>,
@@ -218,4 +601,84 @@ pub trait Screen {
     fn line_invalidated_range(&self, line: i16) -> &Range<i16>;
 
     fn line_invalidated_range_mut(&mut self, line: i16) -> &mut Range<i16>;
+
+    /// Returns `false` if the backend can display only a narrow (non-Unicode) character set.
+    fn is_unicode(&self) -> bool { true }
+
+    /// Returns the backend clipboard, if the backend has one.
+    fn clipboard(&mut self) -> Option<&mut dyn Clipboard> { None }
+}
+
+pub trait Clipboard {
+    fn text(&self) -> &str;
+
+    fn set_text(&mut self, text: &str);
+}
+
+/// In-process clipboard, not shared with other applications.
+#[derive(Debug, Clone, Default)]
+pub struct LocalClipboard {
+    text: String,
+}
+
+impl LocalClipboard {
+    pub const fn new() -> Self {
+        LocalClipboard { text: String::new() }
+    }
+}
+
+impl Clipboard for LocalClipboard {
+    fn text(&self) -> &str { &self.text }
+
+    fn set_text(&mut self, text: &str) {
+        self.text.clear();
+        self.text.push_str(text);
+    }
+}
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Terminal clipboard, which mirrors copied text to the system clipboard
+/// with the OSC 52 escape sequence.
+///
+/// Terminals rarely allow reading the system clipboard back, so `text` returns the last copied text,
+/// and text copied in other applications arrives as a regular (bracketed) paste.
+#[derive(Debug, Clone, Default)]
+pub struct Osc52Clipboard {
+    text: String,
+    pending: bool,
+}
+
+impl Osc52Clipboard {
+    pub const fn new() -> Self {
+        Osc52Clipboard { text: String::new(), pending: false }
+    }
+
+    /// Returns the OSC 52 escape sequence the backend should write to the terminal,
+    /// if the clipboard text has changed since the last call.
+    pub fn take_sequence(&mut self) -> Option<String> {
+        if !self.pending { return None; }
+        self.pending = false;
+        let mut sequence = String::with_capacity(8 + self.text.len().div_ceil(3) * 4);
+        sequence.push_str("\x1B]52;c;");
+        for chunk in self.text.as_bytes().chunks(3) {
+            let b = [chunk[0], chunk.get(1).copied().unwrap_or(0), chunk.get(2).copied().unwrap_or(0)];
+            let n = (u32::from(b[0]) << 16) | (u32::from(b[1]) << 8) | u32::from(b[2]);
+            for i in 0 .. 4 {
+                sequence.push(if i <= chunk.len() { char::from(BASE64[((n >> (18 - 6 * i)) & 0x3F) as usize]) } else { '=' });
+            }
+        }
+        sequence.push('\x07');
+        Some(sequence)
+    }
+}
+
+impl Clipboard for Osc52Clipboard {
+    fn text(&self) -> &str { &self.text }
+
+    fn set_text(&mut self, text: &str) {
+        self.text.clear();
+        self.text.push_str(text);
+        self.pending = true;
+    }
 }
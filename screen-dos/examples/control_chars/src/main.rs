@@ -37,7 +37,7 @@ mod no_std {
 }
 
 use exit_no_std::exit;
-use tuifw_screen_base::{Bg, Fg, Screen, Point, Event, Key};
+use tuifw_screen_base::{Attr, Bg, Fg, Screen, Point, Event, Key};
 
 const CONTROL_CHARS: &str = "\
     \x00\x01\x02\x03\x04\x05\x06\x07\x08\x09\x0A\x0B\x0C\x0D\x0E\x0F\
@@ -54,9 +54,9 @@ const WIDE_CHARS: &str = "好 女 子";
 #[allow(clippy::redundant_clone)]
 fn draw(screen: &mut dyn Screen) {
     let w = 0 .. screen.size().x;
-    screen.out(Point { x: 0, y: 0 }, Fg::LightGray, Bg::Blue, CONTROL_CHARS, w.clone(), w.clone());
-    screen.out(Point { x: 0, y: 1 }, Fg::LightGray, Bg::Blue, DOS_CHARS, w.clone(), w.clone());
-    screen.out(Point { x: 0, y: 3 }, Fg::LightGray, Bg::Blue, WIDE_CHARS, w.clone(), w.clone());
+    screen.out(Point { x: 0, y: 0 }, Fg::LightGray.into(), Bg::Blue.into(), Attr::NONE, CONTROL_CHARS, w.clone(), w.clone());
+    screen.out(Point { x: 0, y: 1 }, Fg::LightGray.into(), Bg::Blue.into(), Attr::NONE, DOS_CHARS, w.clone(), w.clone());
+    screen.out(Point { x: 0, y: 3 }, Fg::LightGray.into(), Bg::Blue.into(), Attr::NONE, WIDE_CHARS, w.clone(), w.clone());
 }
 
 #[allow(non_snake_case)]
@@ -67,12 +67,12 @@ extern "C" fn mainCRTStartup() -> ! {
     draw(screen);
     loop {
         if let Some(e) = screen.update(None, true).unwrap() {
-            if matches!(e, Event::Key(_, Key::Escape)) { break; }
+            if matches!(e, Event::Key(_, Key::Escape, _)) { break; }
             if matches!(e, Event::Resize) {
                 let w = 0 .. screen.size().x;
                 for x in 0 .. screen.size().x {
                     for y in 0 .. screen.size().y {
-                        screen.out(Point { x, y }, Fg::LightGray, Bg::None, " ", w.clone(), w.clone());
+                        screen.out(Point { x, y }, Fg::LightGray.into(), Bg::None.into(), Attr::NONE, " ", w.clone(), w.clone());
                     }
                 }
                 draw(screen);
@@ -12,6 +12,8 @@
 
 extern crate alloc;
 
+mod mouse;
+
 use alloc::boxed::Box;
 use core::alloc::Allocator;
 use core::cmp::{min, max};
@@ -28,6 +30,7 @@ use pc_ints::*;
 use tuifw_screen_base::*;
 use tuifw_screen_base::Screen as base_Screen;
 use unicode_width::UnicodeWidthChar;
+use mouse::*;
 
 const GLOBAL: composable_allocators::Global = composable_allocators::Global;
 
@@ -35,6 +38,13 @@ pub struct Screen {
     error_alloc: &'static dyn Allocator,
     original_mode: u8,
     code_page: &'static CodePage,
+    mouse: Option<Mouse>,
+}
+
+struct Mouse {
+    buttons: u8,
+    position: Point,
+    double_click: DoubleClickDetector,
 }
 
 impl Screen {
@@ -60,10 +70,18 @@ impl Screen {
         let third_page_ptr = video_ptr.add(80 * 25 * 2) as *mut MaybeUninit<Range<i16>>;
         let data = slice::from_raw_parts_mut(third_page_ptr, 25);
         data.fill_with(|| MaybeUninit::new(0 .. 80));
+        let mouse = if int_33h_ax_0000h_reset() {
+            int_33h_ax_0011h_wheel_api();
+            int_33h_ax_0001h_show_cursor();
+            Some(Mouse { buttons: 0, position: Point { x: 0, y: 0 }, double_click: DoubleClickDetector::new() })
+        } else {
+            None
+        };
         Ok(Screen {
             error_alloc,
             code_page,
             original_mode,
+            mouse,
         })
     }
 }
@@ -71,6 +89,9 @@ impl Screen {
 impl Drop for Screen {
     #[allow(clippy::panicking_unwrap)]
     fn drop(&mut self) {
+        if self.mouse.is_some() {
+            int_33h_ax_0002h_hide_cursor();
+        }
         int_10h_ah_02h_set_cursor_position(0, 25, 0);
         if self.original_mode != 0x03 {
             let e = int_10h_ah_00h_set_video_mode(self.original_mode)
@@ -80,7 +101,7 @@ impl Drop for Screen {
     }
 }
 
-fn attr(fg: Fg, bg: Bg) -> u8 {
+fn vga_attr(fg: Fg, bg: Bg, attr: Attr) -> u8 {
     let fg = match fg {
         Fg::Black => 0x00,
         Fg::Blue => 0x01,
@@ -109,7 +130,12 @@ fn attr(fg: Fg, bg: Bg) -> u8 {
         Bg::Brown => 0x60,
         Bg::LightGray => 0x70,
     };
-    fg | bg
+    let fg = if attr.contains(Attr::BOLD) { fg | 0x08 } else { fg };
+    if attr.contains(Attr::REVERSE) {
+        ((fg & 0x07) << 4) | (bg >> 4)
+    } else {
+        fg | bg
+    }
 }
 
 fn use_dos_graph_chars(c: char) -> char {
@@ -152,11 +178,14 @@ fn use_dos_graph_chars(c: char) -> char {
 impl base_Screen for Screen {
     fn size(&self) -> Vector { Vector { x: 80, y: 25 } }
 
+    fn is_unicode(&self) -> bool { false }
+
     fn out(
         &mut self,
         p: Point,
-        fg: Fg,
-        bg: Bg,
+        fg: ExtFg,
+        bg: ExtBg,
+        attr: Attr,
         text: &str,
         hard: Range<i16>,
         soft: Range<i16>
@@ -168,7 +197,7 @@ impl base_Screen for Screen {
         let text_start = if soft.start <= p.x { 0 } else { soft.start.saturating_sub(p.x) };
         let line = p.y as u16 as usize;
         let line = ((0xB800usize << 4) + 80 * 25 * 2 + line * 80 * 2) as *mut u16;
-        let attr = (attr(fg, bg) as u16) << 8;
+        let attr = (vga_attr(fg.to_cga(), bg.to_cga(), attr) as u16) << 8;
         let text = text.chars()
             .filter(|&x| x != '\0' && x.width().is_some())
             .map(use_dos_graph_chars)
@@ -206,12 +235,18 @@ impl base_Screen for Screen {
 
     fn update(&mut self, cursor: Option<Point>, wait: bool) -> Result<Option<Event>, Error> {
         let video_ptr = (0xB800usize << 4) as *mut u16;
+        if self.mouse.is_some() {
+            int_33h_ax_0002h_hide_cursor();
+        }
         for i in 0 .. 80 * 25 {
             unsafe {
                 let c: u16 = ptr::read_volatile(video_ptr.add(80 * 25 + i));
                 ptr::write_volatile(video_ptr.add(i), c);
             }
         }
+        if self.mouse.is_some() {
+            int_33h_ax_0001h_show_cursor();
+        }
         int_10h_ah_02h_set_cursor_position(
             0,
             cursor.map_or(25, |c| if c.y < 0 || c.y > 25 { 25 } else { c.y as i8 as u8 }),
@@ -219,7 +254,9 @@ impl base_Screen for Screen {
         );
         loop {
             if let Some(c) = self.code_page.inkey().map_err(|_| Error::System(Box::new_in("read key error", self.error_alloc)))? {
-                break Ok(dos_key(c).map(|c| Event::Key(NonZeroU16::new(1).unwrap(), c)));
+                break Ok(dos_key(c).map(|(c, mods)| Event::Key(NonZeroU16::new(1).unwrap(), c, mods)));
+            } else if let Some(e) = self.mouse_event() {
+                break Ok(Some(e));
             } else {
                 if !wait {
                     break Ok(None);
@@ -281,44 +318,123 @@ fn dos_ctrl(c: char) -> Option<Ctrl> {
     }
 }
 
-fn dos_key(c: Either<u8, char>) -> Option<Key> {
+impl Screen {
+    fn mouse_event(&mut self) -> Option<Event> {
+        const BUTTONS: [(u8, MouseButton); 3] = [
+            (0x01, MouseButton::Left),
+            (0x02, MouseButton::Right),
+            (0x04, MouseButton::Middle),
+        ];
+        let mouse = self.mouse.as_mut()?;
+        let state = int_33h_ax_0003h_state();
+        let point = Point { x: (state.cx_x / 8) as i16, y: (state.dx_y / 8) as i16 };
+        let mods = shift_state();
+        if state.bh_wheel != 0 {
+            return Some(Event::Wheel(point, state.bh_wheel.into(), mods));
+        }
+        for (mask, button) in BUTTONS {
+            let pressed = state.bl_buttons & mask != 0;
+            if pressed == (mouse.buttons & mask != 0) { continue; }
+            mouse.buttons ^= mask;
+            mouse.position = point;
+            return Some(if !pressed {
+                Event::MouseUp(point, button, mods)
+            } else if mouse.double_click.press(point, button, bios_ticks().wrapping_mul(55)) {
+                Event::DoubleClick(point, button, mods)
+            } else {
+                Event::MouseDown(point, button, mods)
+            });
+        }
+        if point != mouse.position {
+            mouse.position = point;
+            let button = BUTTONS.iter().find(|&&(mask, _)| mouse.buttons & mask != 0).map(|&(_, button)| button);
+            return Some(Event::MouseMove(point, button, mods));
+        }
+        None
+    }
+}
+
+fn bios_ticks() -> u32 {
+    unsafe { ptr::read_volatile(0x46Cusize as *const u32) }
+}
+
+fn shift_state() -> Mods {
+    let flags = unsafe { ptr::read_volatile(0x417usize as *const u8) };
+    let mut mods = Mods::NONE;
+    if flags & 0x03 != 0 { mods |= Mods::SHIFT; }
+    if flags & 0x04 != 0 { mods |= Mods::CTRL; }
+    if flags & 0x08 != 0 { mods |= Mods::ALT; }
+    mods
+}
+
+fn dos_key(c: Either<u8, char>) -> Option<(Key, Mods)> {
     Some(match c {
         Right(c) => {
             if let Some(ctrl) = dos_ctrl(c) {
-                Key::Ctrl(ctrl)
+                (Key::Ctrl(ctrl), Mods::NONE)
             } else {
                 match c {
-                    '\r' => Key::Enter,
-                    '\t' => Key::Tab,
-                    '\x1b' => Key::Escape,
-                    '\x08' => Key::Backspace,
-                    '\x7f' => Key::Backspace,
-                    c => Key::Char(c)
+                    '\r' => (Key::Enter, Mods::NONE),
+                    '\t' => (Key::Tab, Mods::NONE),
+                    '\x1b' => (Key::Escape, Mods::NONE),
+                    '\x08' => (Key::Backspace, Mods::NONE),
+                    '\x7f' => (Key::Backspace, Mods::NONE),
+                    c => (Key::Char(c), Mods::NONE)
                 }
             }
         },
-        Left(80) => Key::Down,
-        Left(72) => Key::Up,
-        Left(75) => Key::Left,
-        Left(77) => Key::Right,
-        Left(71) => Key::Home,
-        Left(79) => Key::End,
-        Left(83) => Key::Delete,
-        Left(82) => Key::Insert,
-        Left(81) => Key::PageDown,
-        Left(73) => Key::PageUp,
-        Left(59) => Key::F1,
-        Left(60) => Key::F2,
-        Left(61) => Key::F3,
-        Left(62) => Key::F4,
-        Left(63) => Key::F5,
-        Left(64) => Key::F6,
-        Left(65) => Key::F7,
-        Left(66) => Key::F8,
-        Left(67) => Key::F9,
-        Left(68) => Key::F10,
-        Left(133) => Key::F11,
-        Left(134) => Key::F12,
-        _ => return None,
+        Left(15) => (Key::Tab, Mods::SHIFT),
+        Left(80) => (Key::Down, shift_state()),
+        Left(72) => (Key::Up, shift_state()),
+        Left(75) => (Key::Left, shift_state()),
+        Left(77) => (Key::Right, shift_state()),
+        Left(71) => (Key::Home, shift_state()),
+        Left(79) => (Key::End, shift_state()),
+        Left(83) => (Key::Delete, shift_state()),
+        Left(82) => (Key::Insert, shift_state()),
+        Left(81) => (Key::PageDown, shift_state()),
+        Left(73) => (Key::PageUp, shift_state()),
+        Left(76) => (Key::KeypadCenter, Mods::NONE),
+        Left(115) => (Key::Left, Mods::CTRL),
+        Left(116) => (Key::Right, Mods::CTRL),
+        Left(117) => (Key::End, Mods::CTRL),
+        Left(118) => (Key::PageDown, Mods::CTRL),
+        Left(119) => (Key::Home, Mods::CTRL),
+        Left(132) => (Key::PageUp, Mods::CTRL),
+        Left(141) => (Key::Up, Mods::CTRL),
+        Left(143) => (Key::KeypadCenter, Mods::CTRL),
+        Left(145) => (Key::Down, Mods::CTRL),
+        Left(146) => (Key::Insert, Mods::CTRL),
+        Left(147) => (Key::Delete, Mods::CTRL),
+        Left(148) => (Key::Tab, Mods::CTRL),
+        Left(151) => (Key::Home, Mods::ALT),
+        Left(152) => (Key::Up, Mods::ALT),
+        Left(153) => (Key::PageUp, Mods::ALT),
+        Left(155) => (Key::Left, Mods::ALT),
+        Left(157) => (Key::Right, Mods::ALT),
+        Left(159) => (Key::End, Mods::ALT),
+        Left(160) => (Key::Down, Mods::ALT),
+        Left(161) => (Key::PageDown, Mods::ALT),
+        Left(162) => (Key::Insert, Mods::ALT),
+        Left(163) => (Key::Delete, Mods::ALT),
+        Left(165) => (Key::Tab, Mods::ALT),
+        Left(n @ 59 ..= 68) => (Key::function(n - 58)?, Mods::NONE),
+        Left(n @ 84 ..= 93) => (Key::function(n - 83)?, Mods::SHIFT),
+        Left(n @ 94 ..= 103) => (Key::function(n - 93)?, Mods::CTRL),
+        Left(n @ 104 ..= 113) => (Key::function(n - 103)?, Mods::ALT),
+        Left(133) => (Key::F11, Mods::NONE),
+        Left(134) => (Key::F12, Mods::NONE),
+        Left(135) => (Key::F11, Mods::SHIFT),
+        Left(136) => (Key::F12, Mods::SHIFT),
+        Left(137) => (Key::F11, Mods::CTRL),
+        Left(138) => (Key::F12, Mods::CTRL),
+        Left(139) => (Key::F11, Mods::ALT),
+        Left(140) => (Key::F12, Mods::ALT),
+        Left(n) => (Key::Alt(alt_char(n)?), Mods::NONE),
     })
 }
+
+fn alt_char(scan_code: u8) -> Option<char> {
+    const ROWS: [(u8, &[u8]); 4] = [(16, b"qwertyuiop"), (30, b"asdfghjkl"), (44, b"zxcvbnm"), (120, b"1234567890")];
+    ROWS.iter().find_map(|&(start, row)| row.get(usize::from(scan_code.checked_sub(start)?))).map(|&c| char::from(c))
+}
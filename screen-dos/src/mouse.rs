@@ -0,0 +1,111 @@
+#[cfg(target_os="dos")]
+use core::arch::asm;
+
+pub struct MouseState {
+    pub bl_buttons: u8,
+    pub bh_wheel: i8,
+    pub cx_x: u16,
+    pub dx_y: u16,
+}
+
+#[cfg(not(target_os="dos"))]
+pub fn int_33h_ax_0000h_reset() -> bool {
+    panic!("cfg(target_os=\"dos\")");
+}
+
+#[cfg(target_os="dos")]
+#[inline]
+pub fn int_33h_ax_0000h_reset() -> bool {
+    let mut ax: u16;
+    unsafe {
+        asm!(
+            "int 0x33",
+            in("ax") 0x0000u16,
+            lateout("ax") ax,
+            lateout("bx") _,
+        );
+    }
+    ax == 0xFFFF
+}
+
+#[cfg(not(target_os="dos"))]
+pub fn int_33h_ax_0001h_show_cursor() {
+    panic!("cfg(target_os=\"dos\")");
+}
+
+#[cfg(target_os="dos")]
+#[inline]
+pub fn int_33h_ax_0001h_show_cursor() {
+    unsafe {
+        asm!(
+            "int 0x33",
+            in("ax") 0x0001u16,
+        );
+    }
+}
+
+#[cfg(not(target_os="dos"))]
+pub fn int_33h_ax_0002h_hide_cursor() {
+    panic!("cfg(target_os=\"dos\")");
+}
+
+#[cfg(target_os="dos")]
+#[inline]
+pub fn int_33h_ax_0002h_hide_cursor() {
+    unsafe {
+        asm!(
+            "int 0x33",
+            in("ax") 0x0002u16,
+        );
+    }
+}
+
+#[cfg(not(target_os="dos"))]
+pub fn int_33h_ax_0003h_state() -> MouseState {
+    panic!("cfg(target_os=\"dos\")");
+}
+
+#[cfg(target_os="dos")]
+#[inline]
+pub fn int_33h_ax_0003h_state() -> MouseState {
+    let mut bx: u16;
+    let mut cx_x: u16;
+    let mut dx_y: u16;
+    unsafe {
+        asm!(
+            "int 0x33",
+            in("ax") 0x0003u16,
+            lateout("bx") bx,
+            lateout("cx") cx_x,
+            lateout("dx") dx_y,
+        );
+    }
+    MouseState {
+        bl_buttons: bx as u8,
+        bh_wheel: (bx >> 8) as u8 as i8,
+        cx_x,
+        dx_y,
+    }
+}
+
+#[cfg(not(target_os="dos"))]
+pub fn int_33h_ax_0011h_wheel_api() -> bool {
+    panic!("cfg(target_os=\"dos\")");
+}
+
+// CuteMouse wheel API: after a successful check, function 0003h reports wheel movement in BH.
+#[cfg(target_os="dos")]
+#[inline]
+pub fn int_33h_ax_0011h_wheel_api() -> bool {
+    let mut ax: u16;
+    unsafe {
+        asm!(
+            "int 0x33",
+            in("ax") 0x0011u16,
+            lateout("ax") ax,
+            lateout("bx") _,
+            lateout("cx") _,
+        );
+    }
+    ax == 0x574D
+}
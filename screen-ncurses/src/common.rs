@@ -1,6 +1,8 @@
 use crate::ncurses::*;
 use alloc::boxed::Box;
 use core::alloc::Allocator;
+use core::ffi::CStr;
+use core::cmp::min;
 use core::ptr::{NonNull, null_mut};
 use core::num::NonZeroU16;
 use either::{Either, Left, Right};
@@ -59,51 +61,266 @@ fn fg_attr(c: Fg) -> chtype {
     }
 }
 
-pub unsafe fn init_settings(error_alloc: &'static dyn Allocator) -> Result<(), Error> {
+fn attr_attr(attr: Attr) -> chtype {
+    let mut res = A_NORMAL;
+    if attr.contains(Attr::BOLD) { res |= A_BOLD; }
+    if attr.contains(Attr::UNDERLINE) { res |= A_UNDERLINE; }
+    if attr.contains(Attr::ITALIC) { res |= A_ITALIC; }
+    if attr.contains(Attr::REVERSE) { res |= A_REVERSE; }
+    res
+}
+
+pub unsafe fn init_settings(error_alloc: &'static dyn Allocator) -> Result<ColorPairs, Error> {
     set_err(non_err(cbreak()), "cbreak", error_alloc)?;
     set_err(non_err(noecho()), "noecho", error_alloc)?;
     set_err(non_err(nonl()), "nonl", error_alloc)?;
-    register_colors(error_alloc)?;
+    let colors = register_colors(error_alloc)?;
     set_escdelay(0);
     set_err(non_err(keypad(stdscr, true)), "keypad", error_alloc)?;
-    mousemask(BUTTON1_PRESSED | BUTTON1_RELEASED, null_mut());
-    Ok(())
+    let mask = mousemask(
+        BUTTON1_PRESSED | BUTTON1_RELEASED | BUTTON2_PRESSED | BUTTON2_RELEASED |
+        BUTTON3_PRESSED | BUTTON3_RELEASED | BUTTON4_PRESSED | BUTTON5_PRESSED |
+        REPORT_MOUSE_POSITION,
+        null_mut()
+    );
+    mouseinterval(0);
+    if mask & REPORT_MOUSE_POSITION != 0 {
+        putp(ANY_MOTION_TRACKING_ON.as_ptr());
+    }
+    Ok(colors)
+}
+
+const ANY_MOTION_TRACKING_ON: &CStr = c"\x1B[?1003h";
+
+const ANY_MOTION_TRACKING_OFF: &CStr = c"\x1B[?1003l";
+
+pub unsafe fn done_settings() {
+    putp(ANY_MOTION_TRACKING_OFF.as_ptr());
 }
 
-unsafe fn register_colors(error_alloc: &'static dyn Allocator) -> Result<(), Error> {
+unsafe fn register_colors(error_alloc: &'static dyn Allocator) -> Result<ColorPairs, Error> {
     set_err(non_err(start_color()), "start_color", error_alloc)?;
     set_err(non_err(use_default_colors()), "use_default_colors", error_alloc)?;
-    for fg in Fg::iter_variants().map(fg_index) {
-        for bg in Bg::iter_variants().map(bg_index) {
-            set_err(non_err(init_pair(1 + (bg + 1) * 8 + fg, fg, bg)), "init_pair", error_alloc)?;
+    let colors = ColorPairs::new();
+    for fg in Fg::iter_variants() {
+        for bg in Bg::iter_variants() {
+            let (pair, fg, bg) = (colors.cga_pair(fg, bg), colors.cga_fg_index(fg), bg_index(bg));
+            set_err(non_err(init_pair(pair, fg, bg)), "init_pair", error_alloc)?;
         }
     }
-    Ok(())
+    Ok(colors)
 }
 
-pub unsafe fn attr_ch(fg: Fg, bg: Bg) -> chtype {
-    let color = COLOR_PAIR((1 + (bg_index(bg) + 1) * 8 + fg_index(fg)) as _);
-    fg_attr(fg) | color as chtype
+// Color pair numbers are stored in 8 bits of chtype/attr_t.
+const MAX_PAIRS: i16 = 256;
+
+const MIN_CGA_PAIRS: i16 = 1 + 9 * 8;
+
+const BRIGHT_CGA_PAIRS: i16 = 1 + 9 * 16;
+
+pub struct ColorPairs {
+    bright: bool,
+    extended: bool,
+    cga_pairs: i16,
+    max_pairs: i16,
+    pairs: [(i16, i16); (MAX_PAIRS - MIN_CGA_PAIRS) as usize],
+    len: usize,
 }
 
-const KEY_F1: c_int = KEY_F(1);
-const KEY_F2: c_int = KEY_F(2);
-const KEY_F3: c_int = KEY_F(3);
-const KEY_F4: c_int = KEY_F(4);
-const KEY_F5: c_int = KEY_F(5);
-const KEY_F6: c_int = KEY_F(6);
-const KEY_F7: c_int = KEY_F(7);
-const KEY_F8: c_int = KEY_F(8);
-const KEY_F9: c_int = KEY_F(9);
-const KEY_F10: c_int = KEY_F(10);
-const KEY_F11: c_int = KEY_F(11);
-const KEY_F12: c_int = KEY_F(12);
+impl ColorPairs {
+    pub unsafe fn new() -> Self {
+        let bright = COLORS >= 16 && COLOR_PAIRS >= BRIGHT_CGA_PAIRS.into();
+        ColorPairs {
+            bright,
+            extended: COLORS >= 256,
+            cga_pairs: if bright { BRIGHT_CGA_PAIRS } else { MIN_CGA_PAIRS },
+            max_pairs: min(COLOR_PAIRS, MAX_PAIRS.into()) as i16,
+            pairs: [(0, 0); (MAX_PAIRS - MIN_CGA_PAIRS) as usize],
+            len: 0,
+        }
+    }
+
+    fn cga_fg_index(&self, fg: Fg) -> i16 {
+        if self.bright { fg as i16 } else { fg_index(fg) }
+    }
+
+    fn cga_pair(&self, fg: Fg, bg: Bg) -> i16 {
+        let fg_count = if self.bright { 16 } else { 8 };
+        1 + (bg_index(bg) + 1) * fg_count + self.cga_fg_index(fg)
+    }
+
+    unsafe fn cga_attr_ch(&self, fg: Fg, bg: Bg) -> chtype {
+        let color = COLOR_PAIR(self.cga_pair(fg, bg).into());
+        let bold = if self.bright { A_NORMAL } else { fg_attr(fg) };
+        bold | color as chtype
+    }
+
+    pub unsafe fn attr_ch(&mut self, fg: ExtFg, bg: ExtBg, attr: Attr) -> chtype {
+        attr_attr(attr) | self.color_attr_ch(fg, bg)
+    }
+
+    unsafe fn color_attr_ch(&mut self, fg: ExtFg, bg: ExtBg) -> chtype {
+        if let (ExtFg::Cga(fg), ExtBg::Cga(bg)) = (fg, bg) {
+            return self.cga_attr_ch(fg, bg);
+        }
+        if self.extended {
+            let pair = (i16::from(fg.to_indexed()), bg.to_indexed().map_or(-1, i16::from));
+            if let Some(i) = self.pairs[.. self.len].iter().position(|&x| x == pair) {
+                return COLOR_PAIR(i32::from(self.cga_pairs) + i as i32) as chtype;
+            }
+            let n = self.cga_pairs + self.len as i16;
+            if n < self.max_pairs && init_pair(n, pair.0, pair.1) != ERR {
+                self.pairs[self.len] = pair;
+                self.len += 1;
+                return COLOR_PAIR(n.into()) as chtype;
+            }
+        }
+        self.cga_attr_ch(fg.to_cga(), bg.to_cga())
+    }
+}
+
+fn extended_key(key: c_int) -> Option<(Key, Mods)> {
+    let name = unsafe { keyname(key) };
+    if name.is_null() { return None; }
+    let name = unsafe { CStr::from_ptr(name) }.to_bytes().strip_prefix(b"k")?;
+    let (base, n) = name.split_at(name.len().checked_sub(1)?);
+    let n = match n[0] {
+        n @ b'2' ..= b'8' => n - b'0',
+        _ => return None,
+    };
+    let key = match base {
+        b"UP" => Key::Up,
+        b"DN" => Key::Down,
+        b"LFT" => Key::Left,
+        b"RIT" => Key::Right,
+        b"HOM" => Key::Home,
+        b"END" => Key::End,
+        b"NXT" => Key::PageDown,
+        b"PRV" => Key::PageUp,
+        b"DC" => Key::Delete,
+        b"IC" => Key::Insert,
+        _ => return None,
+    };
+    Some((key, Mods::from_xterm_param(n)))
+}
+
+/// Returns the modifiers encoded in the xterm-style escape sequence bound to `key`, e.g. `ESC [ 1 ; 2 P`.
+fn bound_mods(key: c_int) -> Option<Mods> {
+    let seq = unsafe { keybound(key, 0) };
+    if seq.is_null() { return None; }
+    let mods = xterm_mods(unsafe { CStr::from_ptr(seq) }.to_bytes());
+    unsafe { free(seq as *mut c_void); }
+    mods
+}
+
+fn xterm_mods(seq: &[u8]) -> Option<Mods> {
+    let (_, body) = seq.split_last()?;
+    let digits = body.iter().rev().take_while(|x| x.is_ascii_digit()).count();
+    let (prefix, n) = body.split_at(body.len() - digits);
+    if !prefix.ends_with(b";") && prefix != b"\x1BO" { return None; }
+    let n = n.iter().try_fold(0u8, |n, &x| n.checked_mul(10)?.checked_add(x - b'0'))?;
+    Some(Mods::from_xterm_param(n)).filter(|x| !x.is_empty())
+}
+
+/// Terminals like xterm bind `kf13` and above to F1–F12 with modifiers, others have genuine F13–F24 keys,
+/// so the bound escape sequence decides.
+fn function_key(n: c_int) -> Option<(Key, Mods)> {
+    let n = u8::try_from(n).ok()?;
+    if n > 12 {
+        if let Some(mods) = bound_mods(KEY_F(n.into())) {
+            return Some((Key::function((n - 1) % 12 + 1)?, mods));
+        }
+    }
+    Some((Key::function(n)?, Mods::NONE))
+}
+
+fn ncurses_key(key: c_int) -> Option<(Key, Mods)> {
+    Some(match key {
+        KEY_DOWN => (Key::Down, Mods::NONE),
+        KEY_UP => (Key::Up, Mods::NONE),
+        KEY_LEFT => (Key::Left, Mods::NONE),
+        KEY_RIGHT => (Key::Right, Mods::NONE),
+        KEY_HOME | KEY_A1 => (Key::Home, Mods::NONE),
+        KEY_END | KEY_C1 => (Key::End, Mods::NONE),
+        KEY_BACKSPACE => (Key::Backspace, Mods::NONE),
+        KEY_DC => (Key::Delete, Mods::NONE),
+        KEY_IC => (Key::Insert, Mods::NONE),
+        KEY_NPAGE | KEY_C3 => (Key::PageDown, Mods::NONE),
+        KEY_PPAGE | KEY_A3 => (Key::PageUp, Mods::NONE),
+        KEY_B2 => (Key::KeypadCenter, Mods::NONE),
+        KEY_ENTER => (Key::Enter, Mods::NONE),
+        KEY_BTAB => (Key::Tab, Mods::SHIFT),
+        KEY_SF => (Key::Down, Mods::SHIFT),
+        KEY_SR => (Key::Up, Mods::SHIFT),
+        KEY_SLEFT => (Key::Left, Mods::SHIFT),
+        KEY_SRIGHT => (Key::Right, Mods::SHIFT),
+        KEY_SHOME => (Key::Home, Mods::SHIFT),
+        KEY_SEND => (Key::End, Mods::SHIFT),
+        KEY_SDC => (Key::Delete, Mods::SHIFT),
+        KEY_SIC => (Key::Insert, Mods::SHIFT),
+        KEY_SNEXT => (Key::PageDown, Mods::SHIFT),
+        KEY_SPREVIOUS => (Key::PageUp, Mods::SHIFT),
+        key if key > KEY_F0 && key <= KEY_F(63) => return function_key(key - KEY_F0),
+        key => return extended_key(key),
+    })
+}
 
 const ONCE: NonZeroU16 = unsafe { NonZeroU16::new_unchecked(1) };
 
+pub struct Mouse {
+    double_click: DoubleClickDetector,
+    pressed: Option<MouseButton>,
+}
+
+impl Mouse {
+    pub const fn new() -> Self {
+        Mouse { double_click: DoubleClickDetector::new(), pressed: None }
+    }
+
+    fn event(&mut self, e: &MEVENT) -> Option<Event> {
+        const BUTTONS: [(c_ulong, c_ulong, MouseButton); 3] = [
+            (BUTTON1_PRESSED, BUTTON1_RELEASED, MouseButton::Left),
+            (BUTTON3_PRESSED, BUTTON3_RELEASED, MouseButton::Right),
+            (BUTTON2_PRESSED, BUTTON2_RELEASED, MouseButton::Middle),
+        ];
+        let point = Point { x: e.x as i16, y: e.y as i16 };
+        let mut mods = Mods::NONE;
+        if e.bstate & BUTTON_SHIFT != 0 { mods |= Mods::SHIFT; }
+        if e.bstate & BUTTON_CTRL != 0 { mods |= Mods::CTRL; }
+        if e.bstate & BUTTON_ALT != 0 { mods |= Mods::ALT; }
+        if e.bstate & BUTTON4_PRESSED != 0 { return Some(Event::Wheel(point, -1, mods)); }
+        if e.bstate & BUTTON5_PRESSED != 0 { return Some(Event::Wheel(point, 1, mods)); }
+        for (pressed, released, button) in BUTTONS {
+            if e.bstate & pressed != 0 {
+                self.pressed = Some(button);
+                return Some(if self.double_click.press(point, button, monotonic_ms()) {
+                    Event::DoubleClick(point, button, mods)
+                } else {
+                    Event::MouseDown(point, button, mods)
+                });
+            }
+            if e.bstate & released != 0 {
+                self.pressed = None;
+                return Some(Event::MouseUp(point, button, mods));
+            }
+        }
+        if e.bstate & REPORT_MOUSE_POSITION != 0 {
+            return Some(Event::MouseMove(point, self.pressed, mods));
+        }
+        None
+    }
+}
+
+fn monotonic_ms() -> u32 {
+    let mut t = timespec { tv_sec: 0, tv_nsec: 0 };
+    unsafe { clock_gettime(CLOCK_MONOTONIC, &mut t as *mut _); }
+    (t.tv_sec as u32).wrapping_mul(1000).wrapping_add((t.tv_nsec / 1_000_000) as u32)
+}
+
 pub fn read_event(
     window: NonNull<WINDOW>,
     getch: impl Fn(NonNull<WINDOW>) -> Option<Either<c_int, char>>,
+    mouse: &mut Mouse,
     error_alloc: &'static dyn Allocator
 ) -> Result<Option<Event>, Error> {
     let e = if let Some(e) = getch(window) {
@@ -114,29 +331,6 @@ pub fn read_event(
     match e {
         Left(key) => Ok(match key {
             KEY_RESIZE => Some(Event::Resize),
-            KEY_DOWN => Some(Event::Key(ONCE, Key::Down)),
-            KEY_UP => Some(Event::Key(ONCE, Key::Up)),
-            KEY_LEFT => Some(Event::Key(ONCE, Key::Left)),
-            KEY_RIGHT => Some(Event::Key(ONCE, Key::Right)),
-            KEY_HOME => Some(Event::Key(ONCE, Key::Home)),
-            KEY_END => Some(Event::Key(ONCE, Key::End)),
-            KEY_BACKSPACE => Some(Event::Key(ONCE, Key::Backspace)),
-            KEY_DC => Some(Event::Key(ONCE, Key::Delete)),
-            KEY_IC => Some(Event::Key(ONCE, Key::Insert)),
-            KEY_NPAGE => Some(Event::Key(ONCE, Key::PageDown)),
-            KEY_PPAGE => Some(Event::Key(ONCE, Key::PageUp)),
-            KEY_F1 => Some(Event::Key(ONCE, Key::F1)),
-            KEY_F2 => Some(Event::Key(ONCE, Key::F2)),
-            KEY_F3 => Some(Event::Key(ONCE, Key::F3)),
-            KEY_F4 => Some(Event::Key(ONCE, Key::F4)),
-            KEY_F5 => Some(Event::Key(ONCE, Key::F5)),
-            KEY_F6 => Some(Event::Key(ONCE, Key::F6)),
-            KEY_F7 => Some(Event::Key(ONCE, Key::F7)),
-            KEY_F8 => Some(Event::Key(ONCE, Key::F8)),
-            KEY_F9 => Some(Event::Key(ONCE, Key::F9)),
-            KEY_F10 => Some(Event::Key(ONCE, Key::F10)),
-            KEY_F11 => Some(Event::Key(ONCE, Key::F11)),
-            KEY_F12 => Some(Event::Key(ONCE, Key::F12)),
             KEY_MOUSE => {
                 let mut e = MEVENT {
                     id: 0, x: 0, y: 0, z: 0, bstate: 0
@@ -144,57 +338,55 @@ pub fn read_event(
                 let m = unsafe { getmouse(&mut e as *mut _) };
                 if m == ERR {
                     None
-                } else if e.bstate & BUTTON1_PRESSED != 0 {
-                    Some(Event::LmbDown(Point { x: e.x as i16, y: e.y as i16 }))
                 } else {
-                    Some(Event::LmbUp(Point { x: e.x as i16, y: e.y as i16 }))
+                    mouse.event(&e)
                 }
             },
-            _ => None
+            key => ncurses_key(key).map(|(key, mods)| Event::Key(ONCE, key, mods)),
         }),
         Right(c) => Ok(match c {
             '\x1B' => {
                 set_err(unsafe { non_err(nodelay(window.as_ptr(), true)) }, "nodelay", error_alloc)?;
                 match getch(window) {
                     Some(Right(c)) if c < ' ' || c == '\x7F' => None,
-                    Some(Right(c)) => Some(Event::Key(ONCE, Key::Alt(c))),
-                    Some(Left(_)) => Some(Event::Key(ONCE, Key::Escape)),
-                    None => Some(Event::Key(ONCE, Key::Escape)),
+                    Some(Right(c)) => Some(Event::Key(ONCE, Key::Alt(c), Mods::NONE)),
+                    Some(Left(key)) => ncurses_key(key).map(|(key, mods)| Event::Key(ONCE, key, mods | Mods::ALT)),
+                    None => Some(Event::Key(ONCE, Key::Escape, Mods::NONE)),
                 }
             },
-            '\0' => Some(Event::Key(ONCE, Key::Ctrl(Ctrl::At))),
-            '\x01' => Some(Event::Key(ONCE, Key::Ctrl(Ctrl::A))),
-            '\x02' => Some(Event::Key(ONCE, Key::Ctrl(Ctrl::B))),
-            '\x03' => Some(Event::Key(ONCE, Key::Ctrl(Ctrl::C))),
-            '\x04' => Some(Event::Key(ONCE, Key::Ctrl(Ctrl::D))),
-            '\x05' => Some(Event::Key(ONCE, Key::Ctrl(Ctrl::E))),
-            '\x06' => Some(Event::Key(ONCE, Key::Ctrl(Ctrl::F))),
-            '\x07' => Some(Event::Key(ONCE, Key::Ctrl(Ctrl::G))),
-            '\x08' => Some(Event::Key(ONCE, Key::Backspace)),
-            '\t' => Some(Event::Key(ONCE, Key::Tab)),
-            '\x0A' => Some(Event::Key(ONCE, Key::Ctrl(Ctrl::J))),
-            '\x0B' => Some(Event::Key(ONCE, Key::Ctrl(Ctrl::K))),
-            '\x0C' => Some(Event::Key(ONCE, Key::Ctrl(Ctrl::L))),
-            '\r' => Some(Event::Key(ONCE, Key::Enter)),
-            '\x0E' => Some(Event::Key(ONCE, Key::Ctrl(Ctrl::N))),
-            '\x0F' => Some(Event::Key(ONCE, Key::Ctrl(Ctrl::O))),
-            '\x10' => Some(Event::Key(ONCE, Key::Ctrl(Ctrl::P))),
-            '\x11' => Some(Event::Key(ONCE, Key::Ctrl(Ctrl::Q))),
-            '\x12' => Some(Event::Key(ONCE, Key::Ctrl(Ctrl::R))),
-            '\x13' => Some(Event::Key(ONCE, Key::Ctrl(Ctrl::S))),
-            '\x14' => Some(Event::Key(ONCE, Key::Ctrl(Ctrl::T))),
-            '\x15' => Some(Event::Key(ONCE, Key::Ctrl(Ctrl::U))),
-            '\x16' => Some(Event::Key(ONCE, Key::Ctrl(Ctrl::V))),
-            '\x17' => Some(Event::Key(ONCE, Key::Ctrl(Ctrl::W))),
-            '\x18' => Some(Event::Key(ONCE, Key::Ctrl(Ctrl::X))),
-            '\x19' => Some(Event::Key(ONCE, Key::Ctrl(Ctrl::Y))),
-            '\x1A' => Some(Event::Key(ONCE, Key::Ctrl(Ctrl::Z))),
-            '\x1C' => Some(Event::Key(ONCE, Key::Ctrl(Ctrl::Backslash))),
-            '\x1D' => Some(Event::Key(ONCE, Key::Ctrl(Ctrl::Bracket))),
-            '\x1E' => Some(Event::Key(ONCE, Key::Ctrl(Ctrl::Caret))),
-            '\x1F' => Some(Event::Key(ONCE, Key::Ctrl(Ctrl::Underscore))),
-            '\x7F' => Some(Event::Key(ONCE, Key::Backspace)),
-            c => Some(Event::Key(ONCE, Key::Char(c)))
+            '\0' => Some(Event::Key(ONCE, Key::Ctrl(Ctrl::At), Mods::NONE)),
+            '\x01' => Some(Event::Key(ONCE, Key::Ctrl(Ctrl::A), Mods::NONE)),
+            '\x02' => Some(Event::Key(ONCE, Key::Ctrl(Ctrl::B), Mods::NONE)),
+            '\x03' => Some(Event::Key(ONCE, Key::Ctrl(Ctrl::C), Mods::NONE)),
+            '\x04' => Some(Event::Key(ONCE, Key::Ctrl(Ctrl::D), Mods::NONE)),
+            '\x05' => Some(Event::Key(ONCE, Key::Ctrl(Ctrl::E), Mods::NONE)),
+            '\x06' => Some(Event::Key(ONCE, Key::Ctrl(Ctrl::F), Mods::NONE)),
+            '\x07' => Some(Event::Key(ONCE, Key::Ctrl(Ctrl::G), Mods::NONE)),
+            '\x08' => Some(Event::Key(ONCE, Key::Backspace, Mods::NONE)),
+            '\t' => Some(Event::Key(ONCE, Key::Tab, Mods::NONE)),
+            '\x0A' => Some(Event::Key(ONCE, Key::Ctrl(Ctrl::J), Mods::NONE)),
+            '\x0B' => Some(Event::Key(ONCE, Key::Ctrl(Ctrl::K), Mods::NONE)),
+            '\x0C' => Some(Event::Key(ONCE, Key::Ctrl(Ctrl::L), Mods::NONE)),
+            '\r' => Some(Event::Key(ONCE, Key::Enter, Mods::NONE)),
+            '\x0E' => Some(Event::Key(ONCE, Key::Ctrl(Ctrl::N), Mods::NONE)),
+            '\x0F' => Some(Event::Key(ONCE, Key::Ctrl(Ctrl::O), Mods::NONE)),
+            '\x10' => Some(Event::Key(ONCE, Key::Ctrl(Ctrl::P), Mods::NONE)),
+            '\x11' => Some(Event::Key(ONCE, Key::Ctrl(Ctrl::Q), Mods::NONE)),
+            '\x12' => Some(Event::Key(ONCE, Key::Ctrl(Ctrl::R), Mods::NONE)),
+            '\x13' => Some(Event::Key(ONCE, Key::Ctrl(Ctrl::S), Mods::NONE)),
+            '\x14' => Some(Event::Key(ONCE, Key::Ctrl(Ctrl::T), Mods::NONE)),
+            '\x15' => Some(Event::Key(ONCE, Key::Ctrl(Ctrl::U), Mods::NONE)),
+            '\x16' => Some(Event::Key(ONCE, Key::Ctrl(Ctrl::V), Mods::NONE)),
+            '\x17' => Some(Event::Key(ONCE, Key::Ctrl(Ctrl::W), Mods::NONE)),
+            '\x18' => Some(Event::Key(ONCE, Key::Ctrl(Ctrl::X), Mods::NONE)),
+            '\x19' => Some(Event::Key(ONCE, Key::Ctrl(Ctrl::Y), Mods::NONE)),
+            '\x1A' => Some(Event::Key(ONCE, Key::Ctrl(Ctrl::Z), Mods::NONE)),
+            '\x1C' => Some(Event::Key(ONCE, Key::Ctrl(Ctrl::Backslash), Mods::NONE)),
+            '\x1D' => Some(Event::Key(ONCE, Key::Ctrl(Ctrl::Bracket), Mods::NONE)),
+            '\x1E' => Some(Event::Key(ONCE, Key::Ctrl(Ctrl::Caret), Mods::NONE)),
+            '\x1F' => Some(Event::Key(ONCE, Key::Ctrl(Ctrl::Underscore), Mods::NONE)),
+            '\x7F' => Some(Event::Key(ONCE, Key::Backspace, Mods::NONE)),
+            c => Some(Event::Key(ONCE, Key::Char(c), Mods::NONE))
         })
     }
 }
@@ -28,6 +28,8 @@ pub struct Screen<A: Allocator> {
     lines: Vec<Line, A>,
     cols: usize,
     chs: Vec<chtype, A>,
+    colors: ColorPairs,
+    mouse: Mouse,
     cd: iconv_t,
     dc: iconv_t,
 }
@@ -56,6 +58,8 @@ impl<A: Allocator> Screen<A> {
             lines: Vec::new_in(alloc.clone()),
             cols: usize::from(size.x as u16),
             chs: Vec::new_in(alloc),
+            colors: ColorPairs::new(),
+            mouse: Mouse::new(),
             cd: ICONV_ERR,
             dc: ICONV_ERR
         };
@@ -63,7 +67,7 @@ impl<A: Allocator> Screen<A> {
         if s.cd == ICONV_ERR { return Err(s.errno()); }
         s.dc = iconv_open(b"UTF-8\0".as_ptr() as _, nl_langinfo(CODESET));
         if s.dc == ICONV_ERR { return Err(s.errno()); }
-        init_settings(error_alloc)?;
+        s.colors = init_settings(error_alloc)?;
         s.resize()?;
         Ok(s)
     }
@@ -91,6 +95,7 @@ impl<A: Allocator> Screen<A> {
     }
 
     unsafe fn drop_raw(&mut self) -> Result<(), Error> {
+        done_settings();
         let e1 = set_err(non_err(endwin()).map(|_| ()), "endwin", self.error_alloc);
         let e2 = if self.cd != ICONV_ERR {
             if iconv_close(self.cd) == -1 {
@@ -155,10 +160,10 @@ impl<A: Allocator> Screen<A> {
             if c == ERR { return None; }
             if c & KEY_CODE_YES == 0 { return Some(Right(decode_char(self.dc, c as c_char as u8))); }
             Some(Left(c & !KEY_CODE_YES))
-        }, self.error_alloc)?;
+        }, &mut self.mouse, self.error_alloc)?;
         match e {
             Some(Event::Resize) => self.resize()?,
-            Some(Event::Key(_, Key::Ctrl(Ctrl::L))) => unsafe { clearok(curscr, true); },
+            Some(Event::Key(_, Key::Ctrl(Ctrl::L), _)) => unsafe { clearok(curscr, true); },
             _ => { }
         }
         Ok(e)
@@ -261,11 +266,14 @@ fn decode_char(dc: iconv_t, c: u8) -> char {
 impl<A: Allocator> base_Screen for Screen<A> {
     fn size(&self) -> Vector { size(self.max_size) }
 
+    fn is_unicode(&self) -> bool { false }
+
     fn out(
         &mut self,
         p: Point,
-        fg: Fg,
-        bg: Bg,
+        fg: ExtFg,
+        bg: ExtBg,
+        attr: Attr,
         text: &str,
         hard: Range<i16>,
         soft: Range<i16>
@@ -277,7 +285,7 @@ impl<A: Allocator> base_Screen for Screen<A> {
         let text_start = if soft.start <= p.x { 0 } else { soft.start.saturating_sub(p.x) };
         let chs = &mut self.chs[usize::from(p.y as u16) * self.cols .. (usize::from(p.y as u16) + 1) * self.cols];
         self.lines[p.y as u16 as usize].invalidated = true;
-        let attr = unsafe { attr_ch(fg, bg) };
+        let attr = unsafe { self.colors.attr_ch(fg, bg, attr) };
         let text = text.chars()
             .filter(|&x| x != '\0' && x.width().is_some())
             .flat_map(|c| encode_char(self.cd, c).map_or_else(
@@ -2,6 +2,7 @@
 
 use crate::common::*;
 use crate::ncurses::*;
+use alloc::ffi::CString;
 use alloc::vec::Vec;
 use core::alloc::Allocator;
 use core::char::{self};
@@ -29,6 +30,9 @@ pub struct Screen<A: Allocator> {
     lines: Vec<Line, A>,
     cols: usize,
     chs: Vec<([char; CCHARW_MAX], attr_t), A>,
+    colors: ColorPairs,
+    mouse: Mouse,
+    clipboard: Osc52Clipboard,
 }
 
 impl<A: Allocator> !Sync for Screen<A> { }
@@ -49,8 +53,11 @@ impl<A: Allocator> Screen<A> {
             lines: Vec::new_in(alloc.clone()),
             cols: usize::from(size.x as u16),
             chs: Vec::new_in(alloc),
+            colors: ColorPairs::new(),
+            mouse: Mouse::new(),
+            clipboard: Osc52Clipboard::new(),
         };
-        init_settings(error_alloc)?;
+        s.colors = init_settings(error_alloc)?;
         s.resize()?;
         Ok(s)
     }
@@ -122,6 +129,11 @@ impl<A: Allocator> Screen<A> {
             }
             set_err(non_err(unsafe { wnoutrefresh(line.window.as_ptr()) }), "wnoutrefresh", self.error_alloc)?;
         }
+        if let Some(sequence) = self.clipboard.take_sequence() {
+            if let Ok(sequence) = CString::new(sequence) {
+                unsafe { putp(sequence.as_ptr()); }
+            }
+        }
         set_err(non_err(unsafe { doupdate() }), "doupdate", self.error_alloc)?;
         let cursor = cursor.and_then(|cursor| {
             if (Rect { tl: Point { x: 0, y: 0 }, size: self.size() }).contains(cursor) {
@@ -154,10 +166,10 @@ impl<A: Allocator> Screen<A> {
             if key == ERR { return None; }
             if key != KEY_CODE_YES { return Some(Right(char::from_u32(c as wchar_t as u32).unwrap())); }
             Some(Left(c as _))
-        }, self.error_alloc)?;
+        }, &mut self.mouse, self.error_alloc)?;
         match e {
             Some(Event::Resize) => self.resize()?,
-            Some(Event::Key(_, Key::Ctrl(Ctrl::L))) => unsafe { clearok(curscr, true); },
+            Some(Event::Key(_, Key::Ctrl(Ctrl::L), _)) => unsafe { clearok(curscr, true); },
             _ => { }
         }
         Ok(e)
@@ -177,6 +189,7 @@ fn size(max_size: Option<(u16, u16)>) -> Vector {
 impl<A: Allocator> Drop for Screen<A> {
     #![allow(clippy::panicking_unwrap)]
     fn drop(&mut self) {
+        unsafe { done_settings(); }
         let e = unsafe { non_err(endwin()) };
         if e.is_err() && !panicking() { e.unwrap(); }
     }
@@ -188,8 +201,9 @@ impl<A: Allocator> base_Screen for Screen<A> {
     fn out(
         &mut self,
         p: Point,
-        fg: Fg,
-        bg: Bg,
+        fg: ExtFg,
+        bg: ExtBg,
+        attr: Attr,
         text: &str,
         hard: Range<i16>,
         soft: Range<i16>
@@ -201,7 +215,7 @@ impl<A: Allocator> base_Screen for Screen<A> {
         let text_start = if soft.start <= p.x { 0 } else { soft.start.saturating_sub(p.x) };
         let line = &mut self.chs[usize::from(p.y as u16) * self.cols .. (usize::from(p.y as u16) + 1) * self.cols];
         self.lines[p.y as u16 as usize].invalidated = true;
-        let attr = unsafe { attr_ch(fg, bg) };
+        let attr = unsafe { self.colors.attr_ch(fg, bg, attr) };
         let graphemes = text.chars().filter(|&c| c != '\0' && c.width().is_some()).peekable().batching(|text| {
             let (c, w) = loop {
                 let c = text.next()?;
@@ -283,4 +297,6 @@ impl<A: Allocator> base_Screen for Screen<A> {
     fn line_invalidated_range(&self, line: i16) -> &Range<i16> { &self.lines[usize::from(line as u16)].data }
 
     fn line_invalidated_range_mut(&mut self, line: i16) -> &mut Range<i16> { &mut self.lines[usize::from(line as u16)].data }
+
+    fn clipboard(&mut self) -> Option<&mut dyn Clipboard> { Some(&mut self.clipboard) }
 }
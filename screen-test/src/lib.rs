@@ -8,24 +8,46 @@
 #![no_std]
 
 extern crate alloc;
+#[cfg(feature="std")]
+extern crate std;
 
 use alloc::alloc::Global;
+use alloc::collections::VecDeque;
+use alloc::rc::Rc;
+use alloc::string::String;
 use alloc::vec::Vec;
 use core::alloc::Allocator;
+use core::cell::RefCell;
+use core::fmt::Write;
 use core::iter::repeat;
 use core::cmp::{min, max};
+use core::num::NonZeroU16;
 use core::ops::Range;
 use tuifw_screen_base::*;
 use tuifw_screen_base::Screen as base_Screen;
 use unicode_width::UnicodeWidthChar;
 
-pub struct Screen<A: Allocator + Clone = Global> {
-    buf: Vec<(char, Fg, Bg), A>,
-    out: Vec<(char, Fg, Bg), A>,
+pub type Cell = (char, ExtFg, ExtBg, Attr);
+
+const BLANK: Cell = (' ', ExtFg::Cga(Fg::LightGray), ExtBg::Cga(Bg::None), Attr::NONE);
+
+enum Input {
+    Event(Event),
+    Resize(Vector),
+}
+
+struct Shared<A: Allocator> {
+    out: Vec<Cell, A>,
     size: Vector,
-    invalidated: Rect,
     cursor: Option<Point>,
+    input: VecDeque<Input, A>,
+}
+
+pub struct Screen<A: Allocator + Clone = Global> {
+    buf: Vec<Cell, A>,
+    invalidated: Rect,
     data: Vec<Range<i16>, A>,
+    shared: Rc<RefCell<Shared<A>>, A>,
 }
 
 impl Screen {
@@ -37,38 +59,54 @@ impl Screen {
 impl<A: Allocator + Clone> Screen<A> {
     pub fn new_in(size: Vector, alloc: A) -> Self {
         assert!(size.x >= 0 && size.y >= 0);
+        let shared = Shared {
+            out: Vec::new_in(alloc.clone()),
+            size: Vector::null(),
+            cursor: None,
+            input: VecDeque::new_in(alloc.clone()),
+        };
         let mut s = Screen {
             data: Vec::new_in(alloc.clone()),
             buf: Vec::new_in(alloc.clone()),
-            out: Vec::new_in(alloc),
-            size: Vector::null(),
             invalidated: Rect { tl: Point { x: 0, y: 0 }, size: Vector::null() },
-            cursor: None,
+            shared: Rc::new_in(RefCell::new(shared), alloc),
         };
         s.resize(size);
         s
     }
 
-    pub fn cursor(&self) -> Option<Point> { self.cursor }
+    pub fn cursor(&self) -> Option<Point> { self.shared.borrow().cursor }
+
+    /// Returns a handle for feeding input and inspecting output
+    /// after the screen is passed to the `WindowTree`.
+    pub fn handle(&self) -> Handle<A> {
+        Handle(self.shared.clone())
+    }
 
     fn resize(&mut self, out_size: Vector) {
+        let area = out_size.rect_area() as usize;
         self.data.clear();
         self.data.resize(usize::from(out_size.y as u16), 0 .. out_size. x);
-        self.buf.resize(out_size.rect_area() as usize, (' ', Fg::LightGray, Bg::None));
-        self.out.resize(out_size.rect_area() as usize, (' ', Fg::LightGray, Bg::None));
-        self.size = out_size;
-        self.invalidated = Rect { tl: Point { x: 0, y: 0 }, size: self.size };
+        self.buf.clear();
+        self.buf.resize(area, BLANK);
+        let mut shared = self.shared.borrow_mut();
+        shared.out.clear();
+        shared.out.resize(area, BLANK);
+        shared.size = out_size;
+        shared.cursor = None;
+        self.invalidated = Rect { tl: Point { x: 0, y: 0 }, size: out_size };
     }
 }
 
 impl<A: Allocator + Clone> base_Screen for Screen<A> {
-    fn size(&self) -> Vector { self.size }
+    fn size(&self) -> Vector { self.shared.borrow().size }
 
     fn out(
         &mut self,
         p: Point,
-        fg: Fg,
-        bg: Bg,
+        fg: ExtFg,
+        bg: ExtBg,
+        attr: Attr,
         text: &str,
         hard: Range<i16>,
         soft: Range<i16>
@@ -78,7 +116,7 @@ impl<A: Allocator + Clone> base_Screen for Screen<A> {
         assert!(soft.start >= 0 && soft.end > soft.start && soft.end <= self.size().x);
         let text_end = if soft.end <= p.x { return 0 .. 0 } else { soft.end.saturating_sub(p.x) };
         let text_start = if soft.start <= p.x { 0 } else { soft.start.saturating_sub(p.x) };
-        let size = self.size;
+        let size = self.size();
         let line = (p.y as u16 as usize) * (size.x as u16 as usize);
         let line = &mut self.buf[line .. line + size.x as u16 as usize];
         let mut before_hard_start = min(p.x, hard.start);
@@ -105,7 +143,7 @@ impl<A: Allocator + Clone> base_Screen for Screen<A> {
             };
             if visible_1 && visible_2 {
                 let col = &mut line[x as u16 as usize];
-                *col = (g, fg, bg);
+                *col = (g, fg, bg, attr);
             }
             x += 1;
         }
@@ -117,24 +155,246 @@ impl<A: Allocator + Clone> base_Screen for Screen<A> {
     }
 
     fn update(&mut self, cursor: Option<Point>, _wait: bool) -> Result<Option<Event>, Error> {
-        for y in self.invalidated.t() .. self.invalidated.b() {
-            let line = (y as u16 as usize) * (self.size.x as u16 as usize);
-            let s = line + self.invalidated.l() as u16 as usize;
-            let f = line + self.invalidated.r() as u16 as usize;
-            self.out[s .. f].copy_from_slice(&self.buf[s .. f]);
-        }
-        self.invalidated.size = Vector::null();
-        self.cursor = cursor.and_then(|cursor| {
-            if (Rect { tl: Point { x: 0, y: 0 }, size: self.size() }).contains(cursor) {
-                Some(cursor)
-            } else {
-                None
+        let input = {
+            let mut shared = self.shared.borrow_mut();
+            let size = shared.size;
+            for y in self.invalidated.t() .. self.invalidated.b() {
+                let line = (y as u16 as usize) * (size.x as u16 as usize);
+                let s = line + self.invalidated.l() as u16 as usize;
+                let f = line + self.invalidated.r() as u16 as usize;
+                shared.out[s .. f].copy_from_slice(&self.buf[s .. f]);
             }
-        });
-        Ok(None)
+            shared.cursor = cursor.and_then(|cursor| {
+                if (Rect { tl: Point { x: 0, y: 0 }, size }).contains(cursor) {
+                    Some(cursor)
+                } else {
+                    None
+                }
+            });
+            shared.input.pop_front()
+        };
+        self.invalidated.size = Vector::null();
+        match input {
+            None => Ok(None),
+            Some(Input::Event(event)) => Ok(Some(event)),
+            Some(Input::Resize(size)) => {
+                self.resize(size);
+                Ok(Some(Event::Resize))
+            },
+        }
     }
 
     fn line_invalidated_range(&self, line: i16) -> &Range<i16> { &self.data[usize::from(line as u16)] }
 
     fn line_invalidated_range_mut(&mut self, line: i16) -> &mut Range<i16> { &mut self.data[usize::from(line as u16)] }
 }
+
+/// Shared access to a test [`Screen`].
+///
+/// Queued input is returned from `Screen::update` one event per call,
+/// so `WindowTree` processes it in the same order it was pushed.
+///
+/// ```
+/// # use core::num::NonZeroU16;
+/// # use tuifw_screen_base::{Attr, Bg, Event, Fg, Key, Mods, Point, Screen, Vector};
+/// let mut screen = tuifw_screen_test::Screen::new(Vector { x: 4, y: 1 });
+/// let handle = screen.handle();
+/// handle.push_key(Key::Enter, Mods::NONE);
+/// screen.out(Point { x: 0, y: 0 }, Fg::Yellow.into(), Bg::Blue.into(), Attr::BOLD, "hi", 0 .. 4, 0 .. 4);
+/// let event = screen.update(None, true).unwrap();
+/// assert_eq!(event, Some(Event::Key(NonZeroU16::new(1).unwrap(), Key::Enter, Mods::NONE)));
+/// assert_eq!(handle.pending(), 0);
+/// assert_eq!(handle.dump(), "\
+/// hi
+///
+/// aabb
+/// a: fg=Yellow bg=Blue attr=Bold
+/// b: fg=LightGray bg=None attr=None
+/// ");
+/// ```
+pub struct Handle<A: Allocator + Clone = Global>(Rc<RefCell<Shared<A>>, A>);
+
+impl<A: Allocator + Clone> Clone for Handle<A> {
+    fn clone(&self) -> Self { Handle(self.0.clone()) }
+}
+
+impl<A: Allocator + Clone> Handle<A> {
+    /// Queues an event. Use [`push_resize`](Handle::push_resize) instead of `Event::Resize`.
+    pub fn push_event(&self, event: Event) {
+        assert!(event != Event::Resize, "use push_resize instead");
+        self.0.borrow_mut().input.push_back(Input::Event(event));
+    }
+
+    pub fn push_key(&self, key: Key, mods: Mods) {
+        self.push_event(Event::Key(NonZeroU16::new(1).unwrap(), key, mods));
+    }
+
+    pub fn push_text(&self, text: &str) {
+        for c in text.chars() {
+            self.push_key(Key::Char(c), Mods::NONE);
+        }
+    }
+
+    pub fn push_paste(&self, text: &str) {
+        self.push_event(Event::Paste(text.into()));
+    }
+
+    pub fn push_click(&self, point: Point) {
+        self.push_event(Event::MouseDown(point, MouseButton::Left, Mods::NONE));
+        self.push_event(Event::MouseUp(point, MouseButton::Left, Mods::NONE));
+    }
+
+    pub fn push_resize(&self, size: Vector) {
+        assert!(size.x >= 0 && size.y >= 0);
+        self.0.borrow_mut().input.push_back(Input::Resize(size));
+    }
+
+    pub fn pending(&self) -> usize { self.0.borrow().input.len() }
+
+    pub fn clear_input(&self) { self.0.borrow_mut().input.clear(); }
+
+    pub fn size(&self) -> Vector { self.0.borrow().size }
+
+    pub fn cursor(&self) -> Option<Point> { self.0.borrow().cursor }
+
+    pub fn cell(&self, p: Point) -> Cell {
+        let shared = self.0.borrow();
+        assert!((Rect { tl: Point { x: 0, y: 0 }, size: shared.size }).contains(p));
+        shared.out[(p.y as u16 as usize) * (shared.size.x as u16 as usize) + p.x as u16 as usize]
+    }
+
+    /// Screen content as text lines, without trailing spaces.
+    /// A double-width character occupies two cells but is written once.
+    pub fn text(&self) -> String {
+        let shared = self.0.borrow();
+        let mut res = String::new();
+        for (y, line) in lines(&shared).enumerate() {
+            if y != 0 { res.push('\n'); }
+            let start = res.len();
+            let mut cells = line.iter();
+            while let Some(&(c, ..)) = cells.next() {
+                res.push(c);
+                if c.width() == Some(2) { cells.next(); }
+            }
+            let trimmed = res[start ..].trim_end_matches(' ').len();
+            res.truncate(start + trimmed);
+        }
+        res
+    }
+
+    /// Per-cell style map followed by the legend.
+    ///
+    /// Every distinct color and attribute combination gets a letter in order of appearance,
+    /// and the legend lists them as `a: fg=LightGray bg=None attr=None`.
+    pub fn colors(&self) -> String {
+        let shared = self.0.borrow();
+        let mut styles: Vec<(ExtFg, ExtBg, Attr)> = Vec::new();
+        let mut res = String::new();
+        for (y, line) in lines(&shared).enumerate() {
+            if y != 0 { res.push('\n'); }
+            for &(_, fg, bg, attr) in line {
+                let style = (fg, bg, attr);
+                let index = styles.iter().position(|&x| x == style).unwrap_or_else(|| {
+                    styles.push(style);
+                    styles.len() - 1
+                });
+                res.push(style_letter(index));
+            }
+        }
+        for (index, (fg, bg, attr)) in styles.into_iter().enumerate() {
+            write!(res, "\n{}: fg={fg} bg={bg} attr={attr}", style_letter(index)).unwrap();
+        }
+        res
+    }
+
+    /// [`text`](Handle::text) and [`colors`](Handle::colors) separated by an empty line,
+    /// suitable for golden files.
+    pub fn dump(&self) -> String {
+        let mut res = self.text();
+        res.push_str("\n\n");
+        res.push_str(&self.colors());
+        res.push('\n');
+        res
+    }
+
+    #[cfg(feature="std")]
+    pub fn assert_golden(&self, path: impl AsRef<std::path::Path>) {
+        assert_golden(path, &self.dump());
+    }
+}
+
+fn lines<A: Allocator>(shared: &Shared<A>) -> impl Iterator<Item=&[Cell]> {
+    let width = shared.size.x as u16 as usize;
+    (0 .. shared.size.y as u16 as usize).map(move |y| &shared.out[y * width .. (y + 1) * width])
+}
+
+fn style_letter(index: usize) -> char {
+    const LETTERS: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+    if let Some(&c) = LETTERS.get(index) { return char::from(c); }
+    char::from_u32(0xC0 + (index - LETTERS.len()) as u32).expect("too many styles")
+}
+
+/// Compares two dumps line by line.
+///
+/// Returns `None` if they are equal, otherwise a report listing every differing line
+/// with `-` for expected and `+` for actual content, and a `^` under the first differing column.
+pub fn diff(expected: &str, actual: &str) -> Option<String> {
+    if expected == actual { return None; }
+    let mut res = String::new();
+    let mut expected_lines = expected.split('\n');
+    let mut actual_lines = actual.split('\n');
+    let mut n = 1usize;
+    loop {
+        let (e, a) = (expected_lines.next(), actual_lines.next());
+        if e.is_none() && a.is_none() { break; }
+        if e != a {
+            if let Some(e) = e { writeln!(res, "{n:4} - {e}").unwrap(); }
+            if let Some(a) = a { writeln!(res, "{n:4} + {a}").unwrap(); }
+            if let (Some(e), Some(a)) = (e, a) {
+                let col = e.chars().zip(a.chars()).take_while(|(x, y)| x == y).count();
+                writeln!(res, "       {:col$}^", "").unwrap();
+            }
+        }
+        n += 1;
+    }
+    Some(res)
+}
+
+/// Compares `actual` with the golden file, panicking with a [`diff`] on mismatch.
+///
+/// If the `TUIFW_BLESS` environment variable is set, the file is (re)written instead.
+#[cfg(feature="std")]
+pub fn assert_golden(path: impl AsRef<std::path::Path>, actual: &str) {
+    let path = path.as_ref();
+    if std::env::var_os("TUIFW_BLESS").is_some() {
+        std::fs::write(path, actual).unwrap_or_else(|e| panic!("cannot write {}: {e}", path.display()));
+        return;
+    }
+    let expected = std::fs::read_to_string(path).unwrap_or_else(|e|
+        panic!("cannot read {}: {e} (run with TUIFW_BLESS=1 to create it)", path.display())
+    );
+    if let Some(diff) = diff(&expected, actual) {
+        panic!("{} mismatch (run with TUIFW_BLESS=1 to update it):\n{diff}", path.display());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_equal() {
+        assert_eq!(diff("ab\ncd\n", "ab\ncd\n"), None);
+    }
+
+    #[test]
+    fn diff_lines() {
+        assert_eq!(diff("ab\ncd\nef", "ab\ncx\nef\ng").as_deref(), Some(concat!(
+            "   2 - cd\n",
+            "   2 + cx\n",
+            "        ^\n",
+            "   4 + g\n",
+        )));
+        assert_eq!(diff("ab\ncd", "ab").as_deref(), Some("   2 - cd\n"));
+    }
+}
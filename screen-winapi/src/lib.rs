@@ -70,6 +70,7 @@ pub struct Screen<A: Allocator = Global> {
     invalidated: Rect,
     cursor_is_visible: bool, 
     data: Vec<Range<i16>, A>,
+    buttons: DWORD,
 }
 
 impl Screen {
@@ -103,6 +104,7 @@ impl<A: Allocator> Screen<A> {
             size: Vector::null(),
             invalidated: Rect { tl: Point { x: 0, y: 0 }, size: Vector::null() },
             cursor_is_visible: false,
+            buttons: 0,
         };
         s.h_input = valid_handle(unsafe { CreateFileA(
             "CONIN$\0".as_ptr() as _,
@@ -122,7 +124,7 @@ impl<A: Allocator> Screen<A> {
             FILE_ATTRIBUTE_NORMAL,
             null_mut())
         }, error_alloc)?;
-        non_zero(unsafe { SetConsoleMode(s.h_input, ENABLE_EXTENDED_FLAGS | ENABLE_WINDOW_INPUT) }, error_alloc)?;
+        non_zero(unsafe { SetConsoleMode(s.h_input, ENABLE_EXTENDED_FLAGS | ENABLE_WINDOW_INPUT | ENABLE_MOUSE_INPUT) }, error_alloc)?;
         non_zero(unsafe { SetConsoleMode(s.h_output, 0) }, error_alloc)?;
         s.resize()?;
         Ok(s)
@@ -262,7 +264,7 @@ impl<A: Allocator> Screen<A> {
         if let Some(cursor) = cursor {
             unsafe { SetConsoleCursorPosition(self.h_output, COORD { X: cursor.x, Y: cursor.y }); }
         }
-        let (count, key, c, ctrl, alt) = loop {
+        let (count, key, c, ctrl, alt, mods) = loop {
             pump_messages();
             if !wait {
                 let mut n: DWORD = 0;
@@ -281,6 +283,12 @@ impl<A: Allocator> Screen<A> {
                     self.resize()?;
                     return Ok(Some(Event::Resize));
                 },
+                MOUSE_EVENT => {
+                    let e = unsafe { input.Event.MouseEvent() };
+                    if let Some(event) = self.mouse_event(e) {
+                        return Ok(Some(event));
+                    }
+                },
                 KEY_EVENT => {
                     let e = unsafe { input.Event.KeyEvent() };
                     if e.bKeyDown != 0 {
@@ -289,7 +297,8 @@ impl<A: Allocator> Screen<A> {
                             e.wVirtualKeyCode,
                             *unsafe { e.uChar.UnicodeChar() },
                             e.dwControlKeyState & (LEFT_CTRL_PRESSED | RIGHT_CTRL_PRESSED) != 0,
-                            e.dwControlKeyState & (LEFT_ALT_PRESSED | RIGHT_ALT_PRESSED) != 0
+                            e.dwControlKeyState & (LEFT_ALT_PRESSED | RIGHT_ALT_PRESSED) != 0,
+                            control_key_state_mods(e.dwControlKeyState)
                         );
                     }
                 },
@@ -297,65 +306,78 @@ impl<A: Allocator> Screen<A> {
             }
         };
         Ok(match key as i32 {
-            VK_RETURN => Some(Event::Key(count, Key::Enter)),
-            VK_TAB => Some(Event::Key(count, Key::Tab)),
-            VK_PRIOR => Some(Event::Key(count, Key::PageUp)),
-            VK_NEXT => Some(Event::Key(count, Key::PageDown)),
-            VK_HOME => Some(Event::Key(count, Key::Home)),
-            VK_END => Some(Event::Key(count, Key::End)),
-            VK_DOWN => Some(Event::Key(count, Key::Down)),
-            VK_UP => Some(Event::Key(count, Key::Up)),
-            VK_LEFT => Some(Event::Key(count, Key::Left)),
-            VK_RIGHT => Some(Event::Key(count, Key::Right)),
-            VK_DELETE => Some(Event::Key(count, Key::Delete)),
-            VK_INSERT => Some(Event::Key(count, Key::Insert)),
-            VK_F1 => Some(Event::Key(count, Key::F1)),
-            VK_F2 => Some(Event::Key(count, Key::F2)),
-            VK_F3 => Some(Event::Key(count, Key::F3)),
-            VK_F4 => Some(Event::Key(count, Key::F4)),
-            VK_F5 => Some(Event::Key(count, Key::F5)),
-            VK_F6 => Some(Event::Key(count, Key::F6)),
-            VK_F7 => Some(Event::Key(count, Key::F7)),
-            VK_F8 => Some(Event::Key(count, Key::F8)),
-            VK_F9 => Some(Event::Key(count, Key::F9)),
-            VK_F10 => Some(Event::Key(count, Key::F10)),
-            VK_F11 => Some(Event::Key(count, Key::F11)),
-            VK_F12 => Some(Event::Key(count, Key::F12)),
-            VK_ESCAPE => Some(Event::Key(count, Key::Escape)),
-            VK_BACK => Some(Event::Key(count, Key::Backspace)),
-            0x32 if ctrl => Some(Event::Key(count, Key::Ctrl(Ctrl::At))),
-            0x41 if ctrl => Some(Event::Key(count, Key::Ctrl(Ctrl::A))),
-            0x42 if ctrl => Some(Event::Key(count, Key::Ctrl(Ctrl::B))),
-            0x43 if ctrl => Some(Event::Key(count, Key::Ctrl(Ctrl::C))),
-            0x44 if ctrl => Some(Event::Key(count, Key::Ctrl(Ctrl::D))),
-            0x45 if ctrl => Some(Event::Key(count, Key::Ctrl(Ctrl::E))),
-            0x46 if ctrl => Some(Event::Key(count, Key::Ctrl(Ctrl::F))),
-            0x47 if ctrl => Some(Event::Key(count, Key::Ctrl(Ctrl::G))),
-            0x48 if ctrl => Some(Event::Key(count, Key::Backspace)),
-            0x49 if ctrl => Some(Event::Key(count, Key::Tab)),
-            0x4A if ctrl => Some(Event::Key(count, Key::Ctrl(Ctrl::J))),
-            0x4B if ctrl => Some(Event::Key(count, Key::Ctrl(Ctrl::K))),
-            0x4C if ctrl => Some(Event::Key(count, Key::Ctrl(Ctrl::L))),
-            0x4D if ctrl => Some(Event::Key(count, Key::Enter)),
-            0x4E if ctrl => Some(Event::Key(count, Key::Ctrl(Ctrl::N))),
-            0x4F if ctrl => Some(Event::Key(count, Key::Ctrl(Ctrl::O))),
-            0x50 if ctrl => Some(Event::Key(count, Key::Ctrl(Ctrl::P))),
-            0x51 if ctrl => Some(Event::Key(count, Key::Ctrl(Ctrl::Q))),
-            0x52 if ctrl => Some(Event::Key(count, Key::Ctrl(Ctrl::R))),
-            0x53 if ctrl => Some(Event::Key(count, Key::Ctrl(Ctrl::S))),
-            0x54 if ctrl => Some(Event::Key(count, Key::Ctrl(Ctrl::T))),
-            0x55 if ctrl => Some(Event::Key(count, Key::Ctrl(Ctrl::U))),
-            0x56 if ctrl => Some(Event::Key(count, Key::Ctrl(Ctrl::V))),
-            0x57 if ctrl => Some(Event::Key(count, Key::Ctrl(Ctrl::W))),
-            0x58 if ctrl => Some(Event::Key(count, Key::Ctrl(Ctrl::X))),
-            0x59 if ctrl => Some(Event::Key(count, Key::Ctrl(Ctrl::Y))),
-            0x5A if ctrl => Some(Event::Key(count, Key::Ctrl(Ctrl::Z))),
-            VK_OEM_4 if ctrl => Some(Event::Key(count, Key::Escape)),
-            VK_OEM_5 if ctrl => Some(Event::Key(count, Key::Ctrl(Ctrl::Backslash))),
-            VK_OEM_6 if ctrl => Some(Event::Key(count, Key::Ctrl(Ctrl::Bracket))),
-            0x36 if ctrl => Some(Event::Key(count, Key::Ctrl(Ctrl::Caret))),
-            VK_OEM_MINUS if ctrl => Some(Event::Key(count, Key::Ctrl(Ctrl::Underscore))),
-            VK_OEM_2 if ctrl => Some(Event::Key(count, Key::Backspace)),
+            VK_RETURN => Some(Event::Key(count, Key::Enter, Mods::NONE)),
+            VK_TAB => Some(Event::Key(count, Key::Tab, mods.without(Mods::ALT | Mods::CTRL))),
+            VK_PRIOR => Some(Event::Key(count, Key::PageUp, mods)),
+            VK_NEXT => Some(Event::Key(count, Key::PageDown, mods)),
+            VK_HOME => Some(Event::Key(count, Key::Home, mods)),
+            VK_END => Some(Event::Key(count, Key::End, mods)),
+            VK_DOWN => Some(Event::Key(count, Key::Down, mods)),
+            VK_UP => Some(Event::Key(count, Key::Up, mods)),
+            VK_LEFT => Some(Event::Key(count, Key::Left, mods)),
+            VK_RIGHT => Some(Event::Key(count, Key::Right, mods)),
+            VK_DELETE => Some(Event::Key(count, Key::Delete, mods)),
+            VK_INSERT => Some(Event::Key(count, Key::Insert, mods)),
+            VK_F1 => Some(Event::Key(count, Key::F1, mods)),
+            VK_F2 => Some(Event::Key(count, Key::F2, mods)),
+            VK_F3 => Some(Event::Key(count, Key::F3, mods)),
+            VK_F4 => Some(Event::Key(count, Key::F4, mods)),
+            VK_F5 => Some(Event::Key(count, Key::F5, mods)),
+            VK_F6 => Some(Event::Key(count, Key::F6, mods)),
+            VK_F7 => Some(Event::Key(count, Key::F7, mods)),
+            VK_F8 => Some(Event::Key(count, Key::F8, mods)),
+            VK_F9 => Some(Event::Key(count, Key::F9, mods)),
+            VK_F10 => Some(Event::Key(count, Key::F10, mods)),
+            VK_F11 => Some(Event::Key(count, Key::F11, mods)),
+            VK_F12 => Some(Event::Key(count, Key::F12, mods)),
+            VK_F13 => Some(Event::Key(count, Key::F13, mods)),
+            VK_F14 => Some(Event::Key(count, Key::F14, mods)),
+            VK_F15 => Some(Event::Key(count, Key::F15, mods)),
+            VK_F16 => Some(Event::Key(count, Key::F16, mods)),
+            VK_F17 => Some(Event::Key(count, Key::F17, mods)),
+            VK_F18 => Some(Event::Key(count, Key::F18, mods)),
+            VK_F19 => Some(Event::Key(count, Key::F19, mods)),
+            VK_F20 => Some(Event::Key(count, Key::F20, mods)),
+            VK_F21 => Some(Event::Key(count, Key::F21, mods)),
+            VK_F22 => Some(Event::Key(count, Key::F22, mods)),
+            VK_F23 => Some(Event::Key(count, Key::F23, mods)),
+            VK_F24 => Some(Event::Key(count, Key::F24, mods)),
+            VK_ESCAPE => Some(Event::Key(count, Key::Escape, mods)),
+            VK_BACK => Some(Event::Key(count, Key::Backspace, mods)),
+            VK_CLEAR => Some(Event::Key(count, Key::KeypadCenter, mods)),
+            0x32 if ctrl => Some(Event::Key(count, Key::Ctrl(Ctrl::At), Mods::NONE)),
+            0x41 if ctrl => Some(Event::Key(count, Key::Ctrl(Ctrl::A), Mods::NONE)),
+            0x42 if ctrl => Some(Event::Key(count, Key::Ctrl(Ctrl::B), Mods::NONE)),
+            0x43 if ctrl => Some(Event::Key(count, Key::Ctrl(Ctrl::C), Mods::NONE)),
+            0x44 if ctrl => Some(Event::Key(count, Key::Ctrl(Ctrl::D), Mods::NONE)),
+            0x45 if ctrl => Some(Event::Key(count, Key::Ctrl(Ctrl::E), Mods::NONE)),
+            0x46 if ctrl => Some(Event::Key(count, Key::Ctrl(Ctrl::F), Mods::NONE)),
+            0x47 if ctrl => Some(Event::Key(count, Key::Ctrl(Ctrl::G), Mods::NONE)),
+            0x48 if ctrl => Some(Event::Key(count, Key::Backspace, Mods::NONE)),
+            0x49 if ctrl => Some(Event::Key(count, Key::Tab, Mods::NONE)),
+            0x4A if ctrl => Some(Event::Key(count, Key::Ctrl(Ctrl::J), Mods::NONE)),
+            0x4B if ctrl => Some(Event::Key(count, Key::Ctrl(Ctrl::K), Mods::NONE)),
+            0x4C if ctrl => Some(Event::Key(count, Key::Ctrl(Ctrl::L), Mods::NONE)),
+            0x4D if ctrl => Some(Event::Key(count, Key::Enter, Mods::NONE)),
+            0x4E if ctrl => Some(Event::Key(count, Key::Ctrl(Ctrl::N), Mods::NONE)),
+            0x4F if ctrl => Some(Event::Key(count, Key::Ctrl(Ctrl::O), Mods::NONE)),
+            0x50 if ctrl => Some(Event::Key(count, Key::Ctrl(Ctrl::P), Mods::NONE)),
+            0x51 if ctrl => Some(Event::Key(count, Key::Ctrl(Ctrl::Q), Mods::NONE)),
+            0x52 if ctrl => Some(Event::Key(count, Key::Ctrl(Ctrl::R), Mods::NONE)),
+            0x53 if ctrl => Some(Event::Key(count, Key::Ctrl(Ctrl::S), Mods::NONE)),
+            0x54 if ctrl => Some(Event::Key(count, Key::Ctrl(Ctrl::T), Mods::NONE)),
+            0x55 if ctrl => Some(Event::Key(count, Key::Ctrl(Ctrl::U), Mods::NONE)),
+            0x56 if ctrl => Some(Event::Key(count, Key::Ctrl(Ctrl::V), Mods::NONE)),
+            0x57 if ctrl => Some(Event::Key(count, Key::Ctrl(Ctrl::W), Mods::NONE)),
+            0x58 if ctrl => Some(Event::Key(count, Key::Ctrl(Ctrl::X), Mods::NONE)),
+            0x59 if ctrl => Some(Event::Key(count, Key::Ctrl(Ctrl::Y), Mods::NONE)),
+            0x5A if ctrl => Some(Event::Key(count, Key::Ctrl(Ctrl::Z), Mods::NONE)),
+            VK_OEM_4 if ctrl => Some(Event::Key(count, Key::Escape, Mods::NONE)),
+            VK_OEM_5 if ctrl => Some(Event::Key(count, Key::Ctrl(Ctrl::Backslash), Mods::NONE)),
+            VK_OEM_6 if ctrl => Some(Event::Key(count, Key::Ctrl(Ctrl::Bracket), Mods::NONE)),
+            0x36 if ctrl => Some(Event::Key(count, Key::Ctrl(Ctrl::Caret), Mods::NONE)),
+            VK_OEM_MINUS if ctrl => Some(Event::Key(count, Key::Ctrl(Ctrl::Underscore), Mods::NONE)),
+            VK_OEM_2 if ctrl => Some(Event::Key(count, Key::Backspace, Mods::NONE)),
             _ => {
                 assert!(!(0xD800 .. 0xDC00).contains(&c));
                 let c = if (0xDC00 .. 0xE000).contains(&c) {
@@ -386,9 +408,9 @@ impl<A: Allocator> Screen<A> {
                 let c = char::from_u32(c).unwrap();
                 if c >= ' ' && c != '\x7F' {
                     if alt {
-                        Some(Event::Key(count, Key::Alt(c)))
+                        Some(Event::Key(count, Key::Alt(c), Mods::NONE))
                     } else {
-                        Some(Event::Key(count, Key::Char(c)))
+                        Some(Event::Key(count, Key::Char(c), Mods::NONE))
                     }
                 } else {
                     None
@@ -397,6 +419,37 @@ impl<A: Allocator> Screen<A> {
         })
     }
 
+    fn mouse_event(&mut self, e: &MOUSE_EVENT_RECORD) -> Option<Event> {
+        const BUTTONS: [(DWORD, MouseButton); 3] = [
+            (FROM_LEFT_1ST_BUTTON_PRESSED, MouseButton::Left),
+            (RIGHTMOST_BUTTON_PRESSED, MouseButton::Right),
+            (FROM_LEFT_2ND_BUTTON_PRESSED, MouseButton::Middle),
+        ];
+        let point = Point { x: e.dwMousePosition.X, y: e.dwMousePosition.Y };
+        let mods = control_key_state_mods(e.dwControlKeyState);
+        if e.dwEventFlags & MOUSE_WHEELED != 0 {
+            let delta = (e.dwButtonState >> 16) as u16 as i16;
+            return Some(Event::Wheel(point, if delta > 0 { -1 } else { 1 }, mods));
+        }
+        for (mask, button) in BUTTONS {
+            let pressed = e.dwButtonState & mask != 0;
+            if pressed == (self.buttons & mask != 0) { continue; }
+            self.buttons ^= mask;
+            return Some(if !pressed {
+                Event::MouseUp(point, button, mods)
+            } else if e.dwEventFlags & DOUBLE_CLICK != 0 {
+                Event::DoubleClick(point, button, mods)
+            } else {
+                Event::MouseDown(point, button, mods)
+            });
+        }
+        if e.dwEventFlags & MOUSE_MOVED != 0 {
+            let button = BUTTONS.iter().find(|&&(mask, _)| self.buttons & mask != 0).map(|&(_, button)| button);
+            return Some(Event::MouseMove(point, button, mods));
+        }
+        None
+    }
+
     fn set_cursor_is_visible(&self, h_output: HANDLE, cursor_is_visible: bool) -> Result<(), Error> {
         let mut cursor = CONSOLE_CURSOR_INFO { dwSize: 100, bVisible: TRUE };
         cursor.bVisible = if !cursor_is_visible { TRUE } else { FALSE };
@@ -431,7 +484,15 @@ impl<A: Allocator> Drop for Screen<A> {
     }
 }
 
-fn attr_w(fg: Fg, bg: Bg) -> WORD {
+fn control_key_state_mods(state: DWORD) -> Mods {
+    let mut mods = Mods::NONE;
+    if state & SHIFT_PRESSED != 0 { mods |= Mods::SHIFT; }
+    if state & (LEFT_CTRL_PRESSED | RIGHT_CTRL_PRESSED) != 0 { mods |= Mods::CTRL; }
+    if state & (LEFT_ALT_PRESSED | RIGHT_ALT_PRESSED) != 0 { mods |= Mods::ALT; }
+    mods
+}
+
+fn attr_w(fg: Fg, bg: Bg, attr: Attr) -> WORD {
     let fg = match fg {
         Fg::Black => 0,
         Fg::DarkGray => FOREGROUND_INTENSITY,
@@ -460,7 +521,11 @@ fn attr_w(fg: Fg, bg: Bg) -> WORD {
         Bg::Cyan => BACKGROUND_BLUE | BACKGROUND_GREEN,
         Bg::LightGray => BACKGROUND_BLUE | BACKGROUND_GREEN | BACKGROUND_RED
     };
-    fg | bg
+    let mut res = fg | bg;
+    if attr.contains(Attr::BOLD) { res |= FOREGROUND_INTENSITY; }
+    if attr.contains(Attr::UNDERLINE) { res |= COMMON_LVB_UNDERSCORE; }
+    if attr.contains(Attr::REVERSE) { res |= COMMON_LVB_REVERSE_VIDEO; }
+    res
 }
 
 impl<A: Allocator> base_Screen for Screen<A> {
@@ -469,8 +534,9 @@ impl<A: Allocator> base_Screen for Screen<A> {
     fn out(
         &mut self,
         p: Point,
-        fg: Fg,
-        bg: Bg,
+        fg: ExtFg,
+        bg: ExtBg,
+        attr: Attr,
         text: &str,
         hard: Range<i16>,
         soft: Range<i16>
@@ -483,7 +549,7 @@ impl<A: Allocator> base_Screen for Screen<A> {
         let size = self.size;
         let line = (p.y as u16 as usize) * (size.x as u16 as usize);
         let line = &mut self.buf[line .. line + size.x as u16 as usize];
-        let attr = attr_w(fg, bg);
+        let attr = attr_w(fg.to_cga(), bg.to_cga(), attr);
         let mut x0 = None;
         let mut x = p.x;
         let mut n = 0i16;
@@ -42,7 +42,7 @@ mod no_std {
 }
 
 use exit_no_std::exit;
-use tuifw_screen_base::{Bg, Fg, Screen, Point, Event, Key};
+use tuifw_screen_base::{Attr, Bg, Fg, Screen, Point, Event, Key};
 
 fn draw(screen: &mut dyn Screen) {
     let w = 0 .. screen.size().x;
@@ -50,7 +50,7 @@ fn draw(screen: &mut dyn Screen) {
         let bg_n: i16 = bg_n.try_into().unwrap();
         for (fg_n, fg) in Fg::iter_variants().enumerate() {
             let fg_n: i16 = fg_n.try_into().unwrap();
-            screen.out(Point { x: 3 * fg_n, y: bg_n }, fg, bg, " ■ ", w.clone(), w.clone());
+            screen.out(Point { x: 3 * fg_n, y: bg_n }, fg.into(), bg.into(), Attr::NONE, " ■ ", w.clone(), w.clone());
         }
     }
 }
@@ -63,12 +63,12 @@ extern "C" fn mainCRTStartup() -> ! {
     draw(screen);
     loop {
         if let Some(e) = screen.update(None, true).unwrap() {
-            if matches!(e, Event::Key(_, Key::Escape)) { break; }
+            if matches!(e, Event::Key(_, Key::Escape, _)) { break; }
             if matches!(e, Event::Resize) {
                 let w = 0 .. screen.size().x;
                 for x in 0 .. screen.size().x {
                     for y in 0 .. screen.size().y {
-                        screen.out(Point { x, y }, Fg::LightGray, Bg::None, " ", w.clone(), w.clone());
+                        screen.out(Point { x, y }, Fg::LightGray.into(), Bg::None.into(), Attr::NONE, " ", w.clone(), w.clone());
                     }
                 }
                 draw(screen);
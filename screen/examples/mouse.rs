@@ -2,11 +2,11 @@
 
 #![deny(warnings)]
 
-use tuifw_screen::{Bg, Fg, Screen, Point, Event, Key};
+use tuifw_screen::{Attr, Bg, Fg, Screen, Point, Event, Key, MouseButton};
 
 fn draw(screen: &mut dyn Screen, point: Point, down: bool) {
     let w = 0 .. screen.size().x;
-    screen.out(point, Fg::Green, Bg::None, if down { "X" } else { "█" }, w.clone(), w.clone());
+    screen.out(point, Fg::Green.into(), Bg::None.into(), Attr::NONE, if down { "X" } else { "█" }, w.clone(), w.clone());
 }
 
 fn main() {
@@ -16,12 +16,12 @@ fn main() {
     loop {
         if let Some(e) = screen.update(None, true).unwrap() {
             match e {
-                Event::Key(_, Key::Escape) => break,
-                Event::LmbDown(p) => {
+                Event::Key(_, Key::Escape, _) => break,
+                Event::MouseDown(p, MouseButton::Left, _) => {
                     lmb_down_point = Some(p);
                     draw(screen, p, true);
                 },
-                Event::LmbUp(p) => {
+                Event::MouseUp(p, MouseButton::Left, _) => {
                     draw(screen, lmb_down_point.take().unwrap_or(p), false);
                 }
                 _ => { },
@@ -5,7 +5,7 @@
 #![windows_subsystem = "windows"]
 
 use std::cmp::{min, max};
-use tuifw_screen::{Bg, Fg, Screen, Point, Vector, Event, Key};
+use tuifw_screen::{Attr, Bg, Fg, Screen, Point, Vector, Event, Key};
 
 #[allow(clippy::redundant_clone)]
 fn draw_box(screen: &mut dyn Screen, p: &mut Point) {
@@ -13,31 +13,31 @@ fn draw_box(screen: &mut dyn Screen, p: &mut Point) {
     p.y = min(max(p.y, 4), screen.size().y - 5);
     if p.y < 0 { return; }
     let w = 0 .. screen.size().x;
-    screen.out(p.offset(Vector { x: -6, y: -4 }), Fg::Green, Bg::None,
+    screen.out(p.offset(Vector { x: -6, y: -4 }), Fg::Green.into(), Bg::None.into(), Attr::NONE,
         "             ", w.clone(), w.clone()
     );
-    screen.out(p.offset(Vector { x: -7, y: -3 }), Fg::Green, Bg::None,
+    screen.out(p.offset(Vector { x: -7, y: -3 }), Fg::Green.into(), Bg::None.into(), Attr::NONE,
         " ╔═══════════╗ ", w.clone(), w.clone()
     );
-    screen.out(p.offset(Vector { x: -7, y: -2 }), Fg::Green, Bg::None,
+    screen.out(p.offset(Vector { x: -7, y: -2 }), Fg::Green.into(), Bg::None.into(), Attr::NONE,
         " ║     ↑     ║ ", w.clone(), w.clone()
     );
-    screen.out(p.offset(Vector { x: -7, y: -1 }), Fg::Green, Bg::None,
+    screen.out(p.offset(Vector { x: -7, y: -1 }), Fg::Green.into(), Bg::None.into(), Attr::NONE,
         " ║     k     ║ ", w.clone(), w.clone()
     );
-    screen.out(p.offset(Vector { x: -7, y: 0 }), Fg::Green, Bg::None,
+    screen.out(p.offset(Vector { x: -7, y: 0 }), Fg::Green.into(), Bg::None.into(), Attr::NONE,
         " ║ ←h     l→ ║ ", w.clone(), w.clone()
     );
-    screen.out(p.offset(Vector { x: -7, y: 1 }), Fg::Green, Bg::None,
+    screen.out(p.offset(Vector { x: -7, y: 1 }), Fg::Green.into(), Bg::None.into(), Attr::NONE,
         " ║     j     ║ ", w.clone(), w.clone()
     );
-    screen.out(p.offset(Vector { x: -7, y: 2 }), Fg::Green, Bg::None,
+    screen.out(p.offset(Vector { x: -7, y: 2 }), Fg::Green.into(), Bg::None.into(), Attr::NONE,
         " ║     ↓     ║ ", w.clone(), w.clone()
     );
-    screen.out(p.offset(Vector { x: -7, y: 3 }), Fg::Green, Bg::None,
+    screen.out(p.offset(Vector { x: -7, y: 3 }), Fg::Green.into(), Bg::None.into(), Attr::NONE,
         " ╚═══════════╝ ", w.clone(), w.clone()
     );
-    screen.out(p.offset(Vector { x: -6, y: 4 }), Fg::Green, Bg::None,
+    screen.out(p.offset(Vector { x: -6, y: 4 }), Fg::Green.into(), Bg::None.into(), Attr::NONE,
         "             ", w.clone(), w.clone()
     );
 }
@@ -50,15 +50,15 @@ fn main() {
     loop {
         if let Some(e) = screen.update(None, true).unwrap() {
             if let Some((m, n, d)) = match e {
-                Event::Key(n, Key::Left) | Event::Key(n, Key::Char('h')) =>
+                Event::Key(n, Key::Left, _) | Event::Key(n, Key::Char('h'), _) =>
                     Some((2, n, Vector { x: -1, y: 0 })),
-                Event::Key(n, Key::Right) | Event::Key(n, Key::Char('l')) =>
+                Event::Key(n, Key::Right, _) | Event::Key(n, Key::Char('l'), _) =>
                     Some((2, n, Vector { x: 1, y: 0 })),
-                Event::Key(n, Key::Up) | Event::Key(n, Key::Char('k')) =>
+                Event::Key(n, Key::Up, _) | Event::Key(n, Key::Char('k'), _) =>
                     Some((1, n, Vector { x: 0, y: -1 })),
-                Event::Key(n, Key::Down) | Event::Key(n, Key::Char('j')) =>
+                Event::Key(n, Key::Down, _) | Event::Key(n, Key::Char('j'), _) =>
                     Some((1, n, Vector { x: 0, y: 1 })),
-                Event::Key(_, Key::Escape) => break,
+                Event::Key(_, Key::Escape, _) => break,
                 _ => None,
             } {
                 for _ in 0 .. m {
@@ -21,6 +21,9 @@ pub use tuifw_screen_base::*;
 /// while `Screen` instance is alive. This rule also applies to another `Screen` instance:
 /// it is not safe to call `init`/`init_in` again until `Screen` created by previous call is dropped.
 ///
+/// Iff compiled with `ansi` feature on Unix-like platform, this function switches the terminal to raw mode
+/// and installs a `SIGWINCH` handler. The same rules apply to terminal settings and `SIGWINCH` disposition.
+///
 /// Also, iff compiled with `cfg(target_os="dos")` this method may not be invoked until it is guaranteed the memory addresses
 /// in `0xB8000 .. 0xBBE80` are not used by Rust abstract machine.
 ///
@@ -36,6 +39,9 @@ pub unsafe fn init(max_size: Option<(u16, u16)>, error_alloc: Option<&'static dy
 /// while `Screen` instance is alive. This rule also applies to another `Screen` instance:
 /// it is not safe to call `init`/`init_in` again until `Screen` created by previous call is dropped.
 ///
+/// Iff compiled with `ansi` feature on Unix-like platform, this function switches the terminal to raw mode
+/// and installs a `SIGWINCH` handler. The same rules apply to terminal settings and `SIGWINCH` disposition.
+///
 /// Also, iff compiled with `cfg(target_os="dos")` this method may not be invoked until it is guaranteed the memory addresses
 /// in `0xB8000 .. 0xBBE80` are not used by Rust abstract machine.
 ///
@@ -70,7 +76,7 @@ unsafe fn init_raw_in<A: Allocator + Clone + 'static>(
     Ok(Box::new(tuifw_screen_winapi::Screen::new_in(max_size, error_alloc, alloc)?))
 }
 
-#[cfg(all(not(target_os="dos"), not(windows)))]
+#[cfg(all(not(target_os="dos"), not(windows), feature="ncurses", not(feature="ansi")))]
 unsafe fn init_raw_in<A: Allocator + Clone + 'static>(
     max_size: Option<(u16, u16)>,
     error_alloc: Option<&'static dyn Allocator>,
@@ -78,3 +84,21 @@ unsafe fn init_raw_in<A: Allocator + Clone + 'static>(
 ) -> Result<Box<dyn Screen>, Error> {
     tuifw_screen_ncurses::init_in(max_size, error_alloc, alloc)
 }
+
+#[cfg(all(not(target_os="dos"), not(windows), feature="ansi"))]
+unsafe fn init_raw_in<A: Allocator + Clone + 'static>(
+    max_size: Option<(u16, u16)>,
+    error_alloc: Option<&'static dyn Allocator>,
+    alloc: A
+) -> Result<Box<dyn Screen>, Error> {
+    Ok(Box::new(tuifw_screen_ansi::Screen::new_in(max_size, error_alloc, alloc)?))
+}
+
+#[cfg(all(not(target_os="dos"), not(windows), not(feature="ncurses"), not(feature="ansi")))]
+unsafe fn init_raw_in<A: Allocator + Clone + 'static>(
+    _max_size: Option<(u16, u16)>,
+    _error_alloc: Option<&'static dyn Allocator>,
+    _alloc: A
+) -> Result<Box<dyn Screen>, Error> {
+    compile_error!("either the `ncurses` or the `ansi` feature should be enabled on Unix-like platforms")
+}
@@ -3,7 +3,7 @@ use alloc::boxed::Box;
 use alloc::string::String;
 use dynamic_cast::impl_supports_interfaces;
 use phantom_type::PhantomType;
-use tuifw_screen_base::{Key, Point, Rect, Vector, Error};
+use tuifw_screen_base::{Key, Mods, Point, Rect, Vector, Error};
 use tuifw_window::{Event, RenderPort, Timer, Widget, WidgetData, Window, WindowTree, label_width, label};
 use tuifw_window::{CMD_GOT_PRIMARY_FOCUS, CMD_LOST_PRIMARY_FOCUS, App, Color};
 use tuifw_window::{CMD_GOT_SECONDARY_FOCUS, CMD_LOST_SECONDARY_FOCUS};
@@ -118,7 +118,7 @@ impl<Button: IsButton> ButtonController<Button> {
         app: &mut dyn App,
     ) -> bool {
         match event {
-            Event::Key(Key::Enter) => {
+            Event::Key(Key::Enter, Mods::NONE) => {
                 if window.actual_is_enabled(tree) {
                     Self::click(tree, window);
                     true
@@ -126,7 +126,7 @@ impl<Button: IsButton> ButtonController<Button> {
                     false
                 }
             },
-            Event::LmbDown(_) => {
+            Event::LmbDown(..) => {
                 if window.actual_is_enabled(tree) {
                     let click_timer = Timer::new(tree, 0, Box::new(move |tree, app| {
                         let data = window.data_mut::<Button>(tree);
@@ -161,7 +161,7 @@ impl<Button: IsButton> ButtonController<Button> {
                 }
                 true
             },
-            Event::PostProcessKey(Key::Alt(c)) | Event::PostProcessKey(Key::Char(c)) => {
+            Event::PostProcessKey(Key::Alt(c), Mods::NONE) | Event::PostProcessKey(Key::Char(c), Mods::NONE) => {
                 if window.actual_is_enabled(tree) {
                     let data = window.data_mut::<Button>(tree);
                     let label = data.label();
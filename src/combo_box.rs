@@ -0,0 +1,528 @@
+use crate::{widget, InputLine, ListBox, Validator};
+use crate::list_box::starts_with_ignore_case;
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cmp::{max, min};
+use dynamic_cast::impl_supports_interfaces;
+use tuifw_screen_base::{Key, Mods, Ctrl, Point, Rect, Vector, Error, text_width};
+use tuifw_window::{Event, RenderPort, Timer, Widget, WidgetData, Window, WindowTree, App, Color, Focus};
+use tuifw_window::{CMD_GOT_PRIMARY_FOCUS, CMD_LOST_PRIMARY_FOCUS};
+use tuifw_window::{COLOR_INPUT_LINE, COLOR_INPUT_LINE_FOCUSED, COLOR_DISABLED, COLOR_INPUT_LINE_FOCUSED_DISABLED};
+
+pub const CMD_COMBO_BOX_SELECTION_CHANGED: u16 = 200;
+
+const DROP_DOWN_ROWS: usize = 8;
+
+widget! {
+    #[widget(ComboBoxWidget, init=init, drop=drop_popup)]
+    pub struct ComboBox {
+        #[property(ref, on_changed=on_items_changed)]
+        items: Vec<String>,
+        #[property(copy, on_changed=on_is_editable_changed)]
+        is_editable: bool,
+        #[property(ref, on_changed=on_is_editable_changed)]
+        validator: Option<Box<dyn Validator>>,
+        selected_index: Option<usize>,
+        popup: Option<Window>,
+        filter_timer: Option<Timer>,
+        selection_changed_timer: Option<Timer>,
+    }
+}
+
+impl ComboBox {
+    fn init(tree: &mut WindowTree, window: Window) -> Result<(), Error> {
+        window.palette_mut(tree, |palette| {
+            palette.set(0, Color::Palette(COLOR_INPUT_LINE));
+            palette.set(1, Color::Palette(COLOR_INPUT_LINE_FOCUSED));
+            palette.set(2, Color::Palette(COLOR_DISABLED));
+            palette.set(3, Color::Palette(COLOR_INPUT_LINE_FOCUSED_DISABLED));
+        });
+        let input_line = InputLine::new(tree, Some(window), None)?;
+        input_line.set_focus_click(tree, Some(Focus::Primary));
+        Ok(())
+    }
+
+    fn drop_popup(&mut self, tree: &mut WindowTree, app: &mut dyn App) {
+        if let Some(filter_timer) = self.filter_timer.take() {
+            filter_timer.drop_timer(tree);
+        }
+        if let Some(selection_changed_timer) = self.selection_changed_timer.take() {
+            selection_changed_timer.drop_timer(tree);
+        }
+        if let Some(popup) = self.popup.take() {
+            popup.drop_window(tree, app);
+        }
+    }
+
+    fn input_line(tree: &WindowTree, window: Window) -> Window {
+        window.first_child(tree).unwrap()
+    }
+
+    pub fn text<'a>(tree: &'a WindowTree, window: Window) -> &'a String {
+        InputLine::text(tree, Self::input_line(tree, window))
+    }
+
+    pub fn set_text<'a>(tree: &mut WindowTree, window: Window, value: impl Into<Cow<'a, str>>) {
+        let input_line = Self::input_line(tree, window);
+        InputLine::set_text(tree, input_line, value);
+        Self::sync_selected_index(tree, window, None);
+    }
+
+    pub fn selected_index(tree: &WindowTree, window: Window) -> Option<usize> {
+        window.data::<ComboBox>(tree).selected_index
+    }
+
+    pub fn set_selected_index(tree: &mut WindowTree, window: Window, value: Option<usize>) {
+        let value = value.filter(|&x| x < window.data::<ComboBox>(tree).items.len());
+        Self::set_selected_index_raw(tree, window, value, None);
+        Self::reset_text(tree, window);
+    }
+
+    pub fn is_drop_down_open(tree: &WindowTree, window: Window) -> bool {
+        window.data::<ComboBox>(tree).popup.is_some()
+    }
+
+    fn sync_selected_index(tree: &mut WindowTree, window: Window, app: Option<&mut dyn App>) {
+        let text = Self::text(tree, window);
+        let index = window.data::<ComboBox>(tree).items.iter().position(|x| x == text);
+        Self::set_selected_index_raw(tree, window, index, app);
+    }
+
+    fn reset_text(tree: &mut WindowTree, window: Window) {
+        let data = window.data::<ComboBox>(tree);
+        let text = data.selected_index.map_or_else(String::new, |x| data.items[x].clone());
+        let input_line = Self::input_line(tree, window);
+        InputLine::set_text(tree, input_line, text);
+    }
+
+    fn set_selected_index_raw(
+        tree: &mut WindowTree,
+        window: Window,
+        value: Option<usize>,
+        app: Option<&mut dyn App>
+    ) {
+        let data = window.data_mut::<ComboBox>(tree);
+        if data.selected_index == value { return; }
+        data.selected_index = value;
+        if let Some(app) = app {
+            window.raise(tree, Event::Cmd(CMD_COMBO_BOX_SELECTION_CHANGED), app);
+        } else {
+            let selection_changed_timer = Timer::new(tree, 0, Box::new(move |tree, app| {
+                window.data_mut::<ComboBox>(tree).selection_changed_timer = None;
+                window.raise(tree, Event::Cmd(CMD_COMBO_BOX_SELECTION_CHANGED), app);
+            }));
+            let data = window.data_mut::<ComboBox>(tree);
+            if let Some(old_timer) = data.selection_changed_timer.replace(selection_changed_timer) {
+                old_timer.drop_timer(tree);
+            }
+        }
+    }
+
+    fn on_items_changed(tree: &mut WindowTree, window: Window) {
+        let data = window.data::<ComboBox>(tree);
+        let has_popup = data.popup.is_some();
+        if data.is_editable {
+            Self::sync_selected_index(tree, window, None);
+        } else {
+            let selected_index = data.selected_index.filter(|&x| x < data.items.len());
+            Self::set_selected_index_raw(tree, window, selected_index, None);
+            Self::reset_text(tree, window);
+        }
+        if has_popup {
+            Self::filter(tree, window, false);
+        }
+        window.invalidate_measure(tree);
+    }
+
+    fn on_is_editable_changed(tree: &mut WindowTree, window: Window) {
+        let data = window.data::<ComboBox>(tree);
+        let validator = if data.is_editable { data.validator.clone() } else { None };
+        let input_line = Self::input_line(tree, window);
+        InputLine::set_validator(tree, input_line, validator);
+        if window.data::<ComboBox>(tree).is_editable {
+            Self::sync_selected_index(tree, window, None);
+        } else {
+            Self::reset_text(tree, window);
+        }
+    }
+
+    fn filter(tree: &mut WindowTree, window: Window, by_text: bool) {
+        let data = window.data::<ComboBox>(tree);
+        let text = InputLine::text(tree, Self::input_line(tree, window));
+        let prefix = text.to_lowercase();
+        let indices = (0 .. data.items.len())
+            .filter(|&i| !by_text || starts_with_ignore_case(&data.items[i], &prefix))
+            .collect::<Vec<_>>();
+        let selected = data.selected_index.and_then(|x| indices.iter().position(|&i| i == x))
+            .or(if by_text && !indices.is_empty() { Some(0) } else { None });
+        let popup = data.popup;
+        let items = indices.iter().map(|&i| data.items[i].clone()).collect::<Vec<_>>();
+        let popup = if let Some(popup) = popup {
+            popup
+        } else {
+            match ComboBoxPopup::new_popup(tree, window) {
+                Ok(popup) => {
+                    window.data_mut::<ComboBox>(tree).popup = Some(popup);
+                    popup
+                },
+                Err(_) => return,
+            }
+        };
+        popup.data_mut::<ComboBoxPopup>(tree).indices = indices;
+        popup.invalidate_measure(tree);
+        let list = ComboBoxPopup::list(tree, popup);
+        ListBox::set_items(tree, list, items);
+        ListBox::set_selected_index(tree, list, selected);
+    }
+
+    fn close(tree: &mut WindowTree, window: Window, app: &mut dyn App) {
+        if let Some(popup) = window.data_mut::<ComboBox>(tree).popup.take() {
+            popup.drop_window(tree, app);
+        }
+    }
+
+    fn commit(tree: &mut WindowTree, window: Window, app: &mut dyn App) {
+        let Some(popup) = window.data::<ComboBox>(tree).popup else { return; };
+        let list = ComboBoxPopup::list(tree, popup);
+        let index = ListBox::selected_index(tree, list).map(|x| popup.data::<ComboBoxPopup>(tree).indices[x]);
+        Self::close(tree, window, app);
+        if let Some(index) = index {
+            Self::set_selected_index_raw(tree, window, Some(index), Some(app));
+            Self::reset_text(tree, window);
+        } else {
+            Self::cancel(tree, window, app);
+        }
+    }
+
+    fn cancel(tree: &mut WindowTree, window: Window, app: &mut dyn App) {
+        Self::close(tree, window, app);
+        if window.data::<ComboBox>(tree).is_editable {
+            Self::sync_selected_index(tree, window, Some(app));
+        } else {
+            Self::reset_text(tree, window);
+        }
+    }
+
+    fn schedule_filter(tree: &mut WindowTree, window: Window) {
+        let filter_timer = Timer::new(tree, 0, Box::new(move |tree, _app| {
+            window.data_mut::<ComboBox>(tree).filter_timer = None;
+            ComboBox::filter(tree, window, true);
+        }));
+        let data = window.data_mut::<ComboBox>(tree);
+        if let Some(old_timer) = data.filter_timer.replace(filter_timer) {
+            old_timer.drop_timer(tree);
+        }
+    }
+
+    fn step(tree: &mut WindowTree, window: Window, forward: bool, app: &mut dyn App) {
+        let data = window.data::<ComboBox>(tree);
+        let Some(last) = data.items.len().checked_sub(1) else { return; };
+        let index = match (data.selected_index, forward) {
+            (None, _) => 0,
+            (Some(i), true) => min(i + 1, last),
+            (Some(i), false) => i.saturating_sub(1),
+        };
+        Self::set_selected_index_raw(tree, window, Some(index), Some(app));
+        Self::reset_text(tree, window);
+    }
+
+    fn preview_key(tree: &mut WindowTree, window: Window, key: Key, mods: Mods, app: &mut dyn App) -> bool {
+        if let Some(popup) = window.data::<ComboBox>(tree).popup {
+            match (key, mods) {
+                (Key::Up | Key::Down | Key::PageUp | Key::PageDown, Mods::NONE) => {
+                    let list = ComboBoxPopup::list(tree, popup);
+                    list.raise(tree, Event::Key(key, mods), app);
+                    return true;
+                },
+                (Key::Enter | Key::F4, Mods::NONE) | (Key::Up | Key::Down, Mods::ALT) => {
+                    Self::commit(tree, window, app);
+                    return true;
+                },
+                (Key::Escape, Mods::NONE) => {
+                    Self::cancel(tree, window, app);
+                    return true;
+                },
+                (Key::Tab, _) => {
+                    Self::commit(tree, window, app);
+                    return false;
+                },
+                _ => { },
+            }
+        } else {
+            match (key, mods) {
+                (Key::F4, Mods::NONE) | (Key::Down, Mods::ALT) => {
+                    Self::filter(tree, window, false);
+                    return true;
+                },
+                (Key::Up, Mods::NONE) => {
+                    Self::step(tree, window, false, app);
+                    return true;
+                },
+                (Key::Down, Mods::NONE) => {
+                    Self::step(tree, window, true, app);
+                    return true;
+                },
+                _ => { },
+            }
+        }
+        let is_editable = window.data::<ComboBox>(tree).is_editable;
+        match (key, mods) {
+            (Key::Char(_), mods) if mods.without(Mods::SHIFT).is_empty() => {
+                if !is_editable { return true; }
+                Self::schedule_filter(tree, window);
+            },
+            (Key::Backspace | Key::Delete | Key::Ctrl(Ctrl::X | Ctrl::V), Mods::NONE) => {
+                if !is_editable { return true; }
+                Self::schedule_filter(tree, window);
+            },
+            (Key::Ctrl(Ctrl::Z | Ctrl::Y), Mods::NONE) if !is_editable => return true,
+            _ => { },
+        }
+        false
+    }
+}
+
+#[derive(Clone, Default)]
+struct ComboBoxWidget;
+
+impl_supports_interfaces!(ComboBoxWidget);
+
+impl Widget for ComboBoxWidget {
+    fn new(&self) -> Box<dyn WidgetData> {
+        Box::new(ComboBox {
+            items: Vec::new(),
+            is_editable: false,
+            validator: None,
+            selected_index: None,
+            popup: None,
+            filter_timer: None,
+            selection_changed_timer: None,
+        })
+    }
+
+    fn clone_data(
+        &self,
+        tree: &mut WindowTree,
+        source: Window,
+        dest: Window,
+        clone_window: Box<dyn Fn(&WindowTree, Window) -> Window>,
+    ) {
+        ComboBox::clone(tree, source, dest, clone_window);
+    }
+
+    fn render(
+        &self,
+        tree: &WindowTree,
+        window: Window,
+        rp: &mut RenderPort,
+        _app: &mut dyn App,
+    ) {
+        let bounds = window.inner_bounds(tree);
+        let focused = ComboBox::input_line(tree, window).is_focused(tree);
+        let is_enabled = window.actual_is_enabled(tree);
+        let color = match (is_enabled, focused) {
+            (true, false) => 0,
+            (true, true) => 1,
+            (false, false) => 2,
+            (false, true) => 3,
+        };
+        rp.text(Point { x: bounds.r_inner(), y: 0 }, window.color(tree, color), "▼");
+    }
+
+    fn measure(
+        &self,
+        tree: &mut WindowTree,
+        window: Window,
+        available_width: Option<i16>,
+        _available_height: Option<i16>,
+        app: &mut dyn App,
+    ) -> Vector {
+        let items_width = window.data::<ComboBox>(tree).items.iter()
+            .map(|x| text_width(x))
+            .fold(0i16, max)
+        ;
+        let width = available_width.unwrap_or(items_width.wrapping_add(3));
+        let input_line = ComboBox::input_line(tree, window);
+        input_line.measure(tree, Some(width.wrapping_sub(1)), Some(1), app);
+        Vector { x: width, y: 1 }
+    }
+
+    fn arrange(
+        &self,
+        tree: &mut WindowTree,
+        window: Window,
+        final_inner_bounds: Rect,
+        app: &mut dyn App,
+    ) -> Vector {
+        let input_line = ComboBox::input_line(tree, window);
+        let width = final_inner_bounds.w().wrapping_sub(1);
+        input_line.arrange(tree, Rect { tl: Point { x: 0, y: 0 }, size: Vector { x: width, y: 1 } }, app);
+        Vector { x: final_inner_bounds.w(), y: 1 }
+    }
+
+    fn update(
+        &self,
+        tree: &mut WindowTree,
+        window: Window,
+        event: Event,
+        event_source: Window,
+        app: &mut dyn App,
+    ) -> bool {
+        let input_line = ComboBox::input_line(tree, window);
+        match event {
+            Event::Cmd(CMD_GOT_PRIMARY_FOCUS) => {
+                if event_source == window {
+                    input_line.set_focused_primary(tree, true);
+                }
+                window.invalidate_render(tree);
+                false
+            },
+            Event::Cmd(CMD_LOST_PRIMARY_FOCUS) => {
+                if event_source == input_line {
+                    ComboBox::cancel(tree, window, app);
+                }
+                window.invalidate_render(tree);
+                false
+            },
+            Event::PreviewKey(key, mods) if window.actual_is_enabled(tree) => {
+                ComboBox::preview_key(tree, window, key, mods, app)
+            },
+            Event::PreviewPaste if window.actual_is_enabled(tree) => {
+                if !window.data::<ComboBox>(tree).is_editable { return true; }
+                ComboBox::schedule_filter(tree, window);
+                false
+            },
+            Event::Key(Key::Tab, mods) if mods == Mods::NONE || mods == Mods::SHIFT => {
+                let focus = if mods == Mods::NONE { window.focus_tab(tree) } else { window.focus_back_tab(tree) };
+                if focus == window { return false; }
+                focus.set_focused_primary(tree, true);
+                true
+            },
+            Event::PreviewLmbDown(..) if window.actual_is_enabled(tree) => {
+                if !window.data::<ComboBox>(tree).is_editable {
+                    ComboBox::filter(tree, window, false);
+                }
+                false
+            },
+            Event::LmbDown(..) if event_source == window && window.actual_is_enabled(tree) => {
+                input_line.set_focused_primary(tree, true);
+                ComboBox::filter(tree, window, false);
+                true
+            },
+            _ => false
+        }
+    }
+}
+
+struct ComboBoxPopup {
+    combo_box: Option<Window>,
+    indices: Vec<usize>,
+}
+
+impl WidgetData for ComboBoxPopup { }
+
+impl ComboBoxPopup {
+    fn new_popup(tree: &mut WindowTree, combo_box: Window) -> Result<Window, Error> {
+        let top = tree.first_child().map(|x| x.prev(tree));
+        let popup = Window::new(tree, Box::new(ComboBoxPopupWidget), None, None)?;
+        popup.move_z(tree, top);
+        popup.data_mut::<ComboBoxPopup>(tree).combo_box = Some(combo_box);
+        let list = ListBox::new(tree, Some(popup), None)?;
+        list.set_focus_click(tree, None);
+        Ok(popup)
+    }
+
+    fn list(tree: &WindowTree, popup: Window) -> Window {
+        popup.first_child(tree).unwrap()
+    }
+}
+
+#[derive(Clone, Default)]
+struct ComboBoxPopupWidget;
+
+impl_supports_interfaces!(ComboBoxPopupWidget);
+
+impl Widget for ComboBoxPopupWidget {
+    fn new(&self) -> Box<dyn WidgetData> {
+        Box::new(ComboBoxPopup {
+            combo_box: None,
+            indices: Vec::new(),
+        })
+    }
+
+    fn clone_data(
+        &self,
+        _tree: &mut WindowTree,
+        _source: Window,
+        _dest: Window,
+        _clone_window: Box<dyn Fn(&WindowTree, Window) -> Window>,
+    ) { }
+
+    fn render(
+        &self,
+        _tree: &WindowTree,
+        _window: Window,
+        _rp: &mut RenderPort,
+        _app: &mut dyn App,
+    ) { }
+
+    fn measure(
+        &self,
+        _tree: &mut WindowTree,
+        _window: Window,
+        available_width: Option<i16>,
+        available_height: Option<i16>,
+        _app: &mut dyn App,
+    ) -> Vector {
+        Vector { x: available_width.unwrap_or(0), y: available_height.unwrap_or(0) }
+    }
+
+    fn arrange(
+        &self,
+        tree: &mut WindowTree,
+        window: Window,
+        final_inner_bounds: Rect,
+        app: &mut dyn App,
+    ) -> Vector {
+        let data = window.data::<ComboBoxPopup>(tree);
+        let anchor = data.combo_box.unwrap().screen_bounds(tree);
+        let rows = data.indices.len().clamp(1, DROP_DOWN_ROWS) as i16;
+        let y = if anchor.b().wrapping_add(rows) <= final_inner_bounds.h() || anchor.t() < rows {
+            anchor.b()
+        } else {
+            anchor.t().wrapping_sub(rows)
+        };
+        let bounds = Rect { tl: Point { x: anchor.l(), y }, size: Vector { x: anchor.w(), y: rows } };
+        let list = ComboBoxPopup::list(tree, window);
+        list.measure(tree, Some(bounds.w()), Some(bounds.h()), app);
+        list.arrange(tree, bounds, app);
+        final_inner_bounds.size
+    }
+
+    fn update(
+        &self,
+        tree: &mut WindowTree,
+        window: Window,
+        event: Event,
+        event_source: Window,
+        app: &mut dyn App,
+    ) -> bool {
+        let combo_box = window.data::<ComboBoxPopup>(tree).combo_box.unwrap();
+        match event {
+            Event::LmbUp if event_source != window => {
+                ComboBox::commit(tree, combo_box, app);
+                true
+            },
+            Event::LmbDown(..) | Event::RmbDown(..) | Event::MmbDown(..) if event_source == window => {
+                ComboBox::cancel(tree, combo_box, app);
+                true
+            },
+            Event::LmbUp | Event::RmbUp | Event::MmbUp | Event::DoubleClick(..) | Event::Wheel(..) => {
+                event_source == window
+            },
+            _ => false
+        }
+    }
+}
@@ -0,0 +1,678 @@
+//! A table of rows provided by a [`DataGridSource`], with sortable and resizable columns.
+//!
+//! Unlike `ListBox` and `TreeView`, `DataGrid` is not built on `VirtItemsPresenter`.
+//! The presenter keeps its offset and extent in `i16`, so it cannot scroll past 32767 items,
+//! while a grid source may have millions of rows. Instead the grid draws the visible rows itself
+//! and keeps a `u32` row offset. It reports that offset to the scroll viewer and scroll bars.
+//!
+//! Rows are never all read. So `Auto` columns are sized from the rows displayed so far, and they only grow.
+//! This keeps the widths stable while scrolling. They are measured anew when the columns or the source change.
+
+use crate::{widget, GridLength, WHEEL_LINES};
+use crate::grid::{Track, track_sizes};
+use crate::scroll_bar::{BoundScrollBars, ScrollBarState};
+use crate::virt_scroll_viewer::*;
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cmp::{Ordering, max, min};
+use core::ops::Range;
+use dyn_clone::{DynClone, clone_trait_object};
+use dynamic_cast::impl_supports_interfaces;
+use tuifw_screen_base::{HAlign, Key, Mods, MouseButton, Point, Rect, Vector, Error, char_width};
+use tuifw_screen_base::text_width;
+use tuifw_window::{Event, RenderPort, Timer, Widget, WidgetData, Window, WindowTree, App, Color, Focus};
+use tuifw_window::TextColor;
+use tuifw_window::{CMD_GOT_PRIMARY_FOCUS, CMD_LOST_PRIMARY_FOCUS, CMD_GOT_SECONDARY_FOCUS, CMD_LOST_SECONDARY_FOCUS};
+use tuifw_window::{COLOR_LIST_BOX, COLOR_LIST_BOX_SELECTED, COLOR_LIST_BOX_FOCUSED};
+use tuifw_window::{COLOR_LIST_BOX_FOCUSED_SELECTED, COLOR_DISABLED};
+use tuifw_window::{COLOR_DATA_GRID_HEADER, COLOR_DATA_GRID_HEADER_FOCUSED};
+
+pub const CMD_DATA_GRID_SELECTION_CHANGED: u16 = 220;
+
+#[derive(Debug, Clone)]
+pub struct DataGridColumn {
+    pub header: String,
+    pub width: GridLength,
+    pub align: HAlign,
+}
+
+pub trait DataGridSource: DynClone {
+    fn row_count(&self) -> usize;
+
+    fn cell(&self, row: usize, column: usize) -> String;
+
+    /// Compares two rows by the given column. Used to sort the grid.
+    ///
+    /// Called `O(n log n)` times per sort, so it should not build the cell texts.
+    fn compare(&self, column: usize, a: usize, b: usize) -> Ordering;
+}
+
+clone_trait_object!(DataGridSource);
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum SortDirection {
+    Ascending,
+    Descending,
+}
+
+struct Resize {
+    column: usize,
+    start_x: i16,
+    start_width: i16,
+}
+
+fn truncate(text: &str, width: i16) -> &str {
+    let mut text_width = 0i16;
+    for (i, c) in text.char_indices() {
+        text_width = text_width.saturating_add(char_width(c));
+        if text_width > width { return &text[.. i]; }
+    }
+    text
+}
+
+fn aligned_text(rp: &mut RenderPort, p: Point, width: i16, align: HAlign, color: impl TextColor, text: &str) {
+    let text = truncate(text, width);
+    let offset = match align {
+        HAlign::Left => 0,
+        HAlign::Center => (width - text_width(text)) / 2,
+        HAlign::Right => width - text_width(text),
+    };
+    rp.text(Point { x: p.x.wrapping_add(offset), y: p.y }, color, text);
+}
+
+widget! {
+    #[widget(DataGridWidget, init=init, drop=drop_timers)]
+    pub struct DataGrid {
+        #[property(ref, arrange, on_changed=on_columns_changed)]
+        columns: Vec<DataGridColumn>,
+        #[property(ref, on_changed=on_source_changed)]
+        source: Option<Box<dyn DataGridSource>>,
+        #[property(copy, render)]
+        cell_selection: bool,
+        order: Vec<usize>,
+        sort: Option<(usize, SortDirection)>,
+        widths: Vec<i16>,
+        auto_widths: Vec<i16>,
+        current_row: Option<usize>,
+        current_column: usize,
+        offset: u32,
+        viewport: i16,
+        h_offset: i16,
+        h_viewport: i16,
+        resize: Option<Resize>,
+        selection_changed_timer: Option<Timer>,
+        scroll_bars: BoundScrollBars,
+    }
+}
+
+impl DataGrid {
+    fn init(tree: &mut WindowTree, window: Window) -> Result<(), Error> {
+        window.palette_mut(tree, |palette| {
+            palette.set(0, Color::Palette(COLOR_LIST_BOX));
+            palette.set(1, Color::Palette(COLOR_LIST_BOX_SELECTED));
+            palette.set(2, Color::Palette(COLOR_LIST_BOX_FOCUSED));
+            palette.set(3, Color::Palette(COLOR_LIST_BOX_FOCUSED_SELECTED));
+            palette.set(4, Color::Palette(COLOR_DISABLED));
+            palette.set(5, Color::Palette(COLOR_DATA_GRID_HEADER));
+            palette.set(6, Color::Palette(COLOR_DATA_GRID_HEADER_FOCUSED));
+        });
+        window.set_focus_click(tree, Some(Focus::Primary));
+        Ok(())
+    }
+
+    fn drop_timers(&mut self, tree: &mut WindowTree, _app: &mut dyn App) {
+        if let Some(selection_changed_timer) = self.selection_changed_timer.take() {
+            selection_changed_timer.drop_timer(tree);
+        }
+        self.scroll_bars.unbind(tree);
+    }
+
+    /// Returns the source index of the selected row.
+    pub fn selected_row(tree: &WindowTree, window: Window) -> Option<usize> {
+        let data = window.data::<DataGrid>(tree);
+        data.current_row.map(|x| data.order[x])
+    }
+
+    /// Selects the row with the given source index.
+    pub fn set_selected_row(tree: &mut WindowTree, window: Window, value: Option<usize>) {
+        let data = window.data::<DataGrid>(tree);
+        let row = value.and_then(|value| data.order.iter().position(|&x| x == value));
+        let column = data.current_column;
+        Self::set_current_raw(tree, window, row, column, None);
+        Self::bring_current_into_view(tree, window);
+    }
+
+    pub fn selected_column(tree: &WindowTree, window: Window) -> usize {
+        window.data::<DataGrid>(tree).current_column
+    }
+
+    pub fn set_selected_column(tree: &mut WindowTree, window: Window, value: usize) {
+        let data = window.data::<DataGrid>(tree);
+        let row = data.current_row;
+        let value = min(value, data.columns.len().saturating_sub(1));
+        Self::set_current_raw(tree, window, row, value, None);
+        Self::bring_current_into_view(tree, window);
+    }
+
+    pub fn sort(tree: &WindowTree, window: Window) -> Option<(usize, SortDirection)> {
+        window.data::<DataGrid>(tree).sort
+    }
+
+    pub fn set_sort(tree: &mut WindowTree, window: Window, value: Option<(usize, SortDirection)>) {
+        let data = window.data_mut::<DataGrid>(tree);
+        let value = value.filter(|&(column, _)| column < data.columns.len());
+        if data.sort == value { return; }
+        data.sort = value;
+        Self::apply_sort(tree, window);
+    }
+
+    /// Returns the actual width of a column, including its right separator.
+    pub fn column_width(tree: &WindowTree, window: Window, column: usize) -> i16 {
+        window.data::<DataGrid>(tree).widths.get(column).copied().unwrap_or(0)
+    }
+
+    /// Makes the column fixed-width.
+    pub fn set_column_width(tree: &mut WindowTree, window: Window, column: usize, value: i16) {
+        if column >= window.data::<DataGrid>(tree).columns.len() { return; }
+        let value = max(2, value);
+        DataGrid::columns_mut(tree, window, |columns| columns[column].width = GridLength::Fixed(value));
+    }
+
+    fn on_columns_changed(tree: &mut WindowTree, window: Window) {
+        let data = window.data_mut::<DataGrid>(tree);
+        let len = data.columns.len();
+        data.auto_widths.clear();
+        data.current_column = min(data.current_column, len.saturating_sub(1));
+        if data.sort.is_some_and(|(column, _)| column >= len) {
+            data.sort = None;
+            Self::apply_sort(tree, window);
+        }
+        window.invalidate_render(tree);
+    }
+
+    fn on_source_changed(tree: &mut WindowTree, window: Window) {
+        let data = window.data_mut::<DataGrid>(tree);
+        let len = data.source.as_ref().map_or(0, |x| x.row_count());
+        data.order = (0 .. len).collect();
+        data.auto_widths.clear();
+        data.current_row = None;
+        data.offset = 0;
+        window.invalidate_render(tree);
+        Self::sync_scroll_bars(tree, window, true);
+        Self::apply_sort(tree, window);
+        let column = window.data::<DataGrid>(tree).current_column;
+        Self::set_current_raw(tree, window, None, column, None);
+        window.invalidate_arrange(tree);
+    }
+
+    fn apply_sort(tree: &mut WindowTree, window: Window) {
+        let data = window.data_mut::<DataGrid>(tree);
+        let selected = data.current_row.map(|x| data.order[x]);
+        if let Some(source) = data.source.as_ref() {
+            match data.sort {
+                None => data.order.sort_unstable(),
+                Some((column, SortDirection::Ascending)) =>
+                    data.order.sort_by(|&a, &b| source.compare(column, a, b).then(a.cmp(&b))),
+                Some((column, SortDirection::Descending)) =>
+                    data.order.sort_by(|&a, &b| source.compare(column, b, a).then(a.cmp(&b))),
+            }
+        }
+        data.current_row = selected.and_then(|selected| data.order.iter().position(|&x| x == selected));
+        window.invalidate_arrange(tree);
+        window.invalidate_render(tree);
+        Self::bring_current_into_view(tree, window);
+    }
+
+    fn set_current_raw(
+        tree: &mut WindowTree,
+        window: Window,
+        row: Option<usize>,
+        column: usize,
+        app: Option<&mut dyn App>
+    ) {
+        let data = window.data_mut::<DataGrid>(tree);
+        let changed = data.current_row != row || (data.cell_selection && data.current_column != column);
+        data.current_row = row;
+        data.current_column = column;
+        window.invalidate_render(tree);
+        if !changed { return; }
+        if let Some(app) = app {
+            window.raise(tree, Event::Cmd(CMD_DATA_GRID_SELECTION_CHANGED), app);
+        } else {
+            let selection_changed_timer = Timer::new(tree, 0, Box::new(move |tree, app| {
+                window.data_mut::<DataGrid>(tree).selection_changed_timer = None;
+                window.raise(tree, Event::Cmd(CMD_DATA_GRID_SELECTION_CHANGED), app);
+            }));
+            let data = window.data_mut::<DataGrid>(tree);
+            if let Some(old_timer) = data.selection_changed_timer.replace(selection_changed_timer) {
+                old_timer.drop_timer(tree);
+            }
+        }
+    }
+
+    fn bring_current_into_view(tree: &mut WindowTree, window: Window) {
+        let data = window.data::<DataGrid>(tree);
+        let column_start = data.widths.iter().take(data.current_column).fold(0i16, |s, &x| s.saturating_add(x));
+        let column_end = column_start.saturating_add(data.widths.get(data.current_column).copied().unwrap_or(0));
+        let h_viewport = max(1, data.h_viewport);
+        if column_start < data.h_offset {
+            Self::set_h_offset(tree, window, column_start);
+        } else if column_end > data.h_offset.saturating_add(h_viewport) {
+            Self::set_h_offset(tree, window, min(column_start, column_end.wrapping_sub(h_viewport)));
+        }
+        let data = window.data::<DataGrid>(tree);
+        let Some(row) = data.current_row else { return; };
+        let row = u32::try_from(row).unwrap_or(u32::MAX);
+        let viewport = u32::from(max(1, data.viewport) as u16);
+        let offset = if row < data.offset {
+            row
+        } else if row >= data.offset.saturating_add(viewport) {
+            row - viewport + 1
+        } else {
+            return;
+        };
+        Self::set_v_offset(tree, window, offset);
+    }
+
+    fn total_width(&self) -> i16 {
+        self.widths.iter().fold(0i16, |s, &x| s.saturating_add(x))
+    }
+
+    fn row_count(&self) -> u32 {
+        u32::try_from(self.order.len()).unwrap_or(u32::MAX)
+    }
+
+    /// Returns the range of row positions fitting the viewport.
+    fn visible_rows(&self) -> Range<usize> {
+        let start = min(usize::try_from(self.offset).unwrap_or(usize::MAX), self.order.len());
+        let end = min(start.saturating_add(usize::from(self.viewport as u16)), self.order.len());
+        start .. end
+    }
+
+    fn state(&self, vertical: bool) -> ScrollBarState {
+        if vertical {
+            ScrollBarState {
+                offset: self.offset,
+                viewport: u32::from(self.viewport as u16),
+                extent: self.row_count(),
+            }
+        } else {
+            ScrollBarState {
+                offset: u32::from(self.h_offset as u16),
+                viewport: u32::from(self.h_viewport as u16),
+                extent: u32::from(self.total_width() as u16),
+            }
+        }
+    }
+
+    /// Passes the scroll state along the axis to the parent scroll viewer and the bound scroll bar.
+    fn sync_scroll_bars(tree: &mut WindowTree, window: Window, vertical: bool) {
+        let state = window.data::<DataGrid>(tree).state(vertical);
+        if let Some(parent) = window.parent(tree) {
+            if let Some(sv) = parent.widget_extension::<dyn VirtScrollViewerWidgetExtension>(tree) {
+                sv.set_extent(tree, parent, vertical, state.extent);
+                sv.set_viewport(tree, parent, vertical, state.viewport);
+                sv.set_offset(tree, parent, vertical, state.offset);
+            }
+        }
+        let scroll_bars = window.data::<DataGrid>(tree).scroll_bars;
+        scroll_bars.set_extent(tree, vertical, state.extent);
+        scroll_bars.set_viewport(tree, vertical, state.viewport);
+        scroll_bars.set_offset(tree, vertical, state.offset);
+    }
+
+    fn set_v_offset(tree: &mut WindowTree, window: Window, value: u32) {
+        let data = window.data_mut::<DataGrid>(tree);
+        let value = min(value, data.state(true).max_offset());
+        if data.offset == value { return; }
+        data.offset = value;
+        window.invalidate_arrange(tree);
+        window.invalidate_render(tree);
+        if let Some(parent) = window.parent(tree) {
+            if let Some(sv) = parent.widget_extension::<dyn VirtScrollViewerWidgetExtension>(tree) {
+                sv.set_offset(tree, parent, true, value);
+            }
+        }
+        let scroll_bars = window.data::<DataGrid>(tree).scroll_bars;
+        scroll_bars.set_offset(tree, true, value);
+    }
+
+    fn set_h_offset(tree: &mut WindowTree, window: Window, value: i16) {
+        let data = window.data_mut::<DataGrid>(tree);
+        let max_offset = max(0, data.total_width().wrapping_sub(data.h_viewport));
+        let value = min(max(0, value), max_offset);
+        if data.h_offset == value { return; }
+        data.h_offset = value;
+        window.invalidate_render(tree);
+        if let Some(parent) = window.parent(tree) {
+            if let Some(sv) = parent.widget_extension::<dyn VirtScrollViewerWidgetExtension>(tree) {
+                sv.set_offset(tree, parent, false, (value as u16).into());
+            }
+        }
+        let scroll_bars = window.data::<DataGrid>(tree).scroll_bars;
+        scroll_bars.set_offset(tree, false, (value as u16).into());
+    }
+
+    /// Returns the column under a point and whether the point is on the column right separator.
+    fn column_at(tree: &WindowTree, window: Window, x: i16) -> Option<(usize, bool)> {
+        let data = window.data::<DataGrid>(tree);
+        let x = x.wrapping_add(data.h_offset);
+        let mut start = 0i16;
+        for (column, &width) in data.widths.iter().enumerate() {
+            let end = start.saturating_add(width);
+            if (start .. end).contains(&x) {
+                return Some((column, x == end.wrapping_sub(1)));
+            }
+            start = end;
+        }
+        None
+    }
+
+    fn key_target(tree: &WindowTree, window: Window, key: Key) -> Option<usize> {
+        let data = window.data::<DataGrid>(tree);
+        let last = data.order.len().checked_sub(1)?;
+        let page = max(1, data.viewport) as usize;
+        let current = data.current_row;
+        Some(match key {
+            Key::Up => current.map_or(0, |x| x.saturating_sub(1)),
+            Key::Down => current.map_or(0, |x| min(x + 1, last)),
+            Key::PageUp => current.map_or(0, |x| x.saturating_sub(page)),
+            Key::PageDown => current.map_or(0, |x| min(x.saturating_add(page), last)),
+            Key::Home => 0,
+            Key::End => last,
+            _ => return None,
+        })
+    }
+
+    fn toggle_sort(tree: &mut WindowTree, window: Window, column: usize) {
+        let direction = match window.data::<DataGrid>(tree).sort {
+            Some((sort_column, SortDirection::Ascending)) if sort_column == column => SortDirection::Descending,
+            _ => SortDirection::Ascending,
+        };
+        Self::set_sort(tree, window, Some((column, direction)));
+    }
+
+    fn resize_column(tree: &mut WindowTree, window: Window, column: usize, delta: i16) {
+        let width = Self::column_width(tree, window, column);
+        Self::set_column_width(tree, window, column, width.saturating_add(delta));
+    }
+}
+
+#[derive(Clone, Default)]
+struct DataGridWidget;
+
+impl_supports_interfaces!(DataGridWidget: VirtScrollViewerWidgetExtension, VirtItemsPresenterWidgetExtension);
+
+impl VirtScrollViewerWidgetExtension for DataGridWidget {
+    fn set_offset(&self, _tree: &mut WindowTree, _window: Window, _vertical: bool, _value: u32) { }
+
+    fn set_viewport(&self, _tree: &mut WindowTree, _window: Window, _vertical: bool, _value: u32) { }
+
+    fn set_extent(&self, _tree: &mut WindowTree, _window: Window, _vertical: bool, _value: u32) { }
+
+    fn scroll_to(&self, tree: &mut WindowTree, window: Window, vertical: bool, value: u32) {
+        VirtItemsPresenterWidgetExtension::set_offset(self, tree, window, vertical, value);
+    }
+
+    fn bind_scroll_bar(&self, tree: &mut WindowTree, window: Window, vertical: bool, scroll_bar: Option<Window>) {
+        window.data_mut::<DataGrid>(tree).scroll_bars.bind(vertical, scroll_bar);
+        if scroll_bar.is_none() { return; }
+        DataGrid::sync_scroll_bars(tree, window, vertical);
+    }
+}
+
+impl VirtItemsPresenterWidgetExtension for DataGridWidget {
+    fn set_offset(&self, tree: &mut WindowTree, window: Window, vertical: bool, value: u32) {
+        if vertical {
+            DataGrid::set_v_offset(tree, window, value);
+        } else {
+            DataGrid::set_h_offset(tree, window, min(value, i16::MAX as u32) as i16);
+        }
+    }
+}
+
+impl Widget for DataGridWidget {
+    fn new(&self) -> Box<dyn WidgetData> {
+        Box::new(DataGrid {
+            columns: Vec::new(),
+            source: None,
+            cell_selection: false,
+            order: Vec::new(),
+            sort: None,
+            widths: Vec::new(),
+            auto_widths: Vec::new(),
+            current_row: None,
+            current_column: 0,
+            offset: 0,
+            viewport: 0,
+            h_offset: 0,
+            h_viewport: 0,
+            resize: None,
+            selection_changed_timer: None,
+            scroll_bars: BoundScrollBars::default(),
+        })
+    }
+
+    fn clone_data(
+        &self,
+        tree: &mut WindowTree,
+        source: Window,
+        dest: Window,
+        clone_window: Box<dyn Fn(&WindowTree, Window) -> Window>,
+    ) {
+        DataGrid::clone(tree, source, dest, clone_window);
+    }
+
+    fn render(
+        &self,
+        tree: &WindowTree,
+        window: Window,
+        rp: &mut RenderPort,
+        _app: &mut dyn App,
+    ) {
+        rp.fill_bg(window.color(tree, 0));
+        let bounds = window.inner_bounds(tree);
+        let data = window.data::<DataGrid>(tree);
+        let focused = window.is_focused(tree);
+        let header_color = window.color(tree, 5);
+        rp.h_line(bounds.tl, bounds.w(), false, header_color);
+        let mut x = data.h_offset.wrapping_neg();
+        for (index, (column, &width)) in data.columns.iter().zip(data.widths.iter()).enumerate() {
+            let color = window.color(tree, if focused && index == data.current_column { 6 } else { 5 });
+            for cell_x in 0 .. width.wrapping_sub(1) {
+                rp.text(Point { x: x.wrapping_add(cell_x), y: 0 }, color, " ");
+            }
+            aligned_text(rp, Point { x, y: 0 }, width.wrapping_sub(2), column.align, color, &column.header);
+            let indicator = match data.sort {
+                Some((sort_column, SortDirection::Ascending)) if sort_column == index => "▲",
+                Some((sort_column, SortDirection::Descending)) if sort_column == index => "▼",
+                _ => " ",
+            };
+            rp.text(Point { x: x.wrapping_add(width).wrapping_sub(2), y: 0 }, color, indicator);
+            rp.v_line(Point { x: x.wrapping_add(width).wrapping_sub(1), y: 0 }, 1, false, header_color);
+            x = x.wrapping_add(width);
+        }
+        let Some(source) = data.source.as_ref() else { return; };
+        let is_enabled = window.actual_is_enabled(tree);
+        let normal_color = window.color(tree, if is_enabled { 0 } else { 4 });
+        for (y, row) in data.visible_rows().enumerate() {
+            let y = (y as i16).wrapping_add(1);
+            let mut x = data.h_offset.wrapping_neg();
+            for (index, (column, &width)) in data.columns.iter().zip(data.widths.iter()).enumerate() {
+                let selected = data.current_row == Some(row) && (!data.cell_selection || index == data.current_column);
+                let color = match (is_enabled, selected, focused) {
+                    (false, _, _) | (true, false, _) => normal_color,
+                    (true, true, false) => window.color(tree, 1),
+                    (true, true, true) => window.color(tree, 3),
+                };
+                for cell_x in 0 .. width.wrapping_sub(1) {
+                    rp.text(Point { x: x.wrapping_add(cell_x), y }, color, " ");
+                }
+                let text = source.cell(data.order[row], index);
+                aligned_text(rp, Point { x, y }, width.wrapping_sub(1), column.align, color, &text);
+                rp.v_line(Point { x: x.wrapping_add(width).wrapping_sub(1), y }, 1, false, normal_color);
+                x = x.wrapping_add(width);
+            }
+            rp.text(Point { x, y }, normal_color, &" ".repeat(usize::from(max(0, bounds.w().wrapping_sub(x)) as u16)));
+        }
+    }
+
+    fn measure(
+        &self,
+        tree: &mut WindowTree,
+        window: Window,
+        available_width: Option<i16>,
+        available_height: Option<i16>,
+        _app: &mut dyn App,
+    ) -> Vector {
+        let data = window.data::<DataGrid>(tree);
+        Vector {
+            x: available_width.unwrap_or_else(|| data.total_width()),
+            y: available_height.map_or(2, |x| max(1, x)),
+        }
+    }
+
+    fn arrange(
+        &self,
+        tree: &mut WindowTree,
+        window: Window,
+        final_inner_bounds: Rect,
+        _app: &mut dyn App,
+    ) -> Vector {
+        let data = window.data_mut::<DataGrid>(tree);
+        data.viewport = max(0, final_inner_bounds.h().wrapping_sub(1));
+        data.offset = min(data.offset, data.state(true).max_offset());
+        data.auto_widths.resize(data.columns.len(), 0);
+        let rows = &data.order[data.visible_rows()];
+        if let Some(source) = data.source.as_ref() {
+            for (index, column) in data.columns.iter().enumerate() {
+                if column.width != GridLength::Auto { continue; }
+                let cells = rows.iter().map(|&row| text_width(&source.cell(row, index))).fold(0i16, max);
+                data.auto_widths[index] = max(data.auto_widths[index], cells);
+            }
+        }
+        let tracks = data.columns.iter().enumerate().map(|(index, column)| {
+            let desired = if column.width == GridLength::Auto {
+                let cells = data.auto_widths[index];
+                max(cells.saturating_add(1), text_width(&column.header).saturating_add(2)).saturating_add(1)
+            } else {
+                0
+            };
+            Track { start: index, span: 1, desired: i32::from(desired as u16) }
+        }).collect::<Vec<_>>();
+        let defs = data.columns.iter().map(|x| x.width).collect::<Vec<_>>();
+        data.widths = track_sizes(&defs, Some(final_inner_bounds.w()), &tracks);
+        data.h_viewport = final_inner_bounds.w();
+        let h_offset = data.h_offset;
+        window.invalidate_render(tree);
+        DataGrid::set_h_offset(tree, window, h_offset);
+        DataGrid::sync_scroll_bars(tree, window, false);
+        DataGrid::sync_scroll_bars(tree, window, true);
+        final_inner_bounds.size
+    }
+
+    fn update(
+        &self,
+        tree: &mut WindowTree,
+        window: Window,
+        event: Event,
+        _event_source: Window,
+        app: &mut dyn App,
+    ) -> bool {
+        match event {
+            Event::Cmd(CMD_GOT_PRIMARY_FOCUS) | Event::Cmd(CMD_GOT_SECONDARY_FOCUS) => {
+                let data = window.data::<DataGrid>(tree);
+                if data.current_row.is_none() && !data.order.is_empty() {
+                    let column = data.current_column;
+                    DataGrid::set_current_raw(tree, window, Some(0), column, Some(app));
+                    DataGrid::bring_current_into_view(tree, window);
+                }
+                window.invalidate_render(tree);
+                return false;
+            },
+            Event::Cmd(CMD_LOST_PRIMARY_FOCUS) | Event::Cmd(CMD_LOST_SECONDARY_FOCUS) => {
+                window.invalidate_render(tree);
+                return false;
+            },
+            _ => { },
+        }
+        if !window.actual_is_enabled(tree) { return false; }
+        match event {
+            Event::Key(
+                key @ (Key::Up | Key::Down | Key::PageUp | Key::PageDown | Key::Home | Key::End),
+                Mods::NONE
+            ) => {
+                let Some(row) = DataGrid::key_target(tree, window, key) else { return false; };
+                let column = window.data::<DataGrid>(tree).current_column;
+                DataGrid::set_current_raw(tree, window, Some(row), column, Some(app));
+                DataGrid::bring_current_into_view(tree, window);
+                true
+            },
+            Event::Key(key @ (Key::Left | Key::Right), Mods::NONE) => {
+                let data = window.data::<DataGrid>(tree);
+                let Some(last) = data.columns.len().checked_sub(1) else { return false; };
+                let row = data.current_row;
+                let column = if key == Key::Left {
+                    data.current_column.saturating_sub(1)
+                } else {
+                    min(data.current_column + 1, last)
+                };
+                DataGrid::set_current_raw(tree, window, row, column, Some(app));
+                DataGrid::bring_current_into_view(tree, window);
+                true
+            },
+            Event::Key(key @ (Key::Left | Key::Right), Mods::CTRL) => {
+                let column = window.data::<DataGrid>(tree).current_column;
+                DataGrid::resize_column(tree, window, column, if key == Key::Left { -1 } else { 1 });
+                true
+            },
+            Event::Key(key @ (Key::Up | Key::Down), Mods::CTRL) => {
+                let column = window.data::<DataGrid>(tree).current_column;
+                let direction = if key == Key::Up { SortDirection::Ascending } else { SortDirection::Descending };
+                DataGrid::set_sort(tree, window, Some((column, direction)));
+                true
+            },
+            Event::LmbDown(point, _) => {
+                let point = window.inner_point(point, tree);
+                let Some((column, separator)) = DataGrid::column_at(tree, window, point.x) else {
+                    return false;
+                };
+                if point.y == 0 {
+                    let data = window.data_mut::<DataGrid>(tree);
+                    let row = data.current_row;
+                    if separator {
+                        data.resize = Some(Resize { column, start_x: point.x, start_width: data.widths[column] });
+                    } else {
+                        DataGrid::toggle_sort(tree, window, column);
+                    }
+                    DataGrid::set_current_raw(tree, window, row, column, Some(app));
+                } else {
+                    let data = window.data::<DataGrid>(tree);
+                    let row = data.visible_rows().start.saturating_add(usize::from(point.y.wrapping_sub(1) as u16));
+                    if row >= data.visible_rows().end { return false; }
+                    DataGrid::set_current_raw(tree, window, Some(row), column, Some(app));
+                    DataGrid::bring_current_into_view(tree, window);
+                }
+                true
+            },
+            Event::Drag(point, MouseButton::Left, _) => {
+                let x = window.inner_point(point, tree).x;
+                let Some(resize) = window.data::<DataGrid>(tree).resize.as_ref() else { return false; };
+                let column = resize.column;
+                let width = resize.start_width.saturating_add(x.wrapping_sub(resize.start_x));
+                DataGrid::set_column_width(tree, window, column, width);
+                true
+            },
+            Event::LmbUp => window.data_mut::<DataGrid>(tree).resize.take().is_some(),
+            Event::Wheel(_, delta, _) => {
+                let offset = window.data::<DataGrid>(tree).offset;
+                let lines = u32::from(WHEEL_LINES).saturating_mul(u32::from(delta.unsigned_abs()));
+                let new_offset = if delta < 0 { offset.saturating_sub(lines) } else { offset.saturating_add(lines) };
+                DataGrid::set_v_offset(tree, window, new_offset);
+                window.data::<DataGrid>(tree).offset != offset
+            },
+            _ => false
+        }
+    }
+}
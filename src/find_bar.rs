@@ -0,0 +1,279 @@
+use crate::{widget, Button, CheckBox, Dock, DockPanel, InputLine, Label, StackPanel, TextEdit};
+use crate::CMD_CHECK_BOX_CLICK;
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use dynamic_cast::impl_supports_interfaces;
+use tuifw_screen_base::{Error, Key, Mods, Rect, Thickness, Vector};
+use tuifw_window::{App, Event, RenderPort, Widget, WidgetData, Window, WindowTree};
+use tuifw_window::CMD_GOT_PRIMARY_FOCUS;
+
+pub const CMD_FIND_BAR_FIND_NEXT: u16 = 270;
+pub const CMD_FIND_BAR_FIND_PREVIOUS: u16 = 271;
+pub const CMD_FIND_BAR_REPLACE: u16 = 272;
+pub const CMD_FIND_BAR_REPLACE_ALL: u16 = 273;
+pub const CMD_FIND_BAR_CLOSE: u16 = 274;
+
+const LABEL_WIDTH: i16 = 9;
+
+#[derive(Clone, Copy)]
+struct Parts {
+    search: Window,
+    replacement: Window,
+    case_sensitive: Window,
+    whole_word: Window,
+    regex: Option<Window>,
+}
+
+widget! {
+    #[widget(FindBarWidget, init=init)]
+    pub struct FindBar {
+        #[property(window)]
+        text_edit: Option<Window>,
+        parts: Option<Parts>,
+    }
+}
+
+impl FindBar {
+    fn init(tree: &mut WindowTree, window: Window) -> Result<(), Error> {
+        let panel = StackPanel::new(tree, Some(window), None)?;
+        let mut focusables = Vec::new();
+        let (search_row, search) = Self::build_row(
+            tree, panel, None, "~F~ind:",
+            &[("~N~ext", CMD_FIND_BAR_FIND_NEXT), ("~P~revious", CMD_FIND_BAR_FIND_PREVIOUS)],
+            &mut focusables
+        )?;
+        let (replacement_row, replacement) = Self::build_row(
+            tree, panel, Some(search_row), "R~e~place:",
+            &[("~R~eplace", CMD_FIND_BAR_REPLACE), ("Replace ~a~ll", CMD_FIND_BAR_REPLACE_ALL)],
+            &mut focusables
+        )?;
+        let options = StackPanel::new(tree, Some(panel), Some(replacement_row))?;
+        StackPanel::set_vertical(tree, options, false);
+        options.set_margin(tree, Thickness::new(i32::from(LABEL_WIDTH), 0, 0, 0));
+        let case_sensitive = Self::build_option(tree, options, None, "Match ~c~ase", &mut focusables)?;
+        let whole_word = Self::build_option(tree, options, Some(case_sensitive), "~W~hole word", &mut focusables)?;
+        #[cfg(feature="regex")]
+        let regex = Some(Self::build_option(tree, options, Some(whole_word), "Rege~x~", &mut focusables)?);
+        #[cfg(not(feature="regex"))]
+        let regex = None;
+        for (&focusable, &next) in focusables.iter().zip(focusables.iter().cycle().skip(1)) {
+            focusable.set_focus_tab(tree, next);
+            next.set_focus_back_tab(tree, focusable);
+        }
+        window.data_mut::<FindBar>(tree).parts = Some(Parts { search, replacement, case_sensitive, whole_word, regex });
+        Ok(())
+    }
+
+    fn build_row(
+        tree: &mut WindowTree,
+        panel: Window,
+        prev: Option<Window>,
+        label: &str,
+        buttons: &[(&str, u16)],
+        focusables: &mut Vec<Window>,
+    ) -> Result<(Window, Window), Error> {
+        let row = DockPanel::new(tree, Some(panel), prev)?;
+        let label_window = Label::new(tree, Some(row), None)?;
+        Label::set_text(tree, label_window, label);
+        label_window.set_width(tree, Some(LABEL_WIDTH));
+        DockPanel::set_dock(tree, label_window, Some(Dock::Left));
+        let mut prev = label_window;
+        let mut button_windows = Vec::new();
+        for &(text, cmd) in buttons.iter().rev() {
+            let button = Button::new(tree, Some(row), Some(prev))?;
+            Button::set_text(tree, button, text);
+            Button::set_cmd(tree, button, cmd);
+            DockPanel::set_dock(tree, button, Some(Dock::Right));
+            button_windows.insert(0, button);
+            prev = button;
+        }
+        let input_line = InputLine::new(tree, Some(row), Some(prev))?;
+        Label::set_focus(tree, label_window, Some(input_line));
+        focusables.push(input_line);
+        focusables.extend(button_windows);
+        Ok((row, input_line))
+    }
+
+    fn build_option(
+        tree: &mut WindowTree,
+        panel: Window,
+        prev: Option<Window>,
+        text: &str,
+        focusables: &mut Vec<Window>,
+    ) -> Result<Window, Error> {
+        let check_box = CheckBox::new(tree, Some(panel), prev)?;
+        CheckBox::set_text(tree, check_box, text);
+        check_box.set_margin(tree, Thickness::new(0, 0, 2, 0));
+        focusables.push(check_box);
+        Ok(check_box)
+    }
+
+    fn parts(tree: &WindowTree, window: Window) -> Parts {
+        window.data::<FindBar>(tree).parts.unwrap()
+    }
+
+    pub fn search<'a>(tree: &'a WindowTree, window: Window) -> &'a String {
+        InputLine::text(tree, Self::parts(tree, window).search)
+    }
+
+    pub fn set_search<'a>(tree: &mut WindowTree, window: Window, value: impl Into<Cow<'a, str>>) {
+        let search = Self::parts(tree, window).search;
+        InputLine::set_text(tree, search, value);
+    }
+
+    pub fn replacement<'a>(tree: &'a WindowTree, window: Window) -> &'a String {
+        InputLine::text(tree, Self::parts(tree, window).replacement)
+    }
+
+    pub fn set_replacement<'a>(tree: &mut WindowTree, window: Window, value: impl Into<Cow<'a, str>>) {
+        let replacement = Self::parts(tree, window).replacement;
+        InputLine::set_text(tree, replacement, value);
+    }
+
+    /// Copies the search pattern and options to the [`text_edit`](FindBar::text_edit).
+    fn sync(tree: &mut WindowTree, window: Window, text_edit: Window) {
+        let parts = Self::parts(tree, window);
+        let search = InputLine::text(tree, parts.search);
+        if TextEdit::search(tree, text_edit) != search {
+            let search = search.clone();
+            TextEdit::set_search(tree, text_edit, search);
+        }
+        let case_sensitive = CheckBox::is_on(tree, parts.case_sensitive);
+        if TextEdit::search_case_sensitive(tree, text_edit) != case_sensitive {
+            TextEdit::set_search_case_sensitive(tree, text_edit, case_sensitive);
+        }
+        let whole_word = CheckBox::is_on(tree, parts.whole_word);
+        if TextEdit::search_whole_word(tree, text_edit) != whole_word {
+            TextEdit::set_search_whole_word(tree, text_edit, whole_word);
+        }
+        let regex = parts.regex.is_some_and(|x| CheckBox::is_on(tree, x));
+        if TextEdit::search_regex(tree, text_edit) != regex {
+            TextEdit::set_search_regex(tree, text_edit, regex);
+        }
+    }
+
+    fn execute(tree: &mut WindowTree, window: Window, cmd: u16) -> bool {
+        let Some(text_edit) = window.data::<FindBar>(tree).text_edit else { return false; };
+        Self::sync(tree, window, text_edit);
+        let replacement = Self::replacement(tree, window).clone();
+        match cmd {
+            CMD_FIND_BAR_FIND_NEXT => { TextEdit::find_next(tree, text_edit); },
+            CMD_FIND_BAR_FIND_PREVIOUS => { TextEdit::find_previous(tree, text_edit); },
+            CMD_FIND_BAR_REPLACE => { TextEdit::replace(tree, text_edit, &replacement); },
+            CMD_FIND_BAR_REPLACE_ALL => { TextEdit::replace_all(tree, text_edit, &replacement); },
+            _ => unreachable!(),
+        }
+        true
+    }
+}
+
+#[derive(Clone, Default)]
+struct FindBarWidget;
+
+impl_supports_interfaces!(FindBarWidget);
+
+impl Widget for FindBarWidget {
+    fn new(&self) -> Box<dyn WidgetData> {
+        Box::new(FindBar {
+            text_edit: None,
+            parts: None,
+        })
+    }
+
+    fn clone_data(
+        &self,
+        tree: &mut WindowTree,
+        source: Window,
+        dest: Window,
+        clone_window: Box<dyn Fn(&WindowTree, Window) -> Window>,
+    ) {
+        let parts = FindBar::parts(tree, source);
+        let parts = Parts {
+            search: clone_window(tree, parts.search),
+            replacement: clone_window(tree, parts.replacement),
+            case_sensitive: clone_window(tree, parts.case_sensitive),
+            whole_word: clone_window(tree, parts.whole_word),
+            regex: parts.regex.map(|x| clone_window(tree, x)),
+        };
+        dest.data_mut::<FindBar>(tree).parts = Some(parts);
+        FindBar::clone(tree, source, dest, clone_window);
+    }
+
+    fn render(
+        &self,
+        _tree: &WindowTree,
+        _window: Window,
+        _rp: &mut RenderPort,
+        _app: &mut dyn App,
+    ) { }
+
+    fn measure(
+        &self,
+        tree: &mut WindowTree,
+        window: Window,
+        available_width: Option<i16>,
+        available_height: Option<i16>,
+        app: &mut dyn App,
+    ) -> Vector {
+        let panel = window.first_child(tree).unwrap();
+        panel.measure(tree, available_width, available_height, app);
+        panel.desired_size(tree)
+    }
+
+    fn arrange(
+        &self,
+        tree: &mut WindowTree,
+        window: Window,
+        final_inner_bounds: Rect,
+        app: &mut dyn App,
+    ) -> Vector {
+        let panel = window.first_child(tree).unwrap();
+        panel.arrange(tree, final_inner_bounds, app);
+        final_inner_bounds.size
+    }
+
+    fn update(
+        &self,
+        tree: &mut WindowTree,
+        window: Window,
+        event: Event,
+        event_source: Window,
+        app: &mut dyn App,
+    ) -> bool {
+        match event {
+            Event::Cmd(CMD_GOT_PRIMARY_FOCUS) if event_source == window => {
+                let search = FindBar::parts(tree, window).search;
+                search.set_focused_primary(tree, true);
+                false
+            },
+            Event::Cmd(CMD_CHECK_BOX_CLICK) => {
+                if let Some(text_edit) = window.data::<FindBar>(tree).text_edit {
+                    FindBar::sync(tree, window, text_edit);
+                }
+                true
+            },
+            Event::Cmd(
+                cmd @ (CMD_FIND_BAR_FIND_NEXT | CMD_FIND_BAR_FIND_PREVIOUS | CMD_FIND_BAR_REPLACE | CMD_FIND_BAR_REPLACE_ALL)
+            ) => FindBar::execute(tree, window, cmd),
+            Event::Cmd(CMD_FIND_BAR_CLOSE) => {
+                if let Some(text_edit) = window.data::<FindBar>(tree).text_edit {
+                    text_edit.set_focused_primary(tree, true);
+                }
+                false
+            },
+            Event::Key(Key::Enter, Mods::NONE) => {
+                let replacement = FindBar::parts(tree, window).replacement;
+                let cmd = if event_source == replacement { CMD_FIND_BAR_REPLACE } else { CMD_FIND_BAR_FIND_NEXT };
+                window.raise(tree, Event::Cmd(cmd), app);
+                true
+            },
+            Event::Key(Key::Escape, Mods::NONE) => {
+                window.raise(tree, Event::Cmd(CMD_FIND_BAR_CLOSE), app);
+                true
+            },
+            _ => false
+        }
+    }
+}
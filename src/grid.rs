@@ -0,0 +1,307 @@
+use crate::widget;
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::cmp::{max, min};
+use dynamic_cast::impl_supports_interfaces;
+use tuifw_screen_base::{Point, Rect, Vector};
+use tuifw_window::{Event, Layout, RenderPort, Widget, WidgetData, Window, WindowTree, App};
+
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub enum GridLength {
+    /// Sized to fit the content of single-span children.
+    Auto,
+    Fixed(i16),
+    /// Proportional share of the space left after fixed and auto tracks.
+    Star(f32),
+}
+
+#[derive(Clone)]
+struct GridLayout {
+    row: u16,
+    column: u16,
+    row_span: u16,
+    column_span: u16,
+}
+
+impl Layout for GridLayout { }
+
+impl Default for GridLayout {
+    fn default() -> Self { GridLayout { row: 0, column: 0, row_span: 1, column_span: 1 } }
+}
+
+widget! {
+    #[widget(GridWidget)]
+    pub struct Grid {
+        #[property(ref, measure)]
+        rows: Vec<GridLength>,
+        #[property(ref, measure)]
+        columns: Vec<GridLength>,
+    }
+}
+
+impl Grid {
+    pub fn row(tree: &WindowTree, window: Window) -> u16 {
+        window.layout::<GridLayout>(tree).map_or(0, |x| x.row)
+    }
+
+    pub fn set_row(tree: &mut WindowTree, window: Window, value: u16) {
+        window.layout_mut(tree, |x: &mut GridLayout| x.row = value);
+    }
+
+    pub fn column(tree: &WindowTree, window: Window) -> u16 {
+        window.layout::<GridLayout>(tree).map_or(0, |x| x.column)
+    }
+
+    pub fn set_column(tree: &mut WindowTree, window: Window, value: u16) {
+        window.layout_mut(tree, |x: &mut GridLayout| x.column = value);
+    }
+
+    pub fn row_span(tree: &WindowTree, window: Window) -> u16 {
+        window.layout::<GridLayout>(tree).map_or(1, |x| x.row_span)
+    }
+
+    pub fn set_row_span(tree: &mut WindowTree, window: Window, value: u16) {
+        window.layout_mut(tree, |x: &mut GridLayout| x.row_span = value);
+    }
+
+    pub fn column_span(tree: &WindowTree, window: Window) -> u16 {
+        window.layout::<GridLayout>(tree).map_or(1, |x| x.column_span)
+    }
+
+    pub fn set_column_span(tree: &mut WindowTree, window: Window, value: u16) {
+        window.layout_mut(tree, |x: &mut GridLayout| x.column_span = value);
+    }
+}
+
+const DEFAULT_DEFS: [GridLength; 1] = [GridLength::Star(1.0)];
+
+fn defs(defs: &[GridLength]) -> &[GridLength] {
+    if defs.is_empty() { &DEFAULT_DEFS } else { defs }
+}
+
+fn cells(start: u16, span: u16, count: usize) -> (usize, usize) {
+    let start = min(usize::from(start), count - 1);
+    let span = min(max(usize::from(span), 1), count - start);
+    (start, span)
+}
+
+pub(crate) struct Track {
+    pub(crate) start: usize,
+    pub(crate) span: usize,
+    pub(crate) desired: i32,
+}
+
+pub(crate) fn track_sizes(defs: &[GridLength], available: Option<i16>, children: &[Track]) -> Vec<i16> {
+    let content_sized = |def: GridLength| match def {
+        GridLength::Auto => true,
+        GridLength::Fixed(_) => false,
+        GridLength::Star(_) => available.is_none(),
+    };
+    let mut sizes: Vec<i32> = defs.iter().map(|&def| match def {
+        GridLength::Fixed(value) => i32::from(max(value, 0)),
+        _ => 0,
+    }).collect();
+    for child in children.iter().filter(|x| x.span == 1) {
+        if content_sized(defs[child.start]) {
+            sizes[child.start] = max(sizes[child.start], child.desired);
+        }
+    }
+    let star_weight = |def: GridLength| if let GridLength::Star(w) = def { w.max(0.0) } else { 0.0 };
+    let star_sum: f32 = defs.iter().map(|&def| star_weight(def)).sum();
+    if available.is_none() && star_sum > 0.0 {
+        let unit = defs.iter().zip(sizes.iter())
+            .filter(|(&def, _)| star_weight(def) > 0.0)
+            .map(|(&def, &size)| size as f32 / star_weight(def))
+            .fold(0.0_f32, f32::max)
+        ;
+        for (&def, size) in defs.iter().zip(sizes.iter_mut()) {
+            if let GridLength::Star(_) = def {
+                *size = (unit * star_weight(def)).ceil().min(f32::from(i16::MAX)) as i32;
+            }
+        }
+    }
+    for child in children.iter().filter(|x| x.span > 1) {
+        let cells = child.start .. child.start + child.span;
+        let deficit = child.desired - sizes[cells.clone()].iter().sum::<i32>();
+        if deficit <= 0 { continue; }
+        if let Some(last) = cells.rev().find(|&i| content_sized(defs[i])) {
+            sizes[last] += deficit;
+        }
+    }
+    if let Some(available) = available {
+        let fixed: i32 = defs.iter().zip(sizes.iter())
+            .filter(|(&def, _)| !matches!(def, GridLength::Star(_)))
+            .map(|(_, &size)| size)
+            .sum()
+        ;
+        let remaining = max(i32::from(available as u16) - fixed, 0);
+        let mut acc = 0.0_f32;
+        let mut prev_end = 0;
+        for (&def, size) in defs.iter().zip(sizes.iter_mut()) {
+            if let GridLength::Star(_) = def {
+                acc += star_weight(def);
+                let end = if star_sum > 0.0 { (remaining as f32 * acc / star_sum).round() as i32 } else { 0 };
+                *size = end - prev_end;
+                prev_end = end;
+            }
+        }
+    }
+    sizes.into_iter().map(|x| min(x, i32::from(i16::MAX)) as i16).collect()
+}
+
+fn span_size(sizes: &[i16], start: usize, span: usize) -> i16 {
+    min(sizes[start .. start + span].iter().map(|&x| i32::from(x)).sum::<i32>(), i32::from(i16::MAX)) as i16
+}
+
+fn total_size(sizes: &[i16]) -> i16 {
+    span_size(sizes, 0, sizes.len())
+}
+
+struct Child {
+    window: Window,
+    column: (usize, usize),
+    row: (usize, usize),
+}
+
+fn children(tree: &WindowTree, window: Window, columns: usize, rows: usize) -> Vec<Child> {
+    let mut res = Vec::new();
+    if let Some(first_child) = window.first_child(tree) {
+        let mut child = first_child;
+        loop {
+            res.push(Child {
+                window: child,
+                column: cells(Grid::column(tree, child), Grid::column_span(tree, child), columns),
+                row: cells(Grid::row(tree, child), Grid::row_span(tree, child), rows),
+            });
+            child = child.next(tree);
+            if child == first_child { break; }
+        }
+    }
+    res
+}
+
+fn column_tracks(tree: &WindowTree, children: &[Child]) -> Vec<Track> {
+    children.iter().map(|child| Track {
+        start: child.column.0,
+        span: child.column.1,
+        desired: i32::from(child.window.desired_size(tree).x as u16),
+    }).collect()
+}
+
+fn row_tracks(tree: &WindowTree, children: &[Child]) -> Vec<Track> {
+    children.iter().map(|child| Track {
+        start: child.row.0,
+        span: child.row.1,
+        desired: i32::from(child.window.desired_size(tree).y as u16),
+    }).collect()
+}
+
+fn fixed_span(defs: &[GridLength], (start, span): (usize, usize)) -> Option<i16> {
+    let mut size = 0i32;
+    for &def in &defs[start .. start + span] {
+        let GridLength::Fixed(value) = def else { return None; };
+        size += i32::from(max(value, 0));
+    }
+    Some(min(size, i32::from(i16::MAX)) as i16)
+}
+
+#[derive(Clone, Default)]
+struct GridWidget;
+
+impl_supports_interfaces!(GridWidget);
+
+impl Widget for GridWidget {
+    fn new(&self) -> Box<dyn WidgetData> {
+        Box::new(Grid {
+            rows: Vec::new(),
+            columns: Vec::new(),
+        })
+    }
+
+    fn clone_data(
+        &self,
+        tree: &mut WindowTree,
+        source: Window,
+        dest: Window,
+        clone_window: Box<dyn Fn(&WindowTree, Window) -> Window>,
+    ) {
+        Grid::clone(tree, source, dest, clone_window);
+    }
+
+    fn render(
+        &self,
+        _tree: &WindowTree,
+        _window: Window,
+        _rp: &mut RenderPort,
+        _app: &mut dyn App,
+    ) { }
+
+    fn measure(
+        &self,
+        tree: &mut WindowTree,
+        window: Window,
+        available_width: Option<i16>,
+        available_height: Option<i16>,
+        app: &mut dyn App,
+    ) -> Vector {
+        let data = window.data::<Grid>(tree);
+        let columns = defs(&data.columns).to_vec();
+        let rows = defs(&data.rows).to_vec();
+        let children = children(tree, window, columns.len(), rows.len());
+        for child in &children {
+            let width = fixed_span(&columns, child.column);
+            let height = fixed_span(&rows, child.row);
+            child.window.measure(tree, width, height, app);
+        }
+        let column_sizes = track_sizes(&columns, available_width, &column_tracks(tree, &children));
+        let row_sizes = track_sizes(&rows, available_height, &row_tracks(tree, &children));
+        for child in &children {
+            let width = span_size(&column_sizes, child.column.0, child.column.1);
+            let height = span_size(&row_sizes, child.row.0, child.row.1);
+            child.window.measure(tree, Some(width), Some(height), app);
+        }
+        Vector { x: total_size(&column_sizes), y: total_size(&row_sizes) }
+    }
+
+    fn arrange(
+        &self,
+        tree: &mut WindowTree,
+        window: Window,
+        final_inner_bounds: Rect,
+        app: &mut dyn App,
+    ) -> Vector {
+        let data = window.data::<Grid>(tree);
+        let columns = defs(&data.columns).to_vec();
+        let rows = defs(&data.rows).to_vec();
+        let children = children(tree, window, columns.len(), rows.len());
+        let column_sizes = track_sizes(&columns, Some(final_inner_bounds.w()), &column_tracks(tree, &children));
+        let row_sizes = track_sizes(&rows, Some(final_inner_bounds.h()), &row_tracks(tree, &children));
+        for child in &children {
+            let tl = Point {
+                x: span_size(&column_sizes, 0, child.column.0),
+                y: span_size(&row_sizes, 0, child.row.0),
+            };
+            let size = Vector {
+                x: span_size(&column_sizes, child.column.0, child.column.1),
+                y: span_size(&row_sizes, child.row.0, child.row.1),
+            };
+            child.window.arrange(
+                tree,
+                Rect { tl, size }.offset(final_inner_bounds.tl.offset_from(Point { x: 0, y: 0 })),
+                app
+            );
+        }
+        Vector { x: total_size(&column_sizes), y: total_size(&row_sizes) }
+    }
+
+    fn update(
+        &self,
+        _tree: &mut WindowTree,
+        _window: Window,
+        _event: Event,
+        _event_source: Window,
+        _app: &mut dyn App,
+    ) -> bool {
+        false
+    }
+}
@@ -1,17 +1,19 @@
 use crate::widget;
+use crate::undo_history::*;
 use alloc::boxed::Box;
-use alloc::string::String;
+use alloc::string::{String, ToString};
+use core::cmp::min;
 use core::ops::Range;
 use core::str::FromStr;
 use dyn_clone::{DynClone, clone_trait_object};
 use dynamic_cast::impl_supports_interfaces;
-use tuifw_screen_base::{Key, Point, Rect, Vector, char_width, text_width};
+use tuifw_screen_base::{Key, Mods, Ctrl, MouseButton, Point, Rect, Vector, char_width, text_width};
 use tuifw_screen_base::{Thickness, Error};
 use tuifw_window::{Event, RenderPort, Timer, Widget, WidgetData, Window, WindowTree, App, Color};
 use tuifw_window::{CMD_GOT_PRIMARY_FOCUS, CMD_LOST_PRIMARY_FOCUS, CMD_LOST_ATTENTION};
 use tuifw_window::{COLOR_INPUT_LINE, COLOR_DISABLED, COLOR_INPUT_LINE_INVALID};
 use tuifw_window::{COLOR_INPUT_LINE_FOCUSED, COLOR_INPUT_LINE_FOCUSED_DISABLED};
-use tuifw_window::{COLOR_INPUT_LINE_FOCUSED_INVALID};
+use tuifw_window::{COLOR_INPUT_LINE_FOCUSED_INVALID, COLOR_TEXT_SELECTION};
 
 pub const CMD_INPUT_LINE_IS_VALID_CHANGED: u16 = 110;
 
@@ -19,6 +21,12 @@ pub trait Validator: DynClone {
     fn is_numeric(&self) -> bool;
 
     fn is_valid(&self, editing: bool, text: &str) -> bool;
+
+    /// Returns the accepted numeric range, if the validator is numeric.
+    fn range(&self) -> Option<(f64, f64)> { None }
+
+    /// Returns `true` if only integer numbers are accepted.
+    fn is_integer(&self) -> bool { false }
 }
 
 clone_trait_object!(Validator);
@@ -32,6 +40,10 @@ pub struct IntValidator {
 impl Validator for IntValidator {
     fn is_numeric(&self) -> bool { true }
 
+    fn range(&self) -> Option<(f64, f64)> { Some((f64::from(self.min), f64::from(self.max))) }
+
+    fn is_integer(&self) -> bool { true }
+
     fn is_valid(&self, editing: bool, text: &str) -> bool {
         if editing && (text.is_empty() || text == "-") { return true; }
         if let Ok(value) = i32::from_str(text) {
@@ -51,6 +63,8 @@ pub struct FloatValidator {
 impl Validator for FloatValidator {
     fn is_numeric(&self) -> bool { true }
 
+    fn range(&self) -> Option<(f64, f64)> { Some((self.min, self.max)) }
+
     fn is_valid(&self, editing: bool, text: &str) -> bool {
         if editing && (text.is_empty() || text == "-") { return true; }
         let text = if editing && (text.ends_with('e') || text.ends_with('E')) {
@@ -75,11 +89,15 @@ widget! {
         validator: Option<Box<dyn Validator>>,
         #[property(str, render, on_changed=on_text_changed)]
         text: String,
+        #[property(copy, on_changed=on_undo_depth_changed)]
+        undo_depth: usize,
+        history: UndoHistory,
         is_valid: bool,
         editing: bool,
         view_padding: i16,
         view: Range<usize>,
         cursor: usize,
+        anchor: Option<usize>,
         width: i16,
         is_valid_timer: Option<Timer>,
     }
@@ -94,6 +112,7 @@ impl InputLine {
             palette.set(3, Color::Palette(COLOR_INPUT_LINE_FOCUSED));
             palette.set(4, Color::Palette(COLOR_INPUT_LINE_FOCUSED_INVALID));
             palette.set(5, Color::Palette(COLOR_INPUT_LINE_FOCUSED_DISABLED));
+            palette.set(6, Color::Palette(COLOR_TEXT_SELECTION));
         });
         Ok(())
     }
@@ -142,6 +161,7 @@ impl InputLine {
 
     fn reset_view(&mut self, focused: bool) {
         self.cursor = self.text.len();
+        self.anchor = None;
         if focused || self.is_numeric_raw() {
             self.calc_view_start(self.text.len());
         } else {
@@ -200,6 +220,10 @@ impl InputLine {
     fn cursor_right(&mut self) {
         let Some(c) = self.text[self.cursor ..].chars().next() else { return; };
         self.cursor += c.len_utf8();
+        self.show_cursor();
+    }
+
+    fn show_cursor(&mut self) {
         if self.cursor >= self.view.end {
             let view_end = if let Some(c) = self.text[self.cursor ..].chars().next() {
                 self.cursor + c.len_utf8()
@@ -210,10 +234,168 @@ impl InputLine {
         }
     }
 
+    /// Returns the selected byte range of the text, if the selection is not empty.
+    pub fn selection(tree: &WindowTree, window: Window) -> Option<Range<usize>> {
+        window.data::<InputLine>(tree).selection_range()
+    }
+
+    fn selection_range(&self) -> Option<Range<usize>> {
+        let anchor = self.anchor?;
+        if anchor < self.cursor {
+            Some(anchor .. self.cursor)
+        } else if anchor > self.cursor {
+            Some(self.cursor .. anchor)
+        } else {
+            None
+        }
+    }
+
+    fn select(&mut self, extend: bool) {
+        self.anchor = if extend { Some(self.anchor.unwrap_or(self.cursor)) } else { None };
+        self.history.seal();
+    }
+
+    fn record(&mut self, range: Range<usize>, s: &str, typing: bool) {
+        let deleted = self.text[range.clone()].to_string();
+        let edit = Edit::new(
+            range.start, deleted, s.to_string(), (self.cursor, self.anchor), (range.start + s.len(), None)
+        );
+        self.history.record(edit, typing);
+    }
+
+    fn replace(&mut self, range: Range<usize>, s: &str) {
+        self.anchor = None;
+        self.text.replace_range(range.clone(), s);
+        self.cursor = range.start + s.len();
+        self.calc_view_end(min(self.view.start, range.start));
+        self.show_cursor();
+    }
+
+    fn delete_selection(&mut self) -> bool {
+        let Some(selection) = self.selection_range() else { return false; };
+        self.record(selection.clone(), "", false);
+        self.replace(selection, "");
+        true
+    }
+
+    fn insert(&mut self, s: &str, typing: bool) -> bool {
+        if self.text.try_reserve(s.len()).is_err() { return false; }
+        let range = self.selection_range().unwrap_or(self.cursor .. self.cursor);
+        self.record(range.clone(), s, typing);
+        self.replace(range, s);
+        true
+    }
+
+    fn undo_raw(&mut self) -> bool {
+        let Some(edit) = self.history.undo() else { return false; };
+        self.replace(edit.pos .. edit.pos + edit.inserted.len(), &edit.deleted);
+        self.cursor = edit.cursor;
+        self.anchor = edit.anchor;
+        if self.cursor < self.view.start {
+            self.calc_view_end(self.cursor);
+        } else {
+            self.show_cursor();
+        }
+        true
+    }
+
+    fn redo_raw(&mut self) -> bool {
+        let Some(edit) = self.history.redo() else { return false; };
+        self.replace(edit.pos .. edit.pos + edit.deleted.len(), &edit.inserted);
+        self.cursor = edit.cursor_after;
+        self.anchor = edit.anchor_after;
+        if self.cursor < self.view.start {
+            self.calc_view_end(self.cursor);
+        } else {
+            self.show_cursor();
+        }
+        true
+    }
+
+    pub fn undo(tree: &mut WindowTree, window: Window) -> bool {
+        if !window.data_mut::<InputLine>(tree).undo_raw() { return false; }
+        Self::text_edited(tree, window, None);
+        true
+    }
+
+    pub fn redo(tree: &mut WindowTree, window: Window) -> bool {
+        if !window.data_mut::<InputLine>(tree).redo_raw() { return false; }
+        Self::text_edited(tree, window, None);
+        true
+    }
+
+    pub fn can_undo(tree: &WindowTree, window: Window) -> bool {
+        window.data::<InputLine>(tree).history.can_undo()
+    }
+
+    pub fn can_redo(tree: &WindowTree, window: Window) -> bool {
+        window.data::<InputLine>(tree).history.can_redo()
+    }
+
+    pub fn clear_undo_history(tree: &mut WindowTree, window: Window) {
+        window.data_mut::<InputLine>(tree).history.clear();
+    }
+
+    /// Marks the current text as saved, so that [`is_modified`](InputLine::is_modified) returns `false`
+    /// until the text is edited again (or the edits are undone past this point).
+    pub fn mark_saved(tree: &mut WindowTree, window: Window) {
+        window.data_mut::<InputLine>(tree).history.mark_saved();
+    }
+
+    pub fn is_modified(tree: &WindowTree, window: Window) -> bool {
+        window.data::<InputLine>(tree).history.is_modified()
+    }
+
+    fn on_undo_depth_changed(tree: &mut WindowTree, window: Window) {
+        let data = window.data_mut::<InputLine>(tree);
+        data.history.set_depth(data.undo_depth);
+    }
+
+    fn pos_at(&self, offset: i16) -> usize {
+        let offset = offset.wrapping_sub(self.view_padding);
+        let index = 'r: {
+            let mut width = 0;
+            for (i, c) in self.text[self.view.clone()].char_indices() {
+                width += char_width(c);
+                if offset < width {
+                    break 'r i;
+                }
+            }
+            self.view.len()
+        };
+        self.view.start + index
+    }
+
+    fn text_edited(tree: &mut WindowTree, window: Window, mut app: Option<&mut dyn App>) {
+        Self::update_is_valid(tree, window, app.as_deref_mut());
+        let data = window.data_mut::<InputLine>(tree);
+        if data.is_valid && !data.editing {
+            data.editing = true;
+            Self::update_is_valid(tree, window, app);
+        }
+        window.invalidate_render(tree);
+    }
+
+    fn copy(tree: &mut WindowTree, window: Window) -> bool {
+        let data = window.data::<InputLine>(tree);
+        let Some(selection) = data.selection_range() else { return false; };
+        let text = data.text[selection].to_string();
+        tree.clipboard().set_text(&text);
+        true
+    }
+
+    fn paste(tree: &mut WindowTree, window: Window, text: &str, app: &mut dyn App) {
+        let line = text.lines().next().unwrap_or("");
+        if window.data_mut::<InputLine>(tree).insert(line, false) {
+            InputLine::text_edited(tree, window, Some(app));
+        }
+    }
+
     fn on_text_changed(tree: &mut WindowTree, window: Window) {
         let focused = window.is_focused(tree);
         let data = &mut window.data_mut::<InputLine>(tree);
         data.reset_view(focused);
+        data.history.clear();
         Self::update_is_valid(tree, window, None);
         let data = &mut window.data_mut::<InputLine>(tree);
         if data.is_valid && !data.editing && focused {
@@ -233,11 +415,14 @@ impl Widget for InputLineWidget {
         Box::new(InputLine {
             validator: None,
             text: String::new(),
+            undo_depth: DEFAULT_UNDO_DEPTH,
+            history: UndoHistory::new(DEFAULT_UNDO_DEPTH),
             is_valid: true,
             editing: false,
             view_padding: 0,
             view: 0 .. 0,
             cursor: 0,
+            anchor: None,
             width: 0,
             is_valid_timer: None,
         })
@@ -280,6 +465,12 @@ impl Widget for InputLineWidget {
             color,
             &data.text[data.view.clone()]
         );
+        if let Some(selection) = data.selection_range().filter(|_| focused) {
+            let start = selection.start.clamp(data.view.start, data.view.end);
+            let end = selection.end.clamp(data.view.start, data.view.end);
+            let x = text_width(&data.text[data.view.start .. start]).wrapping_add(data.view_padding).wrapping_add(1);
+            rp.text(Point { x, y: 0 }, window.color(tree, 6), &data.text[start .. end]);
+        }
         if data.view.start > 0 {
             rp.text(Point { x: 0, y: 0 }, color, "◄");
         }
@@ -356,106 +547,135 @@ impl Widget for InputLineWidget {
                 window.invalidate_render(tree);
                 false
             },
-            Event::Key(Key::Char(c)) => {
+            Event::Key(Key::Char(c), Mods::NONE) => {
                 if window.actual_is_enabled(tree) {
                     let data = window.data_mut::<InputLine>(tree);
-                    if data.text.try_reserve(c.len_utf8()).is_ok() {
-                        data.text.insert(data.cursor, c);
-                        data.calc_view_end(data.view.start);
-                        data.cursor_right();
-                        InputLine::update_is_valid(tree, window, Some(app));
-                        let data = window.data_mut::<InputLine>(tree);
-                        if data.is_valid && !data.editing {
-                            data.editing = true;
-                            InputLine::update_is_valid(tree, window, Some(app));
-                        }
-                        window.invalidate_render(tree);
+                    let mut b = [0; 4];
+                    if data.insert(c.encode_utf8(&mut b), true) {
+                        InputLine::text_edited(tree, window, Some(app));
                     }
                     true
                 } else {
                     false
                 }
             },
-            Event::Key(Key::Backspace) => {
+            Event::Key(Key::Backspace, Mods::NONE) => {
                 if window.actual_is_enabled(tree) {
                     let data = window.data_mut::<InputLine>(tree);
-                    if !data.text.is_empty() {
+                    if data.delete_selection() {
+                        InputLine::text_edited(tree, window, Some(app));
+                    } else if let Some(c) = data.text[.. data.cursor].chars().next_back() {
+                        data.record(data.cursor - c.len_utf8() .. data.cursor, "", false);
                         data.cursor_left();
                         let c = data.text.remove(data.cursor);
                         data.calc_view_start(data.view.end - c.len_utf8());
-                        InputLine::update_is_valid(tree, window, Some(app));
-                        let data = window.data_mut::<InputLine>(tree);
-                        if data.is_valid && !data.editing {
-                            data.editing = true;
-                            InputLine::update_is_valid(tree, window, Some(app));
-                        }
-                        window.invalidate_render(tree);
+                        InputLine::text_edited(tree, window, Some(app));
                     }
                     true
                 } else {
                     false
                 }
             },
-            Event::Key(Key::Delete) => {
+            Event::Key(Key::Delete, Mods::NONE) => {
                 if window.actual_is_enabled(tree) {
                     let data = window.data_mut::<InputLine>(tree);
-                    if data.cursor != data.text.len() {
-                        let c = data.text.remove(data.cursor);
+                    if data.delete_selection() {
+                        InputLine::text_edited(tree, window, Some(app));
+                    } else if let Some(c) = data.text[data.cursor ..].chars().next() {
+                        data.record(data.cursor .. data.cursor + c.len_utf8(), "", false);
+                        data.text.remove(data.cursor);
                         data.calc_view_start(data.view.end - c.len_utf8());
-                        InputLine::update_is_valid(tree, window, Some(app));
-                        let data = window.data_mut::<InputLine>(tree);
-                        if data.is_valid && !data.editing {
-                            data.editing = true;
-                            InputLine::update_is_valid(tree, window, Some(app));
-                        }
-                        window.invalidate_render(tree);
+                        InputLine::text_edited(tree, window, Some(app));
                     }
                     true
                 } else {
                     false
                 }
             },
-            Event::Key(Key::Left) => {
+            Event::Key(Key::Left, mods) if mods == Mods::NONE || mods == Mods::SHIFT => {
                 let data = window.data_mut::<InputLine>(tree);
+                data.select(mods == Mods::SHIFT);
                 data.cursor_left();
                 window.invalidate_render(tree);
                 true
             },
-            Event::Key(Key::Right) => {
+            Event::Key(Key::Right, mods) if mods == Mods::NONE || mods == Mods::SHIFT => {
                 let data = window.data_mut::<InputLine>(tree);
+                data.select(mods == Mods::SHIFT);
                 data.cursor_right();
                 window.invalidate_render(tree);
                 true
             },
-            Event::Key(Key::Home) => {
+            Event::Key(Key::Home, mods) if mods == Mods::NONE || mods == Mods::SHIFT => {
                 let data = window.data_mut::<InputLine>(tree);
+                data.select(mods == Mods::SHIFT);
                 data.cursor = 0;
                 data.calc_view_end(0);
                 window.invalidate_render(tree);
                 true
             },
-            Event::Key(Key::End) => {
+            Event::Key(Key::End, mods) if mods == Mods::NONE || mods == Mods::SHIFT => {
                 let data = window.data_mut::<InputLine>(tree);
+                data.select(mods == Mods::SHIFT);
                 data.cursor = data.text.len();
                 data.calc_view_start(data.text.len());
                 window.invalidate_render(tree);
                 true
             },
-            Event::LmbDown(point) => {
+            Event::Key(Key::Ctrl(Ctrl::A), Mods::NONE) => {
+                let data = window.data_mut::<InputLine>(tree);
+                data.anchor = Some(0);
+                data.cursor = data.text.len();
+                data.calc_view_start(data.text.len());
+                window.invalidate_render(tree);
+                true
+            },
+            Event::Key(Key::Ctrl(Ctrl::C), Mods::NONE) => InputLine::copy(tree, window),
+            Event::Key(Key::Ctrl(Ctrl::X), Mods::NONE) => {
+                if !window.actual_is_enabled(tree) || !InputLine::copy(tree, window) { return false; }
+                window.data_mut::<InputLine>(tree).delete_selection();
+                InputLine::text_edited(tree, window, Some(app));
+                true
+            },
+            Event::Key(Key::Ctrl(Ctrl::V), Mods::NONE) => {
+                if !window.actual_is_enabled(tree) { return false; }
+                let text = tree.clipboard().text().to_string();
+                InputLine::paste(tree, window, &text, app);
+                true
+            },
+            Event::Paste => {
+                if !window.actual_is_enabled(tree) { return false; }
+                let text = tree.pasted_text().to_string();
+                InputLine::paste(tree, window, &text, app);
+                true
+            },
+            Event::Key(Key::Ctrl(Ctrl::Z), Mods::NONE) => {
+                if !window.actual_is_enabled(tree) { return false; }
+                if window.data_mut::<InputLine>(tree).undo_raw() {
+                    InputLine::text_edited(tree, window, Some(app));
+                }
+                true
+            },
+            Event::Key(Key::Ctrl(Ctrl::Y), Mods::NONE) => {
+                if !window.actual_is_enabled(tree) { return false; }
+                if window.data_mut::<InputLine>(tree).redo_raw() {
+                    InputLine::text_edited(tree, window, Some(app));
+                }
+                true
+            },
+            Event::LmbDown(point, mods) => {
                 let offset = window.inner_point(point, tree).offset(Vector { x: -1, y: 0 }).x;
                 let data = window.data_mut::<InputLine>(tree);
-                let offset = offset.wrapping_sub(data.view_padding);
-                let index = 'r: {
-                    let mut width = 0;
-                    for (i, c) in data.text[data.view.clone()].char_indices() {
-                        width += char_width(c);
-                        if offset < width {
-                            break 'r i;
-                        }
-                    }
-                    data.view.len()
-                };
-                data.cursor = data.view.start + index;
+                data.select(mods == Mods::SHIFT);
+                data.cursor = data.pos_at(offset);
+                window.invalidate_render(tree);
+                true
+            },
+            Event::Drag(point, MouseButton::Left, _) => {
+                let offset = window.inner_point(point, tree).offset(Vector { x: -1, y: 0 }).x;
+                let data = window.data_mut::<InputLine>(tree);
+                data.select(true);
+                data.cursor = data.pos_at(offset);
                 window.invalidate_render(tree);
                 true
             },
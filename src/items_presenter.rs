@@ -3,7 +3,7 @@ use alloc::boxed::Box;
 use alloc::string::ToString;
 use alloc::vec::Vec;
 use dynamic_cast::impl_supports_interfaces;
-use tuifw_screen_base::{Rect, Vector, Error, Fg, Bg, Key};
+use tuifw_screen_base::{Rect, Vector, Error, Fg, Bg, Key, Mods};
 use tuifw_window::{Event, RenderPort, Widget, WidgetData, Window, WindowTree, App, Timer, Data};
 use tuifw_window::Visibility;
 
@@ -207,7 +207,7 @@ impl Widget for ItemsPresenterWidget {
         _app: &mut dyn App,
     ) -> bool {
         match event {
-            Event::Key(Key::Tab) => {
+            Event::Key(Key::Tab, Mods::NONE) => {
                 let data = window.data::<ItemsPresenter>(tree);
                 if data.tab_navigation {
                     if event_source.parent(tree).and_then(|x| x.parent(tree)) == Some(window) {
@@ -228,7 +228,7 @@ impl Widget for ItemsPresenterWidget {
                     false
                 }
             },
-            Event::Key(Key::Down) => {
+            Event::Key(Key::Down, Mods::NONE) => {
                 let data = window.data::<ItemsPresenter>(tree);
                 if data.up_down_navigation {
                     if event_source.parent(tree).and_then(|x| x.parent(tree)) == Some(window) {
@@ -253,7 +253,7 @@ impl Widget for ItemsPresenterWidget {
                     false
                 }
             },
-            Event::Key(Key::Up) => {
+            Event::Key(Key::Up, Mods::NONE) => {
                 let data = window.data::<ItemsPresenter>(tree);
                 if data.up_down_navigation {
                     if event_source.parent(tree).and_then(|x| x.parent(tree)) == Some(window) {
@@ -285,7 +285,7 @@ impl Widget for ItemsPresenterWidget {
                     false
                 }
             },
-            Event::Key(Key::Right) => {
+            Event::Key(Key::Right, Mods::NONE) => {
                 let data = window.data::<ItemsPresenter>(tree);
                 if data.left_right_navigation {
                     if event_source.parent(tree).and_then(|x| x.parent(tree)) == Some(window) {
@@ -310,7 +310,7 @@ impl Widget for ItemsPresenterWidget {
                     false
                 }
             },
-            Event::Key(Key::Left) => {
+            Event::Key(Key::Left, Mods::NONE) => {
                 let data = window.data::<ItemsPresenter>(tree);
                 if data.left_right_navigation {
                     if event_source.parent(tree).and_then(|x| x.parent(tree)) == Some(window) {
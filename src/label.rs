@@ -2,7 +2,7 @@ use crate::widget;
 use alloc::boxed::Box;
 use alloc::string::String;
 use dynamic_cast::impl_supports_interfaces;
-use tuifw_screen_base::{Point, Rect, Vector, Key, Error};
+use tuifw_screen_base::{Point, Rect, Vector, Key, Mods, Error};
 use tuifw_window::{Event, RenderPort, Widget, WidgetData, Window, WindowTree, Timer, label_width, label};
 use tuifw_window::{COLOR_LABEL, COLOR_HOTKEY, COLOR_DISABLED, App, Color};
 
@@ -108,7 +108,7 @@ impl Widget for LabelWidget {
         let data = window.data::<Label>(tree);
         let label = label(&data.text);
         let Some(label) = label else { return false; };
-        if event == Event::PostProcessKey(Key::Alt(label)) || event == Event::PostProcessKey(Key::Char(label)) {
+        if event == Event::PostProcessKey(Key::Alt(label), Mods::NONE) || event == Event::PostProcessKey(Key::Char(label), Mods::NONE) {
             if window.actual_is_enabled(tree) {
                 let click_timer = Timer::new(tree, 0, Box::new(move |tree, app| {
                     let data = window.data_mut::<Label>(tree);
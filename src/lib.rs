@@ -13,6 +13,9 @@
 
 extern crate alloc;
 
+#[cfg(feature="std")]
+extern crate std;
+
 mod stack_panel;
 pub use stack_panel::*;
 
@@ -25,12 +28,24 @@ pub use dock_panel::*;
 mod canvas;
 pub use canvas::*;
 
+mod grid;
+pub use grid::*;
+
+mod wrap_panel;
+pub use wrap_panel::*;
+
 mod static_text;
 pub use static_text::*;
 
 mod background;
 pub use background::*;
 
+mod undo_history;
+
+mod text_buffer;
+
+mod text_search;
+
 mod input_line;
 pub use input_line::*;
 
@@ -61,11 +76,50 @@ pub use virt_items_presenter::*;
 mod scroll_viewer;
 pub use scroll_viewer::*;
 
+mod scroll_bar;
+pub use scroll_bar::*;
+
 pub mod virt_scroll_viewer;
 
+mod list_box;
+pub use list_box::*;
+
+mod combo_box;
+pub use combo_box::*;
+
+mod tree_view;
+pub use tree_view::*;
+
+mod data_grid;
+pub use data_grid::*;
+
+mod tab_control;
+pub use tab_control::*;
+
+mod progress_bar;
+pub use progress_bar::*;
+
+mod spinner;
+pub use spinner::*;
+
+mod slider;
+pub use slider::*;
+
+mod spin_box;
+pub use spin_box::*;
+
 mod text_edit;
 pub use text_edit::*;
 
+mod find_bar;
+pub use find_bar::*;
+
+mod menu;
+pub use menu::*;
+
+mod message_box;
+pub use message_box::*;
+
 #[doc(hidden)]
 pub use alloc::boxed::Box as alloc_boxed_Box;
 #[doc(hidden)]
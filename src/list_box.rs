@@ -0,0 +1,525 @@
+use crate::{widget, VirtItemsPresenter};
+use crate::scroll_bar::BoundScrollBars;
+use crate::virt_scroll_viewer::*;
+use alloc::boxed::Box;
+use alloc::collections::BTreeSet;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cmp::{max, min};
+use dynamic_cast::impl_supports_interfaces;
+use tuifw_screen_base::{Key, Mods, Point, Rect, Vector, Error, text_width};
+use tuifw_window::{Event, RenderPort, Timer, Widget, WidgetData, Window, WindowTree, App, Color, Data, Focus};
+use tuifw_window::{CMD_GOT_PRIMARY_FOCUS, CMD_LOST_PRIMARY_FOCUS, CMD_GOT_SECONDARY_FOCUS, CMD_LOST_SECONDARY_FOCUS};
+use tuifw_window::{COLOR_LIST_BOX, COLOR_LIST_BOX_SELECTED, COLOR_LIST_BOX_FOCUSED};
+use tuifw_window::{COLOR_LIST_BOX_FOCUSED_SELECTED, COLOR_DISABLED};
+
+pub const CMD_LIST_BOX_SELECTION_CHANGED: u16 = 190;
+
+const TYPE_AHEAD_MS: u16 = 1000;
+
+pub(crate) fn starts_with_ignore_case(text: &str, prefix: &str) -> bool {
+    let mut text = text.chars().flat_map(char::to_lowercase);
+    prefix.chars().all(|c| text.next() == Some(c))
+}
+
+widget! {
+    #[widget(ListBoxWidget, init=init, drop=drop_timers)]
+    pub struct ListBox {
+        #[property(ref, on_changed=on_items_changed)]
+        items: Vec<String>,
+        #[property(copy, on_changed=on_multiple_changed)]
+        multiple: bool,
+        selection: BTreeSet<usize>,
+        current: Option<usize>,
+        anchor: Option<usize>,
+        offset: i16,
+        viewport: i16,
+        search: String,
+        search_timer: Option<Timer>,
+        selection_changed_timer: Option<Timer>,
+        scroll_bars: BoundScrollBars,
+    }
+}
+
+impl ListBox {
+    fn init(tree: &mut WindowTree, window: Window) -> Result<(), Error> {
+        window.palette_mut(tree, |palette| {
+            palette.set(0, Color::Palette(COLOR_LIST_BOX));
+            palette.set(1, Color::Palette(COLOR_LIST_BOX_SELECTED));
+            palette.set(2, Color::Palette(COLOR_LIST_BOX_FOCUSED));
+            palette.set(3, Color::Palette(COLOR_LIST_BOX_FOCUSED_SELECTED));
+            palette.set(4, Color::Palette(COLOR_DISABLED));
+        });
+        window.set_focus_click(tree, Some(Focus::Primary));
+        let presenter = VirtItemsPresenter::new(tree, Some(window), None)?;
+        let item_template = Window::new_template(tree, Box::new(ListBoxItemWidget))?;
+        VirtItemsPresenter::set_item_template(tree, presenter, Some(item_template));
+        Ok(())
+    }
+
+    fn drop_timers(&mut self, tree: &mut WindowTree, _app: &mut dyn App) {
+        if let Some(search_timer) = self.search_timer.take() {
+            search_timer.drop_timer(tree);
+        }
+        if let Some(selection_changed_timer) = self.selection_changed_timer.take() {
+            selection_changed_timer.drop_timer(tree);
+        }
+        self.scroll_bars.unbind(tree);
+    }
+
+    fn presenter(tree: &WindowTree, window: Window) -> Window {
+        window.first_child(tree).unwrap()
+    }
+
+    fn item_list_box(tree: &WindowTree, item: Window) -> Option<Window> {
+        item.parent(tree)?.parent(tree)?.parent(tree)
+    }
+
+    pub fn selected_index(tree: &WindowTree, window: Window) -> Option<usize> {
+        window.data::<ListBox>(tree).selection.first().copied()
+    }
+
+    pub fn set_selected_index(tree: &mut WindowTree, window: Window, value: Option<usize>) {
+        let data = window.data_mut::<ListBox>(tree);
+        let value = value.filter(|&x| x < data.items.len());
+        if value.is_some() {
+            data.current = value;
+            data.anchor = value;
+        }
+        Self::set_selection_raw(tree, window, value.into_iter().collect(), None);
+        Self::bring_current_into_view(tree, window);
+    }
+
+    pub fn selected_indices<'a>(tree: &'a WindowTree, window: Window) -> &'a BTreeSet<usize> {
+        &window.data::<ListBox>(tree).selection
+    }
+
+    pub fn set_selected_indices(tree: &mut WindowTree, window: Window, mut value: BTreeSet<usize>) {
+        let data = window.data::<ListBox>(tree);
+        value.split_off(&data.items.len());
+        if !data.multiple {
+            value = value.first().copied().into_iter().collect();
+        }
+        Self::set_selection_raw(tree, window, value, None);
+    }
+
+    pub fn current_index(tree: &WindowTree, window: Window) -> Option<usize> {
+        window.data::<ListBox>(tree).current
+    }
+
+    fn on_items_changed(tree: &mut WindowTree, window: Window) {
+        let presenter = Self::presenter(tree, window);
+        let data = window.data_mut::<ListBox>(tree);
+        let len = data.items.len();
+        data.current = data.current.filter(|&x| x < len);
+        data.anchor = data.anchor.filter(|&x| x < len);
+        let mut selection = data.selection.clone();
+        selection.split_off(&len);
+        VirtItemsPresenter::items_mut(tree, presenter, |items| {
+            items.clear();
+            items.extend((0 .. len).map(|_| Box::new(ListBoxItemData) as Box<dyn Data>));
+        });
+        Self::set_selection_raw(tree, window, selection, None);
+        window.invalidate_measure(tree);
+    }
+
+    fn on_multiple_changed(tree: &mut WindowTree, window: Window) {
+        let data = window.data::<ListBox>(tree);
+        if data.multiple || data.selection.len() <= 1 { return; }
+        let selected = data.current.filter(|x| data.selection.contains(x)).or(data.selection.first().copied());
+        Self::set_selection_raw(tree, window, selected.into_iter().collect(), None);
+    }
+
+    fn set_selection_raw(
+        tree: &mut WindowTree,
+        window: Window,
+        selection: BTreeSet<usize>,
+        app: Option<&mut dyn App>
+    ) {
+        let data = window.data_mut::<ListBox>(tree);
+        if data.selection == selection { return; }
+        data.selection = selection;
+        window.invalidate_render(tree);
+        if let Some(app) = app {
+            window.raise(tree, Event::Cmd(CMD_LIST_BOX_SELECTION_CHANGED), app);
+        } else {
+            let selection_changed_timer = Timer::new(tree, 0, Box::new(move |tree, app| {
+                window.data_mut::<ListBox>(tree).selection_changed_timer = None;
+                window.raise(tree, Event::Cmd(CMD_LIST_BOX_SELECTION_CHANGED), app);
+            }));
+            let data = window.data_mut::<ListBox>(tree);
+            if let Some(old_timer) = data.selection_changed_timer.replace(selection_changed_timer) {
+                old_timer.drop_timer(tree);
+            }
+        }
+    }
+
+    fn bring_current_into_view(tree: &mut WindowTree, window: Window) {
+        let data = window.data::<ListBox>(tree);
+        let Some(current) = data.current else { return; };
+        let current = min(current, i16::MAX as usize) as i16;
+        let viewport = max(1, data.viewport);
+        let offset = if current < data.offset {
+            current
+        } else if current >= data.offset.saturating_add(viewport) {
+            current.wrapping_sub(viewport).wrapping_add(1)
+        } else {
+            return;
+        };
+        let presenter = Self::presenter(tree, window);
+        VirtItemsPresenter::set_offset(tree, presenter, offset);
+    }
+
+    fn key_target(tree: &WindowTree, window: Window, key: Key) -> Option<usize> {
+        let data = window.data::<ListBox>(tree);
+        let last = data.items.len().checked_sub(1)?;
+        let page = max(1, data.viewport) as usize;
+        let current = data.current;
+        Some(match key {
+            Key::Up => current.map_or(0, |x| x.saturating_sub(1)),
+            Key::Down => current.map_or(0, |x| min(x + 1, last)),
+            Key::PageUp => current.map_or(0, |x| x.saturating_sub(page)),
+            Key::PageDown => current.map_or(0, |x| min(x.saturating_add(page), last)),
+            Key::Home => 0,
+            Key::End => last,
+            _ => return None,
+        })
+    }
+
+    fn navigate(tree: &mut WindowTree, window: Window, index: usize, mods: Mods, app: &mut dyn App) {
+        let data = window.data_mut::<ListBox>(tree);
+        data.current = Some(index);
+        let selection = if !data.multiple || mods == Mods::NONE {
+            data.anchor = Some(index);
+            Some(BTreeSet::from([index]))
+        } else if mods == Mods::SHIFT {
+            let anchor = *data.anchor.get_or_insert(index);
+            Some((min(anchor, index) ..= max(anchor, index)).collect())
+        } else {
+            None
+        };
+        window.invalidate_render(tree);
+        if let Some(selection) = selection {
+            Self::set_selection_raw(tree, window, selection, Some(app));
+        }
+        Self::bring_current_into_view(tree, window);
+    }
+
+    fn toggle(tree: &mut WindowTree, window: Window, index: usize, app: &mut dyn App) {
+        let data = window.data_mut::<ListBox>(tree);
+        if !data.multiple {
+            return Self::navigate(tree, window, index, Mods::NONE, app);
+        }
+        data.current = Some(index);
+        data.anchor = Some(index);
+        let mut selection = data.selection.clone();
+        if !selection.remove(&index) {
+            selection.insert(index);
+        }
+        window.invalidate_render(tree);
+        Self::set_selection_raw(tree, window, selection, Some(app));
+        Self::bring_current_into_view(tree, window);
+    }
+
+    fn type_ahead(tree: &mut WindowTree, window: Window, c: char, app: &mut dyn App) {
+        let search_timer = Timer::new(tree, TYPE_AHEAD_MS, Box::new(move |tree, _app| {
+            let data = window.data_mut::<ListBox>(tree);
+            data.search_timer = None;
+            data.search.clear();
+        }));
+        let data = window.data_mut::<ListBox>(tree);
+        if let Some(old_timer) = data.search_timer.replace(search_timer) {
+            old_timer.drop_timer(tree);
+        }
+        let data = window.data_mut::<ListBox>(tree);
+        let first_char = data.search.is_empty();
+        data.search.extend(c.to_lowercase());
+        let len = data.items.len();
+        if len == 0 { return; }
+        let start = data.current.map_or(0, |x| if first_char { x + 1 } else { x });
+        let found = (0 .. len)
+            .map(|i| (start + i) % len)
+            .find(|&i| starts_with_ignore_case(&data.items[i], &data.search));
+        if let Some(index) = found {
+            Self::navigate(tree, window, index, Mods::NONE, app);
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+struct ListBoxWidget;
+
+impl_supports_interfaces!(ListBoxWidget: VirtScrollViewerWidgetExtension, VirtItemsPresenterWidgetExtension);
+
+impl VirtScrollViewerWidgetExtension for ListBoxWidget {
+    fn set_offset(&self, tree: &mut WindowTree, window: Window, vertical: bool, value: u32) {
+        if vertical {
+            window.data_mut::<ListBox>(tree).offset = u16::try_from(value).unwrap() as i16;
+        }
+        if let Some(parent) = window.parent(tree) {
+            if let Some(sv) = parent.widget_extension::<dyn VirtScrollViewerWidgetExtension>(tree) {
+                sv.set_offset(tree, parent, vertical, value);
+            }
+        }
+        let scroll_bars = window.data::<ListBox>(tree).scroll_bars;
+        scroll_bars.set_offset(tree, vertical, value);
+    }
+
+    fn set_viewport(&self, tree: &mut WindowTree, window: Window, vertical: bool, value: u32) {
+        if vertical {
+            window.data_mut::<ListBox>(tree).viewport = u16::try_from(value).unwrap() as i16;
+        }
+        if let Some(parent) = window.parent(tree) {
+            if let Some(sv) = parent.widget_extension::<dyn VirtScrollViewerWidgetExtension>(tree) {
+                sv.set_viewport(tree, parent, vertical, value);
+            }
+        }
+        let scroll_bars = window.data::<ListBox>(tree).scroll_bars;
+        scroll_bars.set_viewport(tree, vertical, value);
+    }
+
+    fn set_extent(&self, tree: &mut WindowTree, window: Window, vertical: bool, value: u32) {
+        if let Some(parent) = window.parent(tree) {
+            if let Some(sv) = parent.widget_extension::<dyn VirtScrollViewerWidgetExtension>(tree) {
+                sv.set_extent(tree, parent, vertical, value);
+            }
+        }
+        let scroll_bars = window.data::<ListBox>(tree).scroll_bars;
+        scroll_bars.set_extent(tree, vertical, value);
+    }
+
+    fn scroll_to(&self, tree: &mut WindowTree, window: Window, vertical: bool, value: u32) {
+        VirtItemsPresenterWidgetExtension::set_offset(self, tree, window, vertical, value);
+    }
+
+    fn bind_scroll_bar(&self, tree: &mut WindowTree, window: Window, vertical: bool, scroll_bar: Option<Window>) {
+        window.data_mut::<ListBox>(tree).scroll_bars.bind(vertical, scroll_bar);
+        if scroll_bar.is_none() { return; }
+        let presenter = ListBox::presenter(tree, window);
+        VirtItemsPresenter::sync_scroll_viewer(tree, presenter);
+    }
+}
+
+impl VirtItemsPresenterWidgetExtension for ListBoxWidget {
+    fn set_offset(&self, tree: &mut WindowTree, window: Window, vertical: bool, value: u32) {
+        let presenter = ListBox::presenter(tree, window);
+        if let Some(virt) = presenter.widget_extension::<dyn VirtItemsPresenterWidgetExtension>(tree) {
+            virt.set_offset(tree, presenter, vertical, value);
+        }
+    }
+}
+
+impl Widget for ListBoxWidget {
+    fn new(&self) -> Box<dyn WidgetData> {
+        Box::new(ListBox {
+            items: Vec::new(),
+            multiple: false,
+            selection: BTreeSet::new(),
+            current: None,
+            anchor: None,
+            offset: 0,
+            viewport: 0,
+            search: String::new(),
+            search_timer: None,
+            selection_changed_timer: None,
+            scroll_bars: BoundScrollBars::default(),
+        })
+    }
+
+    fn clone_data(
+        &self,
+        tree: &mut WindowTree,
+        source: Window,
+        dest: Window,
+        clone_window: Box<dyn Fn(&WindowTree, Window) -> Window>,
+    ) {
+        ListBox::clone(tree, source, dest, clone_window);
+    }
+
+    fn render(
+        &self,
+        tree: &WindowTree,
+        window: Window,
+        rp: &mut RenderPort,
+        _app: &mut dyn App,
+    ) {
+        rp.fill_bg(window.color(tree, 0));
+    }
+
+    fn measure(
+        &self,
+        tree: &mut WindowTree,
+        window: Window,
+        available_width: Option<i16>,
+        available_height: Option<i16>,
+        app: &mut dyn App,
+    ) -> Vector {
+        let presenter = ListBox::presenter(tree, window);
+        presenter.measure(tree, available_width, available_height, app);
+        presenter.desired_size(tree)
+    }
+
+    fn arrange(
+        &self,
+        tree: &mut WindowTree,
+        window: Window,
+        final_inner_bounds: Rect,
+        app: &mut dyn App,
+    ) -> Vector {
+        let presenter = ListBox::presenter(tree, window);
+        presenter.arrange(tree, final_inner_bounds, app);
+        final_inner_bounds.size
+    }
+
+    fn update(
+        &self,
+        tree: &mut WindowTree,
+        window: Window,
+        event: Event,
+        event_source: Window,
+        app: &mut dyn App,
+    ) -> bool {
+        match event {
+            Event::Cmd(CMD_GOT_PRIMARY_FOCUS) | Event::Cmd(CMD_GOT_SECONDARY_FOCUS) => {
+                let data = window.data_mut::<ListBox>(tree);
+                if data.current.is_none() && !data.items.is_empty() {
+                    data.current = Some(data.selection.first().copied().unwrap_or(0));
+                    ListBox::bring_current_into_view(tree, window);
+                }
+                window.invalidate_render(tree);
+                return false;
+            },
+            Event::Cmd(CMD_LOST_PRIMARY_FOCUS) | Event::Cmd(CMD_LOST_SECONDARY_FOCUS) => {
+                window.invalidate_render(tree);
+                return false;
+            },
+            _ => { },
+        }
+        if !window.actual_is_enabled(tree) { return false; }
+        match event {
+            Event::Key(
+                key @ (Key::Up | Key::Down | Key::PageUp | Key::PageDown | Key::Home | Key::End),
+                mods
+            ) if mods == Mods::NONE || mods == Mods::SHIFT || mods == Mods::CTRL => {
+                let Some(index) = ListBox::key_target(tree, window, key) else { return false; };
+                ListBox::navigate(tree, window, index, mods, app);
+                true
+            },
+            Event::Key(Key::Char(' '), Mods::NONE) if window.data::<ListBox>(tree).search.is_empty() => {
+                let Some(current) = window.data::<ListBox>(tree).current else { return false; };
+                ListBox::toggle(tree, window, current, app);
+                true
+            },
+            Event::Key(Key::Char(c), mods) if mods.without(Mods::SHIFT).is_empty() => {
+                ListBox::type_ahead(tree, window, c, app);
+                true
+            },
+            Event::LmbDown(_, mods) => {
+                let presenter = ListBox::presenter(tree, window);
+                if event_source.parent(tree).and_then(|x| x.parent(tree)) != Some(presenter) { return false; }
+                let Some(index) = event_source.source_index(tree) else { return false; };
+                if mods == Mods::CTRL {
+                    ListBox::toggle(tree, window, index, app);
+                } else {
+                    let mods = if mods == Mods::SHIFT { mods } else { Mods::NONE };
+                    ListBox::navigate(tree, window, index, mods, app);
+                }
+                true
+            },
+            _ => false
+        }
+    }
+}
+
+#[derive(Clone)]
+struct ListBoxItemData;
+
+impl Data for ListBoxItemData { }
+
+struct ListBoxItem;
+
+impl WidgetData for ListBoxItem { }
+
+#[derive(Clone, Default)]
+struct ListBoxItemWidget;
+
+impl_supports_interfaces!(ListBoxItemWidget);
+
+impl Widget for ListBoxItemWidget {
+    fn new(&self) -> Box<dyn WidgetData> {
+        Box::new(ListBoxItem)
+    }
+
+    fn clone_data(
+        &self,
+        _tree: &mut WindowTree,
+        _source: Window,
+        _dest: Window,
+        _clone_window: Box<dyn Fn(&WindowTree, Window) -> Window>,
+    ) { }
+
+    fn render(
+        &self,
+        tree: &WindowTree,
+        window: Window,
+        rp: &mut RenderPort,
+        _app: &mut dyn App,
+    ) {
+        let Some(list_box) = ListBox::item_list_box(tree, window) else { return; };
+        let Some(index) = window.source_index(tree) else { return; };
+        let data = list_box.data::<ListBox>(tree);
+        let Some(text) = data.items.get(index) else { return; };
+        let focused = data.current == Some(index) && list_box.is_focused(tree);
+        let selected = data.selection.contains(&index);
+        let color = if !list_box.actual_is_enabled(tree) {
+            4
+        } else {
+            match (focused, selected) {
+                (false, false) => 0,
+                (false, true) => 1,
+                (true, false) => 2,
+                (true, true) => 3,
+            }
+        };
+        let color = list_box.color(tree, color);
+        rp.fill_bg(color);
+        rp.text(Point { x: 1, y: 0 }, color, text);
+    }
+
+    fn measure(
+        &self,
+        tree: &mut WindowTree,
+        window: Window,
+        _available_width: Option<i16>,
+        _available_height: Option<i16>,
+        _app: &mut dyn App,
+    ) -> Vector {
+        let width = ListBox::item_list_box(tree, window)
+            .zip(window.source_index(tree))
+            .and_then(|(list_box, index)| list_box.data::<ListBox>(tree).items.get(index))
+            .map_or(0, |text| text_width(text))
+        ;
+        Vector { x: width.wrapping_add(2), y: 1 }
+    }
+
+    fn arrange(
+        &self,
+        _tree: &mut WindowTree,
+        _window: Window,
+        final_inner_bounds: Rect,
+        _app: &mut dyn App,
+    ) -> Vector {
+        Vector { x: final_inner_bounds.w(), y: 1 }
+    }
+
+    fn update(
+        &self,
+        _tree: &mut WindowTree,
+        _window: Window,
+        _event: Event,
+        _event_source: Window,
+        _app: &mut dyn App,
+    ) -> bool {
+        false
+    }
+}
@@ -0,0 +1,945 @@
+use crate::widget;
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cmp::max;
+use dynamic_cast::impl_supports_interfaces;
+use tuifw_screen_base::{Key, Mods, Point, Rect, Vector, Error, ExtFg, ExtBg, Attr, text_width};
+use tuifw_window::{Event, RenderPort, Widget, WidgetData, Window, WindowTree, App, Color, label_width, label};
+use tuifw_window::ContextMenuWidgetExtension;
+use tuifw_window::{COLOR_MENU, COLOR_MENU_HOTKEY, COLOR_MENU_DISABLED, COLOR_MENU_SELECTED};
+use tuifw_window::{COLOR_MENU_SELECTED_HOTKEY, COLOR_MENU_SELECTED_DISABLED};
+
+pub const CMD_MENU_ITEM_CLICK: u16 = 170;
+
+fn init_menu_palette(tree: &mut WindowTree, window: Window) -> Result<(), Error> {
+    window.palette_mut(tree, |palette| {
+        palette.set(0, Color::Palette(COLOR_MENU));
+        palette.set(1, Color::Palette(COLOR_MENU_HOTKEY));
+        palette.set(2, Color::Palette(COLOR_MENU_DISABLED));
+        palette.set(3, Color::Palette(COLOR_MENU_SELECTED));
+        palette.set(4, Color::Palette(COLOR_MENU_SELECTED_HOTKEY));
+        palette.set(5, Color::Palette(COLOR_MENU_SELECTED_DISABLED));
+    });
+    Ok(())
+}
+
+fn menu_colors(
+    tree: &WindowTree,
+    window: Window,
+    selected: bool,
+    enabled: bool
+) -> ((ExtFg, ExtBg, Attr), (ExtFg, ExtBg, Attr)) {
+    let base = if selected { 3 } else { 0 };
+    if enabled {
+        (window.color(tree, base), window.color(tree, base + 1))
+    } else {
+        let color = window.color(tree, base + 2);
+        (color, color)
+    }
+}
+
+enum Entry {
+    Separator,
+    Item { text: String, shortcut: String, is_checked: bool },
+    Submenu { text: String },
+}
+
+trait MenuEntryWidgetExtension {
+    fn entry(&self, tree: &WindowTree, window: Window) -> Entry;
+}
+
+fn entry(tree: &WindowTree, window: Window) -> Option<Entry> {
+    window.widget_extension::<dyn MenuEntryWidgetExtension>(tree).map(|x| x.entry(tree, window))
+}
+
+fn entries(tree: &WindowTree, menu: Window) -> Vec<Window> {
+    let mut res = Vec::new();
+    if let Some(first_child) = menu.first_child(tree) {
+        let mut child = first_child;
+        loop {
+            if entry(tree, child).is_some() {
+                res.push(child);
+            }
+            child = child.next(tree);
+            if child == first_child { break; }
+        }
+    }
+    res
+}
+
+fn entry_hotkey(tree: &WindowTree, window: Window) -> Option<char> {
+    match entry(tree, window)? {
+        Entry::Separator => None,
+        Entry::Item { text, .. } | Entry::Submenu { text } => label(&text),
+    }
+}
+
+widget! {
+    #[widget(MenuItemWidget)]
+    pub struct MenuItem {
+        #[property(str)]
+        text: String,
+        #[property(str)]
+        shortcut: String,
+        #[property(copy)]
+        accel: Option<(Key, Mods)>,
+        #[property(copy)]
+        is_checked: bool,
+        #[property(copy)]
+        cmd: u16,
+    }
+}
+
+#[derive(Clone, Default)]
+struct MenuItemWidget;
+
+impl_supports_interfaces!(MenuItemWidget: MenuEntryWidgetExtension);
+
+impl MenuEntryWidgetExtension for MenuItemWidget {
+    fn entry(&self, tree: &WindowTree, window: Window) -> Entry {
+        let data = window.data::<MenuItem>(tree);
+        Entry::Item { text: data.text.clone(), shortcut: data.shortcut.clone(), is_checked: data.is_checked }
+    }
+}
+
+impl Widget for MenuItemWidget {
+    fn new(&self) -> Box<dyn WidgetData> {
+        Box::new(MenuItem {
+            text: String::new(),
+            shortcut: String::new(),
+            accel: None,
+            is_checked: false,
+            cmd: CMD_MENU_ITEM_CLICK,
+        })
+    }
+
+    fn clone_data(
+        &self,
+        tree: &mut WindowTree,
+        source: Window,
+        dest: Window,
+        clone_window: Box<dyn Fn(&WindowTree, Window) -> Window>,
+    ) {
+        MenuItem::clone(tree, source, dest, clone_window);
+    }
+
+    fn render(
+        &self,
+        _tree: &WindowTree,
+        _window: Window,
+        _rp: &mut RenderPort,
+        _app: &mut dyn App,
+    ) { }
+
+    fn measure(
+        &self,
+        _tree: &mut WindowTree,
+        _window: Window,
+        _available_width: Option<i16>,
+        _available_height: Option<i16>,
+        _app: &mut dyn App,
+    ) -> Vector {
+        Vector::null()
+    }
+
+    fn arrange(
+        &self,
+        _tree: &mut WindowTree,
+        _window: Window,
+        _final_inner_bounds: Rect,
+        _app: &mut dyn App,
+    ) -> Vector {
+        Vector::null()
+    }
+
+    fn update(
+        &self,
+        _tree: &mut WindowTree,
+        _window: Window,
+        _event: Event,
+        _event_source: Window,
+        _app: &mut dyn App,
+    ) -> bool {
+        false
+    }
+}
+
+widget! {
+    #[widget(MenuSeparatorWidget)]
+    pub struct MenuSeparator { }
+}
+
+#[derive(Clone, Default)]
+struct MenuSeparatorWidget;
+
+impl_supports_interfaces!(MenuSeparatorWidget: MenuEntryWidgetExtension);
+
+impl MenuEntryWidgetExtension for MenuSeparatorWidget {
+    fn entry(&self, _tree: &WindowTree, _window: Window) -> Entry {
+        Entry::Separator
+    }
+}
+
+impl Widget for MenuSeparatorWidget {
+    fn new(&self) -> Box<dyn WidgetData> {
+        Box::new(MenuSeparator { })
+    }
+
+    fn clone_data(
+        &self,
+        tree: &mut WindowTree,
+        source: Window,
+        dest: Window,
+        clone_window: Box<dyn Fn(&WindowTree, Window) -> Window>,
+    ) {
+        MenuSeparator::clone(tree, source, dest, clone_window);
+    }
+
+    fn render(
+        &self,
+        _tree: &WindowTree,
+        _window: Window,
+        _rp: &mut RenderPort,
+        _app: &mut dyn App,
+    ) { }
+
+    fn measure(
+        &self,
+        _tree: &mut WindowTree,
+        _window: Window,
+        _available_width: Option<i16>,
+        _available_height: Option<i16>,
+        _app: &mut dyn App,
+    ) -> Vector {
+        Vector::null()
+    }
+
+    fn arrange(
+        &self,
+        _tree: &mut WindowTree,
+        _window: Window,
+        _final_inner_bounds: Rect,
+        _app: &mut dyn App,
+    ) -> Vector {
+        Vector::null()
+    }
+
+    fn update(
+        &self,
+        _tree: &mut WindowTree,
+        _window: Window,
+        _event: Event,
+        _event_source: Window,
+        _app: &mut dyn App,
+    ) -> bool {
+        false
+    }
+}
+
+widget! {
+    #[widget(MenuWidget, init=init_palette)]
+    pub struct Menu {
+        #[property(str, measure)]
+        text: String,
+        is_open: bool,
+    }
+}
+
+impl Menu {
+    fn init_palette(tree: &mut WindowTree, window: Window) -> Result<(), Error> {
+        init_menu_palette(tree, window)
+    }
+
+    fn set_is_open(tree: &mut WindowTree, window: Window, value: bool) {
+        window.data_mut::<Menu>(tree).is_open = value;
+        window.invalidate_render(tree);
+    }
+}
+
+#[derive(Clone, Default)]
+struct MenuWidget;
+
+impl_supports_interfaces!(MenuWidget: MenuEntryWidgetExtension, ContextMenuWidgetExtension);
+
+impl MenuEntryWidgetExtension for MenuWidget {
+    fn entry(&self, tree: &WindowTree, window: Window) -> Entry {
+        Entry::Submenu { text: window.data::<Menu>(tree).text.clone() }
+    }
+}
+
+impl ContextMenuWidgetExtension for MenuWidget {
+    fn open_context_menu(
+        &self,
+        tree: &mut WindowTree,
+        template: Window,
+        owner: Window,
+        point: Point,
+        app: &mut dyn App,
+    ) -> Result<(), Error> {
+        let popup = MenuPopup::new_popup(tree)?;
+        let menu = match template.new_instance(tree, Some(popup), None) {
+            Ok(menu) => menu,
+            Err(e) => {
+                popup.drop_window(tree, app);
+                return Err(e);
+            },
+        };
+        popup.data_mut::<MenuPopup>(tree).owner = Some(owner);
+        MenuPopup::push_level(tree, popup, menu, Rect { tl: point, size: Vector::null() }, false);
+        Ok(())
+    }
+}
+
+impl Widget for MenuWidget {
+    fn new(&self) -> Box<dyn WidgetData> {
+        Box::new(Menu {
+            text: String::new(),
+            is_open: false,
+        })
+    }
+
+    fn clone_data(
+        &self,
+        tree: &mut WindowTree,
+        source: Window,
+        dest: Window,
+        clone_window: Box<dyn Fn(&WindowTree, Window) -> Window>,
+    ) {
+        Menu::clone(tree, source, dest, clone_window);
+    }
+
+    fn render(
+        &self,
+        tree: &WindowTree,
+        window: Window,
+        rp: &mut RenderPort,
+        _app: &mut dyn App,
+    ) {
+        let data = window.data::<Menu>(tree);
+        let (color, color_hotkey) = menu_colors(tree, window, data.is_open, window.actual_is_enabled(tree));
+        rp.fill_bg(color);
+        rp.label(Point { x: 1, y: 0 }, color, color_hotkey, &data.text);
+    }
+
+    fn measure(
+        &self,
+        tree: &mut WindowTree,
+        window: Window,
+        _available_width: Option<i16>,
+        _available_height: Option<i16>,
+        _app: &mut dyn App,
+    ) -> Vector {
+        let data = window.data::<Menu>(tree);
+        Vector { x: label_width(&data.text).wrapping_add(2), y: 1 }
+    }
+
+    fn arrange(
+        &self,
+        tree: &mut WindowTree,
+        window: Window,
+        _final_inner_bounds: Rect,
+        _app: &mut dyn App,
+    ) -> Vector {
+        let data = window.data::<Menu>(tree);
+        Vector { x: label_width(&data.text).wrapping_add(2), y: 1 }
+    }
+
+    fn update(
+        &self,
+        _tree: &mut WindowTree,
+        _window: Window,
+        _event: Event,
+        _event_source: Window,
+        _app: &mut dyn App,
+    ) -> bool {
+        false
+    }
+}
+
+widget! {
+    #[widget(MenuBarWidget, init=init_palette, drop=drop_popup)]
+    pub struct MenuBar {
+        popup: Option<Window>,
+    }
+}
+
+impl MenuBar {
+    fn init_palette(tree: &mut WindowTree, window: Window) -> Result<(), Error> {
+        init_menu_palette(tree, window)
+    }
+
+    fn drop_popup(&mut self, tree: &mut WindowTree, app: &mut dyn App) {
+        if let Some(popup) = self.popup.take() {
+            popup.drop_window(tree, app);
+        }
+    }
+
+    fn menus(tree: &WindowTree, window: Window) -> Vec<Window> {
+        entries(tree, window).into_iter()
+            .filter(|&x| matches!(entry(tree, x), Some(Entry::Submenu { .. })))
+            .collect()
+    }
+
+    fn open(tree: &mut WindowTree, window: Window, menu: Window) -> Result<(), Error> {
+        let anchor = menu.screen_bounds(tree);
+        let popup = MenuPopup::open(tree, window, menu, anchor)?;
+        window.data_mut::<MenuBar>(tree).popup = Some(popup);
+        Ok(())
+    }
+
+    fn find_accel(tree: &WindowTree, menu: Window, key: Key, mods: Mods) -> Option<Window> {
+        for item in entries(tree, menu) {
+            if !item.actual_is_enabled(tree) { continue; }
+            match entry(tree, item) {
+                Some(Entry::Item { .. }) => {
+                    if item.data::<MenuItem>(tree).accel == Some((key, mods)) {
+                        return Some(item);
+                    }
+                },
+                Some(Entry::Submenu { .. }) => {
+                    if let Some(item) = Self::find_accel(tree, item, key, mods) {
+                        return Some(item);
+                    }
+                },
+                _ => { },
+            }
+        }
+        None
+    }
+}
+
+#[derive(Clone, Default)]
+struct MenuBarWidget;
+
+impl_supports_interfaces!(MenuBarWidget);
+
+impl Widget for MenuBarWidget {
+    fn new(&self) -> Box<dyn WidgetData> {
+        Box::new(MenuBar {
+            popup: None,
+        })
+    }
+
+    fn clone_data(
+        &self,
+        tree: &mut WindowTree,
+        source: Window,
+        dest: Window,
+        clone_window: Box<dyn Fn(&WindowTree, Window) -> Window>,
+    ) {
+        MenuBar::clone(tree, source, dest, clone_window);
+    }
+
+    fn render(
+        &self,
+        tree: &WindowTree,
+        window: Window,
+        rp: &mut RenderPort,
+        _app: &mut dyn App,
+    ) {
+        rp.fill_bg(window.color(tree, 0));
+    }
+
+    fn measure(
+        &self,
+        tree: &mut WindowTree,
+        window: Window,
+        _available_width: Option<i16>,
+        _available_height: Option<i16>,
+        app: &mut dyn App,
+    ) -> Vector {
+        let mut width = 1i16;
+        for menu in MenuBar::menus(tree, window) {
+            menu.measure(tree, None, Some(1), app);
+            width = width.wrapping_add(menu.desired_size(tree).x);
+        }
+        Vector { x: width, y: 1 }
+    }
+
+    fn arrange(
+        &self,
+        tree: &mut WindowTree,
+        window: Window,
+        final_inner_bounds: Rect,
+        app: &mut dyn App,
+    ) -> Vector {
+        let mut x = 1i16;
+        for menu in MenuBar::menus(tree, window) {
+            let width = menu.desired_size(tree).x;
+            menu.arrange(tree, Rect { tl: Point { x, y: 0 }, size: Vector { x: width, y: 1 } }, app);
+            x = x.wrapping_add(width);
+        }
+        Vector { x: final_inner_bounds.w(), y: 1 }
+    }
+
+    fn update(
+        &self,
+        tree: &mut WindowTree,
+        window: Window,
+        event: Event,
+        event_source: Window,
+        app: &mut dyn App,
+    ) -> bool {
+        if window.data::<MenuBar>(tree).popup.is_some() { return false; }
+        match event {
+            Event::PreProcessKey(Key::F10, Mods::NONE) => {
+                let menu = MenuBar::menus(tree, window).into_iter().find(|x| x.actual_is_enabled(tree));
+                menu.is_some_and(|menu| MenuBar::open(tree, window, menu).is_ok())
+            },
+            Event::PreProcessKey(Key::Alt(c), Mods::NONE) if window.actual_is_enabled(tree) => {
+                let c = c.to_lowercase().next().unwrap();
+                let menu = MenuBar::menus(tree, window).into_iter()
+                    .find(|&x| x.actual_is_enabled(tree) && entry_hotkey(tree, x) == Some(c));
+                menu.is_some_and(|menu| MenuBar::open(tree, window, menu).is_ok())
+            },
+            Event::PreProcessKey(key, mods) if window.actual_is_enabled(tree) => {
+                if let Some(item) = MenuBar::find_accel(tree, window, key, mods) {
+                    let cmd = item.data::<MenuItem>(tree).cmd;
+                    item.raise(tree, Event::Cmd(cmd), app);
+                    return true;
+                }
+                false
+            },
+            Event::LmbDown(..) if event_source != window => {
+                if event_source.parent(tree) == Some(window) && event_source.actual_is_enabled(tree) {
+                    if matches!(entry(tree, event_source), Some(Entry::Submenu { .. })) {
+                        return MenuBar::open(tree, window, event_source).is_ok();
+                    }
+                }
+                false
+            },
+            _ => false
+        }
+    }
+
+    fn pre_process(&self) -> bool { true }
+}
+
+struct Level {
+    menu: Window,
+    bounds: Rect,
+    selected: Option<Window>,
+}
+
+#[derive(Clone, Copy)]
+enum Press {
+    Item(Window),
+    Close,
+}
+
+struct MenuPopup {
+    bar: Option<Window>,
+    owner: Option<Window>,
+    levels: Vec<Level>,
+    press: Option<Press>,
+}
+
+impl WidgetData for MenuPopup { }
+
+impl MenuPopup {
+    fn new_popup(tree: &mut WindowTree) -> Result<Window, Error> {
+        let top = tree.first_child().map(|x| x.prev(tree));
+        let popup = Window::new(tree, Box::new(MenuPopupWidget), None, None)?;
+        init_menu_palette(tree, popup)?;
+        popup.move_z(tree, top);
+        Ok(popup)
+    }
+
+    fn open(tree: &mut WindowTree, bar: Window, menu: Window, anchor: Rect) -> Result<Window, Error> {
+        let popup = Self::new_popup(tree)?;
+        popup.data_mut::<MenuPopup>(tree).bar = Some(bar);
+        Self::push_level(tree, popup, menu, anchor, false);
+        Ok(popup)
+    }
+
+    fn close(tree: &mut WindowTree, popup: Window, app: &mut dyn App) {
+        let data = popup.data::<MenuPopup>(tree);
+        let bar = data.bar;
+        let root = data.levels.first().map(|x| x.menu);
+        if let Some(root) = root.filter(|_| bar.is_some()) {
+            Menu::set_is_open(tree, root, false);
+        }
+        if let Some(bar) = bar {
+            bar.data_mut::<MenuBar>(tree).popup = None;
+        }
+        popup.drop_window(tree, app);
+    }
+
+    fn level_size(tree: &WindowTree, menu: Window) -> Vector {
+        let mut text = 0;
+        let mut right = 0;
+        let entries = entries(tree, menu);
+        for &item in &entries {
+            match entry(tree, item) {
+                Some(Entry::Item { text: item_text, shortcut, .. }) => {
+                    text = max(text, label_width(&item_text));
+                    right = max(right, text_width(&shortcut));
+                },
+                Some(Entry::Submenu { text: item_text }) => {
+                    text = max(text, label_width(&item_text));
+                    right = max(right, 1);
+                },
+                _ => { },
+            }
+        }
+        let right = if right == 0 { 0 } else { right.wrapping_add(2) };
+        Vector {
+            x: text.wrapping_add(right).wrapping_add(5),
+            y: (entries.len() as i16).wrapping_add(2)
+        }
+    }
+
+    fn push_level(tree: &mut WindowTree, popup: Window, menu: Window, anchor: Rect, submenu: bool) {
+        let size = Self::level_size(tree, menu);
+        let screen = tree.screen_size();
+        let mut tl = if submenu {
+            Point { x: anchor.r(), y: anchor.t().wrapping_sub(1) }
+        } else {
+            Point { x: anchor.l(), y: anchor.b() }
+        };
+        if tl.x.wrapping_add(size.x) > screen.x {
+            tl.x = if submenu { anchor.l().wrapping_sub(size.x) } else { screen.x.wrapping_sub(size.x) };
+        }
+        if tl.y.wrapping_add(size.y) > screen.y {
+            tl.y = screen.y.wrapping_sub(size.y);
+        }
+        let bounds = Rect { tl: Point { x: max(tl.x, 0), y: max(tl.y, 0) }, size };
+        let selected = entries(tree, menu).into_iter().find(|&x| Self::is_selectable(tree, x));
+        if !submenu {
+            if popup.data::<MenuPopup>(tree).bar.is_some() {
+                Menu::set_is_open(tree, menu, true);
+            }
+        }
+        popup.data_mut::<MenuPopup>(tree).levels.push(Level { menu, bounds, selected });
+        popup.invalidate_rect(tree, bounds);
+    }
+
+    fn pop_level(tree: &mut WindowTree, popup: Window) {
+        let level = popup.data_mut::<MenuPopup>(tree).levels.pop().unwrap();
+        popup.invalidate_rect(tree, level.bounds);
+    }
+
+    fn switch_root(tree: &mut WindowTree, popup: Window, menu: Window) {
+        while popup.data::<MenuPopup>(tree).levels.len() > 1 {
+            Self::pop_level(tree, popup);
+        }
+        let old_menu = popup.data::<MenuPopup>(tree).levels[0].menu;
+        if old_menu == menu { return; }
+        Self::pop_level(tree, popup);
+        Menu::set_is_open(tree, old_menu, false);
+        let anchor = menu.screen_bounds(tree);
+        Self::push_level(tree, popup, menu, anchor, false);
+    }
+
+    fn switch_root_by(tree: &mut WindowTree, popup: Window, delta: isize) {
+        let Some(bar) = popup.data::<MenuPopup>(tree).bar else { return; };
+        let root = popup.data::<MenuPopup>(tree).levels[0].menu;
+        let menus = MenuBar::menus(tree, bar).into_iter()
+            .filter(|x| x.actual_is_enabled(tree))
+            .collect::<Vec<_>>();
+        let Some(index) = menus.iter().position(|&x| x == root) else { return; };
+        let index = (index as isize + delta).rem_euclid(menus.len() as isize) as usize;
+        Self::switch_root(tree, popup, menus[index]);
+    }
+
+    fn is_selectable(tree: &WindowTree, item: Window) -> bool {
+        !matches!(entry(tree, item), None | Some(Entry::Separator))
+    }
+
+    fn select(tree: &mut WindowTree, popup: Window, item: Option<Window>) {
+        let level = popup.data_mut::<MenuPopup>(tree).levels.last_mut().unwrap();
+        level.selected = item;
+        let bounds = level.bounds;
+        popup.invalidate_rect(tree, bounds);
+    }
+
+    fn move_selection(tree: &mut WindowTree, popup: Window, forward: bool) {
+        let level = popup.data::<MenuPopup>(tree).levels.last().unwrap();
+        let mut items = entries(tree, level.menu);
+        if !forward { items.reverse(); }
+        let start = level.selected.and_then(|x| items.iter().position(|&i| i == x)).map_or(0, |x| x + 1);
+        let next = (0 .. items.len())
+            .map(|i| items[(start + i) % items.len()])
+            .find(|&x| Self::is_selectable(tree, x));
+        Self::select(tree, popup, next);
+    }
+
+    fn open_submenu(tree: &mut WindowTree, popup: Window, item: Window) {
+        let level = popup.data::<MenuPopup>(tree).levels.last().unwrap();
+        if level.menu == item { return; }
+        let Some(index) = entries(tree, level.menu).iter().position(|&x| x == item) else { return; };
+        let anchor = Rect {
+            tl: Point { x: level.bounds.l(), y: level.bounds.t().wrapping_add(1).wrapping_add(index as i16) },
+            size: Vector { x: level.bounds.w(), y: 1 }
+        };
+        Self::push_level(tree, popup, item, anchor, true);
+    }
+
+    fn activate(tree: &mut WindowTree, popup: Window, item: Window, app: &mut dyn App) {
+        if !item.actual_is_enabled(tree) { return; }
+        match entry(tree, item) {
+            Some(Entry::Submenu { .. }) => Self::open_submenu(tree, popup, item),
+            Some(Entry::Item { .. }) => {
+                let cmd = item.data::<MenuItem>(tree).cmd;
+                let target = popup.data::<MenuPopup>(tree).owner.unwrap_or(item);
+                Self::close(tree, popup, app);
+                target.raise(tree, Event::Cmd(cmd), app);
+            },
+            _ => { },
+        }
+    }
+
+    fn key(tree: &mut WindowTree, popup: Window, key: Key, mods: Mods, app: &mut dyn App) {
+        let levels = popup.data::<MenuPopup>(tree).levels.len();
+        let selected = popup.data::<MenuPopup>(tree).levels.last().unwrap().selected;
+        match (key, mods) {
+            (Key::Escape, Mods::NONE) => if levels > 1 {
+                Self::pop_level(tree, popup);
+            } else {
+                Self::close(tree, popup, app);
+            },
+            (Key::F10, Mods::NONE) => Self::close(tree, popup, app),
+            (Key::Up, Mods::NONE) => Self::move_selection(tree, popup, false),
+            (Key::Down, Mods::NONE) => Self::move_selection(tree, popup, true),
+            (Key::Home, Mods::NONE) => {
+                Self::select(tree, popup, None);
+                Self::move_selection(tree, popup, true);
+            },
+            (Key::End, Mods::NONE) => {
+                Self::select(tree, popup, None);
+                Self::move_selection(tree, popup, false);
+            },
+            (Key::Enter, Mods::NONE) => if let Some(selected) = selected {
+                Self::activate(tree, popup, selected, app);
+            },
+            (Key::Right, Mods::NONE) => {
+                if let Some(selected) = selected.filter(|&x| matches!(entry(tree, x), Some(Entry::Submenu { .. }))) {
+                    Self::activate(tree, popup, selected, app);
+                } else {
+                    Self::switch_root_by(tree, popup, 1);
+                }
+            },
+            (Key::Left, Mods::NONE) => if levels > 1 {
+                Self::pop_level(tree, popup);
+            } else {
+                Self::switch_root_by(tree, popup, -1);
+            },
+            (Key::Char(c), Mods::NONE) | (Key::Alt(c), Mods::NONE) => {
+                let c = c.to_lowercase().next().unwrap();
+                let menu = popup.data::<MenuPopup>(tree).levels.last().unwrap().menu;
+                let item = entries(tree, menu).into_iter().find(|&x| entry_hotkey(tree, x) == Some(c));
+                if let Some(item) = item {
+                    Self::select(tree, popup, Some(item));
+                    Self::activate(tree, popup, item, app);
+                } else if matches!(key, Key::Alt(_)) {
+                    if let Some(bar) = popup.data::<MenuPopup>(tree).bar {
+                        let menu = MenuBar::menus(tree, bar).into_iter()
+                            .find(|&x| x.actual_is_enabled(tree) && entry_hotkey(tree, x) == Some(c));
+                        if let Some(menu) = menu {
+                            Self::switch_root(tree, popup, menu);
+                        }
+                    }
+                }
+            },
+            _ => { },
+        }
+    }
+
+    fn hit_item(tree: &WindowTree, popup: Window, p: Point) -> Option<(usize, Option<Window>)> {
+        let levels = &popup.data::<MenuPopup>(tree).levels;
+        for (level_index, level) in levels.iter().enumerate().rev() {
+            if !level.bounds.contains(p) { continue; }
+            let row = p.y.wrapping_sub(level.bounds.t()).wrapping_sub(1);
+            let item = if row < 0 || p.x == level.bounds.l() || p.x == level.bounds.r_inner() {
+                None
+            } else {
+                entries(tree, level.menu).get(row as usize).copied()
+            };
+            return Some((level_index, item.filter(|&x| Self::is_selectable(tree, x))));
+        }
+        None
+    }
+
+    fn press(tree: &mut WindowTree, popup: Window, p: Point) -> Option<Press> {
+        if let Some((level_index, item)) = Self::hit_item(tree, popup, p) {
+            while popup.data::<MenuPopup>(tree).levels.len() > level_index + 1 {
+                Self::pop_level(tree, popup);
+            }
+            let item = item?;
+            Self::select(tree, popup, Some(item));
+            if matches!(entry(tree, item), Some(Entry::Submenu { .. })) {
+                if item.actual_is_enabled(tree) {
+                    Self::open_submenu(tree, popup, item);
+                }
+                return None;
+            }
+            return Some(Press::Item(item));
+        }
+        if let Some(bar) = popup.data::<MenuPopup>(tree).bar {
+            let menu = MenuBar::menus(tree, bar).into_iter()
+                .find(|&x| x.screen_bounds(tree).contains(p) && x.actual_is_enabled(tree));
+            if let Some(menu) = menu {
+                if popup.data::<MenuPopup>(tree).levels[0].menu == menu { return Some(Press::Close); }
+                Self::switch_root(tree, popup, menu);
+                return None;
+            }
+        }
+        Some(Press::Close)
+    }
+
+    fn render_level(tree: &WindowTree, popup: Window, level: &Level, rp: &mut RenderPort) {
+        let bounds = level.bounds;
+        let color = popup.color(tree, 0);
+        for y in bounds.t() .. bounds.b() {
+            for x in bounds.l() .. bounds.r() {
+                rp.text(Point { x, y }, color, " ");
+            }
+        }
+        rp.h_line(bounds.tl, bounds.w(), false, color);
+        rp.h_line(bounds.bl_inner(), bounds.w(), false, color);
+        rp.v_line(bounds.tl, bounds.h(), false, color);
+        rp.v_line(bounds.tr_inner(), bounds.h(), false, color);
+        rp.tl_edge(bounds.tl, false, color);
+        rp.tr_edge(bounds.tr_inner(), false, color);
+        rp.br_edge(bounds.br_inner(), false, color);
+        rp.bl_edge(bounds.bl_inner(), false, color);
+        for (i, item) in entries(tree, level.menu).into_iter().enumerate() {
+            let y = bounds.t().wrapping_add(1).wrapping_add(i as i16);
+            let Some(entry) = entry(tree, item) else { continue; };
+            let (text, right, is_checked) = match entry {
+                Entry::Separator => {
+                    rp.text(Point { x: bounds.l(), y }, color, "├");
+                    rp.h_line(Point { x: bounds.l().wrapping_add(1), y }, bounds.w().wrapping_sub(2), false, color);
+                    rp.text(Point { x: bounds.r_inner(), y }, color, "┤");
+                    continue;
+                },
+                Entry::Item { text, shortcut, is_checked } => (text, shortcut, is_checked),
+                Entry::Submenu { text } => (text, String::from("►"), false),
+            };
+            let selected = level.selected == Some(item);
+            let (color, color_hotkey) = menu_colors(tree, popup, selected, item.actual_is_enabled(tree));
+            for x in bounds.l().wrapping_add(1) .. bounds.r_inner() {
+                rp.text(Point { x, y }, color, " ");
+            }
+            if is_checked {
+                rp.text(Point { x: bounds.l().wrapping_add(1), y }, color, "√");
+            }
+            rp.label(Point { x: bounds.l().wrapping_add(3), y }, color, color_hotkey, &text);
+            let right_x = bounds.r_inner().wrapping_sub(1).wrapping_sub(text_width(&right));
+            rp.text(Point { x: right_x, y }, color, &right);
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+struct MenuPopupWidget;
+
+impl_supports_interfaces!(MenuPopupWidget);
+
+impl Widget for MenuPopupWidget {
+    fn new(&self) -> Box<dyn WidgetData> {
+        Box::new(MenuPopup {
+            bar: None,
+            owner: None,
+            levels: Vec::new(),
+            press: None,
+        })
+    }
+
+    fn clone_data(
+        &self,
+        _tree: &mut WindowTree,
+        _source: Window,
+        _dest: Window,
+        _clone_window: Box<dyn Fn(&WindowTree, Window) -> Window>,
+    ) { }
+
+    fn render(
+        &self,
+        tree: &WindowTree,
+        window: Window,
+        rp: &mut RenderPort,
+        _app: &mut dyn App,
+    ) {
+        let data = window.data::<MenuPopup>(tree);
+        for level in &data.levels {
+            MenuPopup::render_level(tree, window, level, rp);
+        }
+    }
+
+    fn measure(
+        &self,
+        _tree: &mut WindowTree,
+        _window: Window,
+        available_width: Option<i16>,
+        available_height: Option<i16>,
+        _app: &mut dyn App,
+    ) -> Vector {
+        Vector { x: available_width.unwrap_or(0), y: available_height.unwrap_or(0) }
+    }
+
+    fn arrange(
+        &self,
+        _tree: &mut WindowTree,
+        _window: Window,
+        final_inner_bounds: Rect,
+        _app: &mut dyn App,
+    ) -> Vector {
+        final_inner_bounds.size
+    }
+
+    fn update(
+        &self,
+        tree: &mut WindowTree,
+        window: Window,
+        event: Event,
+        _event_source: Window,
+        app: &mut dyn App,
+    ) -> bool {
+        match event {
+            Event::PreProcessKey(key, mods) => {
+                MenuPopup::key(tree, window, key, mods, app);
+                true
+            },
+            Event::LmbDown(point, _) | Event::RmbDown(point, _) => {
+                let point = window.inner_point(point, tree);
+                let press = MenuPopup::press(tree, window, point);
+                window.data_mut::<MenuPopup>(tree).press = press;
+                true
+            },
+            Event::Drag(point, _, _) => {
+                let point = window.inner_point(point, tree);
+                if let Some((level_index, item)) = MenuPopup::hit_item(tree, window, point) {
+                    if level_index + 1 == window.data::<MenuPopup>(tree).levels.len() {
+                        MenuPopup::select(tree, window, item);
+                        let press = item.filter(|&x| matches!(entry(tree, x), Some(Entry::Item { .. })));
+                        window.data_mut::<MenuPopup>(tree).press = press.map(Press::Item);
+                    }
+                }
+                true
+            },
+            Event::MouseMove(point, _) => {
+                let point = window.inner_point(point, tree);
+                if let Some((level_index, Some(item))) = MenuPopup::hit_item(tree, window, point) {
+                    if level_index + 1 == window.data::<MenuPopup>(tree).levels.len() {
+                        MenuPopup::select(tree, window, Some(item));
+                    }
+                }
+                true
+            },
+            Event::LmbUp | Event::RmbUp => {
+                match window.data_mut::<MenuPopup>(tree).press.take() {
+                    Some(Press::Item(item)) => MenuPopup::activate(tree, window, item, app),
+                    Some(Press::Close) => MenuPopup::close(tree, window, app),
+                    None => { },
+                }
+                true
+            },
+            Event::MmbDown(..) | Event::MmbUp | Event::DoubleClick(..) | Event::Wheel(..) => true,
+            _ => false
+        }
+    }
+
+    fn pre_process(&self) -> bool { true }
+}
@@ -0,0 +1,248 @@
+use crate::{Button, Frame, StackPanel, StaticText};
+use alloc::boxed::Box;
+use dynamic_cast::impl_supports_interfaces;
+use tuifw_screen_base::{Error, HAlign, Key, Mods, Rect, Thickness, VAlign, Vector};
+use tuifw_window::{App, Event, RenderPort, Timer, Widget, WidgetData, Window, WindowTree};
+
+pub const CMD_MESSAGE_BOX_OK: u16 = 180;
+pub const CMD_MESSAGE_BOX_CANCEL: u16 = 181;
+pub const CMD_MESSAGE_BOX_YES: u16 = 182;
+pub const CMD_MESSAGE_BOX_NO: u16 = 183;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum MessageBoxButtons {
+    Ok,
+    OkCancel,
+    YesNo,
+    YesNoCancel,
+}
+
+impl MessageBoxButtons {
+    fn cmds(self) -> &'static [u16] {
+        match self {
+            MessageBoxButtons::Ok => &[CMD_MESSAGE_BOX_OK],
+            MessageBoxButtons::OkCancel => &[CMD_MESSAGE_BOX_OK, CMD_MESSAGE_BOX_CANCEL],
+            MessageBoxButtons::YesNo => &[CMD_MESSAGE_BOX_YES, CMD_MESSAGE_BOX_NO],
+            MessageBoxButtons::YesNoCancel => &[CMD_MESSAGE_BOX_YES, CMD_MESSAGE_BOX_NO, CMD_MESSAGE_BOX_CANCEL],
+        }
+    }
+
+    fn escape_cmd(self) -> u16 {
+        match self {
+            MessageBoxButtons::Ok => CMD_MESSAGE_BOX_OK,
+            MessageBoxButtons::YesNo => CMD_MESSAGE_BOX_NO,
+            MessageBoxButtons::OkCancel | MessageBoxButtons::YesNoCancel => CMD_MESSAGE_BOX_CANCEL,
+        }
+    }
+}
+
+fn button_text(cmd: u16) -> &'static str {
+    match cmd {
+        CMD_MESSAGE_BOX_OK => "~O~K",
+        CMD_MESSAGE_BOX_CANCEL => "~C~ancel",
+        CMD_MESSAGE_BOX_YES => "~Y~es",
+        CMD_MESSAGE_BOX_NO => "~N~o",
+        _ => unreachable!(),
+    }
+}
+
+pub type MessageBoxCallback = Box<dyn FnOnce(&mut WindowTree, u16, &mut dyn App)>;
+
+pub struct MessageBox {
+    buttons: MessageBoxButtons,
+    callback: Option<MessageBoxCallback>,
+    close_timer: Option<Timer>,
+}
+
+impl WidgetData for MessageBox {
+    fn drop_widget_data(&mut self, tree: &mut WindowTree, _app: &mut dyn App) {
+        if let Some(close_timer) = self.close_timer.take() {
+            close_timer.drop_timer(tree);
+        }
+    }
+}
+
+impl MessageBox {
+    /// Shows a modal message box on top of all windows.
+    ///
+    /// The box closes when one of its buttons is clicked or Escape is pressed,
+    /// and then `callback` receives the chosen `CMD_MESSAGE_BOX_*` command.
+    pub fn show(
+        tree: &mut WindowTree,
+        title: &str,
+        text: &str,
+        buttons: MessageBoxButtons,
+        callback: MessageBoxCallback,
+        app: &mut dyn App,
+    ) -> Result<Window, Error> {
+        let top = tree.first_child().map(|x| x.prev(tree));
+        let window = Window::new(tree, Box::new(MessageBoxWidget), None, None)?;
+        window.move_z(tree, top);
+        let data = window.data_mut::<MessageBox>(tree);
+        data.buttons = buttons;
+        data.callback = Some(callback);
+        window.set_h_align(tree, Some(HAlign::Center));
+        window.set_v_align(tree, Some(VAlign::Center));
+        let focused = match Self::build(tree, window, title, text, buttons) {
+            Ok(focused) => focused,
+            Err(e) => {
+                window.drop_window(tree, app);
+                return Err(e);
+            },
+        };
+        focused.set_focused_primary(tree, true);
+        window.begin_modal(tree, app);
+        Ok(window)
+    }
+
+    fn build(
+        tree: &mut WindowTree,
+        window: Window,
+        title: &str,
+        text: &str,
+        buttons: MessageBoxButtons,
+    ) -> Result<Window, Error> {
+        let frame = Frame::new(tree, Some(window), None)?;
+        Frame::set_double(tree, frame, true);
+        Frame::set_text(tree, frame, title);
+        Frame::set_text_align(tree, frame, HAlign::Center);
+        let panel = StackPanel::new(tree, Some(frame), None)?;
+        panel.set_margin(tree, Thickness::new(1, 0, 1, 0));
+        let mut prev = None;
+        for line in text.split('\n') {
+            let static_text = StaticText::new(tree, Some(panel), prev)?;
+            StaticText::set_text(tree, static_text, line);
+            prev = Some(static_text);
+        }
+        let buttons_panel = StackPanel::new(tree, Some(panel), prev)?;
+        StackPanel::set_vertical(tree, buttons_panel, false);
+        buttons_panel.set_h_align(tree, Some(HAlign::Center));
+        buttons_panel.set_margin(tree, Thickness::new(0, 1, 0, 0));
+        let mut first = None;
+        let mut prev = None;
+        for &cmd in buttons.cmds() {
+            let button = Button::new(tree, Some(buttons_panel), prev)?;
+            Button::set_text(tree, button, button_text(cmd));
+            Button::set_cmd(tree, button, cmd);
+            button.set_margin(tree, Thickness::new(1, 0, 1, 0));
+            if let Some(prev) = prev {
+                prev.set_focus_right(tree, button);
+                prev.set_focus_tab(tree, button);
+                button.set_focus_back_tab(tree, prev);
+            }
+            first.get_or_insert(button);
+            prev = Some(button);
+        }
+        let first = first.unwrap();
+        let last = prev.unwrap();
+        last.set_focus_tab(tree, first);
+        first.set_focus_back_tab(tree, last);
+        Ok(first)
+    }
+
+    fn close(tree: &mut WindowTree, window: Window, cmd: u16) {
+        if window.data::<MessageBox>(tree).close_timer.is_some() { return; }
+        let close_timer = Timer::new(tree, 0, Box::new(move |tree, app| {
+            let data = window.data_mut::<MessageBox>(tree);
+            data.close_timer = None;
+            let callback = data.callback.take();
+            window.drop_window(tree, app);
+            if let Some(callback) = callback {
+                callback(tree, cmd, app);
+            }
+        }));
+        window.data_mut::<MessageBox>(tree).close_timer = Some(close_timer);
+    }
+}
+
+#[derive(Clone, Default)]
+struct MessageBoxWidget;
+
+impl_supports_interfaces!(MessageBoxWidget);
+
+impl Widget for MessageBoxWidget {
+    fn new(&self) -> Box<dyn WidgetData> {
+        Box::new(MessageBox {
+            buttons: MessageBoxButtons::Ok,
+            callback: None,
+            close_timer: None,
+        })
+    }
+
+    fn clone_data(
+        &self,
+        _tree: &mut WindowTree,
+        _source: Window,
+        _dest: Window,
+        _clone_window: Box<dyn Fn(&WindowTree, Window) -> Window>,
+    ) { }
+
+    fn render(
+        &self,
+        _tree: &WindowTree,
+        _window: Window,
+        _rp: &mut RenderPort,
+        _app: &mut dyn App,
+    ) { }
+
+    fn measure(
+        &self,
+        tree: &mut WindowTree,
+        window: Window,
+        available_width: Option<i16>,
+        available_height: Option<i16>,
+        app: &mut dyn App,
+    ) -> Vector {
+        let mut size = Vector::null();
+        if let Some(first_child) = window.first_child(tree) {
+            let mut child = first_child;
+            loop {
+                child.measure(tree, available_width, available_height, app);
+                size = size.max(child.desired_size(tree));
+                child = child.next(tree);
+                if child == first_child { break; }
+            }
+        }
+        size
+    }
+
+    fn arrange(
+        &self,
+        tree: &mut WindowTree,
+        window: Window,
+        final_inner_bounds: Rect,
+        app: &mut dyn App,
+    ) -> Vector {
+        if let Some(first_child) = window.first_child(tree) {
+            let mut child = first_child;
+            loop {
+                child.arrange(tree, final_inner_bounds, app);
+                child = child.next(tree);
+                if child == first_child { break; }
+            }
+        }
+        final_inner_bounds.size
+    }
+
+    fn update(
+        &self,
+        tree: &mut WindowTree,
+        window: Window,
+        event: Event,
+        _event_source: Window,
+        _app: &mut dyn App,
+    ) -> bool {
+        let buttons = window.data::<MessageBox>(tree).buttons;
+        match event {
+            Event::Cmd(cmd) if buttons.cmds().contains(&cmd) => {
+                MessageBox::close(tree, window, cmd);
+                true
+            },
+            Event::Key(Key::Escape, Mods::NONE) => {
+                MessageBox::close(tree, window, buttons.escape_cmd());
+                true
+            },
+            _ => false
+        }
+    }
+}
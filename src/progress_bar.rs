@@ -0,0 +1,190 @@
+use crate::widget;
+use alloc::boxed::Box;
+use alloc::format;
+use core::cmp::{max, min};
+use dynamic_cast::impl_supports_interfaces;
+use tuifw_screen_base::{Point, Rect, Vector, Error};
+use tuifw_window::{Event, RenderPort, Timer, Widget, WidgetData, Window, WindowTree, App, Color};
+use tuifw_window::{COLOR_PROGRESS_BAR, COLOR_LABEL, COLOR_DISABLED};
+
+const MARQUEE_SPAN_MS: u16 = 100;
+
+const PARTIAL_BLOCKS: [&str; 8] = [" ", "▏", "▎", "▍", "▌", "▋", "▊", "▉"];
+
+widget! {
+    #[widget(ProgressBarWidget, init=init_palette, drop=drop_timer)]
+    pub struct ProgressBar {
+        #[property(copy, render)]
+        value: f64,
+        #[property(copy, render)]
+        min: f64,
+        #[property(copy, render)]
+        max: f64,
+        #[property(copy, measure)]
+        show_percentage: bool,
+        #[property(copy, render, on_changed=on_is_indeterminate_changed)]
+        is_indeterminate: bool,
+        phase: u16,
+        timer: Option<Timer>,
+    }
+}
+
+impl ProgressBar {
+    fn init_palette(tree: &mut WindowTree, window: Window) -> Result<(), Error> {
+        window.palette_mut(tree, |palette| {
+            palette.set(0, Color::Palette(COLOR_PROGRESS_BAR));
+            palette.set(1, Color::Palette(COLOR_LABEL));
+            palette.set(2, Color::Palette(COLOR_DISABLED));
+        });
+        Ok(())
+    }
+
+    fn drop_timer(&mut self, tree: &mut WindowTree, _app: &mut dyn App) {
+        if let Some(timer) = self.timer.take() {
+            timer.drop_timer(tree);
+        }
+    }
+
+    fn on_is_indeterminate_changed(tree: &mut WindowTree, window: Window) {
+        let data = window.data_mut::<ProgressBar>(tree);
+        data.phase = 0;
+        if let Some(timer) = data.timer.take() {
+            timer.drop_timer(tree);
+        }
+        if window.data::<ProgressBar>(tree).is_indeterminate {
+            Self::schedule_step(tree, window);
+        }
+    }
+
+    fn schedule_step(tree: &mut WindowTree, window: Window) {
+        let timer = Timer::new(tree, MARQUEE_SPAN_MS, Box::new(move |tree, _app| {
+            let data = window.data_mut::<ProgressBar>(tree);
+            data.timer = None;
+            data.phase = data.phase.wrapping_add(1);
+            window.invalidate_render(tree);
+            Self::schedule_step(tree, window);
+        }));
+        window.data_mut::<ProgressBar>(tree).timer = Some(timer);
+    }
+
+    /// Returns the current value as a fraction of the `min..max` range, clamped to `0.0 ..= 1.0`.
+    pub fn fraction(tree: &WindowTree, window: Window) -> f64 {
+        let data = window.data::<ProgressBar>(tree);
+        let range = data.max - data.min;
+        if range <= 0.0 { return 0.0; }
+        ((data.value - data.min) / range).clamp(0.0, 1.0)
+    }
+}
+
+#[derive(Clone, Default)]
+struct ProgressBarWidget;
+
+impl_supports_interfaces!(ProgressBarWidget);
+
+impl Widget for ProgressBarWidget {
+    fn new(&self) -> Box<dyn WidgetData> {
+        Box::new(ProgressBar {
+            value: 0.0,
+            min: 0.0,
+            max: 100.0,
+            show_percentage: false,
+            is_indeterminate: false,
+            phase: 0,
+            timer: None,
+        })
+    }
+
+    fn clone_data(
+        &self,
+        tree: &mut WindowTree,
+        source: Window,
+        dest: Window,
+        clone_window: Box<dyn Fn(&WindowTree, Window) -> Window>,
+    ) {
+        ProgressBar::clone(tree, source, dest, clone_window);
+    }
+
+    fn render(
+        &self,
+        tree: &WindowTree,
+        window: Window,
+        rp: &mut RenderPort,
+        _app: &mut dyn App,
+    ) {
+        let is_enabled = window.actual_is_enabled(tree);
+        let bar_color = window.color(tree, if is_enabled { 0 } else { 2 });
+        let text_color = window.color(tree, if is_enabled { 1 } else { 2 });
+        let bounds = window.inner_bounds(tree);
+        let is_unicode = rp.is_unicode();
+        let full = if is_unicode { "█" } else { "#" };
+        let data = window.data::<ProgressBar>(tree);
+        let show_percentage = data.show_percentage && !data.is_indeterminate;
+        let width = if show_percentage { max(0, bounds.w() - 5) } else { bounds.w() };
+        rp.fill_bg(bar_color);
+        for y in 0 .. bounds.h() {
+            if data.is_indeterminate {
+                let segment = max(1, width / 4);
+                let span = max(1, width - segment);
+                let step = (data.phase % (2 * span as u16)) as i16;
+                let start = if step < span { step } else { 2 * span - step };
+                for x in start .. min(width, start + segment) {
+                    rp.text(Point { x, y }, bar_color, full);
+                }
+            } else {
+                let fraction = ProgressBar::fraction(tree, window);
+                if is_unicode {
+                    let eighths = (fraction * f64::from(width) * 8.0 + 0.5) as i16;
+                    for x in 0 .. eighths / 8 {
+                        rp.text(Point { x, y }, bar_color, full);
+                    }
+                    if eighths % 8 != 0 {
+                        rp.text(Point { x: eighths / 8, y }, bar_color, PARTIAL_BLOCKS[(eighths % 8) as usize]);
+                    }
+                } else {
+                    let filled = (fraction * f64::from(width) + 0.5) as i16;
+                    for x in 0 .. filled {
+                        rp.text(Point { x, y }, bar_color, full);
+                    }
+                }
+            }
+        }
+        if show_percentage {
+            let percentage = (ProgressBar::fraction(tree, window) * 100.0 + 0.5) as u8;
+            rp.text(Point { x: width, y: bounds.h() / 2 }, text_color, &format!("{percentage:>4}%"));
+        }
+    }
+
+    fn measure(
+        &self,
+        tree: &mut WindowTree,
+        window: Window,
+        available_width: Option<i16>,
+        _available_height: Option<i16>,
+        _app: &mut dyn App,
+    ) -> Vector {
+        let data = window.data::<ProgressBar>(tree);
+        let min_width = if data.show_percentage { 6 } else { 1 };
+        Vector { x: max(min_width, available_width.unwrap_or(min_width)), y: 1 }
+    }
+
+    fn arrange(
+        &self,
+        _tree: &mut WindowTree,
+        _window: Window,
+        final_inner_bounds: Rect,
+        _app: &mut dyn App,
+    ) -> Vector {
+        final_inner_bounds.size
+    }
+
+    fn update(
+        &self,
+        _tree: &mut WindowTree,
+        _window: Window,
+        _event: Event,
+        _event_source: Window,
+        _app: &mut dyn App,
+    ) -> bool {
+        false
+    }
+}
@@ -3,7 +3,7 @@ use alloc::boxed::Box;
 use alloc::string::String;
 use dynamic_cast::impl_supports_interfaces;
 use phantom_type::PhantomType;
-use tuifw_screen_base::{Key, Point, Rect, Vector, Error};
+use tuifw_screen_base::{Key, Mods, Point, Rect, Vector, Error};
 use tuifw_window::{Event, RenderPort, Widget, WidgetData, Window, WindowTree, App, Color};
 use tuifw_window::{CMD_GOT_PRIMARY_FOCUS, CMD_LOST_PRIMARY_FOCUS, label_width, label};
 use tuifw_window::{COLOR_LABEL, COLOR_HOTKEY, COLOR_DISABLED};
@@ -112,21 +112,21 @@ impl<RadioButton: IsRadioButton> RadioButtonController<RadioButton> {
         app: &mut dyn App,
     ) -> bool {
         match event {
-            Event::Key(Key::Char(' ')) => {
+            Event::Key(Key::Char(' '), Mods::NONE) => {
                 if window.actual_is_enabled(tree) {
                     Self::click(tree, window, app)
                 } else {
                     false
                 }
             },
-            Event::LmbDown(_) => {
+            Event::LmbDown(..) => {
                 if window.actual_is_enabled(tree) {
                     Self::click(tree, window, app)
                 } else {
                     false
                 }
             },
-            Event::PostProcessKey(Key::Alt(c)) | Event::PostProcessKey(Key::Char(c)) => {
+            Event::PostProcessKey(Key::Alt(c), Mods::NONE) | Event::PostProcessKey(Key::Char(c), Mods::NONE) => {
                 if window.actual_is_enabled(tree) {
                     let data = window.data_mut::<RadioButton>(tree);
                     let label = data.label();
@@ -0,0 +1,341 @@
+use crate::widget;
+use crate::virt_scroll_viewer::*;
+use alloc::boxed::Box;
+use core::cmp::{max, min};
+use dynamic_cast::impl_supports_interfaces;
+use tuifw_screen_base::{MouseButton, Point, Rect, Vector, Error};
+use tuifw_window::{Event, RenderPort, TextColor, Widget, WidgetData, Window, WindowTree, App, Color};
+use tuifw_window::{COLOR_FRAME};
+
+pub const CMD_SCROLL_BAR_OFFSET_CHANGED: u16 = 260;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(crate) enum ScrollBarPart {
+    LineBack,
+    PageBack,
+    Thumb,
+    PageForward,
+    LineForward,
+}
+
+/// Scroll bar geometry along its axis: arrows at both ends, a track, and a thumb proportional to the viewport.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ScrollBarState {
+    pub offset: u32,
+    pub viewport: u32,
+    pub extent: u32,
+}
+
+impl ScrollBarState {
+    pub fn max_offset(self) -> u32 {
+        self.extent.saturating_sub(self.viewport)
+    }
+
+    fn thumb(self, len: i16) -> (i16, i16) {
+        let track = max(0, len.wrapping_sub(2));
+        let max_offset = self.max_offset();
+        if max_offset == 0 || track == 0 { return (0, track); }
+        let size = (u64::from(track as u16) * u64::from(self.viewport) / u64::from(self.extent)) as i16;
+        let size = size.clamp(1, track);
+        let free = u64::from((track - size) as u16);
+        let offset = u64::from(min(self.offset, max_offset));
+        let start = ((offset * free + u64::from(max_offset) / 2) / u64::from(max_offset)) as i16;
+        (start, size)
+    }
+
+    pub fn part_at(self, len: i16, pos: i16) -> ScrollBarPart {
+        if pos <= 0 { return ScrollBarPart::LineBack; }
+        if pos >= len.wrapping_sub(1) { return ScrollBarPart::LineForward; }
+        let (start, size) = self.thumb(len);
+        let pos = pos - 1;
+        if pos < start {
+            ScrollBarPart::PageBack
+        } else if pos < start + size {
+            ScrollBarPart::Thumb
+        } else {
+            ScrollBarPart::PageForward
+        }
+    }
+
+    pub fn part_offset(self, part: ScrollBarPart) -> u32 {
+        let page = max(1, self.viewport);
+        match part {
+            ScrollBarPart::LineBack => self.offset.saturating_sub(1),
+            ScrollBarPart::PageBack => self.offset.saturating_sub(page),
+            ScrollBarPart::Thumb => self.offset,
+            ScrollBarPart::PageForward => min(self.offset.saturating_add(page), self.max_offset()),
+            ScrollBarPart::LineForward => min(self.offset.saturating_add(1), self.max_offset()),
+        }
+    }
+
+    pub fn drag_offset(self, len: i16, start_offset: u32, delta: i16) -> u32 {
+        let (_, size) = self.thumb(len);
+        let free = max(0, len.wrapping_sub(2)) - size;
+        let max_offset = self.max_offset();
+        if free <= 0 { return min(start_offset, max_offset); }
+        let offset = i64::from(start_offset) + i64::from(delta) * i64::from(max_offset) / i64::from(free);
+        offset.clamp(0, i64::from(max_offset)) as u32
+    }
+
+    pub fn render(
+        self,
+        rp: &mut RenderPort,
+        color: impl TextColor + Copy,
+        start: Point,
+        len: i16,
+        vertical: bool,
+        is_unicode: bool,
+    ) {
+        let (back, forward, track, thumb) = match (vertical, is_unicode) {
+            (true, true) => ("▲", "▼", "░", "█"),
+            (false, true) => ("◄", "►", "░", "█"),
+            (true, false) => ("^", "v", ":", "#"),
+            (false, false) => ("<", ">", ":", "#"),
+        };
+        let point = |pos: i16| if vertical {
+            Point { x: start.x, y: start.y.wrapping_add(pos) }
+        } else {
+            Point { x: start.x.wrapping_add(pos), y: start.y }
+        };
+        if len <= 0 { return; }
+        let (thumb_start, thumb_size) = self.thumb(len);
+        rp.text(point(0), color, back);
+        for pos in 1 .. len.wrapping_sub(1) {
+            let in_thumb = (thumb_start .. thumb_start + thumb_size).contains(&(pos - 1));
+            rp.text(point(pos), color, if in_thumb { thumb } else { track });
+        }
+        if len > 1 {
+            rp.text(point(len - 1), color, forward);
+        }
+    }
+}
+
+/// Scroll bars bound to a [`VirtScrollViewerWidgetExtension`] implementor.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct BoundScrollBars {
+    h: Option<Window>,
+    v: Option<Window>,
+}
+
+impl BoundScrollBars {
+    pub fn bind(&mut self, vertical: bool, scroll_bar: Option<Window>) {
+        if vertical {
+            self.v = scroll_bar;
+        } else {
+            self.h = scroll_bar;
+        }
+    }
+
+    fn get(self, vertical: bool) -> Option<Window> {
+        if vertical { self.v } else { self.h }
+    }
+
+    pub fn set_offset(self, tree: &mut WindowTree, vertical: bool, value: u32) {
+        if let Some(scroll_bar) = self.get(vertical) {
+            ScrollBar::set_offset(tree, scroll_bar, value);
+        }
+    }
+
+    pub fn set_viewport(self, tree: &mut WindowTree, vertical: bool, value: u32) {
+        if let Some(scroll_bar) = self.get(vertical) {
+            ScrollBar::set_viewport(tree, scroll_bar, value);
+        }
+    }
+
+    pub fn set_extent(self, tree: &mut WindowTree, vertical: bool, value: u32) {
+        if let Some(scroll_bar) = self.get(vertical) {
+            ScrollBar::set_extent(tree, scroll_bar, value);
+        }
+    }
+
+    pub fn unbind(&mut self, tree: &mut WindowTree) {
+        for scroll_bar in [self.h.take(), self.v.take()].into_iter().flatten() {
+            scroll_bar.data_mut::<ScrollBar>(tree).target = None;
+        }
+    }
+}
+
+widget! {
+    #[widget(ScrollBarWidget, init=init_palette, drop=drop_target)]
+    pub struct ScrollBar {
+        #[property(copy, measure)]
+        vertical: bool,
+        #[property(copy, render)]
+        offset: u32,
+        #[property(copy, render)]
+        viewport: u32,
+        #[property(copy, render)]
+        extent: u32,
+        target: Option<Window>,
+        drag: Option<(i16, u32)>,
+    }
+}
+
+impl ScrollBar {
+    fn init_palette(tree: &mut WindowTree, window: Window) -> Result<(), Error> {
+        window.palette_mut(tree, |palette| {
+            palette.set(0, Color::Palette(COLOR_FRAME));
+        });
+        Ok(())
+    }
+
+    fn drop_target(&mut self, tree: &mut WindowTree, _app: &mut dyn App) {
+        if let Some(target) = self.target.take() {
+            if let Some(sv) = target.widget_extension::<dyn VirtScrollViewerWidgetExtension>(tree) {
+                sv.bind_scroll_bar(tree, target, self.vertical, None);
+            }
+        }
+    }
+
+    pub fn target(tree: &WindowTree, window: Window) -> Option<Window> {
+        window.data::<ScrollBar>(tree).target
+    }
+
+    /// Binds the scroll bar to a window implementing [`VirtScrollViewerWidgetExtension`],
+    /// so that it mirrors and controls the target scroll state along the scroll bar axis.
+    pub fn set_target(tree: &mut WindowTree, window: Window, value: Option<Window>) {
+        let data = window.data_mut::<ScrollBar>(tree);
+        let vertical = data.vertical;
+        if let Some(old_target) = data.target.take() {
+            if let Some(sv) = old_target.widget_extension::<dyn VirtScrollViewerWidgetExtension>(tree) {
+                sv.bind_scroll_bar(tree, old_target, vertical, None);
+            }
+        }
+        let Some(target) = value else { return; };
+        let sv = target.widget_extension::<dyn VirtScrollViewerWidgetExtension>(tree)
+            .expect("scroll bar target should implement VirtScrollViewerWidgetExtension");
+        window.data_mut::<ScrollBar>(tree).target = Some(target);
+        sv.bind_scroll_bar(tree, target, vertical, Some(window));
+    }
+
+    fn state(&self) -> ScrollBarState {
+        ScrollBarState { offset: self.offset, viewport: self.viewport, extent: self.extent }
+    }
+
+    fn len(tree: &WindowTree, window: Window) -> i16 {
+        let bounds = window.inner_bounds(tree);
+        if window.data::<ScrollBar>(tree).vertical { bounds.h() } else { bounds.w() }
+    }
+
+    fn scroll(tree: &mut WindowTree, window: Window, offset: u32, app: &mut dyn App) {
+        let data = window.data::<ScrollBar>(tree);
+        if offset == data.offset { return; }
+        if let Some(target) = data.target {
+            let vertical = data.vertical;
+            if let Some(sv) = target.widget_extension::<dyn VirtScrollViewerWidgetExtension>(tree) {
+                sv.scroll_to(tree, target, vertical, offset);
+            }
+        }
+        ScrollBar::set_offset(tree, window, offset);
+        window.raise(tree, Event::Cmd(CMD_SCROLL_BAR_OFFSET_CHANGED), app);
+    }
+}
+
+#[derive(Clone, Default)]
+struct ScrollBarWidget;
+
+impl_supports_interfaces!(ScrollBarWidget);
+
+impl Widget for ScrollBarWidget {
+    fn new(&self) -> Box<dyn WidgetData> {
+        Box::new(ScrollBar {
+            vertical: false,
+            offset: 0,
+            viewport: 0,
+            extent: 0,
+            target: None,
+            drag: None,
+        })
+    }
+
+    fn clone_data(
+        &self,
+        tree: &mut WindowTree,
+        source: Window,
+        dest: Window,
+        clone_window: Box<dyn Fn(&WindowTree, Window) -> Window>,
+    ) {
+        ScrollBar::clone(tree, source, dest, clone_window);
+    }
+
+    fn render(
+        &self,
+        tree: &WindowTree,
+        window: Window,
+        rp: &mut RenderPort,
+        _app: &mut dyn App,
+    ) {
+        let color = window.color(tree, 0);
+        let len = ScrollBar::len(tree, window);
+        let data = window.data::<ScrollBar>(tree);
+        rp.fill_bg(color);
+        data.state().render(rp, color, Point { x: 0, y: 0 }, len, data.vertical, rp.is_unicode());
+    }
+
+    fn measure(
+        &self,
+        tree: &mut WindowTree,
+        window: Window,
+        available_width: Option<i16>,
+        available_height: Option<i16>,
+        _app: &mut dyn App,
+    ) -> Vector {
+        if window.data::<ScrollBar>(tree).vertical {
+            Vector { x: 1, y: max(2, available_height.unwrap_or(2)) }
+        } else {
+            Vector { x: max(2, available_width.unwrap_or(2)), y: 1 }
+        }
+    }
+
+    fn arrange(
+        &self,
+        tree: &mut WindowTree,
+        window: Window,
+        final_inner_bounds: Rect,
+        _app: &mut dyn App,
+    ) -> Vector {
+        if window.data::<ScrollBar>(tree).vertical {
+            Vector { x: 1, y: final_inner_bounds.h() }
+        } else {
+            Vector { x: final_inner_bounds.w(), y: 1 }
+        }
+    }
+
+    fn update(
+        &self,
+        tree: &mut WindowTree,
+        window: Window,
+        event: Event,
+        _event_source: Window,
+        app: &mut dyn App,
+    ) -> bool {
+        match event {
+            Event::LmbDown(point, _) => {
+                let point = window.inner_point(point, tree);
+                let len = ScrollBar::len(tree, window);
+                let data = window.data_mut::<ScrollBar>(tree);
+                let pos = if data.vertical { point.y } else { point.x };
+                let state = data.state();
+                let part = state.part_at(len, pos);
+                if part == ScrollBarPart::Thumb {
+                    data.drag = Some((pos, state.offset));
+                } else {
+                    ScrollBar::scroll(tree, window, state.part_offset(part), app);
+                }
+                true
+            },
+            Event::Drag(point, MouseButton::Left, _) => {
+                let point = window.inner_point(point, tree);
+                let len = ScrollBar::len(tree, window);
+                let data = window.data::<ScrollBar>(tree);
+                let Some((start_pos, start_offset)) = data.drag else { return false; };
+                let pos = if data.vertical { point.y } else { point.x };
+                let offset = data.state().drag_offset(len, start_offset, pos.wrapping_sub(start_pos));
+                ScrollBar::scroll(tree, window, offset, app);
+                true
+            },
+            Event::LmbUp => window.data_mut::<ScrollBar>(tree).drag.take().is_some(),
+            _ => false
+        }
+    }
+}
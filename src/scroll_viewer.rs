@@ -1,15 +1,18 @@
 use crate::widget;
+use crate::scroll_bar::{BoundScrollBars, ScrollBarPart, ScrollBarState};
 use crate::virt_scroll_viewer::*;
 use alloc::boxed::Box;
 use alloc::string::String;
-use core::cmp::max;
+use core::cmp::{max, min};
 use dynamic_cast::impl_supports_interfaces;
-use tuifw_screen_base::{Point, Rect, Vector, Thickness, text_width, HAlign, VAlign, Error};
+use tuifw_screen_base::{MouseButton, Point, Rect, Vector, Thickness, text_width, HAlign, VAlign, Error, Mods};
 use tuifw_window::{Event, RenderPort, Widget, WidgetData, Window, WindowTree, App, Color};
 use tuifw_window::{COLOR_FRAME};
 
+pub const WHEEL_LINES: u16 = 3;
+
 widget! {
-    #[widget(ScrollViewerWidget, init=init_palette)]
+    #[widget(ScrollViewerWidget, init=init_palette, drop=drop_scroll_bars)]
     pub struct ScrollViewer {
         #[property(str, render)]
         text: String,
@@ -28,6 +31,8 @@ widget! {
         v_offset: u32,
         v_viewport: u32,
         has_virtual_child: bool,
+        drag: Option<(bool, i16, u32)>,
+        scroll_bars: BoundScrollBars,
     }
 }
 
@@ -39,6 +44,10 @@ impl ScrollViewer {
         Ok(())
     }
 
+    fn drop_scroll_bars(&mut self, tree: &mut WindowTree, _app: &mut dyn App) {
+        self.scroll_bars.unbind(tree);
+    }
+
     pub fn h_extent(tree: &WindowTree, window: Window) -> u32 {
         let data = window.data::<ScrollViewer>(tree);
         data.h_extent
@@ -58,6 +67,46 @@ impl ScrollViewer {
         let data = window.data::<ScrollViewer>(tree);
         data.v_viewport
     }
+
+    fn state(&self, vertical: bool) -> ScrollBarState {
+        if vertical {
+            ScrollBarState { offset: self.v_offset, viewport: self.v_viewport, extent: self.v_extent }
+        } else {
+            ScrollBarState { offset: self.h_offset, viewport: self.h_viewport, extent: self.h_extent }
+        }
+    }
+
+    fn sync_scroll_bars(tree: &mut WindowTree, window: Window) {
+        let scroll_bars = window.data::<ScrollViewer>(tree).scroll_bars;
+        for vertical in [false, true] {
+            let state = window.data::<ScrollViewer>(tree).state(vertical);
+            scroll_bars.set_extent(tree, vertical, state.extent);
+            scroll_bars.set_viewport(tree, vertical, state.viewport);
+            scroll_bars.set_offset(tree, vertical, state.offset);
+        }
+    }
+
+    fn scroll_to(tree: &mut WindowTree, window: Window, vertical: bool, offset: u32) {
+        let mut has_virtual_child = false;
+        if let Some(first_child) = window.first_child(tree) {
+            let mut child = first_child;
+            loop {
+                if let Some(virtual_child) = child.widget_extension::<dyn VirtItemsPresenterWidgetExtension>(tree) {
+                    virtual_child.set_offset(tree, child, vertical, offset);
+                    has_virtual_child = true;
+                }
+                child = child.next(tree);
+                if child == first_child { break; }
+            }
+        }
+        if !has_virtual_child {
+            if vertical {
+                ScrollViewer::set_v_offset(tree, window, offset);
+            } else {
+                ScrollViewer::set_h_offset(tree, window, offset);
+            }
+        }
+    }
 }
 
 #[derive(Clone, Default)]
@@ -74,6 +123,8 @@ impl VirtScrollViewerWidgetExtension for ScrollViewerWidget {
             data.h_offset = value;
         }
         window.invalidate_render(tree);
+        let scroll_bars = window.data::<ScrollViewer>(tree).scroll_bars;
+        scroll_bars.set_offset(tree, vertical, value);
     }
 
     fn set_viewport(&self, tree: &mut WindowTree, window: Window, vertical: bool, value: u32) {
@@ -84,6 +135,8 @@ impl VirtScrollViewerWidgetExtension for ScrollViewerWidget {
             data.h_viewport = value;
         }
         window.invalidate_render(tree);
+        let scroll_bars = window.data::<ScrollViewer>(tree).scroll_bars;
+        scroll_bars.set_viewport(tree, vertical, value);
     }
 
     fn set_extent(&self, tree: &mut WindowTree, window: Window, vertical: bool, value: u32) {
@@ -94,6 +147,18 @@ impl VirtScrollViewerWidgetExtension for ScrollViewerWidget {
             data.h_extent = value;
         }
         window.invalidate_render(tree);
+        let scroll_bars = window.data::<ScrollViewer>(tree).scroll_bars;
+        scroll_bars.set_extent(tree, vertical, value);
+    }
+
+    fn scroll_to(&self, tree: &mut WindowTree, window: Window, vertical: bool, value: u32) {
+        ScrollViewer::scroll_to(tree, window, vertical, value);
+    }
+
+    fn bind_scroll_bar(&self, tree: &mut WindowTree, window: Window, vertical: bool, scroll_bar: Option<Window>) {
+        window.data_mut::<ScrollViewer>(tree).scroll_bars.bind(vertical, scroll_bar);
+        if scroll_bar.is_none() { return; }
+        ScrollViewer::sync_scroll_bars(tree, window);
     }
 }
 
@@ -110,6 +175,8 @@ impl Widget for ScrollViewerWidget {
             v_offset: 0,
             v_viewport: 0,
             has_virtual_child: false,
+            drag: None,
+            scroll_bars: BoundScrollBars::default(),
         })
     }
 
@@ -144,32 +211,12 @@ impl Widget for ScrollViewerWidget {
         rp.bl_edge(bounds.bl_inner(), true, color);
         let indicator_area = Thickness::all(1).shrink_rect(bounds);
         if data.v_scroll {
-            let v_indicator_range = (indicator_area.h() as u16).saturating_sub(1) as i16;
-            let v_indicator =
-                (
-                    (
-                        i64::from(data.v_offset) * i64::from(v_indicator_range) +
-                        i64::from(data.v_extent - data.v_viewport) / 2
-                    )
-                    /
-                    i64::from(data.v_extent - data.v_viewport)
-                ) as i16
-            ;
-            rp.text(Point { x: bounds.r_inner(), y: indicator_area.t().wrapping_add(v_indicator) }, color, "╬");
+            let start = Point { x: bounds.r_inner(), y: indicator_area.t() };
+            data.state(true).render(rp, color, start, indicator_area.h(), true, rp.is_unicode());
         }
         if data.h_scroll {
-            let h_indicator_range = (indicator_area.w() as u16).saturating_sub(1) as i16;
-            let h_indicator =
-                (
-                    (
-                        i64::from(data.h_offset) * i64::from(h_indicator_range) +
-                        i64::from(data.h_extent - data.h_viewport) / 2
-                    )
-                    /
-                    i64::from(data.h_extent - data.h_viewport)
-                ) as i16
-            ;
-            rp.text(Point { x: indicator_area.l().wrapping_add(h_indicator), y: bounds.b_inner() }, color, "╩");
+            let start = Point { x: indicator_area.l(), y: bounds.b_inner() };
+            data.state(false).render(rp, color, start, indicator_area.w(), false, rp.is_unicode());
         }
         if !data.text.is_empty() {
             let text_area_bounds = Thickness::new(2, 0, 2, 0).shrink_rect(bounds.t_line());
@@ -287,6 +334,9 @@ impl Widget for ScrollViewerWidget {
                 if child == first_child { break; }
             }
         }
+        if !window.data::<ScrollViewer>(tree).has_virtual_child {
+            ScrollViewer::sync_scroll_bars(tree, window);
+        }
         final_inner_bounds.size
     }
 
@@ -337,79 +387,71 @@ impl Widget for ScrollViewerWidget {
         _event_source: Window,
         _app: &mut dyn App,
     ) -> bool {
-        if let Event::LmbDown(point) = event {
-            let point = window.inner_point(point, tree); // TODO swap args
-            let bounds = window.inner_bounds(tree);
-            let data = window.data::<ScrollViewer>(tree);
-            if point.x == bounds.r_inner() && data.v_scroll {
+        match event {
+            Event::LmbDown(point, _) => {
+                let point = window.inner_point(point, tree); // TODO swap args
+                let bounds = window.inner_bounds(tree);
                 let indicator_area = Thickness::all(1).shrink_rect(bounds);
-                if indicator_area.v_range().contains(point.y) {
-                    let new_indicator_pos = point.offset_from(indicator_area.tl).y;
-                    let v_indicator_range = (indicator_area.h() as u16).saturating_sub(1) as i16;
-                    let offset = if v_indicator_range == 0 {
-                        0
-                    } else {
-                        ((
-                            u64::from(new_indicator_pos as u16) * u64::from(data.v_extent - data.v_viewport) +
-                            u64::from(v_indicator_range as u16) / 2
-                        ) / u64::from(v_indicator_range as u16)) as u32
-                    };
-                    let mut has_virtual_child = false;
-                    if let Some(first_child) = window.first_child(tree) {
-                        let mut child = first_child;
-                        loop {
-                            if let Some(virtual_child) = child.widget_extension::<dyn VirtItemsPresenterWidgetExtension>(tree) {
-                                virtual_child.set_offset(tree, child, true, offset);
-                                has_virtual_child = true;
-                            }
-                            child = child.next(tree);
-                            if child == first_child { break; }
-                        }
-                    }
-                    if !has_virtual_child {
-                        ScrollViewer::set_v_offset(tree, window, offset);
-                    }
-                    true
+                let data = window.data_mut::<ScrollViewer>(tree);
+                let (vertical, pos, len) = if point.x == bounds.r_inner() && data.v_scroll {
+                    if !indicator_area.v_range().contains(point.y) { return false; }
+                    (true, point.y.wrapping_sub(indicator_area.t()), indicator_area.h())
+                } else if point.y == bounds.b_inner() && data.h_scroll {
+                    if !indicator_area.h_range().contains(point.x) { return false; }
+                    (false, point.x.wrapping_sub(indicator_area.l()), indicator_area.w())
                 } else {
-                    false
+                    return false;
+                };
+                let state = data.state(vertical);
+                let part = state.part_at(len, pos);
+                if part == ScrollBarPart::Thumb {
+                    data.drag = Some((vertical, pos, state.offset));
+                } else {
+                    let offset = state.part_offset(part);
+                    if offset != state.offset {
+                        ScrollViewer::scroll_to(tree, window, vertical, offset);
+                    }
                 }
-            } else if point.y == bounds.b_inner() && data.h_scroll {
+                true
+            },
+            Event::Drag(point, MouseButton::Left, _) => {
+                let point = window.inner_point(point, tree);
+                let bounds = window.inner_bounds(tree);
                 let indicator_area = Thickness::all(1).shrink_rect(bounds);
-                if indicator_area.h_range().contains(point.x) {
-                    let new_indicator_pos = point.offset_from(indicator_area.tl).x;
-                    let h_indicator_range = (indicator_area.w() as u16).saturating_sub(1) as i16;
-                    let offset = if h_indicator_range == 0 {
-                        0
-                    } else {
-                        ((
-                            u64::from(new_indicator_pos as u16) * u64::from(data.h_extent - data.h_viewport) +
-                            u64::from(h_indicator_range as u16) / 2
-                        ) / u64::from(h_indicator_range as u16)) as u32
-                    };
-                    let mut has_virtual_child = false;
-                    if let Some(first_child) = window.first_child(tree) {
-                        let mut child = first_child;
-                        loop {
-                            if let Some(virtual_child) = child.widget_extension::<dyn VirtItemsPresenterWidgetExtension>(tree) {
-                                virtual_child.set_offset(tree, child, false, offset);
-                                has_virtual_child = true;
-                            }
-                            child = child.next(tree);
-                            if child == first_child { break; }
-                        }
-                    }
-                    if !has_virtual_child {
-                        ScrollViewer::set_h_offset(tree, window, offset);
-                    }
-                    true
+                let data = window.data::<ScrollViewer>(tree);
+                let Some((vertical, start_pos, start_offset)) = data.drag else { return false; };
+                let (pos, len) = if vertical {
+                    (point.y.wrapping_sub(indicator_area.t()), indicator_area.h())
                 } else {
-                    false
+                    (point.x.wrapping_sub(indicator_area.l()), indicator_area.w())
+                };
+                let state = data.state(vertical);
+                let offset = state.drag_offset(len, start_offset, pos.wrapping_sub(start_pos));
+                if offset != state.offset {
+                    ScrollViewer::scroll_to(tree, window, vertical, offset);
                 }
-            } else {
-                false
-            }
-        } else {
-            false
+                true
+            },
+            Event::LmbUp => window.data_mut::<ScrollViewer>(tree).drag.take().is_some(),
+            Event::Wheel(_, delta, mods) => {
+                let data = window.data::<ScrollViewer>(tree);
+                let vertical = !mods.contains(Mods::SHIFT);
+                let (offset, max_offset) = if vertical {
+                    (data.v_offset, data.v_extent.saturating_sub(data.v_viewport))
+                } else {
+                    (data.h_offset, data.h_extent.saturating_sub(data.h_viewport))
+                };
+                let step = u32::from(WHEEL_LINES) * u32::from(delta.unsigned_abs());
+                let new_offset = if delta < 0 {
+                    offset.saturating_sub(step)
+                } else {
+                    min(offset.saturating_add(step), max_offset)
+                };
+                if new_offset == offset { return false; }
+                ScrollViewer::scroll_to(tree, window, vertical, new_offset);
+                true
+            },
+            _ => false
         }
     }
 }
@@ -0,0 +1,249 @@
+use crate::widget;
+use alloc::boxed::Box;
+use core::cmp::max;
+use dynamic_cast::impl_supports_interfaces;
+use tuifw_screen_base::{Key, Mods, MouseButton, Point, Rect, Vector, Error};
+use tuifw_window::{Event, RenderPort, Timer, Widget, WidgetData, Window, WindowTree, App, Color, Focus};
+use tuifw_window::{CMD_GOT_PRIMARY_FOCUS, CMD_LOST_PRIMARY_FOCUS};
+use tuifw_window::{COLOR_BUTTON, COLOR_BUTTON_FOCUSED, COLOR_DISABLED, COLOR_BUTTON_FOCUSED_DISABLED};
+
+pub const CMD_SLIDER_VALUE_CHANGED: u16 = 240;
+
+widget! {
+    #[widget(SliderWidget, init=init, drop=drop_timer)]
+    pub struct Slider {
+        #[property(copy, measure)]
+        vertical: bool,
+        #[property(copy, on_changed=on_range_changed)]
+        min: f64,
+        #[property(copy, on_changed=on_range_changed)]
+        max: f64,
+        #[property(copy)]
+        step: f64,
+        #[property(copy)]
+        page_step: f64,
+        value: f64,
+        value_changed_timer: Option<Timer>,
+    }
+}
+
+impl Slider {
+    fn init(tree: &mut WindowTree, window: Window) -> Result<(), Error> {
+        window.palette_mut(tree, |palette| {
+            palette.set(0, Color::Palette(COLOR_BUTTON));
+            palette.set(1, Color::Palette(COLOR_BUTTON_FOCUSED));
+            palette.set(2, Color::Palette(COLOR_DISABLED));
+            palette.set(3, Color::Palette(COLOR_BUTTON_FOCUSED_DISABLED));
+        });
+        window.set_focus_click(tree, Some(Focus::Primary));
+        Ok(())
+    }
+
+    fn drop_timer(&mut self, tree: &mut WindowTree, _app: &mut dyn App) {
+        if let Some(timer) = self.value_changed_timer.take() {
+            timer.drop_timer(tree);
+        }
+    }
+
+    fn on_range_changed(tree: &mut WindowTree, window: Window) {
+        let value = window.data::<Slider>(tree).value;
+        Self::set_value_raw(tree, window, value, None);
+        window.invalidate_render(tree);
+    }
+
+    pub fn value(tree: &WindowTree, window: Window) -> f64 {
+        window.data::<Slider>(tree).value
+    }
+
+    pub fn set_value(tree: &mut WindowTree, window: Window, value: f64) {
+        Self::set_value_raw(tree, window, value, None);
+    }
+
+    fn set_value_raw(tree: &mut WindowTree, window: Window, value: f64, app: Option<&mut dyn App>) {
+        let data = window.data_mut::<Slider>(tree);
+        let value = if data.max > data.min { value.clamp(data.min, data.max) } else { data.min };
+        if data.value == value { return; }
+        data.value = value;
+        window.invalidate_render(tree);
+        if let Some(app) = app {
+            window.raise(tree, Event::Cmd(CMD_SLIDER_VALUE_CHANGED), app);
+        } else {
+            let value_changed_timer = Timer::new(tree, 0, Box::new(move |tree, app| {
+                window.data_mut::<Slider>(tree).value_changed_timer = None;
+                window.raise(tree, Event::Cmd(CMD_SLIDER_VALUE_CHANGED), app);
+            }));
+            let data = window.data_mut::<Slider>(tree);
+            if let Some(old_timer) = data.value_changed_timer.replace(value_changed_timer) {
+                old_timer.drop_timer(tree);
+            }
+        }
+    }
+
+    fn change_value(tree: &mut WindowTree, window: Window, delta: f64, app: &mut dyn App) {
+        let value = window.data::<Slider>(tree).value + delta;
+        Self::set_value_raw(tree, window, value, Some(app));
+    }
+
+    fn track_len(tree: &WindowTree, window: Window) -> i16 {
+        let bounds = window.inner_bounds(tree);
+        if window.data::<Slider>(tree).vertical { bounds.h() } else { bounds.w() }
+    }
+
+    fn thumb_pos(tree: &WindowTree, window: Window) -> i16 {
+        let data = window.data::<Slider>(tree);
+        let len = Self::track_len(tree, window);
+        if len <= 1 || data.max <= data.min { return 0; }
+        let fraction = (data.value - data.min) / (data.max - data.min);
+        (fraction * f64::from(len - 1) + 0.5) as i16
+    }
+
+    fn value_at(tree: &WindowTree, window: Window, point: Point) -> f64 {
+        let data = window.data::<Slider>(tree);
+        let len = Self::track_len(tree, window);
+        if len <= 1 || data.max <= data.min { return data.min; }
+        let pos = if data.vertical { point.y } else { point.x }.clamp(0, len - 1);
+        let value = data.min + f64::from(pos) / f64::from(len - 1) * (data.max - data.min);
+        if data.step > 0.0 {
+            let steps = ((value - data.min) / data.step + 0.5) as i64;
+            data.min + steps as f64 * data.step
+        } else {
+            value
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+struct SliderWidget;
+
+impl_supports_interfaces!(SliderWidget);
+
+impl Widget for SliderWidget {
+    fn new(&self) -> Box<dyn WidgetData> {
+        Box::new(Slider {
+            vertical: false,
+            min: 0.0,
+            max: 100.0,
+            step: 1.0,
+            page_step: 10.0,
+            value: 0.0,
+            value_changed_timer: None,
+        })
+    }
+
+    fn clone_data(
+        &self,
+        tree: &mut WindowTree,
+        source: Window,
+        dest: Window,
+        clone_window: Box<dyn Fn(&WindowTree, Window) -> Window>,
+    ) {
+        Slider::clone(tree, source, dest, clone_window);
+    }
+
+    fn render(
+        &self,
+        tree: &WindowTree,
+        window: Window,
+        rp: &mut RenderPort,
+        _app: &mut dyn App,
+    ) {
+        let focused = window.is_focused(tree);
+        let is_enabled = window.actual_is_enabled(tree);
+        let color = match (is_enabled, focused) {
+            (true, false) => 0,
+            (true, true) => 1,
+            (false, false) => 2,
+            (false, true) => 3,
+        };
+        let color = window.color(tree, color);
+        let is_unicode = rp.is_unicode();
+        let thumb = if is_unicode { "█" } else { "#" };
+        let len = Slider::track_len(tree, window);
+        let pos = Slider::thumb_pos(tree, window);
+        rp.fill_bg(color);
+        if window.data::<Slider>(tree).vertical {
+            let track = if is_unicode { "│" } else { "|" };
+            for y in 0 .. len {
+                rp.text(Point { x: 0, y }, color, if y == pos { thumb } else { track });
+            }
+        } else {
+            let track = if is_unicode { "─" } else { "-" };
+            for x in 0 .. len {
+                rp.text(Point { x, y: 0 }, color, if x == pos { thumb } else { track });
+            }
+        }
+    }
+
+    fn measure(
+        &self,
+        tree: &mut WindowTree,
+        window: Window,
+        available_width: Option<i16>,
+        available_height: Option<i16>,
+        _app: &mut dyn App,
+    ) -> Vector {
+        if window.data::<Slider>(tree).vertical {
+            Vector { x: 1, y: max(2, available_height.unwrap_or(2)) }
+        } else {
+            Vector { x: max(2, available_width.unwrap_or(2)), y: 1 }
+        }
+    }
+
+    fn arrange(
+        &self,
+        tree: &mut WindowTree,
+        window: Window,
+        final_inner_bounds: Rect,
+        _app: &mut dyn App,
+    ) -> Vector {
+        if window.data::<Slider>(tree).vertical {
+            Vector { x: 1, y: final_inner_bounds.h() }
+        } else {
+            Vector { x: final_inner_bounds.w(), y: 1 }
+        }
+    }
+
+    fn update(
+        &self,
+        tree: &mut WindowTree,
+        window: Window,
+        event: Event,
+        _event_source: Window,
+        app: &mut dyn App,
+    ) -> bool {
+        match event {
+            Event::Cmd(CMD_GOT_PRIMARY_FOCUS) | Event::Cmd(CMD_LOST_PRIMARY_FOCUS) => {
+                window.invalidate_render(tree);
+                false
+            },
+            Event::Key(key, Mods::NONE) if window.actual_is_enabled(tree) => {
+                let data = window.data::<Slider>(tree);
+                let (step, page_step, vertical) = (data.step, data.page_step, data.vertical);
+                match key {
+                    Key::Left if !vertical => Slider::change_value(tree, window, -step, app),
+                    Key::Right if !vertical => Slider::change_value(tree, window, step, app),
+                    Key::Up if vertical => Slider::change_value(tree, window, -step, app),
+                    Key::Down if vertical => Slider::change_value(tree, window, step, app),
+                    Key::PageUp => Slider::change_value(tree, window, -page_step, app),
+                    Key::PageDown => Slider::change_value(tree, window, page_step, app),
+                    Key::Home => Slider::set_value_raw(tree, window, f64::MIN, Some(app)),
+                    Key::End => Slider::set_value_raw(tree, window, f64::MAX, Some(app)),
+                    _ => return false,
+                }
+                true
+            },
+            Event::LmbDown(point, _) | Event::Drag(point, MouseButton::Left, _) if window.actual_is_enabled(tree) => {
+                let point = window.inner_point(point, tree);
+                let value = Slider::value_at(tree, window, point);
+                Slider::set_value_raw(tree, window, value, Some(app));
+                true
+            },
+            Event::Wheel(_, delta, _) if window.actual_is_enabled(tree) => {
+                let step = window.data::<Slider>(tree).step;
+                Slider::change_value(tree, window, step * f64::from(delta), app);
+                true
+            },
+            _ => false
+        }
+    }
+}
@@ -0,0 +1,302 @@
+use crate::{widget, InputLine, Validator};
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::String;
+use core::cmp::max;
+use core::str::FromStr;
+use dynamic_cast::impl_supports_interfaces;
+use tuifw_screen_base::{Key, Mods, Point, Rect, Vector, Error, text_width};
+use tuifw_window::{Event, RenderPort, Timer, Widget, WidgetData, Window, WindowTree, App, Color, Focus};
+use tuifw_window::{CMD_GOT_PRIMARY_FOCUS, CMD_LOST_PRIMARY_FOCUS};
+use tuifw_window::{COLOR_INPUT_LINE, COLOR_INPUT_LINE_FOCUSED, COLOR_DISABLED, COLOR_INPUT_LINE_FOCUSED_DISABLED};
+
+pub const CMD_SPIN_BOX_VALUE_CHANGED: u16 = 250;
+
+fn round(value: f64) -> i64 {
+    (if value < 0.0 { value - 0.5 } else { value + 0.5 }) as i64
+}
+
+widget! {
+    #[widget(SpinBoxWidget, init=init, drop=drop_timer)]
+    pub struct SpinBox {
+        #[property(ref, measure, on_changed=on_validator_changed)]
+        validator: Option<Box<dyn Validator>>,
+        #[property(copy)]
+        step: f64,
+        #[property(copy)]
+        page_step: f64,
+        value: f64,
+        value_changed_timer: Option<Timer>,
+    }
+}
+
+impl SpinBox {
+    fn init(tree: &mut WindowTree, window: Window) -> Result<(), Error> {
+        window.palette_mut(tree, |palette| {
+            palette.set(0, Color::Palette(COLOR_INPUT_LINE));
+            palette.set(1, Color::Palette(COLOR_INPUT_LINE_FOCUSED));
+            palette.set(2, Color::Palette(COLOR_DISABLED));
+            palette.set(3, Color::Palette(COLOR_INPUT_LINE_FOCUSED_DISABLED));
+        });
+        let input_line = InputLine::new(tree, Some(window), None)?;
+        input_line.set_focus_click(tree, Some(Focus::Primary));
+        Self::reset_text(tree, window);
+        Ok(())
+    }
+
+    fn drop_timer(&mut self, tree: &mut WindowTree, _app: &mut dyn App) {
+        if let Some(timer) = self.value_changed_timer.take() {
+            timer.drop_timer(tree);
+        }
+    }
+
+    fn input_line(tree: &WindowTree, window: Window) -> Window {
+        window.first_child(tree).unwrap()
+    }
+
+    fn on_validator_changed(tree: &mut WindowTree, window: Window) {
+        let validator = window.data::<SpinBox>(tree).validator.clone();
+        let input_line = Self::input_line(tree, window);
+        InputLine::set_validator(tree, input_line, validator);
+        let value = window.data::<SpinBox>(tree).value;
+        Self::set_value_raw(tree, window, value, None);
+        Self::reset_text(tree, window);
+    }
+
+    pub fn value(tree: &WindowTree, window: Window) -> f64 {
+        window.data::<SpinBox>(tree).value
+    }
+
+    pub fn set_value(tree: &mut WindowTree, window: Window, value: f64) {
+        Self::set_value_raw(tree, window, value, None);
+        Self::reset_text(tree, window);
+    }
+
+    /// Returns the value rounded to the nearest integer,
+    /// which is exact when the validator [`is_integer`](Validator::is_integer).
+    pub fn int_value(tree: &WindowTree, window: Window) -> i64 {
+        round(window.data::<SpinBox>(tree).value)
+    }
+
+    pub fn set_int_value(tree: &mut WindowTree, window: Window, value: i64) {
+        Self::set_value(tree, window, value as f64);
+    }
+
+    fn coerce(&self, value: f64) -> f64 {
+        let Some(validator) = self.validator.as_deref() else { return value; };
+        let value = if validator.is_integer() {
+            round(value) as f64
+        } else {
+            value
+        };
+        if let Some((min, max)) = validator.range().filter(|&(min, max)| min <= max) {
+            value.clamp(min, max)
+        } else {
+            value
+        }
+    }
+
+    fn format(&self, value: f64) -> String {
+        if self.validator.as_deref().is_some_and(|x| x.is_integer()) {
+            format!("{}", value as i64)
+        } else {
+            format!("{value}")
+        }
+    }
+
+    fn set_value_raw(tree: &mut WindowTree, window: Window, value: f64, app: Option<&mut dyn App>) {
+        let data = window.data_mut::<SpinBox>(tree);
+        let value = data.coerce(value);
+        if data.value == value { return; }
+        data.value = value;
+        if let Some(app) = app {
+            window.raise(tree, Event::Cmd(CMD_SPIN_BOX_VALUE_CHANGED), app);
+        } else {
+            let value_changed_timer = Timer::new(tree, 0, Box::new(move |tree, app| {
+                window.data_mut::<SpinBox>(tree).value_changed_timer = None;
+                window.raise(tree, Event::Cmd(CMD_SPIN_BOX_VALUE_CHANGED), app);
+            }));
+            let data = window.data_mut::<SpinBox>(tree);
+            if let Some(old_timer) = data.value_changed_timer.replace(value_changed_timer) {
+                old_timer.drop_timer(tree);
+            }
+        }
+    }
+
+    fn reset_text(tree: &mut WindowTree, window: Window) {
+        let data = window.data::<SpinBox>(tree);
+        let text = data.format(data.value);
+        let input_line = Self::input_line(tree, window);
+        if InputLine::text(tree, input_line) != &text {
+            InputLine::set_text(tree, input_line, text);
+        }
+    }
+
+    fn commit(tree: &mut WindowTree, window: Window, app: &mut dyn App) {
+        let input_line = Self::input_line(tree, window);
+        if InputLine::is_valid(tree, input_line) {
+            if let Ok(value) = f64::from_str(InputLine::text(tree, input_line)) {
+                Self::set_value_raw(tree, window, value, Some(app));
+            }
+        }
+        Self::reset_text(tree, window);
+    }
+
+    fn change_value(tree: &mut WindowTree, window: Window, delta: f64, app: &mut dyn App) {
+        Self::commit(tree, window, app);
+        let value = window.data::<SpinBox>(tree).value + delta;
+        Self::set_value_raw(tree, window, value, Some(app));
+        Self::reset_text(tree, window);
+    }
+
+    fn preview_key(tree: &mut WindowTree, window: Window, key: Key, mods: Mods, app: &mut dyn App) -> bool {
+        if mods != Mods::NONE { return false; }
+        let data = window.data::<SpinBox>(tree);
+        let (step, page_step) = (data.step, data.page_step);
+        match key {
+            Key::Up => Self::change_value(tree, window, step, app),
+            Key::Down => Self::change_value(tree, window, -step, app),
+            Key::PageUp => Self::change_value(tree, window, page_step, app),
+            Key::PageDown => Self::change_value(tree, window, -page_step, app),
+            Key::Enter => {
+                Self::commit(tree, window, app);
+                return false;
+            },
+            _ => return false,
+        }
+        true
+    }
+}
+
+#[derive(Clone, Default)]
+struct SpinBoxWidget;
+
+impl_supports_interfaces!(SpinBoxWidget);
+
+impl Widget for SpinBoxWidget {
+    fn new(&self) -> Box<dyn WidgetData> {
+        Box::new(SpinBox {
+            validator: None,
+            step: 1.0,
+            page_step: 10.0,
+            value: 0.0,
+            value_changed_timer: None,
+        })
+    }
+
+    fn clone_data(
+        &self,
+        tree: &mut WindowTree,
+        source: Window,
+        dest: Window,
+        clone_window: Box<dyn Fn(&WindowTree, Window) -> Window>,
+    ) {
+        SpinBox::clone(tree, source, dest, clone_window);
+    }
+
+    fn render(
+        &self,
+        tree: &WindowTree,
+        window: Window,
+        rp: &mut RenderPort,
+        _app: &mut dyn App,
+    ) {
+        let bounds = window.inner_bounds(tree);
+        let focused = SpinBox::input_line(tree, window).is_focused(tree);
+        let is_enabled = window.actual_is_enabled(tree);
+        let color = match (is_enabled, focused) {
+            (true, false) => 0,
+            (true, true) => 1,
+            (false, false) => 2,
+            (false, true) => 3,
+        };
+        let arrows = if rp.is_unicode() { "▲▼" } else { "^v" };
+        rp.text(Point { x: bounds.r_inner().wrapping_sub(1), y: 0 }, window.color(tree, color), arrows);
+    }
+
+    fn measure(
+        &self,
+        tree: &mut WindowTree,
+        window: Window,
+        available_width: Option<i16>,
+        _available_height: Option<i16>,
+        app: &mut dyn App,
+    ) -> Vector {
+        let data = window.data::<SpinBox>(tree);
+        let value_width = data.validator.as_deref().and_then(|x| x.range()).map_or(10, |(min_value, max_value)| {
+            max(text_width(&data.format(min_value)), text_width(&data.format(max_value)))
+        });
+        let width = available_width.unwrap_or(max(value_width, 1).wrapping_add(3));
+        let input_line = SpinBox::input_line(tree, window);
+        input_line.measure(tree, Some(width.wrapping_sub(2)), Some(1), app);
+        Vector { x: width, y: 1 }
+    }
+
+    fn arrange(
+        &self,
+        tree: &mut WindowTree,
+        window: Window,
+        final_inner_bounds: Rect,
+        app: &mut dyn App,
+    ) -> Vector {
+        let input_line = SpinBox::input_line(tree, window);
+        let width = final_inner_bounds.w().wrapping_sub(2);
+        input_line.arrange(tree, Rect { tl: Point { x: 0, y: 0 }, size: Vector { x: width, y: 1 } }, app);
+        Vector { x: final_inner_bounds.w(), y: 1 }
+    }
+
+    fn update(
+        &self,
+        tree: &mut WindowTree,
+        window: Window,
+        event: Event,
+        event_source: Window,
+        app: &mut dyn App,
+    ) -> bool {
+        let input_line = SpinBox::input_line(tree, window);
+        match event {
+            Event::Cmd(CMD_GOT_PRIMARY_FOCUS) => {
+                if event_source == window {
+                    input_line.set_focused_primary(tree, true);
+                }
+                window.invalidate_render(tree);
+                false
+            },
+            Event::Cmd(CMD_LOST_PRIMARY_FOCUS) => {
+                if event_source == input_line {
+                    SpinBox::commit(tree, window, app);
+                }
+                window.invalidate_render(tree);
+                false
+            },
+            Event::PreviewKey(key, mods) if window.actual_is_enabled(tree) => {
+                SpinBox::preview_key(tree, window, key, mods, app)
+            },
+            Event::Key(Key::Tab, mods) if mods == Mods::NONE || mods == Mods::SHIFT => {
+                let focus = if mods == Mods::NONE { window.focus_tab(tree) } else { window.focus_back_tab(tree) };
+                if focus == window { return false; }
+                focus.set_focused_primary(tree, true);
+                true
+            },
+            Event::LmbDown(point, _) if event_source == window && window.actual_is_enabled(tree) => {
+                input_line.set_focused_primary(tree, true);
+                let x = window.inner_point(point, tree).x;
+                let r = window.inner_bounds(tree).r_inner();
+                let data = window.data::<SpinBox>(tree);
+                let step = data.step;
+                if x == r.wrapping_sub(1) {
+                    SpinBox::change_value(tree, window, step, app);
+                } else if x == r {
+                    SpinBox::change_value(tree, window, -step, app);
+                }
+                true
+            },
+            Event::Wheel(_, delta, _) if window.actual_is_enabled(tree) => {
+                let step = window.data::<SpinBox>(tree).step;
+                SpinBox::change_value(tree, window, -step * f64::from(delta), app);
+                true
+            },
+            _ => false
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use crate::widget;
+use alloc::boxed::Box;
+use dynamic_cast::impl_supports_interfaces;
+use tuifw_screen_base::{Point, Rect, Vector, Error};
+use tuifw_window::{Event, RenderPort, Timer, Widget, WidgetData, Window, WindowTree, App, Color};
+use tuifw_window::{COLOR_LABEL, COLOR_DISABLED};
+
+const SPINNER_SPAN_MS: u16 = 100;
+
+const UNICODE_FRAMES: [&str; 8] = ["⣾", "⣽", "⣻", "⢿", "⡿", "⣟", "⣯", "⣷"];
+
+const NON_UNICODE_FRAMES: [&str; 4] = ["|", "/", "-", "\\"];
+
+widget! {
+    #[widget(SpinnerWidget, init=init_palette, drop=drop_timer)]
+    pub struct Spinner {
+        #[property(copy, render, on_changed=on_is_active_changed)]
+        is_active: bool,
+        phase: u16,
+        timer: Option<Timer>,
+    }
+}
+
+impl Spinner {
+    fn init_palette(tree: &mut WindowTree, window: Window) -> Result<(), Error> {
+        window.palette_mut(tree, |palette| {
+            palette.set(0, Color::Palette(COLOR_LABEL));
+            palette.set(1, Color::Palette(COLOR_DISABLED));
+        });
+        Ok(())
+    }
+
+    fn drop_timer(&mut self, tree: &mut WindowTree, _app: &mut dyn App) {
+        if let Some(timer) = self.timer.take() {
+            timer.drop_timer(tree);
+        }
+    }
+
+    fn on_is_active_changed(tree: &mut WindowTree, window: Window) {
+        let data = window.data_mut::<Spinner>(tree);
+        data.phase = 0;
+        if let Some(timer) = data.timer.take() {
+            timer.drop_timer(tree);
+        }
+        if window.data::<Spinner>(tree).is_active {
+            Self::schedule_step(tree, window);
+        }
+    }
+
+    fn schedule_step(tree: &mut WindowTree, window: Window) {
+        let timer = Timer::new(tree, SPINNER_SPAN_MS, Box::new(move |tree, _app| {
+            let data = window.data_mut::<Spinner>(tree);
+            data.timer = None;
+            data.phase = data.phase.wrapping_add(1);
+            window.invalidate_render(tree);
+            Self::schedule_step(tree, window);
+        }));
+        window.data_mut::<Spinner>(tree).timer = Some(timer);
+    }
+}
+
+#[derive(Clone, Default)]
+struct SpinnerWidget;
+
+impl_supports_interfaces!(SpinnerWidget);
+
+impl Widget for SpinnerWidget {
+    fn new(&self) -> Box<dyn WidgetData> {
+        Box::new(Spinner {
+            is_active: false,
+            phase: 0,
+            timer: None,
+        })
+    }
+
+    fn clone_data(
+        &self,
+        tree: &mut WindowTree,
+        source: Window,
+        dest: Window,
+        clone_window: Box<dyn Fn(&WindowTree, Window) -> Window>,
+    ) {
+        Spinner::clone(tree, source, dest, clone_window);
+    }
+
+    fn render(
+        &self,
+        tree: &WindowTree,
+        window: Window,
+        rp: &mut RenderPort,
+        _app: &mut dyn App,
+    ) {
+        let is_enabled = window.actual_is_enabled(tree);
+        let color = window.color(tree, if is_enabled { 0 } else { 1 });
+        let data = window.data::<Spinner>(tree);
+        if !data.is_active { return; }
+        let frames: &[&str] = if rp.is_unicode() { &UNICODE_FRAMES } else { &NON_UNICODE_FRAMES };
+        rp.text(Point { x: 0, y: 0 }, color, frames[usize::from(data.phase) % frames.len()]);
+    }
+
+    fn measure(
+        &self,
+        _tree: &mut WindowTree,
+        _window: Window,
+        _available_width: Option<i16>,
+        _available_height: Option<i16>,
+        _app: &mut dyn App,
+    ) -> Vector {
+        Vector { x: 1, y: 1 }
+    }
+
+    fn arrange(
+        &self,
+        _tree: &mut WindowTree,
+        _window: Window,
+        _final_inner_bounds: Rect,
+        _app: &mut dyn App,
+    ) -> Vector {
+        Vector { x: 1, y: 1 }
+    }
+
+    fn update(
+        &self,
+        _tree: &mut WindowTree,
+        _window: Window,
+        _event: Event,
+        _event_source: Window,
+        _app: &mut dyn App,
+    ) -> bool {
+        false
+    }
+}
@@ -0,0 +1,399 @@
+use crate::widget;
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cmp::{max, min};
+use dynamic_cast::impl_supports_interfaces;
+use tuifw_screen_base::{Key, Mods, Point, Rect, Thickness, Vector, Error};
+use tuifw_window::{Event, RenderPort, Timer, Widget, WidgetData, Window, WindowTree, App, Color, Visibility};
+use tuifw_window::{label, label_width};
+use tuifw_window::{COLOR_BUTTON, COLOR_HOTKEY, COLOR_DISABLED, COLOR_BUTTON_FOCUSED, COLOR_BUTTON_FOCUSED_HOTKEY};
+use tuifw_window::{COLOR_FRAME};
+
+pub const CMD_TAB_CONTROL_SELECTION_CHANGED: u16 = 230;
+
+trait TabItemWidgetExtension { }
+
+fn is_tab_item(tree: &WindowTree, window: Window) -> bool {
+    window.widget_extension::<dyn TabItemWidgetExtension>(tree).is_some()
+}
+
+widget! {
+    #[widget(TabItemWidget)]
+    pub struct TabItem {
+        #[property(str, on_changed=on_header_changed)]
+        header: String,
+        focused: Option<Window>,
+    }
+}
+
+impl TabItem {
+    fn on_header_changed(tree: &mut WindowTree, window: Window) {
+        if let Some(parent) = window.parent(tree) {
+            parent.invalidate_measure(tree);
+            parent.invalidate_render(tree);
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+struct TabItemWidget;
+
+impl_supports_interfaces!(TabItemWidget: TabItemWidgetExtension);
+
+impl TabItemWidgetExtension for TabItemWidget { }
+
+impl Widget for TabItemWidget {
+    fn new(&self) -> Box<dyn WidgetData> {
+        Box::new(TabItem {
+            header: String::new(),
+            focused: None,
+        })
+    }
+
+    fn clone_data(
+        &self,
+        tree: &mut WindowTree,
+        source: Window,
+        dest: Window,
+        clone_window: Box<dyn Fn(&WindowTree, Window) -> Window>,
+    ) {
+        TabItem::clone(tree, source, dest, clone_window);
+    }
+
+    fn render(
+        &self,
+        _tree: &WindowTree,
+        _window: Window,
+        _rp: &mut RenderPort,
+        _app: &mut dyn App,
+    ) { }
+
+    fn measure(
+        &self,
+        tree: &mut WindowTree,
+        window: Window,
+        available_width: Option<i16>,
+        available_height: Option<i16>,
+        app: &mut dyn App,
+    ) -> Vector {
+        let mut size = Vector::null();
+        if let Some(first_child) = window.first_child(tree) {
+            let mut child = first_child;
+            loop {
+                child.measure(tree, available_width, available_height, app);
+                size = size.max(child.desired_size(tree));
+                child = child.next(tree);
+                if child == first_child { break; }
+            }
+        }
+        size
+    }
+
+    fn arrange(
+        &self,
+        tree: &mut WindowTree,
+        window: Window,
+        final_inner_bounds: Rect,
+        app: &mut dyn App,
+    ) -> Vector {
+        if let Some(first_child) = window.first_child(tree) {
+            let mut child = first_child;
+            loop {
+                child.arrange(tree, final_inner_bounds, app);
+                child = child.next(tree);
+                if child == first_child { break; }
+            }
+        }
+        final_inner_bounds.size
+    }
+
+    fn update(
+        &self,
+        _tree: &mut WindowTree,
+        _window: Window,
+        _event: Event,
+        _event_source: Window,
+        _app: &mut dyn App,
+    ) -> bool {
+        false
+    }
+}
+
+widget! {
+    #[widget(TabControlWidget, init=init_palette, drop=drop_timers)]
+    pub struct TabControl {
+        selected_index: usize,
+        selection_changed_timer: Option<Timer>,
+    }
+}
+
+impl TabControl {
+    fn init_palette(tree: &mut WindowTree, window: Window) -> Result<(), Error> {
+        window.palette_mut(tree, |palette| {
+            palette.set(0, Color::Palette(COLOR_BUTTON));
+            palette.set(1, Color::Palette(COLOR_HOTKEY));
+            palette.set(2, Color::Palette(COLOR_DISABLED));
+            palette.set(3, Color::Palette(COLOR_BUTTON_FOCUSED));
+            palette.set(4, Color::Palette(COLOR_BUTTON_FOCUSED_HOTKEY));
+            palette.set(5, Color::Palette(COLOR_FRAME));
+        });
+        Ok(())
+    }
+
+    fn drop_timers(&mut self, tree: &mut WindowTree, _app: &mut dyn App) {
+        if let Some(selection_changed_timer) = self.selection_changed_timer.take() {
+            selection_changed_timer.drop_timer(tree);
+        }
+    }
+
+    /// Returns the [`TabItem`] children. Other children are ignored.
+    fn pages(tree: &WindowTree, window: Window) -> Vec<Window> {
+        let mut pages = Vec::new();
+        if let Some(first_child) = window.first_child(tree) {
+            let mut child = first_child;
+            loop {
+                if is_tab_item(tree, child) {
+                    pages.push(child);
+                }
+                child = child.next(tree);
+                if child == first_child { break; }
+            }
+        }
+        pages
+    }
+
+    fn contains(tree: &WindowTree, ancestor: Window, mut window: Window) -> bool {
+        loop {
+            if window == ancestor { return true; }
+            let Some(parent) = window.parent(tree) else { return false; };
+            window = parent;
+        }
+    }
+
+    pub fn selected_index(tree: &WindowTree, window: Window) -> usize {
+        window.data::<TabControl>(tree).selected_index
+    }
+
+    pub fn set_selected_index(tree: &mut WindowTree, window: Window, value: usize) {
+        Self::select(tree, window, value, false, None);
+    }
+
+    pub fn selected_page(tree: &WindowTree, window: Window) -> Option<Window> {
+        let index = window.data::<TabControl>(tree).selected_index;
+        Self::pages(tree, window).get(index).copied()
+    }
+
+    fn sync_visibility(tree: &mut WindowTree, window: Window) {
+        let selected_index = window.data::<TabControl>(tree).selected_index;
+        for (index, page) in Self::pages(tree, window).into_iter().enumerate() {
+            let visibility = if index == selected_index { Visibility::Visible } else { Visibility::Collapsed };
+            if page.visibility(tree) != visibility {
+                page.set_visibility(tree, visibility);
+            }
+        }
+    }
+
+    /// Switches to another page, remembering the focused window of the previous one.
+    ///
+    /// Focus moves to the new page if it was inside the control or `take_focus` is set.
+    fn select(tree: &mut WindowTree, window: Window, index: usize, take_focus: bool, app: Option<&mut dyn App>) {
+        let pages = Self::pages(tree, window);
+        let Some(last) = pages.len().checked_sub(1) else {
+            window.data_mut::<TabControl>(tree).selected_index = index;
+            return;
+        };
+        let index = min(index, last);
+        let old_index = window.data::<TabControl>(tree).selected_index;
+        window.data_mut::<TabControl>(tree).selected_index = index;
+        Self::sync_visibility(tree, window);
+        window.invalidate_render(tree);
+        if old_index == index { return; }
+        let focused = tree.primary_focused();
+        let focus_inside = focused.is_some_and(|x| Self::contains(tree, window, x));
+        if let Some(&old_page) = pages.get(old_index) {
+            let focused = focused.filter(|&x| Self::contains(tree, old_page, x));
+            old_page.data_mut::<TabItem>(tree).focused = focused;
+        }
+        if focus_inside || take_focus {
+            let page = pages[index];
+            let focus = page.data::<TabItem>(tree).focused
+                .filter(|&x| Self::contains(tree, page, x))
+                .unwrap_or(window)
+            ;
+            focus.set_focused_primary(tree, true);
+        }
+        if let Some(app) = app {
+            window.raise(tree, Event::Cmd(CMD_TAB_CONTROL_SELECTION_CHANGED), app);
+        } else {
+            let selection_changed_timer = Timer::new(tree, 0, Box::new(move |tree, app| {
+                window.data_mut::<TabControl>(tree).selection_changed_timer = None;
+                window.raise(tree, Event::Cmd(CMD_TAB_CONTROL_SELECTION_CHANGED), app);
+            }));
+            let data = window.data_mut::<TabControl>(tree);
+            if let Some(old_timer) = data.selection_changed_timer.replace(selection_changed_timer) {
+                old_timer.drop_timer(tree);
+            }
+        }
+    }
+
+    /// Selects the nearest enabled page in the given direction, if any.
+    fn step(tree: &mut WindowTree, window: Window, forward: bool, wrap: bool, app: &mut dyn App) -> bool {
+        let pages = Self::pages(tree, window);
+        let count = pages.len();
+        if count == 0 { return false; }
+        let selected_index = window.data::<TabControl>(tree).selected_index;
+        let mut index = selected_index;
+        loop {
+            index = match (forward, wrap) {
+                (true, true) => (index + 1) % count,
+                (true, false) if index + 1 < count => index + 1,
+                (false, true) => (index + count - 1) % count,
+                (false, false) if index > 0 => index - 1,
+                _ => return true,
+            };
+            if index == selected_index { return true; }
+            if pages[index].is_enabled(tree) { break; }
+        }
+        Self::select(tree, window, index, false, Some(app));
+        true
+    }
+
+    fn tab_at(tree: &WindowTree, window: Window, x: i16) -> Option<usize> {
+        let mut start = 0i16;
+        for (index, page) in Self::pages(tree, window).into_iter().enumerate() {
+            let end = start.saturating_add(label_width(&page.data::<TabItem>(tree).header)).saturating_add(2);
+            if (start .. end).contains(&x) { return Some(index); }
+            start = end;
+        }
+        None
+    }
+}
+
+#[derive(Clone, Default)]
+struct TabControlWidget;
+
+impl_supports_interfaces!(TabControlWidget);
+
+impl Widget for TabControlWidget {
+    fn new(&self) -> Box<dyn WidgetData> {
+        Box::new(TabControl {
+            selected_index: 0,
+            selection_changed_timer: None,
+        })
+    }
+
+    fn clone_data(
+        &self,
+        tree: &mut WindowTree,
+        source: Window,
+        dest: Window,
+        clone_window: Box<dyn Fn(&WindowTree, Window) -> Window>,
+    ) {
+        TabControl::clone(tree, source, dest, clone_window);
+        let selected_index = source.data::<TabControl>(tree).selected_index;
+        dest.data_mut::<TabControl>(tree).selected_index = selected_index;
+    }
+
+    fn render(
+        &self,
+        tree: &WindowTree,
+        window: Window,
+        rp: &mut RenderPort,
+        _app: &mut dyn App,
+    ) {
+        let bounds = window.inner_bounds(tree);
+        let is_enabled = window.actual_is_enabled(tree);
+        let selected_index = window.data::<TabControl>(tree).selected_index;
+        let mut x = 0i16;
+        for (index, page) in TabControl::pages(tree, window).into_iter().enumerate() {
+            let header = &page.data::<TabItem>(tree).header;
+            let (color, color_hotkey) = match (is_enabled && page.is_enabled(tree), index == selected_index) {
+                (false, _) => (window.color(tree, 2), window.color(tree, 2)),
+                (true, false) => (window.color(tree, 0), window.color(tree, 1)),
+                (true, true) => (window.color(tree, 3), window.color(tree, 4)),
+            };
+            let width = label_width(header).wrapping_add(2);
+            rp.text(Point { x, y: 0 }, color, " ");
+            rp.label(Point { x: x.wrapping_add(1), y: 0 }, color, color_hotkey, header);
+            rp.text(Point { x: x.wrapping_add(width).wrapping_sub(1), y: 0 }, color, " ");
+            x = x.wrapping_add(width);
+        }
+        rp.h_line(Point { x: 0, y: 1 }, bounds.w(), false, window.color(tree, 5));
+    }
+
+    fn measure(
+        &self,
+        tree: &mut WindowTree,
+        window: Window,
+        available_width: Option<i16>,
+        available_height: Option<i16>,
+        app: &mut dyn App,
+    ) -> Vector {
+        TabControl::sync_visibility(tree, window);
+        let available_height = available_height.map(|x| max(0, x.wrapping_sub(2)));
+        let mut headers_width = 0i16;
+        let mut size = Vector::null();
+        for page in TabControl::pages(tree, window) {
+            headers_width = headers_width
+                .wrapping_add(label_width(&page.data::<TabItem>(tree).header))
+                .wrapping_add(2)
+            ;
+            page.measure(tree, available_width, available_height, app);
+            size = size.max(page.desired_size(tree));
+        }
+        Vector { x: max(size.x, headers_width), y: size.y.wrapping_add(2) }
+    }
+
+    fn arrange(
+        &self,
+        tree: &mut WindowTree,
+        window: Window,
+        final_inner_bounds: Rect,
+        app: &mut dyn App,
+    ) -> Vector {
+        let page_bounds = Thickness::new(0, 2, 0, 0).shrink_rect(final_inner_bounds);
+        for page in TabControl::pages(tree, window) {
+            page.arrange(tree, page_bounds, app);
+        }
+        final_inner_bounds.size
+    }
+
+    fn update(
+        &self,
+        tree: &mut WindowTree,
+        window: Window,
+        event: Event,
+        event_source: Window,
+        app: &mut dyn App,
+    ) -> bool {
+        if !window.actual_is_enabled(tree) { return false; }
+        match event {
+            Event::PreviewKey(key @ (Key::PageUp | Key::PageDown), Mods::CTRL) => {
+                TabControl::step(tree, window, key == Key::PageDown, true, app)
+            },
+            Event::Key(key @ (Key::Left | Key::Right), Mods::NONE) if event_source == window => {
+                TabControl::step(tree, window, key == Key::Right, false, app)
+            },
+            Event::LmbDown(point, _) if event_source == window => {
+                let point = window.inner_point(point, tree);
+                if point.y != 0 { return false; }
+                let Some(index) = TabControl::tab_at(tree, window, point.x) else { return false; };
+                if !TabControl::pages(tree, window)[index].is_enabled(tree) { return false; }
+                TabControl::select(tree, window, index, true, Some(app));
+                true
+            },
+            Event::PostProcessKey(Key::Alt(c), Mods::NONE) => {
+                let index = TabControl::pages(tree, window).into_iter().position(|page|
+                    page.is_enabled(tree) && label(&page.data::<TabItem>(tree).header) == Some(c)
+                );
+                let Some(index) = index else { return false; };
+                TabControl::select(tree, window, index, true, Some(app));
+                true
+            },
+            _ => false
+        }
+    }
+
+    fn post_process(&self) -> bool { true }
+}
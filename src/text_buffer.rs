@@ -0,0 +1,410 @@
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cmp::min;
+use core::mem::{replace, take};
+use core::ops::Range;
+
+const CHUNK_LEN: usize = 4096;
+const MAX_CHUNK_LEN: usize = 2 * CHUNK_LEN;
+
+struct Chunk {
+    text: String,
+    line_breaks: usize,
+}
+
+impl Chunk {
+    fn weight(&self) -> (usize, usize) { (self.text.len(), self.line_breaks) }
+}
+
+/// Fenwick tree over the chunks' `(byte length, line break count)` pairs.
+struct ChunkIndex {
+    nodes: Vec<(usize, usize)>,
+}
+
+impl ChunkIndex {
+    fn new(chunks: &[Chunk]) -> Self {
+        let mut nodes: Vec<_> = chunks.iter().map(Chunk::weight).collect();
+        for i in 0 .. nodes.len() {
+            let parent = i | (i + 1);
+            if parent < nodes.len() {
+                nodes[parent].0 += nodes[i].0;
+                nodes[parent].1 += nodes[i].1;
+            }
+        }
+        ChunkIndex { nodes }
+    }
+
+    fn update(&mut self, mut i: usize, old: (usize, usize), new: (usize, usize)) {
+        while i < self.nodes.len() {
+            let node = &mut self.nodes[i];
+            node.0 = node.0 - old.0 + new.0;
+            node.1 = node.1 - old.1 + new.1;
+            i |= i + 1;
+        }
+    }
+
+    /// Returns the sums over the first `count` chunks.
+    fn prefix(&self, mut count: usize) -> (usize, usize) {
+        let mut sum = (0, 0);
+        while count != 0 {
+            let node = self.nodes[count - 1];
+            sum.0 += node.0;
+            sum.1 += node.1;
+            count &= count - 1;
+        }
+        sum
+    }
+
+    /// Returns the largest `count` such that `f` holds for the sums over the first `count` chunks,
+    /// along with these sums. `f` should be monotone.
+    fn search(&self, f: impl Fn((usize, usize)) -> bool) -> (usize, (usize, usize)) {
+        let mut count = 0;
+        let mut sum = (0, 0);
+        let mut step = self.nodes.len().checked_ilog2().map_or(0, |x| 1 << x);
+        while step != 0 {
+            if count + step <= self.nodes.len() {
+                let node = self.nodes[count + step - 1];
+                let next = (sum.0 + node.0, sum.1 + node.1);
+                if f(next) {
+                    count += step;
+                    sum = next;
+                }
+            }
+            step >>= 1;
+        }
+        (count, sum)
+    }
+}
+
+/// Rope-like text storage: a sequence of chunks with per-chunk line break counts.
+///
+/// A chunk ends with a line break where possible, but a line longer than `MAX_CHUNK_LEN` is split
+/// across several chunks at character boundaries. A line break itself never spans chunks.
+/// Chunk starts and line numbers are found through a Fenwick tree in `O(log n)`;
+/// it is rebuilt only when an edit changes the chunk count.
+pub(crate) struct TextBuffer {
+    chunks: Vec<Chunk>,
+    index: ChunkIndex,
+    line_break: String,
+}
+
+fn split_chunks(text: &str, line_break: &str) -> Vec<Chunk> {
+    let mut chunks = Vec::new();
+    let mut chunk = String::new();
+    for mut line in text.split_inclusive(line_break) {
+        while chunk.len() + line.len() > MAX_CHUNK_LEN {
+            let content_len = line.strip_suffix(line_break).map_or(line.len(), str::len);
+            let mut cut = min(MAX_CHUNK_LEN - chunk.len(), content_len);
+            while !line.is_char_boundary(cut) { cut -= 1; }
+            chunk.push_str(&line[.. cut]);
+            line = &line[cut ..];
+            let line_breaks = chunk.matches(line_break).count();
+            chunks.push(Chunk { text: take(&mut chunk), line_breaks });
+        }
+        chunk.push_str(line);
+        if chunk.len() >= CHUNK_LEN {
+            let line_breaks = chunk.matches(line_break).count();
+            chunks.push(Chunk { text: take(&mut chunk), line_breaks });
+        }
+    }
+    if !chunk.is_empty() {
+        let line_breaks = chunk.matches(line_break).count();
+        chunks.push(Chunk { text: chunk, line_breaks });
+    }
+    chunks
+}
+
+/// Checks if a line break can span the boundary between `a` and `b`.
+///
+/// Errs on the side of `true` when `a` or `b` is shorter than the line break part it should hold.
+fn spans_line_break(a: &str, b: &str, line_break: &str) -> bool {
+    (1 .. line_break.len()).filter(|&i| line_break.is_char_boundary(i)).any(|i| {
+        let (head, tail) = line_break.split_at(i);
+        (a.ends_with(head) || head.ends_with(a)) && (b.starts_with(tail) || tail.starts_with(b))
+    })
+}
+
+impl TextBuffer {
+    pub fn new(text: &str, line_break: &str) -> Self {
+        let mut chunks = split_chunks(text, line_break);
+        if chunks.is_empty() {
+            chunks.push(Chunk { text: String::new(), line_breaks: 0 });
+        }
+        let index = ChunkIndex::new(&chunks);
+        TextBuffer { chunks, index, line_break: line_break.into() }
+    }
+
+    pub fn line_break(&self) -> &str { &self.line_break }
+
+    pub fn set_line_break(&mut self, line_break: &str) {
+        let text = self.substring(0 .. self.len());
+        *self = TextBuffer::new(&text, line_break);
+    }
+
+    pub fn len(&self) -> usize {
+        self.index.prefix(self.chunks.len()).0
+    }
+
+    pub fn is_empty(&self) -> bool { self.len() == 0 }
+
+    pub fn ends_with_line_break(&self) -> bool {
+        !self.is_empty() && self.chunks.last().unwrap().text.ends_with(&self.line_break)
+    }
+
+    /// Returns the index and the start of the chunk containing `pos`.
+    fn chunk_at(&self, pos: usize) -> (usize, usize) {
+        let (count, (start, _)) = self.index.search(|(len, _)| len <= pos);
+        if count == self.chunks.len() {
+            let index = count - 1;
+            (index, start - self.chunks[index].text.len())
+        } else {
+            (count, start)
+        }
+    }
+
+    /// Returns a text slice, borrowing it if it lies within one chunk.
+    pub fn slice(&self, range: Range<usize>) -> Cow<'_, str> {
+        let (index, start) = self.chunk_at(range.start);
+        let chunk = &self.chunks[index].text;
+        if range.end - start <= chunk.len() {
+            Cow::Borrowed(&chunk[range.start - start .. range.end - start])
+        } else {
+            Cow::Owned(self.substring(range))
+        }
+    }
+
+    /// Returns the beginning of a text slice, lying within one chunk.
+    /// It is empty only if the slice is empty, and holds the whole first character or line break.
+    pub fn slice_head(&self, range: Range<usize>) -> &str {
+        let (index, start) = self.chunk_at(range.start);
+        let chunk = &self.chunks[index].text;
+        &chunk[range.start - start .. min(range.end - start, chunk.len())]
+    }
+
+    /// Returns the ending of a text slice, lying within one chunk.
+    /// It is empty only if the slice is empty, and holds the whole last character or line break.
+    pub fn slice_tail(&self, range: Range<usize>) -> &str {
+        if range.is_empty() { return ""; }
+        let (index, start) = self.chunk_at(range.end - 1);
+        &self.chunks[index].text[range.start.saturating_sub(start) .. range.end - start]
+    }
+
+    pub fn substring(&self, range: Range<usize>) -> String {
+        let mut res = String::with_capacity(range.len());
+        let mut pos = range.start;
+        while pos < range.end {
+            let (index, start) = self.chunk_at(pos);
+            let chunk = &self.chunks[index].text;
+            let end = range.end.min(start + chunk.len());
+            res.push_str(&chunk[pos - start .. end - start]);
+            pos = end;
+        }
+        res
+    }
+
+    #[cfg(feature="std")]
+    pub fn chunks(&self) -> impl Iterator<Item=&str> {
+        self.chunks.iter().map(|x| &x.text[..])
+    }
+
+    /// Returns the start of the line containing `pos`.
+    pub fn line_start(&self, pos: usize) -> usize {
+        self.line_offset(self.line_of(pos))
+    }
+
+    /// Returns the end of the line containing `pos`, excluding the line break.
+    pub fn line_end(&self, pos: usize) -> usize {
+        let next = self.line_of(pos) + 1;
+        if next == self.line_count() {
+            self.len()
+        } else {
+            self.line_offset(next) - self.line_break.len()
+        }
+    }
+
+    /// Returns the start of the line following the line containing `pos`, or the text length.
+    pub fn next_line_start(&self, pos: usize) -> usize {
+        self.line_offset(self.line_of(pos) + 1)
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.index.prefix(self.chunks.len()).1 + 1
+    }
+
+    /// Returns the zero-based number of the line containing `pos`.
+    pub fn line_of(&self, pos: usize) -> usize {
+        let (index, start) = self.chunk_at(pos);
+        self.index.prefix(index).1 + self.chunks[index].text[.. pos - start].matches(&self.line_break).count()
+    }
+
+    /// Returns the start of the line with the given zero-based number, or the text length if there is no such line.
+    pub fn line_offset(&self, line: usize) -> usize {
+        let (index, (start, line_breaks)) = self.index.search(|(_, line_breaks)| line_breaks < line);
+        let Some(chunk) = self.chunks.get(index) else { return start; };
+        let line = line - line_breaks;
+        if line == 0 { return start; }
+        let (offset, _) = chunk.text.match_indices(&self.line_break).nth(line - 1).unwrap();
+        start + offset + self.line_break.len()
+    }
+
+    pub fn replace(&mut self, range: Range<usize>, s: &str) {
+        let (mut first, mut start) = self.chunk_at(range.start);
+        if first != 0 && self.chunks[first - 1].text.len() + self.chunks[first].text.len() < CHUNK_LEN {
+            first -= 1;
+            start -= self.chunks[first].text.len();
+        }
+        let (last, last_start) = self.chunk_at(range.end.saturating_sub(1));
+        let (last, last_start) = if last < first { (first, start) } else { (last, last_start) };
+        let mut text = self.substring(start .. range.start);
+        text.push_str(s);
+        text.push_str(&self.chunks[last].text[range.end - last_start ..]);
+        let mut end = last + 1;
+        while first != 0 && spans_line_break(&self.chunks[first - 1].text, &text, &self.line_break) {
+            first -= 1;
+            text.insert_str(0, &self.chunks[first].text);
+        }
+        while end < self.chunks.len() && spans_line_break(&text, &self.chunks[end].text, &self.line_break) {
+            text.push_str(&self.chunks[end].text);
+            end += 1;
+        }
+        let mut chunks = split_chunks(&text, &self.line_break);
+        if chunks.len() == end - first {
+            for (i, chunk) in (first ..).zip(chunks) {
+                let old = replace(&mut self.chunks[i], chunk);
+                self.index.update(i, old.weight(), self.chunks[i].weight());
+            }
+            return;
+        }
+        if chunks.is_empty() && self.chunks.len() == end - first {
+            chunks.push(Chunk { text: String::new(), line_breaks: 0 });
+        }
+        self.chunks.splice(first .. end, chunks);
+        self.index = ChunkIndex::new(&self.chunks);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::format;
+    use core::cmp::min;
+
+    fn numbered_lines(count: usize, line_break: &str) -> String {
+        (0 .. count).map(|i| format!("line {i}{line_break}")).collect()
+    }
+
+    fn check(buf: &TextBuffer, text: &str) {
+        assert_eq!(buf.len(), text.len());
+        assert_eq!(buf.substring(0 .. buf.len()), text);
+        let line_break = buf.line_break();
+        let mut start = 0;
+        for (line, s) in text.split(line_break).enumerate() {
+            assert_eq!(buf.line_offset(line), start);
+            assert_eq!(buf.line_of(start), line);
+            assert_eq!(buf.line_of(start + s.len()), line);
+            assert_eq!(buf.line_start(start + s.len()), start);
+            assert_eq!(buf.line_end(start), start + s.len());
+            start += s.len() + line_break.len();
+        }
+        assert_eq!(buf.line_count(), text.split(line_break).count());
+        assert_eq!(buf.line_offset(buf.line_count()), text.len());
+        for chunk in &buf.chunks {
+            assert!(chunk.text.len() <= MAX_CHUNK_LEN);
+        }
+        for (a, b) in buf.chunks.iter().zip(&buf.chunks[1 ..]) {
+            assert!(a.text.ends_with(line_break) || !spans_line_break(&a.text, &b.text, line_break));
+        }
+    }
+
+    #[test]
+    fn empty() {
+        let buf = TextBuffer::new("", "\n");
+        check(&buf, "");
+        assert!(buf.is_empty());
+        assert!(!buf.ends_with_line_break());
+    }
+
+    #[test]
+    fn line_mapping() {
+        let text = numbered_lines(2000, "\n");
+        let buf = TextBuffer::new(&text, "\n");
+        assert!(buf.chunks.len() > 2);
+        check(&buf, &text);
+        assert_eq!(buf.slice(buf.line_offset(1500) .. buf.line_end(buf.line_offset(1500))), "line 1500");
+        assert!(buf.ends_with_line_break());
+    }
+
+    #[test]
+    fn line_mapping_crlf() {
+        let text = numbered_lines(2000, "\r\n") + "tail";
+        let buf = TextBuffer::new(&text, "\r\n");
+        check(&buf, &text);
+        assert!(!buf.ends_with_line_break());
+    }
+
+    #[test]
+    fn replace_across_chunk_boundary() {
+        let mut text = numbered_lines(2000, "\n");
+        let mut buf = TextBuffer::new(&text, "\n");
+        let boundary = buf.chunks[0].text.len();
+        let range = boundary - 3 .. boundary + 3;
+        buf.replace(range.clone(), "X");
+        text.replace_range(range, "X");
+        check(&buf, &text);
+        let range = boundary - 100 .. boundary;
+        buf.replace(range.clone(), "a\nb\n");
+        text.replace_range(range, "a\nb\n");
+        check(&buf, &text);
+        let range = 10 .. text.len() - 10;
+        buf.replace(range.clone(), "");
+        text.replace_range(range, "");
+        check(&buf, &text);
+        buf.replace(0 .. text.len(), "");
+        check(&buf, "");
+        let text = numbered_lines(1000, "\n");
+        buf.replace(0 .. 0, &text);
+        check(&buf, &text);
+    }
+
+    #[test]
+    fn long_lines() {
+        let mut text = "x".repeat(3 * MAX_CHUNK_LEN) + "\r\n" + &"\u{e9}".repeat(MAX_CHUNK_LEN) + "\r\n" + "tail";
+        let mut buf = TextBuffer::new(&text, "\r\n");
+        assert!(buf.chunks.len() > 4);
+        check(&buf, &text);
+        assert_eq!(buf.line_end(10), 3 * MAX_CHUNK_LEN);
+        assert_eq!(buf.next_line_start(MAX_CHUNK_LEN + 10), 3 * MAX_CHUNK_LEN + 2);
+        assert_eq!(buf.slice(5 .. 2 * MAX_CHUNK_LEN), &text[5 .. 2 * MAX_CHUNK_LEN]);
+        assert_eq!(buf.slice_head(5 .. 2 * MAX_CHUNK_LEN), &text[5 .. MAX_CHUNK_LEN]);
+        let boundary = buf.chunks[0].text.len();
+        for (pos, s) in [(boundary, "\r"), (boundary + 1, "\n"), (boundary, "\n"), (boundary + 2, "\r")] {
+            buf.replace(pos .. pos, s);
+            text.replace_range(pos .. pos, s);
+            check(&buf, &text);
+        }
+        let range = 100 .. text.len() - 100;
+        buf.replace(range.clone(), "\r\n");
+        text.replace_range(range, "\r\n");
+        check(&buf, &text);
+    }
+
+    #[test]
+    fn replace_many() {
+        let mut text = numbered_lines(3000, "\n");
+        let mut buf = TextBuffer::new(&text, "\n");
+        let mut seed = 1u32;
+        let mut random = |n: usize| {
+            seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+            (seed >> 8) as usize % n
+        };
+        for i in 0 .. 300 {
+            let start = random(text.len() + 1);
+            let end = start + random(min(text.len() - start, 10000) + 1);
+            let s = if i % 3 == 0 { numbered_lines(random(200), "\n") } else { format!("{i}\n") };
+            buf.replace(start .. end, &s);
+            text.replace_range(start .. end, &s);
+            check(&buf, &text);
+        }
+    }
+}
@@ -1,47 +1,104 @@
+use alloc::borrow::Cow;
 use alloc::boxed::Box;
 use alloc::string::{String, ToString};
 use alloc::vec::Vec;
-use core::cmp::{Ordering, min};
+use core::cmp::{Ordering, max, min};
 use core::ops::Range;
 use dynamic_cast::impl_supports_interfaces;
-use tuifw_screen_base::{Vector, Rect, Point, Fg, Bg, text_width, Key};
-use tuifw_window::{App, Window, Event, WindowTree, RenderPort, Widget, WidgetData};
+use tuifw_screen_base::{Vector, Rect, Point, Fg, Bg, text_width, Key, Mods, Ctrl, MouseButton, Error};
+use tuifw_window::{App, Window, Event, WindowTree, RenderPort, Widget, WidgetData, Color, Focus};
+use tuifw_window::{CMD_GOT_PRIMARY_FOCUS, CMD_LOST_PRIMARY_FOCUS, COLOR_TEXT_SELECTION, COLOR_TEXT_MATCH};
 use unicode_width::UnicodeWidthChar;
 use crate::widget;
+use crate::text_buffer::TextBuffer;
+use crate::text_search::Searcher;
+use crate::undo_history::*;
 
+/// [`TextEdit`] long lines handling mode.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum TextWrap {
+    /// Lines are not wrapped, the view scrolls horizontally.
+    None,
+    /// Lines are wrapped at any character.
+    Char,
+    /// Lines are wrapped after whitespace, or at any character if a word does not fit.
+    Word,
+}
+
+/// A visual line: a whole text line, or, if wrapping is on, its part.
 struct Line {
     range: Range<usize>,
     view: Range<usize>,
     filled: bool,
     padding: u16,
+    wrapped: bool,
+}
+
+impl Line {
+    fn new(text: &TextBuffer, range: Range<usize>, wrapped: bool, column: u64, width: u64) -> Line {
+        let mut view_start = range.start;
+        let mut padding = 0;
+        let mut x = 0;
+        while x != column {
+            let Some(c) = first_char(text.slice_head(view_start .. range.end), text.line_break()) else { break; };
+            let c_width = c.width();
+            view_start += c.len(text.line_break());
+            if x + c_width > column {
+                padding = x + c_width - column;
+                break;
+            }
+            x += c_width;
+        }
+        let mut view_end = view_start;
+        let mut x = 0;
+        let filled = loop {
+            if x == width { break true; }
+            let Some(c) = first_char(text.slice_head(view_end .. range.end), text.line_break()) else { break false; };
+            let c_width = c.width();
+            if x + c_width > width { break true; }
+            view_end += c.len(text.line_break());
+            x += c_width;
+        };
+        Line {
+            range,
+            view: view_start .. view_end,
+            filled,
+            padding: u16::try_from(padding).unwrap(),
+            wrapped,
+        }
+    }
 }
 
 widget! {
-    #[widget(TextEditWidget)]
+    #[widget(TextEditWidget, init=init)]
     pub struct TextEdit {
-        #[property(str, on_changed=reset_view)]
-        text: String,
-        #[property(str, on_changed=reset_view)]
+        text: TextBuffer,
+        #[property(str, on_changed=on_line_break_changed)]
         line_break: String,
+        #[property(copy, on_changed=on_undo_depth_changed)]
+        undo_depth: usize,
+        #[property(copy, on_changed=on_wrap_changed)]
+        wrap: TextWrap,
+        #[property(str, render, on_changed=on_search_changed)]
+        search: String,
+        #[property(copy, render, on_changed=on_search_changed)]
+        search_case_sensitive: bool,
+        #[property(copy, render, on_changed=on_search_changed)]
+        search_whole_word: bool,
+        #[property(copy, render, on_changed=on_search_changed)]
+        search_regex: bool,
+        searcher: Option<Searcher>,
+        history: UndoHistory,
         size: Vector,
         lines: Vec<Line>,
         column: u64,
         cursor: usize,
+        anchor: Option<usize>,
         cursor_line: Option<usize>,
         cursor_column: u64,
     }
 }
 
-fn actual_text(text: &str) -> &str {
-    if
-        size_of::<usize>() <= size_of::<u32>() || text.len() <= usize::try_from(u32::MAX).unwrap()
-    {
-        text
-    } else {
-        &text[.. usize::try_from(u32::MAX).unwrap()]
-    }
-}
-
 #[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
 enum Char {
     Char(char),
@@ -85,110 +142,181 @@ fn last_char(text: &str, line_break: &str) -> Option<Char> {
     }
 }
 
+fn cursor_line(lines: &[Line], text: &TextBuffer, cursor: usize) -> Option<usize> {
+    if text.is_empty() {
+        return if lines.is_empty() { None } else { Some(0) };
+    }
+    lines.binary_search_by(|line| {
+        let line_text = text.slice_tail(line.range.clone());
+        if line_text.is_empty() { return Ordering::Greater; }
+        if
+            line.range.contains(&cursor) ||
+            !line.wrapped && !line_text.ends_with(text.line_break()) && cursor == line.range.end
+        {
+            Ordering::Equal
+        } else if cursor >= line.range.start {
+            Ordering::Less
+        } else {
+            Ordering::Greater
+        }
+    }).ok()
+}
+
+fn str_width(s: &str) -> u64 {
+    s.chars().map(|x| if x == '\0' { 0 } else { x.width().unwrap_or(0) as u64 }).sum()
+}
+
+/// The cursor cannot be placed after the trailing line break, because there is no line there.
+fn valid_cursor(text: &TextBuffer, pos: usize) -> usize {
+    if pos == text.len() && text.ends_with_line_break() {
+        pos - text.line_break().len()
+    } else {
+        pos
+    }
+}
+
+fn normalize_line_breaks(s: &str, line_break: &str) -> String {
+    let s = s.replace("\r\n", "\n").replace('\r', "\n");
+    if line_break == "\n" { s } else { s.replace('\n', line_break) }
+}
+
 impl TextEdit {
+    fn init(tree: &mut WindowTree, window: Window) -> Result<(), Error> {
+        window.palette_mut(tree, |palette| {
+            palette.set(0, Color::Palette(COLOR_TEXT_SELECTION));
+            palette.set(1, Color::Palette(COLOR_TEXT_MATCH));
+        });
+        window.set_focus_click(tree, Some(Focus::Primary));
+        Ok(())
+    }
+
     fn reset_view(tree: &mut WindowTree, window: Window) {
         let data = window.data_mut::<TextEdit>(tree);
         data.cursor = 0;
+        data.anchor = None;
         data.cursor_column = 0;
         data.column = 0;
         data.lines.clear();
+        data.history.clear();
+        Self::recalc_view(tree, window, None);
+    }
+
+    fn on_line_break_changed(tree: &mut WindowTree, window: Window) {
+        let data = window.data_mut::<TextEdit>(tree);
+        data.text.set_line_break(&data.line_break);
+        Self::reset_view(tree, window);
+    }
+
+    fn on_undo_depth_changed(tree: &mut WindowTree, window: Window) {
+        let data = window.data_mut::<TextEdit>(tree);
+        data.history.set_depth(data.undo_depth);
+    }
+
+    fn on_wrap_changed(tree: &mut WindowTree, window: Window) {
+        let data = window.data_mut::<TextEdit>(tree);
+        data.column = 0;
+        let cursor = data.cursor;
         Self::recalc_view(tree, window, None);
+        Self::move_cursor(tree, window, cursor);
+    }
+
+    fn on_search_changed(tree: &mut WindowTree, window: Window) {
+        let data = window.data_mut::<TextEdit>(tree);
+        data.searcher = Searcher::new(&data.search, data.search_case_sensitive, data.search_whole_word, data.search_regex);
+    }
+
+    pub fn text(tree: &WindowTree, window: Window) -> String {
+        let text = &window.data::<TextEdit>(tree).text;
+        text.substring(0 .. text.len())
+    }
+
+    pub fn set_text<'a>(tree: &mut WindowTree, window: Window, value: impl Into<Cow<'a, str>>) {
+        let data = window.data_mut::<TextEdit>(tree);
+        data.text = TextBuffer::new(&value.into(), &data.line_break);
+        Self::reset_view(tree, window);
+    }
+
+    /// Replaces the text with the reader contents, converting all line breaks
+    /// to the [`line_break`](TextEdit::line_break) sequence.
+    #[cfg(feature="std")]
+    pub fn load(tree: &mut WindowTree, window: Window, mut reader: impl std::io::Read) -> std::io::Result<()> {
+        let mut text = String::new();
+        reader.read_to_string(&mut text)?;
+        let text = normalize_line_breaks(&text, &window.data::<TextEdit>(tree).line_break);
+        Self::set_text(tree, window, text);
+        Ok(())
+    }
+
+    /// Writes the text to the writer and [marks it as saved](TextEdit::mark_saved).
+    #[cfg(feature="std")]
+    pub fn save(tree: &mut WindowTree, window: Window, mut writer: impl std::io::Write) -> std::io::Result<()> {
+        for chunk in window.data::<TextEdit>(tree).text.chunks() {
+            writer.write_all(chunk.as_bytes())?;
+        }
+        writer.flush()?;
+        Self::mark_saved(tree, window);
+        Ok(())
+    }
+
+    pub fn line_count(tree: &WindowTree, window: Window) -> usize {
+        window.data::<TextEdit>(tree).text.line_count()
+    }
+
+    /// Returns the zero-based number of the line containing the cursor.
+    pub fn cursor_line_number(tree: &WindowTree, window: Window) -> usize {
+        let data = window.data::<TextEdit>(tree);
+        data.text.line_of(data.cursor)
+    }
+
+    /// Moves the cursor to the start of the line with the given zero-based number
+    /// (or to the text end, if there are not so many lines).
+    pub fn go_to_line(tree: &mut WindowTree, window: Window, line: usize) {
+        let pos = window.data::<TextEdit>(tree).text.line_offset(line);
+        Self::select(tree, window, false);
+        Self::move_cursor(tree, window, pos);
     }
 
     fn recalc_view(tree: &mut WindowTree, window: Window, start: Option<usize>) {
         let data = window.data_mut::<TextEdit>(tree);
-        let text = actual_text(&data.text);
-        let mut line_start = start.unwrap_or_else(|| data.lines.first().map_or(0, |x| x.range.start));
+        let start = start.unwrap_or_else(|| data.lines.first().map_or(0, |x| x.range.start));
+        let mut line_start = data.visual_line_start(start);
         data.lines.clear();
         for _ in 0 .. data.size.y as u16 {
-            let line_end = text[line_start ..]
-                .find(&data.line_break)
-                .map_or(text.len(), |x| line_start + x + data.line_break.len());
-            let mut line_view_start = line_start;
-            let line_padding;
-            let mut width = 0;
-            loop {
-                if width == data.column {
-                    line_padding = 0;
-                    break;
-                }
-                let Some(c) = first_char(&text[line_view_start .. line_end], &data.line_break) else {
-                    line_padding = 0;
-                    break;
-                };
-                let c_width = c.width();
-                if width + c_width > data.column {
-                    line_padding = width + c_width - data.column;                    
-                    line_view_start += c.len(&data.line_break);
-                    break;
-                }
-                line_view_start += c.len(&data.line_break);
-                width += c_width;
-            }
-            let line_filled;
-            let mut line_view_end = line_view_start;
-            let mut width = 0;
-            loop {
-                if width == data.size.x as u16 as u64 {
-                    line_filled = true;
-                    break;
-                }
-                let Some(c) = first_char(&text[line_view_end .. line_end], &data.line_break) else {
-                    line_filled = false;
-                    break;
-                };
-                let c_width = c.width();
-                if width + c_width > data.size.x as u16 as u64 {
-                    line_filled = true;
-                    break;
-                }
-                line_view_end += c.len(&data.line_break);
-                width += c_width;
-            }
-            data.lines.push(Line {
-                range: line_start .. line_end,
-                view: line_view_start .. line_view_end,
-                filled: line_filled,
-                padding: u16::try_from(line_padding).unwrap(),
-            });
+            let (line_end, wrapped) = data.visual_line_end(line_start);
+            let line = Line::new(&data.text, line_start .. line_end, wrapped, data.column, data.size.x as u16 as u64);
+            data.lines.push(line);
             line_start = line_end;
         }
-        data.cursor_line = data.lines.binary_search_by(|line| {
-            if text[line.range.clone()].is_empty() { return Ordering::Greater; }
-            if line.range.contains(&data.cursor) || !text[line.range.clone()].ends_with(&data.line_break) && data.cursor == line.range.end {
-                Ordering::Equal
-            } else if data.cursor >= line.range.start {
-                Ordering::Less
-            } else {
-                Ordering::Greater
-            }
-        }).ok();
+        data.cursor_line = cursor_line(&data.lines, &data.text, data.cursor);
         window.invalidate_render(tree);
     }
 
     fn cursor_left(tree: &mut WindowTree, window: Window) -> bool {
         let data = window.data_mut::<TextEdit>(tree);
-        let text = actual_text(&data.text);
+        if data.wrap_width().is_some() {
+            let Some(Char::Char(c)) = data.char_before(data.cursor) else { return false; };
+            let pos = data.cursor - c.len_utf8();
+            Self::move_cursor(tree, window, pos);
+            return true;
+        }
         if data.cursor_line.is_none() {
-            let line_start =
-                text[.. data.cursor].rfind(&data.line_break).map_or(0, |x| x + data.line_break.len());
+            let line_start = data.text.line_start(data.cursor);
             Self::recalc_view(tree, window, Some(line_start));
         }
         let data = window.data_mut::<TextEdit>(tree);
         let line = &data.lines[data.cursor_line.unwrap()];
         if data.cursor > line.view.end || data.cursor == line.view.end && line.filled {
-            let text = actual_text(&data.text);
-            data.column = text[line.range.start .. data.cursor].chars().map(|x| if x == '\0' { 0 } else { x.width().unwrap_or(0) as u64 }).sum();
+            data.column = str_width(&data.text.slice(line.range.start .. data.cursor));
             Self::recalc_view(tree, window, None);
         }
         let data = window.data_mut::<TextEdit>(tree);
-        let text = actual_text(&data.text);
+        let text = &data.text;
         let line = &data.lines[data.cursor_line.unwrap()];
-        let Some(Char::Char(c)) = last_char(&text[line.range.start .. data.cursor], &data.line_break) else { return false; };
+        let Some(Char::Char(c)) = last_char(text.slice_tail(line.range.start .. data.cursor), &data.line_break) else { return false; };
         data.cursor -= c.len_utf8();
-        data.cursor_column = text[line.range.start .. data.cursor].chars().map(|x| if x == '\0' { 0 } else { x.width().unwrap_or(0) as u64 }).sum();
+        data.cursor_column = str_width(&text.slice(line.range.start .. data.cursor));
         if data.cursor < line.view.start {
-            let offset = text[data.cursor .. line.view.start].chars().map(|x| if x == '\0' { 0 } else { x.width().unwrap_or(0) as u64 }).sum();
+            let offset = str_width(&text.slice(data.cursor .. line.view.start));
             Self::scroll_left(tree, window, offset);
         }
         window.invalidate_render(tree);
@@ -197,28 +325,31 @@ impl TextEdit {
 
     fn cursor_right(tree: &mut WindowTree, window: Window) -> bool {
         let data = window.data_mut::<TextEdit>(tree);
-        let text = actual_text(&data.text);
+        if data.wrap_width().is_some() {
+            let Some(Char::Char(c)) = data.char_after(data.cursor) else { return false; };
+            let pos = data.cursor + c.len_utf8();
+            Self::move_cursor(tree, window, pos);
+            return true;
+        }
         if data.cursor_line.is_none() {
-            let line_start =
-                text[.. data.cursor].rfind(&data.line_break).map_or(0, |x| x + data.line_break.len());
+            let line_start = data.text.line_start(data.cursor);
             Self::recalc_view(tree, window, Some(line_start));
         }
         let data = window.data_mut::<TextEdit>(tree);
         let line = &data.lines[data.cursor_line.unwrap()];
         if data.cursor < line.view.start {
-            let text = actual_text(&data.text);
-            data.column = text[line.range.start .. data.cursor].chars().map(|x| if x == '\0' { 0 } else { x.width().unwrap_or(0) as u64 }).sum();
+            data.column = str_width(&data.text.slice(line.range.start .. data.cursor));
             Self::recalc_view(tree, window, None);
         }
         let data = window.data_mut::<TextEdit>(tree);
-        let text = actual_text(&data.text);
+        let text = &data.text;
         let line = &data.lines[data.cursor_line.unwrap()];
-        let Some(Char::Char(c)) = first_char(&text[data.cursor .. line.range.end], &data.line_break) else { return false; };
+        let Some(Char::Char(c)) = first_char(text.slice_head(data.cursor .. line.range.end), &data.line_break) else { return false; };
         data.cursor += c.len_utf8();
-        data.cursor_column = text[line.range.start .. data.cursor].chars().map(|x| if x == '\0' { 0 } else { x.width().unwrap_or(0) as u64 }).sum();
+        data.cursor_column = str_width(&text.slice(line.range.start .. data.cursor));
         if data.cursor > line.view.end || data.cursor == line.view.end && line.filled {
-            let mut offset = text[line.view.end .. data.cursor].chars().map(|x| if x == '\0' { 0 } else { x.width().unwrap_or(0) as u64 }).sum();
-            offset += first_char(&text[data.cursor .. line.range.end], &data.line_break).map_or(1, |x| x.width());
+            let mut offset = str_width(&text.slice(line.view.end .. data.cursor));
+            offset += first_char(text.slice_head(data.cursor .. line.range.end), &data.line_break).map_or(1, |x| x.width());
             if offset != 0 {
                 Self::scroll_right(tree, window, offset);
             }
@@ -229,17 +360,15 @@ impl TextEdit {
 
     fn cursor_down(tree: &mut WindowTree, window: Window) -> bool {
         let data = window.data_mut::<TextEdit>(tree);
-        let text = actual_text(&data.text);
+        if data.wrap_width().is_some() { return Self::visual_line_down(tree, window); }
         if data.cursor_line.is_none() {
-            let line_start =
-                text[.. data.cursor].rfind(&data.line_break).map_or(0, |x| x + data.line_break.len());
+            let line_start = data.text.line_start(data.cursor);
             Self::recalc_view(tree, window, Some(line_start));
         }
         let data = window.data_mut::<TextEdit>(tree);
         let line = &data.lines[data.cursor_line.unwrap()];
         if !line.view.contains(&data.cursor) && (data.cursor != line.view.end || line.filled) {
-            let text = actual_text(&data.text);
-            data.column = text[line.range.start .. data.cursor].chars().map(|x| if x == '\0' { 0 } else { x.width().unwrap_or(0) as u64 }).sum();
+            data.column = str_width(&data.text.slice(line.range.start .. data.cursor));
             Self::recalc_view(tree, window, None);
         }
         let data = window.data_mut::<TextEdit>(tree);
@@ -251,7 +380,7 @@ impl TextEdit {
         };
         let data = window.data_mut::<TextEdit>(tree);
         if data.lines[data.cursor_line.unwrap() + 1].range.is_empty() { return scrolled; }
-        let text = actual_text(&data.text);
+        let text = &data.text;
         let next_line = &data.lines[data.cursor_line.unwrap() + 1];
         let mut new_cursor = next_line.range.start;
         let mut width = 0;
@@ -259,7 +388,7 @@ impl TextEdit {
             if width == data.cursor_column {
                 break;
             }
-            let Some(Char::Char(c)) = first_char(&text[new_cursor .. next_line.range.end], &data.line_break) else {
+            let Some(Char::Char(c)) = first_char(text.slice_head(new_cursor .. next_line.range.end), &data.line_break) else {
                 break;
             };
             let c_width = if c == '\0' { 0 } else { c.width().unwrap_or(0) as u64 };
@@ -277,17 +406,15 @@ impl TextEdit {
 
     fn cursor_up(tree: &mut WindowTree, window: Window) -> bool {
         let data = window.data_mut::<TextEdit>(tree);
-        let text = actual_text(&data.text);
+        if data.wrap_width().is_some() { return Self::visual_line_up(tree, window); }
         if data.cursor_line.is_none() {
-            let line_start =
-                text[.. data.cursor].rfind(&data.line_break).map_or(0, |x| x + data.line_break.len());
+            let line_start = data.text.line_start(data.cursor);
             Self::recalc_view(tree, window, Some(line_start));
         }
         let data = window.data_mut::<TextEdit>(tree);
         let line = &data.lines[data.cursor_line.unwrap()];
         if !line.view.contains(&data.cursor) && (data.cursor != line.view.end || line.filled) {
-            let text = actual_text(&data.text);
-            data.column = text[line.range.start .. data.cursor].chars().map(|x| if x == '\0' { 0 } else { x.width().unwrap_or(0) as u64 }).sum();
+            data.column = str_width(&data.text.slice(line.range.start .. data.cursor));
             Self::recalc_view(tree, window, None);
         }
         let data = window.data_mut::<TextEdit>(tree);
@@ -295,7 +422,7 @@ impl TextEdit {
             if !Self::scroll_up(tree, window) { return false; }
         }
         let data = window.data_mut::<TextEdit>(tree);
-        let text = actual_text(&data.text);
+        let text = &data.text;
         let prev_line = &data.lines[data.cursor_line.unwrap() - 1];
         let mut new_cursor = prev_line.range.start;
         let mut width = 0;
@@ -303,7 +430,7 @@ impl TextEdit {
             if width == data.cursor_column {
                 break;
             }
-            let Some(Char::Char(c)) = first_char(&text[new_cursor .. prev_line.range.end], &data.line_break) else {
+            let Some(Char::Char(c)) = first_char(text.slice_head(new_cursor .. prev_line.range.end), &data.line_break) else {
                 break;
             };
             let c_width = if c == '\0' { 0 } else { c.width().unwrap_or(0) as u64 };
@@ -325,67 +452,11 @@ impl TextEdit {
         let new_line_end = first_line.range.start;
         if new_line_end == 0 { return false; }
         data.lines.remove(data.lines.len() - 1);
-        let text = actual_text(&data.text);
-        let new_line_start = text[.. new_line_end - data.line_break.len()]
-            .rfind(&data.line_break)
-            .map_or(0, |x| x + data.line_break.len());
-        let mut line_view_start = new_line_start;
-        let line_padding;
-        let mut width = 0;
-        loop {
-            if width == data.column {
-                line_padding = 0;
-                break;
-            }
-            let Some(c) = first_char(&text[line_view_start .. new_line_end], &data.line_break) else {
-                line_padding = 0;
-                break;
-            };
-            let c_width = c.width();
-            if width + c_width > data.column {
-                line_padding = width + c_width - data.column;                    
-                line_view_start += c.len(&data.line_break);
-                break;
-            }
-            line_view_start += c.len(&data.line_break);
-            width += c_width;
-        }
-        let line_filled;
-        let mut line_view_end = line_view_start;
-        let mut width = 0;
-        loop {
-            if width == data.size.x as u16 as u64 {
-                line_filled = true;
-                break;
-            }
-            let Some(c) = first_char(&text[line_view_end .. new_line_end], &data.line_break) else {
-                line_filled = false;
-                break;
-            };
-            let c_width = c.width();
-            if width + c_width > data.size.x as u16 as u64 {
-                line_filled = true;
-                break;
-            }
-            line_view_end += c.len(&data.line_break);
-            width += c_width;
-        }
-        data.lines.insert(0, Line {
-            range: new_line_start .. new_line_end,
-            view: line_view_start .. line_view_end,
-            filled: line_filled,
-            padding: u16::try_from(line_padding).unwrap(),
-        });
-        data.cursor_line = data.lines.binary_search_by(|line| {
-            if text[line.range.clone()].is_empty() { return Ordering::Greater; }
-            if line.range.contains(&data.cursor) || !text[line.range.clone()].ends_with(&data.line_break) && data.cursor == line.range.end {
-                Ordering::Equal
-            } else if data.cursor >= line.range.start {
-                Ordering::Less
-            } else {
-                Ordering::Greater
-            }
-        }).ok();
+        let new_line_start = data.prev_visual_line_start(new_line_end);
+        let wrapped = data.visual_line_end(new_line_start).1;
+        let line = Line::new(&data.text, new_line_start .. new_line_end, wrapped, data.column, data.size.x as u16 as u64);
+        data.lines.insert(0, line);
+        data.cursor_line = cursor_line(&data.lines, &data.text, data.cursor);
         true
     }
 
@@ -394,229 +465,560 @@ impl TextEdit {
         let Some(last_line) = data.lines.last() else { return false; };
         let new_line_start = last_line.range.end;
         data.lines.remove(0);
-        let text = actual_text(&data.text);
-        let new_line_end = text[new_line_start ..]
-            .find(&data.line_break)
-            .map_or(text.len(), |x| new_line_start + x + data.line_break.len());
-        let mut line_view_start = new_line_start;
-        let line_padding;
-        let mut width = 0;
+        let (new_line_end, wrapped) = data.visual_line_end(new_line_start);
+        let line = Line::new(&data.text, new_line_start .. new_line_end, wrapped, data.column, data.size.x as u16 as u64);
+        data.lines.push(line);
+        data.cursor_line = cursor_line(&data.lines, &data.text, data.cursor);
+        true
+    }
+
+    fn scroll_left(tree: &mut WindowTree, window: Window, delta: u64) {
+        let data = window.data_mut::<TextEdit>(tree);
+        data.column -= delta;
+        data.update_lines_view();
+    }
+
+    fn scroll_right(tree: &mut WindowTree, window: Window, delta: u64) {
+        let data = window.data_mut::<TextEdit>(tree);
+        data.column += delta;
+        data.update_lines_view();
+    }
+
+    fn update_lines_view(&mut self) {
+        for line in &mut self.lines {
+            *line = Line::new(&self.text, line.range.clone(), line.wrapped, self.column, self.size.x as u16 as u64);
+        }
+    }
+
+    fn wrap_width(&self) -> Option<u64> {
+        if self.wrap == TextWrap::None || self.size.x <= 0 { return None; }
+        Some(self.size.x as u16 as u64)
+    }
+
+    /// Returns the end of the visual line starting at `start`, and whether the line is wrapped there.
+    ///
+    /// The line break and the text end occupy one cell, so that the cursor placed on them stays visible.
+    fn visual_line_end(&self, start: usize) -> (usize, bool) {
+        let line_end = self.text.next_line_start(start);
+        let Some(width) = self.wrap_width() else { return (line_end, false); };
+        let mut pos = start;
+        let mut x = 0;
+        let mut word_end = None;
         loop {
-            if width == data.column {
-                line_padding = 0;
-                break;
+            let c = first_char(self.text.slice_head(pos .. line_end), &self.line_break);
+            let c_width = c.map_or(1, |x| x.width());
+            if x + c_width > width {
+                let end = if self.wrap == TextWrap::Word { word_end.unwrap_or(pos) } else { pos };
+                let end = if end == start {
+                    // Too narrow even for one character.
+                    let Some(Char::Char(c)) = c else { return (line_end, false); };
+                    start + c.len_utf8()
+                } else if end == line_end {
+                    // Do not leave an empty visual line at the text end.
+                    end - self.char_before(end).unwrap().len(&self.line_break)
+                } else {
+                    end
+                };
+                return if end == start || end == line_end { (line_end, false) } else { (end, true) };
             }
-            let Some(c) = first_char(&text[line_view_start .. new_line_end], &data.line_break) else {
-                line_padding = 0;
-                break;
-            };
-            let c_width = c.width();
-            if width + c_width > data.column {
-                line_padding = width + c_width - data.column;                    
-                line_view_start += c.len(&data.line_break);
-                break;
+            match c {
+                None | Some(Char::LineBreak) => return (line_end, false),
+                Some(Char::Char(c)) => {
+                    pos += c.len_utf8();
+                    x += c_width;
+                    if c.is_whitespace() {
+                        word_end = Some(pos);
+                    }
+                },
             }
-            line_view_start += c.len(&data.line_break);
-            width += c_width;
         }
-        let line_filled;
-        let mut line_view_end = line_view_start;
-        let mut width = 0;
+    }
+
+    /// Returns the start of the visual line containing `pos`.
+    fn visual_line_start(&self, pos: usize) -> usize {
+        let mut start = self.text.line_start(pos);
         loop {
-            if width == data.size.x as u16 as u64 {
-                line_filled = true;
-                break;
-            }
-            let Some(c) = first_char(&text[line_view_end .. new_line_end], &data.line_break) else {
-                line_filled = false;
-                break;
-            };
-            let c_width = c.width();
-            if width + c_width > data.size.x as u16 as u64 {
-                line_filled = true;
-                break;
-            }
-            line_view_end += c.len(&data.line_break);
-            width += c_width;
+            let (end, wrapped) = self.visual_line_end(start);
+            if !wrapped || pos < end { return start; }
+            start = end;
         }
-        data.lines.push(Line {
-            range: new_line_start .. new_line_end,
-            view: line_view_start .. line_view_end,
-            filled: line_filled,
-            padding: u16::try_from(line_padding).unwrap(),
-        });
-        data.cursor_line = data.lines.binary_search_by(|line| {
-            if text[line.range.clone()].is_empty() { return Ordering::Greater; }
-            if line.range.contains(&data.cursor) || !text[line.range.clone()].ends_with(&data.line_break) && data.cursor == line.range.end {
-                Ordering::Equal
-            } else if data.cursor >= line.range.start {
-                Ordering::Less
-            } else {
-                Ordering::Greater
-            }
-        }).ok();
+    }
+
+    fn prev_visual_line_start(&self, start: usize) -> usize {
+        let c = self.char_before(start).unwrap();
+        self.visual_line_start(start - c.len(&self.line_break))
+    }
+
+    /// Returns the position in the visual line starting at `start`, which is closest to the given column.
+    fn visual_line_pos(&self, start: usize, column: u64) -> usize {
+        let (end, wrapped) = self.visual_line_end(start);
+        let mut pos = start;
+        let mut x = 0;
+        while let Some(Char::Char(c)) = first_char(self.text.slice_head(pos .. end), &self.line_break) {
+            let c_width = Char::Char(c).width();
+            if x + c_width > column || wrapped && pos + c.len_utf8() == end { break; }
+            x += c_width;
+            pos += c.len_utf8();
+        }
+        pos
+    }
+
+    fn visual_line_up(tree: &mut WindowTree, window: Window) -> bool {
+        let data = window.data::<TextEdit>(tree);
+        let start = data.visual_line_start(data.cursor);
+        if start == 0 { return false; }
+        let cursor_column = data.cursor_column;
+        let pos = data.visual_line_pos(data.prev_visual_line_start(start), cursor_column);
+        Self::move_cursor(tree, window, pos);
+        window.data_mut::<TextEdit>(tree).cursor_column = cursor_column;
         true
     }
 
-    fn scroll_left(tree: &mut WindowTree, window: Window, delta: u64) {
+    fn visual_line_down(tree: &mut WindowTree, window: Window) -> bool {
+        let data = window.data::<TextEdit>(tree);
+        let (end, wrapped) = data.visual_line_end(data.visual_line_start(data.cursor));
+        if !wrapped && end == data.text.len() { return false; }
+        let cursor_column = data.cursor_column;
+        let pos = data.visual_line_pos(end, cursor_column);
+        Self::move_cursor(tree, window, pos);
+        window.data_mut::<TextEdit>(tree).cursor_column = cursor_column;
+        true
+    }
+
+    /// Returns the selected byte range of the text, if the selection is not empty.
+    pub fn selection(tree: &WindowTree, window: Window) -> Option<Range<usize>> {
+        window.data::<TextEdit>(tree).selection_range()
+    }
+
+    pub fn select_all(tree: &mut WindowTree, window: Window) {
         let data = window.data_mut::<TextEdit>(tree);
-        data.column -= delta;
-        let text = actual_text(&data.text);
-        for line in &mut data.lines {
-            let mut delta = delta;
-            if line.padding != 0 {
-                let c = last_char(&text[line.range.start .. line.view.start], &data.line_break).unwrap();
-                let c_width = c.width();
-                let padding_delta = u16::try_from(min(delta, c_width - u64::from(line.padding))).unwrap();
-                line.padding += padding_delta;
-                delta -= u64::from(padding_delta);
-                if u64::from(line.padding) == c_width {
-                    line.padding = 0;
-                    line.view.start -= c.len(&data.line_break);
-                }
-            }
-            let mut new_view_start = line.view.start;
-            if delta != 0 {
-                loop {
-                    let Some(c) = last_char(&text[line.range.start .. new_view_start], &data.line_break) else {
-                        line.padding = 0;
-                        break;
-                    };
-                    let c_width = c.width();
-                    if c_width > delta {
-                        line.padding = u16::try_from(delta).unwrap();
-                        break;
-                    }
-                    delta -= c_width;
-                    new_view_start -= c.len(&data.line_break);
-                }
-            }
-            let line_filled;
-            let mut line_view_end = new_view_start;
-            let mut width = 0;
-            loop {
-                if width == data.size.x as u16 as u64 {
-                    line_filled = true;
-                    break;
-                }
-                let Some(c) = first_char(&text[line_view_end .. line.range.end], &data.line_break) else {
-                    line_filled = false;
-                    break;
-                };
-                let c_width = c.width();
-                if width + c_width > data.size.x as u16 as u64 {
-                    line_filled = true;
-                    break;
-                }
-                line_view_end += c.len(&data.line_break);
-                width += c_width;
-            }
-            line.view = new_view_start .. line_view_end;
-            line.filled = line_filled;
+        let end = data.text.len();
+        data.anchor = Some(0);
+        Self::move_cursor(tree, window, end);
+    }
+
+    fn selection_range(&self) -> Option<Range<usize>> {
+        let anchor = self.anchor?;
+        match anchor.cmp(&self.cursor) {
+            Ordering::Less => Some(anchor .. self.cursor),
+            Ordering::Equal => None,
+            Ordering::Greater => Some(self.cursor .. anchor),
         }
     }
 
-    fn scroll_right(tree: &mut WindowTree, window: Window, delta: u64) {
+    fn select(tree: &mut WindowTree, window: Window, extend: bool) {
         let data = window.data_mut::<TextEdit>(tree);
-        data.column += delta;
-        let text = actual_text(&data.text);
-        for line in &mut data.lines {
-            let mut delta = delta;
-            let padding_delta = u16::try_from(min(delta, u64::from(line.padding))).unwrap();
-            line.padding -= padding_delta;
-            delta -= u64::from(padding_delta);
-            let mut new_view_start = line.view.start;
-            if delta != 0 {
-                loop {
-                    let Some(c) = first_char(&text[new_view_start .. line.range.end], &data.line_break) else {
-                        line.padding = 0;
-                        break;
-                    };
-                    let c_width = c.width();
-                    if c_width >= delta {
-                        line.padding = u16::try_from(c_width - delta).unwrap();
-                        new_view_start += c.len(&data.line_break);
-                        break;
-                    }
-                    delta -= c_width;
-                    new_view_start += c.len(&data.line_break);
-                }
+        let anchor = if extend { Some(data.anchor.unwrap_or(data.cursor)) } else { None };
+        data.history.seal();
+        if data.anchor != anchor {
+            data.anchor = anchor;
+            window.invalidate_render(tree);
+        }
+    }
+
+    /// Moves the cursor to the given text position and scrolls the view to make it visible.
+    fn move_cursor(tree: &mut WindowTree, window: Window, pos: usize) {
+        let data = window.data_mut::<TextEdit>(tree);
+        let pos = valid_cursor(&data.text, pos);
+        let cursor_line_start = data.visual_line_start(pos);
+        let cursor_column = str_width(&data.text.slice(cursor_line_start .. pos));
+        let column = data.column;
+        if data.wrap == TextWrap::None {
+            let next_width = data.char_after(pos).map_or(1, |x| max(1, x.width()));
+            let width = data.size.x as u16 as u64;
+            if cursor_column < data.column {
+                data.column = cursor_column;
+            } else if cursor_column + next_width > data.column + width {
+                data.column = (cursor_column + next_width).saturating_sub(width);
             }
-            let line_filled;
-            let mut line_view_end = new_view_start;
-            let mut width = 0;
-            loop {
-                if width == data.size.x as u16 as u64 {
-                    line_filled = true;
-                    break;
-                }
-                let Some(c) = first_char(&text[line_view_end .. line.range.end], &data.line_break) else {
-                    line_filled = false;
-                    break;
-                };
-                let c_width = c.width();
-                if width + c_width > data.size.x as u16 as u64 {
-                    line_filled = true;
-                    break;
+        }
+        let height = data.size.y as u16 as usize;
+        let old_first = data.lines.first().map_or(0, |x| x.range.start);
+        let first = if cursor_line_start <= old_first {
+            cursor_line_start
+        } else {
+            let mut line = old_first;
+            let mut n = 0;
+            while line < cursor_line_start && n < height {
+                line = data.visual_line_end(line).0;
+                n += 1;
+            }
+            if line == cursor_line_start && n < height {
+                old_first
+            } else {
+                let mut line = cursor_line_start;
+                for _ in 1 .. height {
+                    if line == 0 { break; }
+                    line = data.prev_visual_line_start(line);
                 }
-                line_view_end += c.len(&data.line_break);
-                width += c_width;
+                line
             }
-            line.view = new_view_start .. line_view_end;
-            line.filled = line_filled;
+        };
+        data.cursor = pos;
+        data.cursor_column = cursor_column;
+        if first != old_first || data.column != column {
+            Self::recalc_view(tree, window, Some(first));
+        } else {
+            data.cursor_line = cursor_line(&data.lines, &data.text, pos);
+            window.invalidate_render(tree);
         }
     }
 
-    fn insert(tree: &mut WindowTree, window: Window, s: &str) {
-        let data = window.data_mut::<TextEdit>(tree);
-        debug_assert!(!s.contains(&data.line_break));
-        let text = actual_text(&data.text);
-        if data.cursor_line.is_none() {
-            let line_start =
-                text[.. data.cursor].rfind(&data.line_break).map_or(0, |x| x + data.line_break.len());
-            Self::recalc_view(tree, window, Some(line_start));
+    fn char_before(&self, pos: usize) -> Option<Char> {
+        let line_start = self.text.line_start(pos);
+        if pos != line_start {
+            last_char(self.text.slice_tail(line_start .. pos), &self.line_break)
+        } else if pos != 0 {
+            Some(Char::LineBreak)
+        } else {
+            None
         }
+    }
+
+    fn char_after(&self, pos: usize) -> Option<Char> {
+        first_char(self.text.slice_head(pos .. self.text.next_line_start(pos)), &self.line_break)
+    }
+
+    fn replace_range(tree: &mut WindowTree, window: Window, range: Range<usize>, s: &str, typing: bool) {
         let data = window.data_mut::<TextEdit>(tree);
-        let line = &mut data.lines[data.cursor_line.unwrap()];
-        if data.cursor < line.view.start {
-            let text = actual_text(&data.text);
-            data.column = text[line.range.start .. data.cursor].chars().map(|x| if x == '\0' { 0 } else { x.width().unwrap_or(0) as u64 }).sum();
-            Self::recalc_view(tree, window, None);
-        }
+        let deleted = data.text.substring(range.clone());
+        let edit = Edit::new(
+            range.start, deleted, s.to_string(), (data.cursor, data.anchor), (range.start + s.len(), None)
+        );
+        data.history.record(edit, typing);
+        Self::replace_text(tree, window, range, s);
+    }
+
+    fn replace_text(tree: &mut WindowTree, window: Window, range: Range<usize>, s: &str) {
         let data = window.data_mut::<TextEdit>(tree);
-        data.text.insert_str(data.cursor, s);
-        let line = &mut data.lines[data.cursor_line.unwrap()];
-        line.range.end += s.len();
-        if data.cursor < line.view.end {
-            let text = actual_text(&data.text);
-            let line_filled;
-            let mut line_view_end = line.view.start;
-            let mut width = 0;
-            loop {
-                if width == data.size.x as u16 as u64 {
-                    line_filled = true;
-                    break;
-                }
-                let Some(c) = first_char(&text[line_view_end .. line.range.end], &data.line_break) else {
-                    line_filled = false;
-                    break;
-                };
-                let c_width = c.width();
-                if width + c_width > data.size.x as u16 as u64 {
-                    line_filled = true;
-                    break;
+        let first = data.lines.first().map_or(0, |x| x.range.start);
+        if !data.replace_in_line(range.clone(), s) {
+            let first = if range.start < first { data.text.line_start(range.start) } else { first };
+            Self::recalc_view(tree, window, Some(first));
+        }
+        Self::move_cursor(tree, window, range.start + s.len());
+    }
+
+    /// Replaces the text range, and, if the range lies inside a visible line and the replacement
+    /// neither adds nor removes line breaks, updates just this line view instead of the whole view.
+    ///
+    /// With wrapping on, an edit can rewrap the neighbouring visual lines, so the view is never updated.
+    fn replace_in_line(&mut self, range: Range<usize>, s: &str) -> bool {
+        let line = if self.wrap_width().is_some() { None } else { self.lines.iter().position(|line| {
+            let line_text = self.text.slice_tail(line.range.clone());
+            let content_end = if line_text.ends_with(&self.line_break) {
+                line.range.end - self.line_break.len()
+            } else {
+                line.range.end
+            };
+            !line_text.is_empty() && line.range.start <= range.start && range.end <= content_end
+        }) };
+        self.text.replace(range.clone(), s);
+        self.anchor = None;
+        let Some(line) = line else { return false; };
+        let shift = |x: usize| (x + s.len()).wrapping_sub(range.len());
+        let start = self.lines[line].range.start;
+        let end = shift(self.lines[line].range.end);
+        if self.text.next_line_start(start) != end { return false; }
+        self.lines[line] = Line::new(&self.text, start .. end, false, self.column, self.size.x as u16 as u64);
+        for line in &mut self.lines[line + 1 ..] {
+            line.range = shift(line.range.start) .. shift(line.range.end);
+            line.view = shift(line.view.start) .. shift(line.view.end);
+        }
+        true
+    }
+
+    fn insert(tree: &mut WindowTree, window: Window, s: &str, typing: bool) {
+        let data = window.data::<TextEdit>(tree);
+        let range = data.selection_range().unwrap_or(data.cursor .. data.cursor);
+        Self::replace_range(tree, window, range, s, typing);
+    }
+
+    pub fn undo(tree: &mut WindowTree, window: Window) -> bool {
+        let Some(edit) = window.data_mut::<TextEdit>(tree).history.undo() else { return false; };
+        Self::replace_text(tree, window, edit.pos .. edit.pos + edit.inserted.len(), &edit.deleted);
+        Self::move_cursor(tree, window, edit.cursor);
+        window.data_mut::<TextEdit>(tree).anchor = edit.anchor;
+        true
+    }
+
+    pub fn redo(tree: &mut WindowTree, window: Window) -> bool {
+        let Some(edit) = window.data_mut::<TextEdit>(tree).history.redo() else { return false; };
+        Self::replace_text(tree, window, edit.pos .. edit.pos + edit.deleted.len(), &edit.inserted);
+        Self::move_cursor(tree, window, edit.cursor_after);
+        window.data_mut::<TextEdit>(tree).anchor = edit.anchor_after;
+        true
+    }
+
+    pub fn can_undo(tree: &WindowTree, window: Window) -> bool {
+        window.data::<TextEdit>(tree).history.can_undo()
+    }
+
+    pub fn can_redo(tree: &WindowTree, window: Window) -> bool {
+        window.data::<TextEdit>(tree).history.can_redo()
+    }
+
+    pub fn clear_undo_history(tree: &mut WindowTree, window: Window) {
+        window.data_mut::<TextEdit>(tree).history.clear();
+    }
+
+    /// Marks the current text as saved, so that [`is_modified`](TextEdit::is_modified) returns `false`
+    /// until the text is edited again (or the edits are undone past this point).
+    pub fn mark_saved(tree: &mut WindowTree, window: Window) {
+        window.data_mut::<TextEdit>(tree).history.mark_saved();
+    }
+
+    pub fn is_modified(tree: &WindowTree, window: Window) -> bool {
+        window.data::<TextEdit>(tree).history.is_modified()
+    }
+
+    /// Returns `false` if the [`search`](TextEdit::search) pattern is not a valid regular expression
+    /// (or is a regular expression, and the `regex` feature is disabled).
+    pub fn is_search_valid(tree: &WindowTree, window: Window) -> bool {
+        let data = window.data::<TextEdit>(tree);
+        data.search.is_empty() || data.searcher.is_some()
+    }
+
+    /// Selects the first match starting at or after the selection start (or the cursor),
+    /// wrapping around the text end. Returns `false` if there are no matches.
+    pub fn find(tree: &mut WindowTree, window: Window) -> bool {
+        let data = window.data::<TextEdit>(tree);
+        let pos = data.selection_range().map_or(data.cursor, |x| x.start);
+        let found = data.find_forward(pos);
+        Self::select_found(tree, window, found)
+    }
+
+    /// Selects the first match starting at or after the selection end (or the cursor),
+    /// wrapping around the text end. Returns `false` if there are no matches.
+    pub fn find_next(tree: &mut WindowTree, window: Window) -> bool {
+        let data = window.data::<TextEdit>(tree);
+        let pos = data.selection_range().map_or(data.cursor, |x| x.end);
+        let found = data.find_forward(pos);
+        Self::select_found(tree, window, found)
+    }
+
+    /// Selects the last match ending at or before the selection start (or the cursor),
+    /// wrapping around the text start. Returns `false` if there are no matches.
+    pub fn find_previous(tree: &mut WindowTree, window: Window) -> bool {
+        let data = window.data::<TextEdit>(tree);
+        let pos = data.selection_range().map_or(data.cursor, |x| x.start);
+        let found = data.find_backward(pos);
+        Self::select_found(tree, window, found)
+    }
+
+    /// Replaces the selection, if it is a match, and selects the next match.
+    /// Returns `false` if nothing was replaced or selected.
+    ///
+    /// With the [`search_regex`](TextEdit::search_regex) option, `$n` and `${name}` in `replacement`
+    /// are substituted with the match capture groups.
+    pub fn replace(tree: &mut WindowTree, window: Window, replacement: &str) -> bool {
+        let data = window.data::<TextEdit>(tree);
+        let selection = data.selection_range().filter(|x| data.find_forward(x.start).as_ref() == Some(x));
+        let replaced = if let Some(selection) = selection {
+            let s = data.expand_replacement(selection.clone(), replacement);
+            Self::replace_range(tree, window, selection, &s, false);
+            true
+        } else {
+            false
+        };
+        let found = Self::find_next(tree, window);
+        replaced || found
+    }
+
+    /// Replaces all matches as one undoable edit, and returns the number of replaced matches.
+    ///
+    /// See [`replace`](TextEdit::replace) for the `replacement` syntax.
+    pub fn replace_all(tree: &mut WindowTree, window: Window, replacement: &str) -> usize {
+        let data = window.data::<TextEdit>(tree);
+        let Some(searcher) = data.searcher.as_ref() else { return 0; };
+        let mut replaced: Option<Range<usize>> = None;
+        let mut s = String::new();
+        let mut count = 0;
+        let mut line_start = 0;
+        loop {
+            let line_end = data.text.line_end(line_start);
+            let line = data.text.slice(line_start .. line_end);
+            for range in searcher.matches(&line) {
+                let start = line_start + range.start;
+                if let Some(replaced) = replaced.as_ref() {
+                    s.push_str(&data.text.substring(replaced.end .. start));
                 }
-                line_view_end += c.len(&data.line_break);
-                width += c_width;
+                s.push_str(&normalize_line_breaks(&searcher.replacement(&line, range.clone(), replacement), &data.line_break));
+                replaced = Some(replaced.map_or(start, |x| x.start) .. line_start + range.end);
+                count += 1;
             }
-            line.view.end = line_view_end;
-            line.filled = line_filled;
+            if line_end == data.text.len() { break; }
+            line_start = line_end + data.line_break.len();
+        }
+        let Some(replaced) = replaced else { return 0; };
+        Self::select(tree, window, false);
+        Self::replace_range(tree, window, replaced, &s, false);
+        count
+    }
+
+    fn select_found(tree: &mut WindowTree, window: Window, found: Option<Range<usize>>) -> bool {
+        let Some(found) = found else { return false; };
+        Self::select(tree, window, false);
+        Self::move_cursor(tree, window, found.start);
+        window.data_mut::<TextEdit>(tree).anchor = Some(found.start);
+        Self::move_cursor(tree, window, found.end);
+        window.bring_into_view(tree);
+        true
+    }
+
+    /// Returns the first match starting at or after `pos`, wrapping around the text end.
+    fn find_forward(&self, pos: usize) -> Option<Range<usize>> {
+        let searcher = self.searcher.as_ref()?;
+        let find = |line_start: usize, from: usize| {
+            let line = self.text.slice(line_start .. self.text.line_end(line_start));
+            searcher.find(&line, from).map(|x| line_start + x.start .. line_start + x.end)
+        };
+        let first_line = self.text.line_start(pos);
+        if let Some(found) = find(first_line, pos - first_line) { return Some(found); }
+        let mut line_start = first_line;
+        loop {
+            let line_end = self.text.line_end(line_start);
+            line_start = if line_end == self.text.len() { 0 } else { line_end + self.line_break.len() };
+            let found = find(line_start, 0);
+            if found.is_some() || line_start == first_line { return found; }
+        }
+    }
+
+    /// Returns the last match ending at or before `pos`, wrapping around the text start.
+    fn find_backward(&self, pos: usize) -> Option<Range<usize>> {
+        let searcher = self.searcher.as_ref()?;
+        let find_last = |line_start: usize, to: usize| {
+            let line = self.text.slice(line_start .. self.text.line_end(line_start));
+            searcher.find_last(&line, to).map(|x| line_start + x.start .. line_start + x.end)
+        };
+        let last_line = self.text.line_start(pos);
+        if let Some(found) = find_last(last_line, pos - last_line) { return Some(found); }
+        let mut line_start = last_line;
+        loop {
+            line_start = if line_start == 0 {
+                self.text.line_start(self.text.len())
+            } else {
+                self.text.line_start(line_start - self.line_break.len())
+            };
+            let found = find_last(line_start, usize::MAX);
+            if found.is_some() || line_start == last_line { return found; }
+        }
+    }
+
+    fn expand_replacement(&self, range: Range<usize>, replacement: &str) -> String {
+        let line_start = self.text.line_start(range.start);
+        let line = self.text.slice(line_start .. self.text.line_end(line_start));
+        let s = self.searcher.as_ref().unwrap().replacement(&line, range.start - line_start .. range.end - line_start, replacement);
+        normalize_line_breaks(&s, &self.line_break)
+    }
+
+    fn delete_back(tree: &mut WindowTree, window: Window) -> bool {
+        let data = window.data::<TextEdit>(tree);
+        if let Some(selection) = data.selection_range() {
+            Self::replace_range(tree, window, selection, "", false);
+            return true;
         }
-        for line in data.lines[data.cursor_line.unwrap() ..].iter_mut().skip(1) {
-            line.range.end += s.len();
-            line.range.start += s.len();
-            line.view.end += s.len();
-            line.view.start += s.len();
+        let Some(c) = data.char_before(data.cursor) else { return false; };
+        let range = data.cursor - c.len(&data.line_break) .. data.cursor;
+        Self::replace_range(tree, window, range, "", false);
+        true
+    }
+
+    fn delete_forward(tree: &mut WindowTree, window: Window) -> bool {
+        let data = window.data::<TextEdit>(tree);
+        if let Some(selection) = data.selection_range() {
+            Self::replace_range(tree, window, selection, "", false);
+            return true;
+        }
+        let Some(c) = data.char_after(data.cursor) else { return false; };
+        let range = data.cursor .. data.cursor + c.len(&data.line_break);
+        Self::replace_range(tree, window, range, "", false);
+        true
+    }
+
+    fn copy(tree: &mut WindowTree, window: Window) -> bool {
+        let data = window.data::<TextEdit>(tree);
+        let Some(selection) = data.selection_range() else { return false; };
+        let text = data.text.substring(selection);
+        tree.clipboard().set_text(&text);
+        true
+    }
+
+    fn paste(tree: &mut WindowTree, window: Window, text: &str) -> bool {
+        let line_break = window.data::<TextEdit>(tree).line_break.clone();
+        let text = normalize_line_breaks(text, &line_break);
+        Self::insert(tree, window, &text, false);
+        true
+    }
+
+    fn home(tree: &mut WindowTree, window: Window) -> bool {
+        let data = window.data::<TextEdit>(tree);
+        let pos = data.visual_line_start(data.cursor);
+        Self::move_cursor(tree, window, pos);
+        true
+    }
+
+    fn end(tree: &mut WindowTree, window: Window) -> bool {
+        let data = window.data::<TextEdit>(tree);
+        let (end, wrapped) = data.visual_line_end(data.visual_line_start(data.cursor));
+        let pos = if wrapped {
+            end - data.char_before(end).unwrap().len(&data.line_break)
+        } else {
+            data.text.line_end(data.cursor)
+        };
+        Self::move_cursor(tree, window, pos);
+        true
+    }
+
+    fn page_up(tree: &mut WindowTree, window: Window) -> bool {
+        let height = window.data::<TextEdit>(tree).size.y;
+        let mut moved = false;
+        for _ in 1 .. max(2, height) {
+            if !Self::cursor_up(tree, window) { break; }
+            moved = true;
+        }
+        moved
+    }
+
+    fn page_down(tree: &mut WindowTree, window: Window) -> bool {
+        let height = window.data::<TextEdit>(tree).size.y;
+        let mut moved = false;
+        for _ in 1 .. max(2, height) {
+            if !Self::cursor_down(tree, window) { break; }
+            moved = true;
+        }
+        moved
+    }
+
+    fn pos_at(tree: &WindowTree, window: Window, point: Point) -> usize {
+        let data = window.data::<TextEdit>(tree);
+        let text = &data.text;
+        let y = point.y.clamp(0, max(0, (data.lines.len() as i16).wrapping_sub(1)));
+        let Some(line) = data.lines.get(y as u16 as usize) else { return data.cursor; };
+        if line.range.is_empty() { return text.len(); }
+        let x = max(0, point.x) as u16 as u64;
+        let mut pos = line.view.start;
+        let mut width = u64::from(line.padding);
+        while let Some(Char::Char(c)) = first_char(text.slice_head(pos .. line.range.end), &data.line_break) {
+            let c_width = Char::Char(c).width();
+            if width + c_width > x || line.wrapped && pos + c.len_utf8() == line.range.end { break; }
+            width += c_width;
+            pos += c.len_utf8();
+        }
+        pos
+    }
+
+    fn key(tree: &mut WindowTree, window: Window, key: Key) -> bool {
+        match key {
+            Key::Right => Self::cursor_right(tree, window),
+            Key::Left => Self::cursor_left(tree, window),
+            Key::Down => Self::cursor_down(tree, window),
+            Key::Up => Self::cursor_up(tree, window),
+            Key::Home => Self::home(tree, window),
+            Key::End => Self::end(tree, window),
+            Key::PageUp => Self::page_up(tree, window),
+            Key::PageDown => Self::page_down(tree, window),
+            _ => false
         }
     }
 }
@@ -629,12 +1031,21 @@ impl_supports_interfaces!(TextEditWidget);
 impl Widget for TextEditWidget {
     fn new(&self) -> Box<dyn WidgetData> {
         Box::new(TextEdit {
-            text: String::new(),
+            text: TextBuffer::new("", "\n"),
             line_break: "\n".to_string(),
+            undo_depth: DEFAULT_UNDO_DEPTH,
+            wrap: TextWrap::None,
+            search: String::new(),
+            search_case_sensitive: false,
+            search_whole_word: false,
+            search_regex: false,
+            searcher: None,
+            history: UndoHistory::new(DEFAULT_UNDO_DEPTH),
             size: Vector::null(),
             lines: Vec::new(),
             column: 0,
             cursor: 0,
+            anchor: None,
             cursor_column: 0,
             cursor_line: None,
         })
@@ -648,6 +1059,8 @@ impl Widget for TextEditWidget {
         clone_window: Box<dyn Fn(&WindowTree, Window) -> Window>,
     ) {
         TextEdit::clone(tree, source, dest, clone_window);
+        let text = TextEdit::text(tree, source);
+        TextEdit::set_text(tree, dest, text);
     }
 
     fn render(
@@ -658,22 +1071,52 @@ impl Widget for TextEditWidget {
         _app: &mut dyn App,
     ) {
         let focused = window.is_focused(tree);
+        let selection_color = window.color(tree, 0);
+        let match_color = window.color(tree, 1);
         let data = window.data::<TextEdit>(tree);
+        let selection = data.selection_range();
         let mut y = 0;
         for line in &data.lines {
             if line.range.end == line.range.start {
                 rp.text(Point { x: line.padding as i16, y }, (Fg::DarkGray, Bg::None), "~");
             } else {
-                let view = if data.text[line.view.clone()].ends_with(&data.line_break) {
+                let view = if data.text.slice_tail(line.view.clone()).ends_with(&data.line_break) {
                     line.view.start .. line.view.end - data.line_break.len()
                 } else {
                     line.view.clone()
                 };
-                rp.text(Point { x: line.padding as i16, y }, (Fg::LightGray, Bg::None), &data.text[view]);
-                if line.view.end == line.range.end && !data.text[line.range.clone()].ends_with(&data.line_break) {
+                rp.text(Point { x: line.padding as i16, y }, (Fg::LightGray, Bg::None), &data.text.slice(view.clone()));
+                if let Some(searcher) = data.searcher.as_ref() {
+                    let line_start = data.text.line_start(line.range.start);
+                    let line_text = data.text.slice(line_start .. data.text.line_end(line_start));
+                    for range in searcher.matches(&line_text).take_while(|x| line_start + x.start < view.end) {
+                        let start = max(line_start + range.start, view.start);
+                        let end = min(line_start + range.end, view.end);
+                        if start < end {
+                            let x = (line.padding as i16).wrapping_add(text_width(&data.text.slice(view.start .. start)));
+                            rp.text(Point { x, y }, match_color, &data.text.slice(start .. end));
+                        }
+                    }
+                }
+                if let Some(selection) = selection.as_ref() {
+                    let start = max(selection.start, view.start);
+                    let end = min(selection.end, view.end);
+                    if start < end {
+                        let x = (line.padding as i16).wrapping_add(text_width(&data.text.slice(view.start .. start)));
+                        rp.text(Point { x, y }, selection_color, &data.text.slice(start .. end));
+                    }
+                    if view.end != line.view.end && selection.contains(&view.end) {
+                        let x = (line.padding as i16).wrapping_add(text_width(&data.text.slice(view.clone())));
+                        rp.text(Point { x, y }, selection_color, " ");
+                    }
+                }
+                if
+                    !line.wrapped && line.view.end == line.range.end &&
+                    !data.text.slice_tail(line.range.clone()).ends_with(&data.line_break)
+                {
                     rp.text(
                         Point {
-                            x: (line.padding as i16).wrapping_add(text_width(&data.text[line.view.clone()])),
+                            x: (line.padding as i16).wrapping_add(text_width(&data.text.slice(line.view.clone()))),
                             y
                         }, (Fg::DarkGray, Bg::None), "%"
                     );
@@ -682,13 +1125,14 @@ impl Widget for TextEditWidget {
             if 
                 focused && (
                     line.view.contains(&data.cursor) ||
-                    line.view.end == line.range.end && !data.text[line.range.clone()].ends_with(&data.line_break) && data.cursor == line.range.end
+                    !line.wrapped && line.view.end == line.range.end &&
+                    !data.text.slice_tail(line.range.clone()).ends_with(&data.line_break) && data.cursor == line.range.end
                 )
             {
                 rp.cursor(Point {
                     x:
                         (line.padding as i16).wrapping_add(
-                        text_width(&data.text[line.view.start .. data.cursor])),
+                        text_width(&data.text.slice(line.view.start .. data.cursor))),
                     y
                 });
             }
@@ -728,17 +1172,61 @@ impl Widget for TextEditWidget {
         _app: &mut dyn App,
     ) -> bool {
         match event {
-            Event::Key(Key::Right) => TextEdit::cursor_right(tree, window),
-            Event::Key(Key::Left) => TextEdit::cursor_left(tree, window),
-            Event::Key(Key::Down) => TextEdit::cursor_down(tree, window),
-            Event::Key(Key::Up) => TextEdit::cursor_up(tree, window),
-            Event::Key(Key::Char(c)) => {
+            Event::Cmd(CMD_GOT_PRIMARY_FOCUS) | Event::Cmd(CMD_LOST_PRIMARY_FOCUS) => {
+                window.invalidate_render(tree);
+                false
+            },
+            Event::Key(
+                key @ (Key::Right | Key::Left | Key::Down | Key::Up | Key::Home | Key::End | Key::PageUp | Key::PageDown),
+                mods
+            ) if mods == Mods::NONE || mods == Mods::SHIFT => {
+                TextEdit::select(tree, window, mods == Mods::SHIFT);
+                TextEdit::key(tree, window, key)
+            },
+            Event::Key(Key::Backspace, Mods::NONE) => TextEdit::delete_back(tree, window),
+            Event::Key(Key::Delete, Mods::NONE) => TextEdit::delete_forward(tree, window),
+            Event::Key(Key::Enter, Mods::NONE) => {
+                let line_break = window.data::<TextEdit>(tree).line_break.clone();
+                TextEdit::insert(tree, window, &line_break, true);
+                true
+            },
+            Event::Key(Key::Ctrl(Ctrl::A), Mods::NONE) => {
+                TextEdit::select_all(tree, window);
+                true
+            },
+            Event::Key(Key::Ctrl(Ctrl::C), Mods::NONE) => TextEdit::copy(tree, window),
+            Event::Key(Key::Ctrl(Ctrl::X), Mods::NONE) => {
+                if !TextEdit::copy(tree, window) { return false; }
+                TextEdit::delete_back(tree, window)
+            },
+            Event::Key(Key::Ctrl(Ctrl::V), Mods::NONE) => {
+                let text = tree.clipboard().text().to_string();
+                TextEdit::paste(tree, window, &text)
+            },
+            Event::Paste => {
+                let text = tree.pasted_text().to_string();
+                TextEdit::paste(tree, window, &text)
+            },
+            Event::Key(Key::Ctrl(Ctrl::Z), Mods::NONE) => TextEdit::undo(tree, window),
+            Event::Key(Key::Ctrl(Ctrl::Y), Mods::NONE) => TextEdit::redo(tree, window),
+            Event::Key(Key::Char(c), Mods::NONE) => {
                 let mut b = [0; 4];
                 let s = c.encode_utf8(&mut b);
-                TextEdit::insert(tree, window, s);
-                TextEdit::cursor_right(tree, window);
+                TextEdit::insert(tree, window, s, true);
                 true
-            }
+            },
+            Event::LmbDown(point, mods) => {
+                let pos = TextEdit::pos_at(tree, window, window.inner_point(point, tree));
+                TextEdit::select(tree, window, mods == Mods::SHIFT);
+                TextEdit::move_cursor(tree, window, pos);
+                true
+            },
+            Event::Drag(point, MouseButton::Left, _) => {
+                let pos = TextEdit::pos_at(tree, window, window.inner_point(point, tree));
+                TextEdit::select(tree, window, true);
+                TextEdit::move_cursor(tree, window, pos);
+                true
+            },
             _ => false
         }
     }
@@ -0,0 +1,194 @@
+use alloc::string::String;
+use core::iter::from_fn;
+use core::ops::Range;
+
+enum Pattern {
+    Plain(String),
+    #[cfg(feature="regex")]
+    Regex(regex::Regex),
+}
+
+/// Compiled [`TextEdit`](crate::TextEdit) search pattern.
+///
+/// Lines are searched separately, so a match never contains a line break. Empty matches are skipped.
+pub(crate) struct Searcher {
+    pattern: Pattern,
+    case_sensitive: bool,
+    whole_word: bool,
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn next_char_boundary(text: &str, pos: usize) -> usize {
+    pos + text[pos ..].chars().next().map_or(1, |x| x.len_utf8())
+}
+
+impl Searcher {
+    /// Returns `None` if the pattern is empty, or is not a valid regular expression,
+    /// or is a regular expression and the `regex` feature is disabled.
+    pub fn new(pattern: &str, case_sensitive: bool, whole_word: bool, regex: bool) -> Option<Self> {
+        if pattern.is_empty() { return None; }
+        let pattern = if regex {
+            Self::regex(pattern, case_sensitive)?
+        } else {
+            Pattern::Plain(pattern.into())
+        };
+        Some(Searcher { pattern, case_sensitive, whole_word })
+    }
+
+    #[cfg(feature="regex")]
+    fn regex(pattern: &str, case_sensitive: bool) -> Option<Pattern> {
+        let regex = regex::RegexBuilder::new(pattern).case_insensitive(!case_sensitive).build().ok()?;
+        Some(Pattern::Regex(regex))
+    }
+
+    #[cfg(not(feature="regex"))]
+    fn regex(_pattern: &str, _case_sensitive: bool) -> Option<Pattern> {
+        None
+    }
+
+    fn plain_match_end(&self, pattern: &str, text: &str, start: usize) -> Option<usize> {
+        let mut end = start;
+        for p in pattern.chars() {
+            let c = text[end ..].chars().next()?;
+            if !c.to_lowercase().eq(p.to_lowercase()) { return None; }
+            end += c.len_utf8();
+        }
+        Some(end)
+    }
+
+    fn find_candidate(&self, line: &str, from: usize) -> Option<Range<usize>> {
+        match &self.pattern {
+            Pattern::Plain(pattern) if self.case_sensitive => {
+                line[from ..].find(&pattern[..]).map(|x| from + x .. from + x + pattern.len())
+            },
+            Pattern::Plain(pattern) => line[from ..].char_indices().find_map(|(start, _)| {
+                let start = from + start;
+                self.plain_match_end(pattern, line, start).map(|end| start .. end)
+            }),
+            #[cfg(feature="regex")]
+            Pattern::Regex(regex) => regex.find_at(line, from).map(|x| x.range()),
+        }
+    }
+
+    fn is_whole_word(line: &str, range: &Range<usize>) -> bool {
+        !line[.. range.start].chars().next_back().is_some_and(is_word_char)
+            && !line[range.end ..].chars().next().is_some_and(is_word_char)
+    }
+
+    /// Returns the first match in the line starting at or after `from`.
+    pub fn find(&self, line: &str, mut from: usize) -> Option<Range<usize>> {
+        while from <= line.len() {
+            let range = self.find_candidate(line, from)?;
+            if !range.is_empty() && (!self.whole_word || Self::is_whole_word(line, &range)) {
+                return Some(range);
+            }
+            from = next_char_boundary(line, range.start);
+        }
+        None
+    }
+
+    /// Returns non-overlapping matches in the line.
+    pub fn matches<'a>(&'a self, line: &'a str) -> impl Iterator<Item=Range<usize>> + 'a {
+        let mut from = 0;
+        from_fn(move || {
+            let range = self.find(line, from)?;
+            from = range.end;
+            Some(range)
+        })
+    }
+
+    /// Returns the last match in the line ending at or before `to`.
+    pub fn find_last(&self, line: &str, to: usize) -> Option<Range<usize>> {
+        self.matches(line).take_while(|x| x.end <= to).last()
+    }
+
+    /// Returns the text replacing the match found in the line.
+    ///
+    /// For a regular expression, `$n` and `${name}` in `replacement` are substituted with the capture groups.
+    #[cfg_attr(not(feature="regex"), allow(unused_variables))]
+    pub fn replacement(&self, line: &str, range: Range<usize>, replacement: &str) -> String {
+        match &self.pattern {
+            Pattern::Plain(_) => replacement.into(),
+            #[cfg(feature="regex")]
+            Pattern::Regex(regex) => {
+                let mut res = String::new();
+                if let Some(captures) = regex.captures_at(line, range.start) {
+                    captures.expand(replacement, &mut res);
+                }
+                res
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    fn matches(searcher: &Searcher, line: &str) -> Vec<Range<usize>> {
+        searcher.matches(line).collect()
+    }
+
+    #[test]
+    fn empty_pattern() {
+        assert!(Searcher::new("", false, false, false).is_none());
+    }
+
+    #[test]
+    fn plain() {
+        let searcher = Searcher::new("foo", false, false, false).unwrap();
+        assert_eq!(matches(&searcher, "Foo xfoo FOOfoo"), [0 .. 3, 5 .. 8, 9 .. 12, 12 .. 15]);
+        let searcher = Searcher::new("foo", true, false, false).unwrap();
+        assert_eq!(matches(&searcher, "Foo xfoo FOOfoo"), [5 .. 8, 12 .. 15]);
+        assert_eq!(searcher.find("Foo xfoo FOOfoo", 6), Some(12 .. 15));
+        assert_eq!(searcher.find_last("Foo xfoo FOOfoo", 14), Some(5 .. 8));
+        let searcher = Searcher::new("ж", false, false, false).unwrap();
+        assert_eq!(matches(&searcher, "Жж"), [0 .. 2, 2 .. 4]);
+    }
+
+    #[test]
+    fn whole_word() {
+        let searcher = Searcher::new("foo", false, true, false).unwrap();
+        assert_eq!(matches(&searcher, "foo foobar _foo (foo) xfoo foo"), [0 .. 3, 17 .. 20, 27 .. 30]);
+        assert_eq!(searcher.find("foofoo foo", 0), Some(7 .. 10));
+        assert_eq!(searcher.find_last("foo foo", 6), Some(0 .. 3));
+    }
+
+    #[cfg(not(feature="regex"))]
+    #[test]
+    fn regex_disabled() {
+        assert!(Searcher::new("f.o", false, false, true).is_none());
+    }
+
+    #[cfg(feature="regex")]
+    #[test]
+    fn regex() {
+        assert!(Searcher::new("f(", false, false, true).is_none());
+        let searcher = Searcher::new("f(o+)", false, false, true).unwrap();
+        assert_eq!(matches(&searcher, "fo Foo f"), [0 .. 2, 3 .. 6]);
+        assert_eq!(searcher.replacement("fo Foo f", 3 .. 6, "<$1>"), "<oo>");
+        let searcher = Searcher::new("f(o+)", true, true, true).unwrap();
+        assert_eq!(matches(&searcher, "fo Foo foo_ foo"), [0 .. 2, 12 .. 15]);
+    }
+
+    #[cfg(feature="regex")]
+    #[test]
+    fn regex_empty_matches() {
+        let searcher = Searcher::new("x*", false, false, true).unwrap();
+        assert_eq!(matches(&searcher, "axxbx"), [1 .. 3, 4 .. 5]);
+        assert_eq!(searcher.find("axxbx", 2), Some(2 .. 3));
+        assert_eq!(searcher.find_last("axxbx", 4), Some(1 .. 3));
+        let searcher = Searcher::new(r"\b", false, false, true).unwrap();
+        assert!(searcher.find("foo bar", 0).is_none());
+        let searcher = Searcher::new("ж*", false, false, true).unwrap();
+        assert_eq!(searcher.find("ыЖж", 0), Some(2 .. 6));
+        assert!(searcher.find("ыЖж", 6).is_none());
+        let searcher = Searcher::new("x*", false, true, true).unwrap();
+        assert_eq!(searcher.find("axx x", 0), Some(4 .. 5));
+        assert!(searcher.find("axx x", 5).is_none());
+    }
+}
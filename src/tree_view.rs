@@ -0,0 +1,646 @@
+use crate::{widget, VirtItemsPresenter};
+use crate::scroll_bar::BoundScrollBars;
+use crate::virt_scroll_viewer::*;
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cmp::{max, min};
+use dyn_clone::{DynClone, clone_trait_object};
+use dynamic_cast::impl_supports_interfaces;
+use tuifw_screen_base::{Key, Mods, MouseButton, Point, Rect, Vector, Error, text_width};
+use tuifw_window::{Event, RenderPort, Timer, Widget, WidgetData, Window, WindowTree, App, Color, Data, Focus};
+use tuifw_window::{CMD_GOT_PRIMARY_FOCUS, CMD_LOST_PRIMARY_FOCUS, CMD_GOT_SECONDARY_FOCUS, CMD_LOST_SECONDARY_FOCUS};
+use tuifw_window::{COLOR_LIST_BOX, COLOR_LIST_BOX_SELECTED, COLOR_LIST_BOX_FOCUSED};
+use tuifw_window::{COLOR_LIST_BOX_FOCUSED_SELECTED, COLOR_DISABLED};
+
+pub const CMD_TREE_VIEW_SELECTION_CHANGED: u16 = 210;
+
+#[derive(Debug, Clone)]
+pub struct TreeViewNode {
+    pub text: String,
+    pub has_children: bool,
+}
+
+pub trait TreeViewSource: DynClone {
+    /// Returns children of the node at `path`, or top-level nodes if `path` is empty.
+    ///
+    /// Called once per node, when the node is expanded for the first time.
+    fn children(&self, path: &[usize]) -> Vec<TreeViewNode>;
+}
+
+clone_trait_object!(TreeViewSource);
+
+struct NodeState {
+    text: String,
+    has_children: bool,
+    is_last: bool,
+    is_expanded: bool,
+    parent: Option<usize>,
+    index: usize,
+    depth: u16,
+    children: Option<Vec<usize>>,
+}
+
+widget! {
+    #[widget(TreeViewWidget, init=init, drop=drop_timers)]
+    pub struct TreeView {
+        #[property(ref, on_changed=on_source_changed)]
+        source: Option<Box<dyn TreeViewSource>>,
+        nodes: Vec<NodeState>,
+        roots: Vec<usize>,
+        visible: Vec<usize>,
+        selected: Option<usize>,
+        offset: i16,
+        viewport: i16,
+        selection_changed_timer: Option<Timer>,
+        scroll_bars: BoundScrollBars,
+    }
+}
+
+impl TreeView {
+    fn init(tree: &mut WindowTree, window: Window) -> Result<(), Error> {
+        window.palette_mut(tree, |palette| {
+            palette.set(0, Color::Palette(COLOR_LIST_BOX));
+            palette.set(1, Color::Palette(COLOR_LIST_BOX_SELECTED));
+            palette.set(2, Color::Palette(COLOR_LIST_BOX_FOCUSED));
+            palette.set(3, Color::Palette(COLOR_LIST_BOX_FOCUSED_SELECTED));
+            palette.set(4, Color::Palette(COLOR_DISABLED));
+        });
+        window.set_focus_click(tree, Some(Focus::Primary));
+        let presenter = VirtItemsPresenter::new(tree, Some(window), None)?;
+        let item_template = Window::new_template(tree, Box::new(TreeViewItemWidget))?;
+        VirtItemsPresenter::set_item_template(tree, presenter, Some(item_template));
+        Ok(())
+    }
+
+    fn drop_timers(&mut self, tree: &mut WindowTree, _app: &mut dyn App) {
+        if let Some(selection_changed_timer) = self.selection_changed_timer.take() {
+            selection_changed_timer.drop_timer(tree);
+        }
+        self.scroll_bars.unbind(tree);
+    }
+
+    fn presenter(tree: &WindowTree, window: Window) -> Window {
+        window.first_child(tree).unwrap()
+    }
+
+    fn item_tree_view(tree: &WindowTree, item: Window) -> Option<Window> {
+        item.parent(tree)?.parent(tree)?.parent(tree)
+    }
+
+    pub fn selected_path(tree: &WindowTree, window: Window) -> Option<Vec<usize>> {
+        let data = window.data::<TreeView>(tree);
+        data.selected.map(|node| data.path(node))
+    }
+
+    /// Selects the node at `path`, expanding all its ancestors.
+    pub fn set_selected_path(tree: &mut WindowTree, window: Window, path: Option<&[usize]>) {
+        let node = path.and_then(|path| Self::resolve(tree, window, path, true));
+        Self::update_visible(tree, window);
+        Self::set_selected_raw(tree, window, node, None);
+        Self::bring_selected_into_view(tree, window);
+    }
+
+    pub fn is_expanded(tree: &WindowTree, window: Window, path: &[usize]) -> bool {
+        let data = window.data::<TreeView>(tree);
+        let mut list = &data.roots;
+        let mut node = None;
+        for &index in path {
+            let Some(&id) = list.get(index) else { return false; };
+            node = Some(&data.nodes[id]);
+            let Some(children) = data.nodes[id].children.as_ref() else { break; };
+            list = children;
+        }
+        node.is_some_and(|x| x.is_expanded)
+    }
+
+    pub fn set_expanded(tree: &mut WindowTree, window: Window, path: &[usize], value: bool) {
+        let Some(node) = Self::resolve(tree, window, path, value) else { return; };
+        Self::set_expanded_raw(tree, window, node, value, None);
+    }
+
+    fn on_source_changed(tree: &mut WindowTree, window: Window) {
+        let data = window.data_mut::<TreeView>(tree);
+        data.nodes.clear();
+        data.roots.clear();
+        data.offset = 0;
+        let roots = data.source.as_ref().map_or_else(Vec::new, |x| x.children(&[]));
+        data.roots = data.add_nodes(None, roots);
+        Self::update_visible(tree, window);
+        Self::set_selected_raw(tree, window, None, None);
+        let presenter = Self::presenter(tree, window);
+        VirtItemsPresenter::set_offset(tree, presenter, 0);
+    }
+
+    fn resolve(tree: &mut WindowTree, window: Window, path: &[usize], expand: bool) -> Option<usize> {
+        let data = window.data_mut::<TreeView>(tree);
+        let mut node = None;
+        for &index in path {
+            if let Some(parent) = node {
+                data.load_children(parent);
+                if expand && data.nodes[parent].has_children {
+                    data.nodes[parent].is_expanded = true;
+                }
+            }
+            let list = node.map_or(&data.roots, |x| data.nodes[x].children.as_ref().unwrap());
+            node = Some(*list.get(index)?);
+        }
+        node
+    }
+
+    fn update_visible(tree: &mut WindowTree, window: Window) {
+        let data = window.data_mut::<TreeView>(tree);
+        let mut visible = Vec::new();
+        let mut stack = data.roots.iter().rev().copied().collect::<Vec<_>>();
+        while let Some(node) = stack.pop() {
+            visible.push(node);
+            let node = &data.nodes[node];
+            if node.is_expanded {
+                if let Some(children) = node.children.as_ref() {
+                    stack.extend(children.iter().rev().copied());
+                }
+            }
+        }
+        let len = visible.len();
+        data.visible = visible;
+        let presenter = Self::presenter(tree, window);
+        VirtItemsPresenter::items_mut(tree, presenter, |items| {
+            items.clear();
+            items.extend((0 .. len).map(|_| Box::new(TreeViewItemData) as Box<dyn Data>));
+        });
+        window.invalidate_measure(tree);
+        window.invalidate_render(tree);
+    }
+
+    fn set_expanded_raw(
+        tree: &mut WindowTree,
+        window: Window,
+        node: usize,
+        value: bool,
+        app: Option<&mut dyn App>
+    ) {
+        let data = window.data_mut::<TreeView>(tree);
+        if value {
+            data.load_children(node);
+        }
+        let value = value && data.nodes[node].has_children;
+        if data.nodes[node].is_expanded == value { return; }
+        data.nodes[node].is_expanded = value;
+        let hides_selected = !value && data.selected.is_some_and(|x| data.is_descendant(x, node));
+        Self::update_visible(tree, window);
+        if hides_selected {
+            Self::set_selected_raw(tree, window, Some(node), app);
+        }
+        Self::bring_selected_into_view(tree, window);
+    }
+
+    fn set_selected_raw(
+        tree: &mut WindowTree,
+        window: Window,
+        value: Option<usize>,
+        app: Option<&mut dyn App>
+    ) {
+        let data = window.data_mut::<TreeView>(tree);
+        if data.selected == value { return; }
+        data.selected = value;
+        window.invalidate_render(tree);
+        if let Some(app) = app {
+            window.raise(tree, Event::Cmd(CMD_TREE_VIEW_SELECTION_CHANGED), app);
+        } else {
+            let selection_changed_timer = Timer::new(tree, 0, Box::new(move |tree, app| {
+                window.data_mut::<TreeView>(tree).selection_changed_timer = None;
+                window.raise(tree, Event::Cmd(CMD_TREE_VIEW_SELECTION_CHANGED), app);
+            }));
+            let data = window.data_mut::<TreeView>(tree);
+            if let Some(old_timer) = data.selection_changed_timer.replace(selection_changed_timer) {
+                old_timer.drop_timer(tree);
+            }
+        }
+    }
+
+    fn selected_row(tree: &WindowTree, window: Window) -> Option<usize> {
+        let data = window.data::<TreeView>(tree);
+        let selected = data.selected?;
+        data.visible.iter().position(|&x| x == selected)
+    }
+
+    fn bring_selected_into_view(tree: &mut WindowTree, window: Window) {
+        let Some(row) = Self::selected_row(tree, window) else { return; };
+        let data = window.data::<TreeView>(tree);
+        let row = min(row, i16::MAX as usize) as i16;
+        let viewport = max(1, data.viewport);
+        let offset = if row < data.offset {
+            row
+        } else if row >= data.offset.saturating_add(viewport) {
+            row.wrapping_sub(viewport).wrapping_add(1)
+        } else {
+            return;
+        };
+        let presenter = Self::presenter(tree, window);
+        VirtItemsPresenter::set_offset(tree, presenter, offset);
+    }
+
+    fn select_row(tree: &mut WindowTree, window: Window, row: usize, app: &mut dyn App) {
+        let node = window.data::<TreeView>(tree).visible[row];
+        Self::set_selected_raw(tree, window, Some(node), Some(app));
+        Self::bring_selected_into_view(tree, window);
+    }
+
+    fn key_target(tree: &WindowTree, window: Window, key: Key) -> Option<usize> {
+        let data = window.data::<TreeView>(tree);
+        let last = data.visible.len().checked_sub(1)?;
+        let page = max(1, data.viewport) as usize;
+        let current = Self::selected_row(tree, window);
+        Some(match key {
+            Key::Up => current.map_or(0, |x| x.saturating_sub(1)),
+            Key::Down => current.map_or(0, |x| min(x + 1, last)),
+            Key::PageUp => current.map_or(0, |x| x.saturating_sub(page)),
+            Key::PageDown => current.map_or(0, |x| min(x.saturating_add(page), last)),
+            Key::Home => 0,
+            Key::End => last,
+            _ => return None,
+        })
+    }
+
+    fn expand_or_enter(tree: &mut WindowTree, window: Window, app: &mut dyn App) -> bool {
+        let data = window.data::<TreeView>(tree);
+        let Some(selected) = data.selected else { return false; };
+        let node = &data.nodes[selected];
+        if !node.has_children { return false; }
+        if node.is_expanded {
+            let Some(&first_child) = node.children.as_ref().and_then(|x| x.first()) else { return false; };
+            Self::set_selected_raw(tree, window, Some(first_child), Some(app));
+            Self::bring_selected_into_view(tree, window);
+        } else {
+            Self::set_expanded_raw(tree, window, selected, true, Some(app));
+        }
+        true
+    }
+
+    fn collapse_or_leave(tree: &mut WindowTree, window: Window, app: &mut dyn App) -> bool {
+        let data = window.data::<TreeView>(tree);
+        let Some(selected) = data.selected else { return false; };
+        let node = &data.nodes[selected];
+        if node.is_expanded {
+            Self::set_expanded_raw(tree, window, selected, false, Some(app));
+        } else {
+            let Some(parent) = node.parent else { return false; };
+            Self::set_selected_raw(tree, window, Some(parent), Some(app));
+            Self::bring_selected_into_view(tree, window);
+        }
+        true
+    }
+}
+
+impl TreeView {
+    fn path(&self, mut node: usize) -> Vec<usize> {
+        let mut path = Vec::new();
+        loop {
+            let state = &self.nodes[node];
+            path.push(state.index);
+            let Some(parent) = state.parent else { break; };
+            node = parent;
+        }
+        path.reverse();
+        path
+    }
+
+    fn is_descendant(&self, mut node: usize, ancestor: usize) -> bool {
+        while let Some(parent) = self.nodes[node].parent {
+            if parent == ancestor { return true; }
+            node = parent;
+        }
+        false
+    }
+
+    fn add_nodes(&mut self, parent: Option<usize>, nodes: Vec<TreeViewNode>) -> Vec<usize> {
+        let depth = parent.map_or(0, |x| self.nodes[x].depth.saturating_add(1));
+        let count = nodes.len();
+        let first = self.nodes.len();
+        self.nodes.extend(nodes.into_iter().enumerate().map(|(index, node)| NodeState {
+            text: node.text,
+            has_children: node.has_children,
+            is_last: index + 1 == count,
+            is_expanded: false,
+            parent,
+            index,
+            depth,
+            children: None,
+        }));
+        (first .. first + count).collect()
+    }
+
+    fn load_children(&mut self, node: usize) {
+        if self.nodes[node].children.is_some() { return; }
+        let children = if self.nodes[node].has_children {
+            let path = self.path(node);
+            self.source.as_ref().map_or_else(Vec::new, |x| x.children(&path))
+        } else {
+            Vec::new()
+        };
+        if children.is_empty() {
+            self.nodes[node].has_children = false;
+        }
+        let children = self.add_nodes(Some(node), children);
+        self.nodes[node].children = Some(children);
+    }
+}
+
+#[derive(Clone, Default)]
+struct TreeViewWidget;
+
+impl_supports_interfaces!(TreeViewWidget: VirtScrollViewerWidgetExtension, VirtItemsPresenterWidgetExtension);
+
+impl VirtScrollViewerWidgetExtension for TreeViewWidget {
+    fn set_offset(&self, tree: &mut WindowTree, window: Window, vertical: bool, value: u32) {
+        if vertical {
+            window.data_mut::<TreeView>(tree).offset = u16::try_from(value).unwrap() as i16;
+        }
+        if let Some(parent) = window.parent(tree) {
+            if let Some(sv) = parent.widget_extension::<dyn VirtScrollViewerWidgetExtension>(tree) {
+                sv.set_offset(tree, parent, vertical, value);
+            }
+        }
+        let scroll_bars = window.data::<TreeView>(tree).scroll_bars;
+        scroll_bars.set_offset(tree, vertical, value);
+    }
+
+    fn set_viewport(&self, tree: &mut WindowTree, window: Window, vertical: bool, value: u32) {
+        if vertical {
+            window.data_mut::<TreeView>(tree).viewport = u16::try_from(value).unwrap() as i16;
+        }
+        if let Some(parent) = window.parent(tree) {
+            if let Some(sv) = parent.widget_extension::<dyn VirtScrollViewerWidgetExtension>(tree) {
+                sv.set_viewport(tree, parent, vertical, value);
+            }
+        }
+        let scroll_bars = window.data::<TreeView>(tree).scroll_bars;
+        scroll_bars.set_viewport(tree, vertical, value);
+    }
+
+    fn set_extent(&self, tree: &mut WindowTree, window: Window, vertical: bool, value: u32) {
+        if let Some(parent) = window.parent(tree) {
+            if let Some(sv) = parent.widget_extension::<dyn VirtScrollViewerWidgetExtension>(tree) {
+                sv.set_extent(tree, parent, vertical, value);
+            }
+        }
+        let scroll_bars = window.data::<TreeView>(tree).scroll_bars;
+        scroll_bars.set_extent(tree, vertical, value);
+    }
+
+    fn scroll_to(&self, tree: &mut WindowTree, window: Window, vertical: bool, value: u32) {
+        VirtItemsPresenterWidgetExtension::set_offset(self, tree, window, vertical, value);
+    }
+
+    fn bind_scroll_bar(&self, tree: &mut WindowTree, window: Window, vertical: bool, scroll_bar: Option<Window>) {
+        window.data_mut::<TreeView>(tree).scroll_bars.bind(vertical, scroll_bar);
+        if scroll_bar.is_none() { return; }
+        let presenter = TreeView::presenter(tree, window);
+        VirtItemsPresenter::sync_scroll_viewer(tree, presenter);
+    }
+}
+
+impl VirtItemsPresenterWidgetExtension for TreeViewWidget {
+    fn set_offset(&self, tree: &mut WindowTree, window: Window, vertical: bool, value: u32) {
+        let presenter = TreeView::presenter(tree, window);
+        if let Some(virt) = presenter.widget_extension::<dyn VirtItemsPresenterWidgetExtension>(tree) {
+            virt.set_offset(tree, presenter, vertical, value);
+        }
+    }
+}
+
+impl Widget for TreeViewWidget {
+    fn new(&self) -> Box<dyn WidgetData> {
+        Box::new(TreeView {
+            source: None,
+            nodes: Vec::new(),
+            roots: Vec::new(),
+            visible: Vec::new(),
+            selected: None,
+            offset: 0,
+            viewport: 0,
+            selection_changed_timer: None,
+            scroll_bars: BoundScrollBars::default(),
+        })
+    }
+
+    fn clone_data(
+        &self,
+        tree: &mut WindowTree,
+        source: Window,
+        dest: Window,
+        clone_window: Box<dyn Fn(&WindowTree, Window) -> Window>,
+    ) {
+        TreeView::clone(tree, source, dest, clone_window);
+    }
+
+    fn render(
+        &self,
+        tree: &WindowTree,
+        window: Window,
+        rp: &mut RenderPort,
+        _app: &mut dyn App,
+    ) {
+        rp.fill_bg(window.color(tree, 0));
+    }
+
+    fn measure(
+        &self,
+        tree: &mut WindowTree,
+        window: Window,
+        available_width: Option<i16>,
+        available_height: Option<i16>,
+        app: &mut dyn App,
+    ) -> Vector {
+        let presenter = TreeView::presenter(tree, window);
+        presenter.measure(tree, available_width, available_height, app);
+        presenter.desired_size(tree)
+    }
+
+    fn arrange(
+        &self,
+        tree: &mut WindowTree,
+        window: Window,
+        final_inner_bounds: Rect,
+        app: &mut dyn App,
+    ) -> Vector {
+        let presenter = TreeView::presenter(tree, window);
+        presenter.arrange(tree, final_inner_bounds, app);
+        final_inner_bounds.size
+    }
+
+    fn update(
+        &self,
+        tree: &mut WindowTree,
+        window: Window,
+        event: Event,
+        event_source: Window,
+        app: &mut dyn App,
+    ) -> bool {
+        match event {
+            Event::Cmd(CMD_GOT_PRIMARY_FOCUS) | Event::Cmd(CMD_GOT_SECONDARY_FOCUS) => {
+                let data = window.data::<TreeView>(tree);
+                if data.selected.is_none() && !data.visible.is_empty() {
+                    TreeView::select_row(tree, window, 0, app);
+                }
+                window.invalidate_render(tree);
+                return false;
+            },
+            Event::Cmd(CMD_LOST_PRIMARY_FOCUS) | Event::Cmd(CMD_LOST_SECONDARY_FOCUS) => {
+                window.invalidate_render(tree);
+                return false;
+            },
+            _ => { },
+        }
+        if !window.actual_is_enabled(tree) { return false; }
+        match event {
+            Event::Key(
+                key @ (Key::Up | Key::Down | Key::PageUp | Key::PageDown | Key::Home | Key::End),
+                Mods::NONE
+            ) => {
+                let Some(row) = TreeView::key_target(tree, window, key) else { return false; };
+                TreeView::select_row(tree, window, row, app);
+                true
+            },
+            Event::Key(Key::Right, Mods::NONE) => TreeView::expand_or_enter(tree, window, app),
+            Event::Key(Key::Left, Mods::NONE) => TreeView::collapse_or_leave(tree, window, app),
+            Event::Key(Key::Char(c @ ('+' | '-')), mods) if mods.without(Mods::SHIFT).is_empty() => {
+                let Some(selected) = window.data::<TreeView>(tree).selected else { return false; };
+                TreeView::set_expanded_raw(tree, window, selected, c == '+', Some(app));
+                true
+            },
+            Event::LmbDown(point, _) | Event::DoubleClick(point, MouseButton::Left, _) => {
+                let presenter = TreeView::presenter(tree, window);
+                if event_source.parent(tree).and_then(|x| x.parent(tree)) != Some(presenter) { return false; }
+                let Some(row) = event_source.source_index(tree) else { return false; };
+                let data = window.data::<TreeView>(tree);
+                let Some(&node) = data.visible.get(row) else { return false; };
+                let expander = 2 * data.nodes[node].depth as i16;
+                let x = event_source.inner_point(point, tree).x;
+                TreeView::select_row(tree, window, row, app);
+                let on_expander = (expander ..= expander + 1).contains(&x);
+                // A double click is preceded by `LmbDown`, which has already toggled the node if on the expander.
+                let toggle = if matches!(event, Event::DoubleClick(..)) { !on_expander } else { on_expander };
+                if toggle {
+                    let is_expanded = window.data::<TreeView>(tree).nodes[node].is_expanded;
+                    TreeView::set_expanded_raw(tree, window, node, !is_expanded, Some(app));
+                }
+                true
+            },
+            _ => false
+        }
+    }
+}
+
+#[derive(Clone)]
+struct TreeViewItemData;
+
+impl Data for TreeViewItemData { }
+
+struct TreeViewItem;
+
+impl WidgetData for TreeViewItem { }
+
+#[derive(Clone, Default)]
+struct TreeViewItemWidget;
+
+impl_supports_interfaces!(TreeViewItemWidget);
+
+impl Widget for TreeViewItemWidget {
+    fn new(&self) -> Box<dyn WidgetData> {
+        Box::new(TreeViewItem)
+    }
+
+    fn clone_data(
+        &self,
+        _tree: &mut WindowTree,
+        _source: Window,
+        _dest: Window,
+        _clone_window: Box<dyn Fn(&WindowTree, Window) -> Window>,
+    ) { }
+
+    fn render(
+        &self,
+        tree: &WindowTree,
+        window: Window,
+        rp: &mut RenderPort,
+        _app: &mut dyn App,
+    ) {
+        let Some(tree_view) = TreeView::item_tree_view(tree, window) else { return; };
+        let Some(row) = window.source_index(tree) else { return; };
+        let data = tree_view.data::<TreeView>(tree);
+        let Some(&id) = data.visible.get(row) else { return; };
+        let node = &data.nodes[id];
+        let selected = data.selected == Some(id);
+        let color = if !tree_view.actual_is_enabled(tree) {
+            4
+        } else {
+            match (tree_view.is_focused(tree), selected) {
+                (_, false) => 0,
+                (false, true) => 1,
+                (true, true) => 3,
+            }
+        };
+        let color = tree_view.color(tree, color);
+        rp.fill_bg(color);
+        let x = 2 * node.depth as i16;
+        if node.depth != 0 {
+            let connector = x.wrapping_sub(2);
+            rp.text(Point { x: connector, y: 0 }, color, if node.is_last { "└" } else { "├" });
+            rp.h_line(Point { x: connector.wrapping_add(1), y: 0 }, 1, false, color);
+        }
+        let mut ancestor = node.parent;
+        while let Some(id) = ancestor {
+            let ancestor_node = &data.nodes[id];
+            if ancestor_node.depth == 0 { break; }
+            if !ancestor_node.is_last {
+                rp.v_line(Point { x: 2 * ancestor_node.depth as i16 - 2, y: 0 }, 1, false, color);
+            }
+            ancestor = ancestor_node.parent;
+        }
+        let expander = if !node.has_children { " " } else if node.is_expanded { "-" } else { "+" };
+        rp.text(Point { x, y: 0 }, color, expander);
+        rp.text(Point { x: x.wrapping_add(2), y: 0 }, color, &node.text);
+    }
+
+    fn measure(
+        &self,
+        tree: &mut WindowTree,
+        window: Window,
+        _available_width: Option<i16>,
+        _available_height: Option<i16>,
+        _app: &mut dyn App,
+    ) -> Vector {
+        let width = TreeView::item_tree_view(tree, window)
+            .zip(window.source_index(tree))
+            .and_then(|(tree_view, row)| {
+                let data = tree_view.data::<TreeView>(tree);
+                let node = &data.nodes[*data.visible.get(row)?];
+                Some((2 * node.depth as i16).wrapping_add(text_width(&node.text)))
+            })
+            .unwrap_or(0)
+        ;
+        Vector { x: width.wrapping_add(3), y: 1 }
+    }
+
+    fn arrange(
+        &self,
+        _tree: &mut WindowTree,
+        _window: Window,
+        final_inner_bounds: Rect,
+        _app: &mut dyn App,
+    ) -> Vector {
+        Vector { x: final_inner_bounds.w(), y: 1 }
+    }
+
+    fn update(
+        &self,
+        _tree: &mut WindowTree,
+        _window: Window,
+        _event: Event,
+        _event_source: Window,
+        _app: &mut dyn App,
+    ) -> bool {
+        false
+    }
+}
@@ -0,0 +1,236 @@
+use alloc::collections::VecDeque;
+use alloc::string::String;
+
+pub(crate) const DEFAULT_UNDO_DEPTH: usize = 100;
+
+/// A single text replacement, together with the cursor and selection anchor before it (restored on undo)
+/// and after it (restored on redo).
+#[derive(Debug, Clone)]
+pub(crate) struct Edit {
+    pub pos: usize,
+    pub deleted: String,
+    pub inserted: String,
+    pub cursor: usize,
+    pub anchor: Option<usize>,
+    pub cursor_after: usize,
+    pub anchor_after: Option<usize>,
+    typing: bool,
+}
+
+impl Edit {
+    pub fn new(
+        pos: usize,
+        deleted: String,
+        inserted: String,
+        (cursor, anchor): (usize, Option<usize>),
+        (cursor_after, anchor_after): (usize, Option<usize>),
+    ) -> Self {
+        Edit { pos, deleted, inserted, cursor, anchor, cursor_after, anchor_after, typing: false }
+    }
+}
+
+/// Edit operation log with a redo tail and a "saved" mark.
+///
+/// Consecutive typed characters are coalesced into one edit per word (with trailing spaces).
+#[derive(Debug, Clone)]
+pub(crate) struct UndoHistory {
+    edits: VecDeque<Edit>,
+    index: usize,
+    saved: Option<usize>,
+    depth: usize,
+    sealed: bool,
+}
+
+impl UndoHistory {
+    pub fn new(depth: usize) -> Self {
+        UndoHistory { edits: VecDeque::new(), index: 0, saved: Some(0), depth, sealed: true }
+    }
+
+    pub fn record(&mut self, mut edit: Edit, typing: bool) {
+        self.edits.truncate(self.index);
+        if self.saved.is_some_and(|x| x > self.index) {
+            self.saved = None;
+        }
+        if typing && !self.sealed && self.saved != Some(self.index) {
+            if let Some(last) = self.edits.back_mut() {
+                let word_ended = last.inserted.ends_with(char::is_whitespace)
+                    && !edit.inserted.starts_with(char::is_whitespace);
+                if
+                    last.typing && edit.deleted.is_empty() && !word_ended
+                    && edit.pos == last.pos + last.inserted.len()
+                {
+                    last.inserted.push_str(&edit.inserted);
+                    last.cursor_after = edit.cursor_after;
+                    last.anchor_after = edit.anchor_after;
+                    return;
+                }
+            }
+        }
+        edit.typing = typing;
+        self.edits.push_back(edit);
+        self.index += 1;
+        self.sealed = false;
+        self.limit();
+    }
+
+    /// Prevents the next typed text from being coalesced with the last edit, e.g. after the cursor moved.
+    pub fn seal(&mut self) {
+        self.sealed = true;
+    }
+
+    /// Returns the edit to revert.
+    pub fn undo(&mut self) -> Option<Edit> {
+        if self.index == 0 { return None; }
+        self.index -= 1;
+        self.sealed = true;
+        Some(self.edits[self.index].clone())
+    }
+
+    /// Returns the edit to reapply.
+    pub fn redo(&mut self) -> Option<Edit> {
+        let edit = self.edits.get(self.index)?.clone();
+        self.index += 1;
+        self.sealed = true;
+        Some(edit)
+    }
+
+    pub fn can_undo(&self) -> bool { self.index != 0 }
+
+    pub fn can_redo(&self) -> bool { self.index != self.edits.len() }
+
+    pub fn clear(&mut self) {
+        self.edits.clear();
+        self.index = 0;
+        self.saved = Some(0);
+        self.sealed = true;
+    }
+
+    pub fn mark_saved(&mut self) {
+        self.saved = Some(self.index);
+        self.sealed = true;
+    }
+
+    pub fn is_modified(&self) -> bool {
+        self.saved != Some(self.index)
+    }
+
+    pub fn set_depth(&mut self, depth: usize) {
+        self.depth = depth;
+        self.limit();
+    }
+
+    fn limit(&mut self) {
+        while self.edits.len() > self.depth && self.index != 0 {
+            self.edits.pop_front();
+            self.index -= 1;
+            self.saved = self.saved.and_then(|x| x.checked_sub(1));
+        }
+        self.edits.truncate(self.depth);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+    use core::iter::from_fn;
+
+    fn edit(pos: usize, deleted: &str, inserted: &str) -> Edit {
+        Edit::new(pos, deleted.into(), inserted.into(), (pos, None), (pos + inserted.len(), None))
+    }
+
+    fn type_text(history: &mut UndoHistory, pos: usize, text: &str) {
+        for (i, c) in text.char_indices() {
+            let mut s = String::new();
+            s.push(c);
+            history.record(edit(pos + i, "", &s), true);
+        }
+    }
+
+    #[test]
+    fn coalesce_typing_by_words() {
+        let mut history = UndoHistory::new(DEFAULT_UNDO_DEPTH);
+        type_text(&mut history, 0, "ab  cd e");
+        assert_eq!(history.undo().unwrap().inserted, "e");
+        assert_eq!(history.undo().unwrap().inserted, "cd ");
+        assert_eq!(history.undo().unwrap().inserted, "ab  ");
+        assert!(history.undo().is_none());
+        assert!(!history.is_modified());
+    }
+
+    #[test]
+    fn coalesced_edit_cursors() {
+        let mut history = UndoHistory::new(DEFAULT_UNDO_DEPTH);
+        history.record(Edit::new(2, "xy".into(), "a".into(), (4, Some(2)), (3, None)), true);
+        type_text(&mut history, 3, "bc");
+        let undone = history.undo().unwrap();
+        assert_eq!((undone.inserted.as_str(), undone.cursor, undone.anchor), ("abc", 4, Some(2)));
+        let redone = history.redo().unwrap();
+        assert_eq!((redone.cursor_after, redone.anchor_after), (5, None));
+    }
+
+    #[test]
+    fn do_not_coalesce_non_typing() {
+        let mut history = UndoHistory::new(DEFAULT_UNDO_DEPTH);
+        type_text(&mut history, 0, "ab");
+        history.record(edit(2, "", "c"), false);
+        type_text(&mut history, 3, "d");
+        history.record(edit(3, "d", "x"), true);
+        type_text(&mut history, 10, "y");
+        history.seal();
+        type_text(&mut history, 11, "z");
+        let undone: Vec<_> = from_fn(|| history.undo()).map(|x| x.inserted).collect();
+        assert_eq!(undone, ["z", "y", "x", "d", "c", "ab"]);
+    }
+
+    #[test]
+    fn do_not_coalesce_with_saved() {
+        let mut history = UndoHistory::new(DEFAULT_UNDO_DEPTH);
+        type_text(&mut history, 0, "a");
+        history.mark_saved();
+        type_text(&mut history, 1, "b");
+        assert!(history.is_modified());
+        assert_eq!(history.undo().unwrap().inserted, "b");
+        assert!(!history.is_modified());
+    }
+
+    #[test]
+    fn redo() {
+        let mut history = UndoHistory::new(DEFAULT_UNDO_DEPTH);
+        history.record(edit(0, "", "a"), false);
+        history.record(edit(1, "", "b"), false);
+        history.undo();
+        assert!(history.can_redo());
+        assert_eq!(history.redo().unwrap().inserted, "b");
+        assert!(history.redo().is_none());
+        history.undo();
+        history.record(edit(1, "", "c"), false);
+        assert!(!history.can_redo());
+        assert_eq!(history.undo().unwrap().inserted, "c");
+        assert_eq!(history.undo().unwrap().inserted, "a");
+    }
+
+    #[test]
+    fn depth_limit() {
+        let mut history = UndoHistory::new(3);
+        for i in 0 .. 5 {
+            history.record(edit(i, "", "a"), false);
+        }
+        let undone: Vec<_> = from_fn(|| history.undo()).map(|x| x.pos).collect();
+        assert_eq!(undone, [4, 3, 2]);
+        assert!(history.is_modified());
+        assert_eq!(history.redo().unwrap().pos, 2);
+        history.set_depth(1);
+        assert!(!history.can_undo());
+        assert_eq!(history.redo().unwrap().pos, 3);
+        assert!(!history.can_redo());
+    }
+
+    #[test]
+    fn zero_depth() {
+        let mut history = UndoHistory::new(0);
+        history.record(edit(0, "", "a"), false);
+        assert!(!history.can_undo());
+        assert!(history.is_modified());
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{widget, StaticText, StackPanel};
+use crate::{widget, StaticText, StackPanel, WHEEL_LINES};
 use crate::virt_scroll_viewer::*;
 use alloc::boxed::Box;
 use alloc::string::ToString;
@@ -7,7 +7,7 @@ use core::cmp::{max, min};
 use core::mem::{replace, size_of};
 use core::ops::Range;
 use dynamic_cast::impl_supports_interfaces;
-use tuifw_screen_base::{Rect, Vector, Error, Fg, Bg, Thickness, Key};
+use tuifw_screen_base::{Rect, Vector, Error, Fg, Bg, Thickness, Key, Mods};
 use tuifw_window::{Event, RenderPort, Widget, WidgetData, Window, WindowTree, App, Timer, Data};
 use tuifw_window::Visibility;
 
@@ -82,6 +82,11 @@ impl VirtItemsPresenter {
     }
 
     fn on_vertical_changed(tree: &mut WindowTree, window: Window) {
+        Self::sync_scroll_viewer(tree, window);
+        Self::on_templates_changed(tree, window);
+    }
+
+    pub(crate) fn sync_scroll_viewer(tree: &mut WindowTree, window: Window) {
         let viewport = window.inner_bounds(tree).size;
         let data = window.data::<VirtItemsPresenter>(tree);
         let vertical = data.vertical;
@@ -95,7 +100,6 @@ impl VirtItemsPresenter {
                 sv.set_viewport(tree, parent, vertical, (viewport as u16).into());
             }
         }
-        Self::on_templates_changed(tree, window);
     }
 
     fn on_extent_changed(tree: &mut WindowTree, window: Window) {
@@ -482,7 +486,7 @@ impl Widget for VirtItemsPresenterWidget {
         _app: &mut dyn App,
     ) -> bool {
         match event {
-            Event::Key(Key::Tab) => {
+            Event::Key(Key::Tab, Mods::NONE) => {
                 let data = window.data::<VirtItemsPresenter>(tree);
                 if data.tab_navigation {
                     if event_source.parent(tree).and_then(|x| x.parent(tree)) == Some(window) {
@@ -517,7 +521,7 @@ impl Widget for VirtItemsPresenterWidget {
                     false
                 }
             },
-            Event::Key(Key::Down) => {
+            Event::Key(Key::Down, Mods::NONE) => {
                 let data = window.data::<VirtItemsPresenter>(tree);
                 if data.up_down_navigation {
                     if event_source.parent(tree).and_then(|x| x.parent(tree)) == Some(window) {
@@ -542,7 +546,7 @@ impl Widget for VirtItemsPresenterWidget {
                     false
                 }
             },
-            Event::Key(Key::Up) => {
+            Event::Key(Key::Up, Mods::NONE) => {
                 let data = window.data::<VirtItemsPresenter>(tree);
                 if data.up_down_navigation {
                     if event_source.parent(tree).and_then(|x| x.parent(tree)) == Some(window) {
@@ -574,7 +578,7 @@ impl Widget for VirtItemsPresenterWidget {
                     false
                 }
             },
-            Event::Key(Key::Right) => {
+            Event::Key(Key::Right, Mods::NONE) => {
                 let data = window.data::<VirtItemsPresenter>(tree);
                 if data.left_right_navigation {
                     if event_source.parent(tree).and_then(|x| x.parent(tree)) == Some(window) {
@@ -599,7 +603,7 @@ impl Widget for VirtItemsPresenterWidget {
                     false
                 }
             },
-            Event::Key(Key::Left) => {
+            Event::Key(Key::Left, Mods::NONE) => {
                 let data = window.data::<VirtItemsPresenter>(tree);
                 if data.left_right_navigation {
                     if event_source.parent(tree).and_then(|x| x.parent(tree)) == Some(window) {
@@ -631,6 +635,15 @@ impl Widget for VirtItemsPresenterWidget {
                     false
                 }
             },
+            Event::Wheel(_, delta, _) => {
+                let data = window.data::<VirtItemsPresenter>(tree);
+                let extent = (data.items.len() as u16 as i16).wrapping_mul(data.item_size);
+                let max_offset = max(0, extent.wrapping_sub(data.viewport));
+                let offset = min(max(0, data.offset.saturating_add((WHEEL_LINES as i16).saturating_mul(delta))), max_offset);
+                if offset == data.offset { return false; }
+                VirtItemsPresenter::set_offset_raw(tree, window, offset, true);
+                true
+            },
             _ => false,
         }
     }
@@ -4,6 +4,8 @@ pub trait VirtScrollViewerWidgetExtension {
     fn set_offset(&self, tree: &mut WindowTree, window: Window, vertical: bool, value: u32);
     fn set_viewport(&self, tree: &mut WindowTree, window: Window, vertical: bool, value: u32);
     fn set_extent(&self, tree: &mut WindowTree, window: Window, vertical: bool, value: u32);
+    fn scroll_to(&self, _tree: &mut WindowTree, _window: Window, _vertical: bool, _value: u32) { }
+    fn bind_scroll_bar(&self, _tree: &mut WindowTree, _window: Window, _vertical: bool, _scroll_bar: Option<Window>) { }
 }
 
 pub trait VirtItemsPresenterWidgetExtension {
@@ -0,0 +1,197 @@
+use crate::widget;
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::cmp::{max, min};
+use dynamic_cast::impl_supports_interfaces;
+use tuifw_screen_base::{Point, Rect, Vector};
+use tuifw_window::{Event, RenderPort, Widget, WidgetData, Window, WindowTree, App};
+
+/// Position of children inside a [`WrapPanel`] line that is shorter than the panel.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum LineAlign { Start, Center, End }
+
+widget! {
+    #[widget(WrapPanelWidget)]
+    pub struct WrapPanel {
+        #[property(copy, measure)]
+        vertical: bool,
+        #[property(copy, measure)]
+        item_spacing: i16,
+        #[property(copy, measure)]
+        line_spacing: i16,
+        #[property(copy, arrange)]
+        line_align: LineAlign,
+    }
+}
+
+struct Line {
+    start: usize,
+    end: usize,
+    main: i32,
+    cross: i32,
+}
+
+fn main_cross(size: Vector, vertical: bool) -> (i32, i32) {
+    let (main, cross) = if vertical { (size.y, size.x) } else { (size.x, size.y) };
+    (i32::from(main as u16), i32::from(cross as u16))
+}
+
+fn from_main_cross(main: i32, cross: i32, vertical: bool) -> Vector {
+    let main = min(main, i32::from(i16::MAX)) as i16;
+    let cross = min(cross, i32::from(i16::MAX)) as i16;
+    if vertical { Vector { x: cross, y: main } } else { Vector { x: main, y: cross } }
+}
+
+fn break_lines(sizes: &[(i32, i32)], available: Option<i16>, spacing: i32) -> Vec<Line> {
+    let available = available.map(|x| i32::from(x as u16));
+    let mut lines: Vec<Line> = Vec::new();
+    for (i, &(main, cross)) in sizes.iter().enumerate() {
+        if let Some(line) = lines.last_mut() {
+            let line_main = line.main + spacing + main;
+            if available.is_none_or(|x| line_main <= x) {
+                line.end = i + 1;
+                line.main = line_main;
+                line.cross = max(line.cross, cross);
+                continue;
+            }
+        }
+        lines.push(Line { start: i, end: i + 1, main, cross });
+    }
+    lines
+}
+
+fn children(tree: &WindowTree, window: Window) -> Vec<Window> {
+    let mut res = Vec::new();
+    if let Some(first_child) = window.first_child(tree) {
+        let mut child = first_child;
+        loop {
+            res.push(child);
+            child = child.next(tree);
+            if child == first_child { break; }
+        }
+    }
+    res
+}
+
+fn panel_size(lines: &[Line], line_spacing: i32, vertical: bool) -> Vector {
+    let main = lines.iter().map(|x| x.main).max().unwrap_or(0);
+    let cross = lines.iter().map(|x| x.cross).sum::<i32>()
+        + line_spacing * (lines.len() as i32 - 1).max(0);
+    from_main_cross(main, cross, vertical)
+}
+
+#[derive(Clone, Default)]
+struct WrapPanelWidget;
+
+impl_supports_interfaces!(WrapPanelWidget);
+
+impl Widget for WrapPanelWidget {
+    fn new(&self) -> Box<dyn WidgetData> {
+        Box::new(WrapPanel {
+            vertical: false,
+            item_spacing: 0,
+            line_spacing: 0,
+            line_align: LineAlign::Start,
+        })
+    }
+
+    fn clone_data(
+        &self,
+        tree: &mut WindowTree,
+        source: Window,
+        dest: Window,
+        clone_window: Box<dyn Fn(&WindowTree, Window) -> Window>,
+    ) {
+        WrapPanel::clone(tree, source, dest, clone_window);
+    }
+
+    fn render(
+        &self,
+        _tree: &WindowTree,
+        _window: Window,
+        _rp: &mut RenderPort,
+        _app: &mut dyn App,
+    ) { }
+
+    fn measure(
+        &self,
+        tree: &mut WindowTree,
+        window: Window,
+        available_width: Option<i16>,
+        available_height: Option<i16>,
+        app: &mut dyn App,
+    ) -> Vector {
+        let data = window.data::<WrapPanel>(tree);
+        let vertical = data.vertical;
+        let item_spacing = i32::from(max(data.item_spacing, 0));
+        let line_spacing = i32::from(max(data.line_spacing, 0));
+        let children = children(tree, window);
+        let mut sizes = Vec::with_capacity(children.len());
+        for &child in &children {
+            if vertical {
+                child.measure(tree, None, available_height, app);
+            } else {
+                child.measure(tree, available_width, None, app);
+            }
+            sizes.push(main_cross(child.desired_size(tree), vertical));
+        }
+        let available = if vertical { available_height } else { available_width };
+        let lines = break_lines(&sizes, available, item_spacing);
+        panel_size(&lines, line_spacing, vertical)
+    }
+
+    fn arrange(
+        &self,
+        tree: &mut WindowTree,
+        window: Window,
+        final_inner_bounds: Rect,
+        app: &mut dyn App,
+    ) -> Vector {
+        let data = window.data::<WrapPanel>(tree);
+        let vertical = data.vertical;
+        let item_spacing = i32::from(max(data.item_spacing, 0));
+        let line_spacing = i32::from(max(data.line_spacing, 0));
+        let line_align = data.line_align;
+        let children = children(tree, window);
+        let sizes = children.iter()
+            .map(|&child| main_cross(child.desired_size(tree), vertical))
+            .collect::<Vec<_>>()
+        ;
+        let available = if vertical { final_inner_bounds.h() } else { final_inner_bounds.w() };
+        let lines = break_lines(&sizes, Some(available), item_spacing);
+        let mut cross_pos = 0;
+        let mut main_size = 0;
+        for line in &lines {
+            let free = max(i32::from(available as u16) - line.main, 0);
+            let mut main_pos = match line_align {
+                LineAlign::Start => 0,
+                LineAlign::Center => free / 2,
+                LineAlign::End => free,
+            };
+            main_size = max(main_size, main_pos + line.main);
+            for (&child, &(main, _)) in children[line.start .. line.end].iter().zip(&sizes[line.start .. line.end]) {
+                let offset = from_main_cross(main_pos, cross_pos, vertical);
+                let tl = Point {
+                    x: final_inner_bounds.l().wrapping_add(offset.x),
+                    y: final_inner_bounds.t().wrapping_add(offset.y),
+                };
+                child.arrange(tree, Rect { tl, size: from_main_cross(main, line.cross, vertical) }, app);
+                main_pos += main + item_spacing;
+            }
+            cross_pos += line.cross + line_spacing;
+        }
+        let (_, cross_size) = main_cross(panel_size(&lines, line_spacing, vertical), vertical);
+        from_main_cross(main_size, cross_size, vertical)
+    }
+
+    fn update(
+        &self,
+        _tree: &mut WindowTree,
+        _window: Window,
+        _event: Event,
+        _event_source: Window,
+        _app: &mut dyn App,
+    ) -> bool {
+        false
+    }
+}
@@ -24,6 +24,7 @@ use alloc::boxed::Box;
 use alloc::string::String;
 use alloc::vec::Vec;
 use components_arena::{Arena, Component, Id, NewtypeComponentId};
+use core::cell::Cell;
 use core::cmp::{max, min};
 use core::mem::replace;
 use core::ptr::{DynMetadata, Pointee};
@@ -33,46 +34,108 @@ use dynamic_cast::{SupportsInterfaces, dyn_cast_box};
 use iter_identify_first_last::IteratorIdentifyFirstLastExt;
 use macro_attr_2018::macro_attr;
 use timer_no_std::{MonoClock, MonoTime};
-use tuifw_screen_base::{Bg, Error, Fg, Key, Point, Rect, Screen, Vector};
+use tuifw_screen_base::{Attr, Bg, Clipboard, Error, ExtBg, ExtFg, Fg, Key, Mods, MouseButton, Point, Rect, Screen, Vector};
 use tuifw_screen_base::Event as screen_Event;
-use tuifw_screen_base::{HAlign, VAlign, Thickness, Range1d, text_width};
+use tuifw_screen_base::{HAlign, VAlign, Thickness, Range1d, LocalClipboard, text_width};
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
 pub enum Event {
-    Key(Key),
-    PreviewKey(Key),
-    PreProcessKey(Key),
-    PostProcessKey(Key),
+    Key(Key, Mods),
+    PreviewKey(Key, Mods),
+    PreProcessKey(Key, Mods),
+    PostProcessKey(Key, Mods),
     Cmd(u16),
     PreviewCmd(u16),
-    LmbDown(Point),
-    PreviewLmbDown(Point),
+    LmbDown(Point, Mods),
+    PreviewLmbDown(Point, Mods),
     LmbUp,
     PreviewLmbUp,
+    RmbDown(Point, Mods),
+    PreviewRmbDown(Point, Mods),
+    RmbUp,
+    PreviewRmbUp,
+    MmbDown(Point, Mods),
+    PreviewMmbDown(Point, Mods),
+    MmbUp,
+    PreviewMmbUp,
+    DoubleClick(Point, MouseButton, Mods),
+    PreviewDoubleClick(Point, MouseButton, Mods),
+    Wheel(Point, i16, Mods),
+    PreviewWheel(Point, i16, Mods),
+    MouseMove(Point, Mods),
+    PreviewMouseMove(Point, Mods),
+    Drag(Point, MouseButton, Mods),
+    PreviewDrag(Point, MouseButton, Mods),
+    /// Text pasted into the terminal, available via [`WindowTree::pasted_text`].
+    Paste,
+    PreviewPaste,
 }
 
 impl Event {
     pub fn is_preview(self) -> bool {
         match self {
-            Event::Key(_) => false,
-            Event::PreviewKey(_) => true,
+            Event::Key(..) => false,
+            Event::PreviewKey(..) => true,
             Event::Cmd(_) => false,
             Event::PreviewCmd(_) => true,
-            Event::PreProcessKey(_) => false,
-            Event::PostProcessKey(_) => false,
-            Event::LmbDown(_) => false,
-            Event::PreviewLmbDown(_) => true,
+            Event::PreProcessKey(..) => false,
+            Event::PostProcessKey(..) => false,
+            Event::LmbDown(..) => false,
+            Event::PreviewLmbDown(..) => true,
             Event::LmbUp => false,
             Event::PreviewLmbUp => true,
+            Event::RmbDown(..) => false,
+            Event::PreviewRmbDown(..) => true,
+            Event::RmbUp => false,
+            Event::PreviewRmbUp => true,
+            Event::MmbDown(..) => false,
+            Event::PreviewMmbDown(..) => true,
+            Event::MmbUp => false,
+            Event::PreviewMmbUp => true,
+            Event::DoubleClick(..) => false,
+            Event::PreviewDoubleClick(..) => true,
+            Event::Wheel(..) => false,
+            Event::PreviewWheel(..) => true,
+            Event::MouseMove(..) => false,
+            Event::PreviewMouseMove(..) => true,
+            Event::Drag(..) => false,
+            Event::PreviewDrag(..) => true,
+            Event::Paste => false,
+            Event::PreviewPaste => true,
+        }
+    }
+
+    fn mouse_down(point: Point, button: MouseButton, mods: Mods) -> Self {
+        match button {
+            MouseButton::Left => Event::LmbDown(point, mods),
+            MouseButton::Right => Event::RmbDown(point, mods),
+            MouseButton::Middle => Event::MmbDown(point, mods),
+        }
+    }
+
+    fn mouse_up(button: MouseButton) -> Self {
+        match button {
+            MouseButton::Left => Event::LmbUp,
+            MouseButton::Right => Event::RmbUp,
+            MouseButton::Middle => Event::MmbUp,
         }
     }
 
     fn preview(self) -> Self {
         match self {
-            Event::Key(k) => Event::PreviewKey(k),
+            Event::Key(k, m) => Event::PreviewKey(k, m),
             Event::Cmd(n) => Event::PreviewCmd(n),
-            Event::LmbDown(p) => Event::PreviewLmbDown(p),
+            Event::LmbDown(p, m) => Event::PreviewLmbDown(p, m),
             Event::LmbUp => Event::PreviewLmbUp,
+            Event::RmbDown(p, m) => Event::PreviewRmbDown(p, m),
+            Event::RmbUp => Event::PreviewRmbUp,
+            Event::MmbDown(p, m) => Event::PreviewMmbDown(p, m),
+            Event::MmbUp => Event::PreviewMmbUp,
+            Event::DoubleClick(p, b, m) => Event::PreviewDoubleClick(p, b, m),
+            Event::Wheel(p, d, m) => Event::PreviewWheel(p, d, m),
+            Event::MouseMove(p, m) => Event::PreviewMouseMove(p, m),
+            Event::Drag(p, b, m) => Event::PreviewDrag(p, b, m),
+            Event::Paste => Event::PreviewPaste,
             _ => unreachable!(),
         }
     }
@@ -125,7 +188,8 @@ pub struct RenderPort {
 }
 
 impl RenderPort {
-    pub fn text(&mut self, p: Point, color: (Fg, Bg), text: &str) {
+    pub fn text(&mut self, p: Point, color: impl TextColor, text: &str) {
+        let color = color.text_color();
         let screen_size = self.screen.size();
         let p = p.offset(self.offset);
         if !self.bounds.v_range().contains(p.y) || self.bounds.size.x == 0 { return; }
@@ -152,7 +216,7 @@ impl RenderPort {
 
         for chunk in &chunks {
             if chunk.start >= chunk.end { continue; }
-            let out = self.screen.out(p, color.0, color.1, text, chunk.clone(), row.clone());
+            let out = self.screen.out(p, color.0, color.1, color.2, text, chunk.clone(), row.clone());
             if out.start >= out.end { continue; }
             let row = self.screen.line_invalidated_range_mut(p.y);
             row.start = min(row.start, out.start);
@@ -165,6 +229,8 @@ impl RenderPort {
         }
     }
 
+    pub fn is_unicode(&self) -> bool { self.screen.is_unicode() }
+
     pub fn cursor(&mut self, p: Point) {
         if self.cursor.is_some() { return; }
         let p = p.offset(self.offset);
@@ -182,7 +248,9 @@ impl RenderPort {
         }
     }
 
-    pub fn label(&mut self, mut p: Point, color: (Fg, Bg), color_hotkey: (Fg, Bg), text: &str) {
+    pub fn label(&mut self, mut p: Point, color: impl TextColor, color_hotkey: impl TextColor, text: &str) {
+        let color = color.text_color();
+        let color_hotkey = color_hotkey.text_color();
         let mut hotkey = false;
         for (first, last, text) in text.split('~').identify_first_last() {
             if !first && !text.is_empty() {
@@ -197,37 +265,40 @@ impl RenderPort {
         }
     }
 
-    pub fn fill_bg(&mut self, color: (Fg, Bg)) {
+    pub fn fill_bg(&mut self, color: impl TextColor) {
+        let color = color.text_color();
         self.fill(|rp, p| rp.text(p, color, " "));
     }
 
-    pub fn h_line(&mut self, start: Point, len: i16, double: bool, color: (Fg, Bg)) {
+    pub fn h_line(&mut self, start: Point, len: i16, double: bool, color: impl TextColor) {
+        let color = color.text_color();
         let s = if double { "═" } else { "─" };
         for x in Range1d::new(start.x, start.x.wrapping_add(len)) {
             self.text(Point { x, y: start.y }, color, s);
         }
     }
 
-    pub fn v_line(&mut self, start: Point, len: i16, double: bool, color: (Fg, Bg)) {
+    pub fn v_line(&mut self, start: Point, len: i16, double: bool, color: impl TextColor) {
+        let color = color.text_color();
         let s = if double { "║" } else { "│" };
         for y in Range1d::new(start.y, start.y.wrapping_add(len)) {
             self.text(Point { x: start.x, y }, color, s);
         }
     }
 
-    pub fn tl_edge(&mut self, p: Point, double: bool, color: (Fg, Bg)) {
+    pub fn tl_edge(&mut self, p: Point, double: bool, color: impl TextColor) {
         self.text(p, color, if double { "╔" } else { "┌" });
     }
 
-    pub fn tr_edge(&mut self, p: Point, double: bool, color: (Fg, Bg)) {
+    pub fn tr_edge(&mut self, p: Point, double: bool, color: impl TextColor) {
         self.text(p, color, if double { "╗" } else { "┐" });
     }
 
-    pub fn bl_edge(&mut self, p: Point, double: bool, color: (Fg, Bg)) {
+    pub fn bl_edge(&mut self, p: Point, double: bool, color: impl TextColor) {
         self.text(p, color, if double { "╚" } else { "└" });
     }
 
-    pub fn br_edge(&mut self, p: Point, double: bool, color: (Fg, Bg)) {
+    pub fn br_edge(&mut self, p: Point, double: bool, color: impl TextColor) {
         self.text(p, color, if double { "╝" } else { "┘" });
     }
 }
@@ -354,16 +425,44 @@ pub trait App: Downcast { }
 
 impl_downcast!(App);
 
+/// Implemented by widgets whose templates can serve as a [`Window::context_menu`].
+pub trait ContextMenuWidgetExtension {
+    fn open_context_menu(
+        &self,
+        tree: &mut WindowTree,
+        template: Window,
+        owner: Window,
+        point: Point,
+        app: &mut dyn App,
+    ) -> Result<(), Error>;
+}
+
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub enum Focus {
     Primary,
     Secondary
 }
 
+pub trait TextColor {
+    fn text_color(self) -> (ExtFg, ExtBg, Attr);
+}
+
+impl<F: Into<ExtFg>, B: Into<ExtBg>> TextColor for (F, B) {
+    fn text_color(self) -> (ExtFg, ExtBg, Attr) {
+        (self.0.into(), self.1.into(), Attr::NONE)
+    }
+}
+
+impl<F: Into<ExtFg>, B: Into<ExtBg>> TextColor for (F, B, Attr) {
+    fn text_color(self) -> (ExtFg, ExtBg, Attr) {
+        (self.0.into(), self.1.into(), self.2)
+    }
+}
+
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub enum Color {
     Parent,
-    Value((Fg, Bg)),
+    Value((ExtFg, ExtBg, Attr)),
     Palette(u8)
 }
 
@@ -440,12 +539,14 @@ macro_attr! {
         max_height: i16,
         event_handler: Option<Box<dyn EventHandler>>,
         focus_tab: Window,
+        focus_back_tab: Window,
         focus_right: Window,
         focus_left: Window,
         focus_up: Window,
         focus_down: Window,
         contains_primary_focus: bool,
         focus_click: Option<Focus>,
+        context_menu: Option<Window>,
         name: String,
         pre_process: Option<Id<PrePostProcess>>,
         post_process: Option<Id<PrePostProcess>>,
@@ -558,11 +659,13 @@ impl Window {
             Box::new(|tree: &WindowTree, window: Window| tree.arena[window.0].cloning.unwrap_or(window))
         ;
         clone.set_focus_tab(tree, clone_window(tree, self.focus_tab(tree)));
+        clone.set_focus_back_tab(tree, clone_window(tree, self.focus_back_tab(tree)));
         clone.set_focus_right(tree, clone_window(tree, self.focus_right(tree)));
         clone.set_focus_left(tree, clone_window(tree, self.focus_left(tree)));
         clone.set_focus_up(tree, clone_window(tree, self.focus_up(tree)));
         clone.set_focus_down(tree, clone_window(tree, self.focus_down(tree)));
         clone.set_focus_click(tree, self.focus_click(tree));
+        clone.set_context_menu(tree, self.context_menu(tree));
         let widget = tree.arena[self.0].widget.clone();
         widget.clone_data(tree, self, clone, clone_window);
         if let Some(first_child) = self.first_child(tree) {
@@ -628,12 +731,14 @@ impl Window {
                 min_height: 0,
                 max_height: -1,
                 focus_tab: Window(window),
+                focus_back_tab: Window(window),
                 focus_right: Window(window),
                 focus_left: Window(window),
                 focus_up: Window(window),
                 focus_down: Window(window),
                 contains_primary_focus: false,
                 focus_click: None,
+                context_menu: None,
                 name: String::new(),
                 pre_process: None,
                 post_process: None,
@@ -882,6 +987,17 @@ impl Window {
         tree.arena[self.0].focus_click = value;
     }
 
+    pub fn context_menu(self, tree: &WindowTree) -> Option<Window> {
+        tree.arena[self.0].context_menu
+    }
+
+    pub fn set_context_menu(self, tree: &mut WindowTree, value: Option<Window>) {
+        if let Some(value) = value {
+            assert!(value.is_template(tree), "context menu should be a template");
+        }
+        tree.arena[self.0].context_menu = value;
+    }
+
     pub fn focus_tab(self, tree: &WindowTree) -> Self {
         tree.arena[self.0].focus_tab
     }
@@ -890,6 +1006,14 @@ impl Window {
         tree.arena[self.0].focus_tab = value;
     }
 
+    pub fn focus_back_tab(self, tree: &WindowTree) -> Self {
+        tree.arena[self.0].focus_back_tab
+    }
+
+    pub fn set_focus_back_tab(self, tree: &mut WindowTree, value: Self) {
+        tree.arena[self.0].focus_back_tab = value;
+    }
+
     pub fn focus_right(self, tree: &WindowTree) -> Self {
         tree.arena[self.0].focus_right
     }
@@ -970,6 +1094,53 @@ impl Window {
         }
     }
 
+    /// Restricts input to the window subtree until [`end_modal`](Window::end_modal) is called
+    /// or the window is dropped. Focus outside the subtree is saved and restored afterwards.
+    pub fn begin_modal(self, tree: &mut WindowTree, app: &mut dyn App) {
+        assert!(!self.is_template(tree), "cannot show template modally");
+        if self.is_modal(tree) { return; }
+        let primary_focused = tree.primary_focused.filter(|&x| !x.is_in_subtree(tree, self));
+        let secondary_focused = tree.secondary_focused.filter(|&x| !x.is_in_subtree(tree, self));
+        let next_primary_focused = tree.next_primary_focused.unwrap_or(tree.primary_focused);
+        if next_primary_focused.is_some_and(|x| !x.is_in_subtree(tree, self)) {
+            tree.next_primary_focused = Some(None);
+        }
+        let next_secondary_focused = tree.next_secondary_focused.unwrap_or(tree.secondary_focused);
+        if next_secondary_focused.is_some_and(|x| !x.is_in_subtree(tree, self)) {
+            tree.next_secondary_focused = Some(None);
+        }
+        if let Some(click) = tree.click.filter(|x| x.0.is_some_and(|x| !x.is_in_subtree(tree, self))) {
+            tree.click = Some((None, click.1));
+        }
+        tree.modal.push(Modal { window: self, primary_focused, secondary_focused });
+        primary_focused.map(|x| x.raise(tree, Event::Cmd(CMD_LOST_ATTENTION), app));
+    }
+
+    pub fn end_modal(self, tree: &mut WindowTree) {
+        let Some(index) = tree.modal.iter().position(|x| x.window == self) else { return; };
+        let modal = tree.modal.remove(index);
+        if let Some(above) = tree.modal.get_mut(index) {
+            above.primary_focused = modal.primary_focused;
+            above.secondary_focused = modal.secondary_focused;
+        } else {
+            tree.next_primary_focused = Some(modal.primary_focused);
+            tree.next_secondary_focused = Some(modal.secondary_focused);
+        }
+    }
+
+    pub fn is_modal(self, tree: &WindowTree) -> bool {
+        tree.modal.iter().any(|x| x.window == self)
+    }
+
+    fn is_in_subtree(self, tree: &WindowTree, root: Window) -> bool {
+        let mut window = self;
+        loop {
+            if window == root { return true; }
+            let Some(parent) = window.parent(tree) else { return false; };
+            window = parent;
+        }
+    }
+
     pub fn palette<'a>(self, tree: &'a WindowTree<'_>) -> &'a Palette {
         &tree.arena[self.0].palette
     }
@@ -984,11 +1155,12 @@ impl Window {
         self.palette_mut(tree, |palette| replace(palette, value));
     }
 
-    pub fn set_color(self, tree: &mut WindowTree, i: u8, color: (Fg, Bg)) {
+    pub fn set_color(self, tree: &mut WindowTree, i: u8, color: impl TextColor) {
+        let color = color.text_color();
         self.palette_mut(tree, |palette| palette.set(i, Color::Value(color)));
     }
 
-    pub fn color(self, tree: &WindowTree, i: u8) -> (Fg, Bg) {
+    pub fn color(self, tree: &WindowTree, i: u8) -> (ExtFg, ExtBg, Attr) {
         let mut window = self;
         let mut index = i;
         loop {
@@ -999,7 +1171,7 @@ impl Window {
                     } else if let Color::Value(color) = tree.palette().get(index) {
                         break color;
                     } else {
-                        break (Fg::Red, Bg::Green);
+                        break (Fg::Red, Bg::Green).text_color();
                     }
                 },
                 Color::Palette(i) => {
@@ -1032,6 +1204,19 @@ impl Window {
         true
     }
 
+    pub fn actual_is_visible(self, tree: &WindowTree) -> bool {
+        let mut window = self;
+        loop {
+            if window.visibility(tree) != Visibility::Visible { return false; }
+            if let Some(parent) = window.parent(tree) {
+                window = parent;
+            } else {
+                break;
+            }
+        }
+        true
+    }
+
     pub fn visibility(self, tree: &WindowTree) -> Visibility {
         tree.arena[self.0].visibility
     }
@@ -1124,7 +1309,7 @@ impl Window {
             }
         }
         if !*handled {
-            if matches!(event, Event::LmbDown(_)) {
+            if matches!(event, Event::LmbDown(..) | Event::RmbDown(..)) {
                 match self.focus_click(tree) {
                     Some(Focus::Primary) => self.set_focused_primary(tree, true),
                     Some(Focus::Secondary) => self.set_focused_secondary(tree, true),
@@ -1356,8 +1541,8 @@ impl Window {
         tree: &mut WindowTree,
         app: &mut dyn App,
     ) {
-        if tree.click == Some(self) {
-            tree.click = None;
+        if let Some(click) = tree.click.as_mut().filter(|x| x.0 == Some(self)) {
+            click.0 = None;
         }
         if tree.next_primary_focused == Some(Some(self)) {
             tree.next_primary_focused = Some(None);
@@ -1371,6 +1556,15 @@ impl Window {
         if tree.secondary_focused == Some(self) {
             tree.focus_secondary_raw(None, app);
         }
+        for modal in &mut tree.modal {
+            if modal.primary_focused == Some(self) {
+                modal.primary_focused = None;
+            }
+            if modal.secondary_focused == Some(self) {
+                modal.secondary_focused = None;
+            }
+        }
+        self.end_modal(tree);
         if let Some(first_child) = self.first_child(tree) {
             let mut child = first_child;
             loop {
@@ -1460,12 +1654,13 @@ impl Window {
         let offset = bounds.tl.offset_from(Point { x: 0, y: 0 });
         let point = point.offset(-offset);
         if let Some(first_child) = self.first_child(tree) {
-            let mut child = first_child;
+            let last_child = first_child.prev(tree);
+            let mut child = last_child;
             loop {
                 let child_hit_test = child.hit_test(point, tree);
                 if child_hit_test.is_some() { return child_hit_test; }
-                child = child.next(tree);
-                if child == first_child { break; }
+                child = child.prev(tree);
+                if child == last_child { break; }
             }
         }
         Some(self)
@@ -1474,6 +1669,59 @@ impl Window {
 
 const FPS: u16 = 40;
 
+/// Time source for [`WindowTree`] timers and frame pacing.
+pub trait Clock {
+    /// Milliseconds elapsed since an arbitrary fixed point.
+    fn time_ms(&self) -> u64;
+
+    fn sleep_ms(&self, ms: u16);
+}
+
+impl<T: Clock + ?Sized> Clock for &T {
+    fn time_ms(&self) -> u64 { (**self).time_ms() }
+
+    fn sleep_ms(&self, ms: u16) { (**self).sleep_ms(ms) }
+}
+
+struct MonoClockAdapter<'clock> {
+    clock: &'clock MonoClock,
+    start: MonoTime,
+}
+
+impl<'clock> Clock for MonoClockAdapter<'clock> {
+    fn time_ms(&self) -> u64 {
+        self.clock.time().delta_ms_u64(self.start).unwrap_or(u64::MAX)
+    }
+
+    fn sleep_ms(&self, ms: u16) {
+        self.clock.sleep_ms_u16(ms);
+    }
+}
+
+/// Manually driven [`Clock`] for deterministic tests.
+///
+/// Time changes only by [`advance_ms`](FakeClock::advance_ms) or by sleeping.
+#[derive(Debug, Default)]
+pub struct FakeClock {
+    ms: Cell<u64>,
+}
+
+impl FakeClock {
+    pub const fn new() -> Self {
+        FakeClock { ms: Cell::new(0) }
+    }
+
+    pub fn advance_ms(&self, ms: u64) {
+        self.ms.set(self.ms.get().saturating_add(ms));
+    }
+}
+
+impl Clock for FakeClock {
+    fn time_ms(&self) -> u64 { self.ms.get() }
+
+    fn sleep_ms(&self, ms: u16) { self.advance_ms(u64::from(ms)); }
+}
+
 pub const COLOR_DISABLED: u8 = 10;
 pub const COLOR_HOTKEY: u8 = 11;
 pub const COLOR_BACKGROUND: u8 = 12;
@@ -1489,25 +1737,55 @@ pub const COLOR_BUTTON_FOCUSED_HOTKEY: u8 = 21;
 pub const COLOR_BUTTON_FOCUSED_DISABLED: u8 = 22;
 pub const COLOR_BUTTON_PRESSED: u8 = 23;
 pub const COLOR_FRAME: u8 = 24;
+pub const COLOR_MENU: u8 = 25;
+pub const COLOR_MENU_HOTKEY: u8 = 26;
+pub const COLOR_MENU_DISABLED: u8 = 27;
+pub const COLOR_MENU_SELECTED: u8 = 28;
+pub const COLOR_MENU_SELECTED_HOTKEY: u8 = 29;
+pub const COLOR_MENU_SELECTED_DISABLED: u8 = 30;
+pub const COLOR_LIST_BOX: u8 = 31;
+pub const COLOR_LIST_BOX_SELECTED: u8 = 32;
+pub const COLOR_LIST_BOX_FOCUSED: u8 = 33;
+pub const COLOR_LIST_BOX_FOCUSED_SELECTED: u8 = 34;
+pub const COLOR_DATA_GRID_HEADER: u8 = 35;
+pub const COLOR_DATA_GRID_HEADER_FOCUSED: u8 = 36;
+pub const COLOR_PROGRESS_BAR: u8 = 37;
+pub const COLOR_TEXT_SELECTION: u8 = 38;
+pub const COLOR_TEXT_MATCH: u8 = 39;
 
 fn root_palette() -> Palette {
     let mut p = Palette::new();
 
-    p.set(COLOR_DISABLED, Color::Value((Fg::DarkGray, Bg::None)));
-    p.set(COLOR_HOTKEY, Color::Value((Fg::White, Bg::None)));
-    p.set(COLOR_BACKGROUND, Color::Value((Fg::LightGray, Bg::None)));
-    p.set(COLOR_LABEL, Color::Value((Fg::LightGray, Bg::None)));
-    p.set(COLOR_INPUT_LINE, Color::Value((Fg::LightGray, Bg::None)));
-    p.set(COLOR_INPUT_LINE_INVALID, Color::Value((Fg::Red, Bg::None)));
-    p.set(COLOR_INPUT_LINE_FOCUSED, Color::Value((Fg::LightGray, Bg::Blue)));
-    p.set(COLOR_INPUT_LINE_FOCUSED_DISABLED, Color::Value((Fg::DarkGray, Bg::Blue)));
-    p.set(COLOR_INPUT_LINE_FOCUSED_INVALID, Color::Value((Fg::LightGray, Bg::Red)));
-    p.set(COLOR_BUTTON, Color::Value((Fg::LightGray, Bg::None)));
-    p.set(COLOR_BUTTON_FOCUSED, Color::Value((Fg::LightGray, Bg::Blue)));
-    p.set(COLOR_BUTTON_FOCUSED_HOTKEY, Color::Value((Fg::White, Bg::Blue)));
-    p.set(COLOR_BUTTON_FOCUSED_DISABLED, Color::Value((Fg::DarkGray, Bg::Blue)));
-    p.set(COLOR_BUTTON_PRESSED, Color::Value((Fg::Blue, Bg::None)));
-    p.set(COLOR_FRAME, Color::Value((Fg::LightGray, Bg::None)));
+    p.set(COLOR_DISABLED, Color::Value((Fg::DarkGray, Bg::None).text_color()));
+    p.set(COLOR_HOTKEY, Color::Value((Fg::White, Bg::None).text_color()));
+    p.set(COLOR_BACKGROUND, Color::Value((Fg::LightGray, Bg::None).text_color()));
+    p.set(COLOR_LABEL, Color::Value((Fg::LightGray, Bg::None).text_color()));
+    p.set(COLOR_INPUT_LINE, Color::Value((Fg::LightGray, Bg::None).text_color()));
+    p.set(COLOR_INPUT_LINE_INVALID, Color::Value((Fg::Red, Bg::None).text_color()));
+    p.set(COLOR_INPUT_LINE_FOCUSED, Color::Value((Fg::LightGray, Bg::Blue).text_color()));
+    p.set(COLOR_INPUT_LINE_FOCUSED_DISABLED, Color::Value((Fg::DarkGray, Bg::Blue).text_color()));
+    p.set(COLOR_INPUT_LINE_FOCUSED_INVALID, Color::Value((Fg::LightGray, Bg::Red).text_color()));
+    p.set(COLOR_BUTTON, Color::Value((Fg::LightGray, Bg::None).text_color()));
+    p.set(COLOR_BUTTON_FOCUSED, Color::Value((Fg::LightGray, Bg::Blue).text_color()));
+    p.set(COLOR_BUTTON_FOCUSED_HOTKEY, Color::Value((Fg::White, Bg::Blue).text_color()));
+    p.set(COLOR_BUTTON_FOCUSED_DISABLED, Color::Value((Fg::DarkGray, Bg::Blue).text_color()));
+    p.set(COLOR_BUTTON_PRESSED, Color::Value((Fg::Blue, Bg::None).text_color()));
+    p.set(COLOR_FRAME, Color::Value((Fg::LightGray, Bg::None).text_color()));
+    p.set(COLOR_MENU, Color::Value((Fg::Black, Bg::LightGray).text_color()));
+    p.set(COLOR_MENU_HOTKEY, Color::Value((Fg::Red, Bg::LightGray).text_color()));
+    p.set(COLOR_MENU_DISABLED, Color::Value((Fg::DarkGray, Bg::LightGray).text_color()));
+    p.set(COLOR_MENU_SELECTED, Color::Value((Fg::Black, Bg::Green).text_color()));
+    p.set(COLOR_MENU_SELECTED_HOTKEY, Color::Value((Fg::Red, Bg::Green).text_color()));
+    p.set(COLOR_MENU_SELECTED_DISABLED, Color::Value((Fg::DarkGray, Bg::Green).text_color()));
+    p.set(COLOR_LIST_BOX, Color::Value((Fg::LightGray, Bg::None).text_color()));
+    p.set(COLOR_LIST_BOX_SELECTED, Color::Value((Fg::Black, Bg::Cyan).text_color()));
+    p.set(COLOR_LIST_BOX_FOCUSED, Color::Value((Fg::LightGray, Bg::Blue).text_color()));
+    p.set(COLOR_LIST_BOX_FOCUSED_SELECTED, Color::Value((Fg::White, Bg::Blue).text_color()));
+    p.set(COLOR_DATA_GRID_HEADER, Color::Value((Fg::Black, Bg::LightGray).text_color()));
+    p.set(COLOR_DATA_GRID_HEADER_FOCUSED, Color::Value((Fg::Black, Bg::Green).text_color()));
+    p.set(COLOR_PROGRESS_BAR, Color::Value((Fg::Cyan, Bg::Blue).text_color()));
+    p.set(COLOR_TEXT_SELECTION, Color::Value((Fg::Black, Bg::Cyan).text_color()));
+    p.set(COLOR_TEXT_MATCH, Color::Value((Fg::Black, Bg::Brown).text_color()));
 
     p
 }
@@ -1515,7 +1793,7 @@ fn root_palette() -> Palette {
 macro_attr! {
     #[derive(Component!)]
     struct TimerData {
-        start: MonoTime,
+        start: u64,
         span_ms: u16,
         alarm: Box<dyn FnOnce(&mut WindowTree, &mut dyn App)>,
     }
@@ -1533,7 +1811,7 @@ impl Timer {
         span_ms: u16,
         alarm: Box<dyn FnOnce(&mut WindowTree, &mut dyn App)>
     ) -> Self {
-        let start = tree.clock.time();
+        let start = tree.clock.time_ms();
         tree.timers.insert(move |id| (TimerData {
             start,
             span_ms,
@@ -1552,6 +1830,12 @@ macro_attr! {
     struct PrePostProcess(Window);
 }
 
+struct Modal {
+    window: Window,
+    primary_focused: Option<Window>,
+    secondary_focused: Option<Window>,
+}
+
 pub struct WindowTree<'clock> {
     screen: Option<Box<dyn Screen>>,
     arena: Arena<WindowNode>,
@@ -1560,20 +1844,41 @@ pub struct WindowTree<'clock> {
     secondary_focused: Option<Window>,
     next_primary_focused: Option<Option<Window>>,
     next_secondary_focused: Option<Option<Window>>,
-    click: Option<Window>,
+    /// The window that got the last mouse button press, or `None` if it was dropped or blocked
+    /// by a modal window before the button was released.
+    click: Option<(Option<Window>, MouseButton)>,
     cursor: Option<Point>,
     quit: bool,
     timers: Arena<TimerData>,
-    clock: &'clock MonoClock,
+    clock: Box<dyn Clock + 'clock>,
     palette: Palette,
     pre_process: Arena<PrePostProcess>,
     post_process: Arena<PrePostProcess>,
+    modal: Vec<Modal>,
+    clipboard: Option<Box<dyn Clipboard>>,
+    local_clipboard: LocalClipboard,
+    pasted_text: String,
 }
 
 impl<'clock> WindowTree<'clock> {
     pub fn new(
         screen: Box<dyn Screen>,
         clock: &'clock MonoClock,
+    ) -> Result<Self, Error> {
+        Self::new_with_clock_raw(screen, Box::new(MonoClockAdapter { clock, start: clock.time() }))
+    }
+
+    /// Creates a tree driven by a custom clock, e.g. [`FakeClock`].
+    pub fn new_with_clock(
+        screen: Box<dyn Screen>,
+        clock: &'clock dyn Clock,
+    ) -> Result<Self, Error> {
+        Self::new_with_clock_raw(screen, Box::new(clock))
+    }
+
+    fn new_with_clock_raw(
+        screen: Box<dyn Screen>,
+        clock: Box<dyn Clock + 'clock>,
     ) -> Result<Self, Error> {
         Ok(WindowTree {
             screen: Some(screen),
@@ -1591,6 +1896,10 @@ impl<'clock> WindowTree<'clock> {
             palette: root_palette(),
             pre_process: Arena::new(),
             post_process: Arena::new(),
+            modal: Vec::new(),
+            clipboard: None,
+            local_clipboard: LocalClipboard::new(),
+            pasted_text: String::new(),
         })
     }
 
@@ -1617,10 +1926,41 @@ impl<'clock> WindowTree<'clock> {
 
     pub fn secondary_focused(&self) -> Option<Window> { self.secondary_focused }
 
+    pub fn modal(&self) -> Option<Window> { self.modal.last().map(|x| x.window) }
+
+    pub fn screen_size(&self) -> Vector {
+        self.screen.as_ref().expect("WindowTree is in invalid state").size()
+    }
+
+    pub fn is_unicode(&self) -> bool {
+        self.screen.as_ref().expect("WindowTree is in invalid state").is_unicode()
+    }
+
     pub fn quit(&mut self) {
         self.quit = true;
     }
 
+    /// Returns the text of the [`Event::Paste`] being raised, with `\n` line breaks.
+    pub fn pasted_text(&self) -> &str { &self.pasted_text }
+
+    /// Returns the clipboard used by text editing widgets: the one set with
+    /// [`set_clipboard`](WindowTree::set_clipboard) if any, otherwise the screen backend clipboard,
+    /// or an in-process clipboard if the backend has none.
+    pub fn clipboard(&mut self) -> &mut dyn Clipboard {
+        let screen = self.screen.as_mut().expect("WindowTree is in invalid state");
+        if let Some(clipboard) = self.clipboard.as_deref_mut() {
+            clipboard
+        } else if let Some(clipboard) = screen.clipboard() {
+            clipboard
+        } else {
+            &mut self.local_clipboard
+        }
+    }
+
+    pub fn set_clipboard(&mut self, clipboard: Option<Box<dyn Clipboard>>) {
+        self.clipboard = clipboard;
+    }
+
     fn screen(&mut self) -> &mut dyn Screen {
         self.screen.as_mut().expect("WindowTree is in invalid state").as_mut()
     }
@@ -1664,42 +2004,66 @@ impl<'clock> WindowTree<'clock> {
         app: &mut dyn App,
         mut action: Option<Box<dyn FnMut(&mut WindowTree, &mut dyn App) -> Result<(), Error>>>
     ) -> Result<(), Error> {
-        let mut time = self.clock.time();
+        let mut time = self.clock.time_ms();
         while !self.quit {
-            if let Some(next_primary_focused) = self.next_primary_focused.take() {
-                self.focus_primary_raw(next_primary_focused, app);
-            }
-            if let Some(next_secondary_focused) = self.next_secondary_focused.take() {
-                self.focus_secondary_raw(next_secondary_focused, app);
-            }
             let no_timers = self.timers.items().is_empty() && action.is_none();
-            let timers_time = self.clock.time();
-            loop {
-                let timer = self.timers.items().iter()
-                    .find(|(_, data)| timers_time.delta_ms_u16(data.start).unwrap_or(u16::MAX) >= data.span_ms)
-                    .map(|(id, _)| id)
-                ;
-                if let Some(timer) = timer {
-                    let alarm = self.timers.remove(timer).alarm;
-                    alarm(self, app);
-                } else {
-                    break;
-                }
-            }
+            self.process_timers(app);
             action.as_mut().map_or(Ok(()), |x| x(self, app))?;
             if no_timers {
                 self.update(true, app)?;
             } else {
-                let ms = time.split_ms_u16(self.clock).unwrap_or(u16::MAX);
+                let ms = u16::try_from(self.clock.time_ms().saturating_sub(time)).unwrap_or(u16::MAX);
+                time = self.clock.time_ms();
                 self.update(false, app)?;
                 assert!(FPS != 0 && u16::MAX / FPS > 8);
-                self.clock.sleep_ms_u16((1000 / FPS).saturating_sub(ms));
+                self.clock.sleep_ms((1000 / FPS).saturating_sub(ms));
             }
         }
         Ok(())
     }
 
-    fn update(&mut self, wait: bool, app: &mut dyn App) -> Result<(), Error> {
+    /// Processes one frame without waiting: fires due timers, renders, and handles at most one input event.
+    ///
+    /// Returns `false` if there was nothing to do.
+    pub fn step(&mut self, app: &mut dyn App) -> Result<bool, Error> {
+        let timers_fired = self.process_timers(app);
+        let event_received = self.update(false, app)?;
+        Ok(timers_fired || event_received)
+    }
+
+    /// Calls [`step`](WindowTree::step) until there is nothing to do or [`quit`](WindowTree::quit) is requested.
+    pub fn run_until_idle(&mut self, app: &mut dyn App) -> Result<(), Error> {
+        while !self.quit && self.step(app)? { }
+        Ok(())
+    }
+
+    fn process_timers(&mut self, app: &mut dyn App) -> bool {
+        if let Some(next_primary_focused) = self.next_primary_focused.take() {
+            self.focus_primary_raw(next_primary_focused, app);
+        }
+        if let Some(next_secondary_focused) = self.next_secondary_focused.take() {
+            self.focus_secondary_raw(next_secondary_focused, app);
+        }
+        let timers_time = self.clock.time_ms();
+        let mut fired = false;
+        loop {
+            let timer = self.timers.items().iter()
+                .filter(|(_, data)| timers_time.saturating_sub(data.start) >= u64::from(data.span_ms))
+                .min_by_key(|(_, data)| data.start.saturating_add(u64::from(data.span_ms)))
+                .map(|(id, _)| id)
+            ;
+            if let Some(timer) = timer {
+                let alarm = self.timers.remove(timer).alarm;
+                alarm(self, app);
+                fired = true;
+            } else {
+                break;
+            }
+        }
+        fired
+    }
+
+    fn update(&mut self, wait: bool, app: &mut dyn App) -> Result<bool, Error> {
         let screen = self.screen.as_mut().expect("WindowTree is in invalid state");
         let screen_size = screen.size();
         if let Some(first_child) = self.first_child {
@@ -1721,28 +2085,32 @@ impl<'clock> WindowTree<'clock> {
             let mut child = first_child;
             loop {
                 let bounds = self.arena[child.0].window_bounds;
-                let offset = bounds.tl.offset_from(Point { x: 0, y: 0 });
-                self.render_window(child, offset, bounds, app);
+                self.render_window(child, Vector::null(), bounds, app);
                 child = child.next(self);
                 if child == first_child { break; }
             }
         }
         let screen = self.screen.as_mut().expect("WindowTree is in invalid state");
-        match screen.update(self.cursor, wait)? {
-            Some(screen_Event::Key(n, key)) => {
+        let event = screen.update(self.cursor, wait)?;
+        let event_received = event.is_some();
+        match event {
+            Some(screen_Event::Key(n, key, mods)) => {
                 for _ in 0 .. n.get() {
                     let mut handled = false;
                     for pre_process in self.pre_process.items().clone().values() {
-                        handled = pre_process.0.raise_core(self, Event::PreProcessKey(key), pre_process.0, app);
+                        if !self.accepts_input(pre_process.0) || !pre_process.0.actual_is_visible(self) {
+                            continue;
+                        }
+                        handled = pre_process.0.raise_core(self, Event::PreProcessKey(key, mods), pre_process.0, app);
                         if handled { break; }
                     }
                     if handled { continue; }
                     handled = self.primary_focused.map_or(false, |x|
-                        x.raise_priv(self, Event::Key(key), false, app)
+                        x.raise_priv(self, Event::Key(key, mods), false, app)
                     );
                     if handled { continue; }
                     handled = self.secondary_focused.map_or(false, |x|
-                        x.raise_priv(self, Event::Key(key), true, app)
+                        x.raise_priv(self, Event::Key(key, mods), true, app)
                     );
                     if handled {
                         self.primary_focused.map(|x|
@@ -1751,12 +2119,27 @@ impl<'clock> WindowTree<'clock> {
                         continue;
                     }
                     for post_process in self.post_process.items().clone().values() {
+                        if !self.accepts_input(post_process.0) || !post_process.0.actual_is_visible(self) {
+                            continue;
+                        }
                         handled =
-                            post_process.0.raise_core(self, Event::PostProcessKey(key), post_process.0, app);
+                            post_process.0.raise_core(self, Event::PostProcessKey(key, mods), post_process.0, app);
                         if handled { break; }
                     }
                     if handled { continue; }
                     match key {
+                        Key::F10 if mods == Mods::SHIFT => {
+                            if let Some(primary_focused) = self.primary_focused {
+                                let point = primary_focused.screen_bounds(self).tl;
+                                if self.open_context_menu(primary_focused, point, app)? { continue; }
+                            }
+                        },
+                        Key::Tab if mods == Mods::SHIFT => {
+                            if let Some(primary_focused) = self.primary_focused {
+                                let focus = primary_focused.focus_back_tab(self);
+                                if self.focus_primary(focus) { continue; }
+                            }
+                        },
                         Key::Tab => {
                             if let Some(primary_focused) = self.primary_focused {
                                 let focus = primary_focused.focus_tab(self);
@@ -1807,33 +2190,98 @@ impl<'clock> WindowTree<'clock> {
                     }
                 }
             },
-            Some(screen_Event::LmbUp(point)) => {
-                if let Some(window) = self.click.take() {
-                    window.raise_priv(self, Event::LmbUp, false, app);
+            Some(screen_Event::MouseUp(point, button, mods)) => {
+                if let Some((window, click_button)) = self.click.filter(|x| x.1 == button) {
+                    self.click = None;
+                    window.map(|x| x.raise_priv(self, Event::mouse_up(click_button), false, app));
                 } else if let Some(window) = self.hit_test(point) {
-                    window.raise_priv(self, Event::LmbDown(point), false, app);
-                    window.raise_priv(self, Event::LmbUp, false, app);
+                    window.raise_priv(self, Event::mouse_down(point, button, mods), false, app);
+                    window.raise_priv(self, Event::mouse_up(button), false, app);
                 }
             },
-            Some(screen_Event::LmbDown(point)) => {
+            Some(screen_Event::MouseDown(point, button, mods)) => {
                 if let Some(window) = self.hit_test(point) {
-                    self.click = Some(window);
-                    window.raise_priv(self, Event::LmbDown(point), false, app);
+                    self.click = Some((Some(window), button));
+                    let handled = window.raise_priv(self, Event::mouse_down(point, button, mods), false, app);
+                    if !handled && button == MouseButton::Right {
+                        self.open_context_menu(window, point, app)?;
+                    }
                 }
             },
+            Some(screen_Event::DoubleClick(point, button, mods)) => {
+                if let Some(window) = self.hit_test(point) {
+                    self.click = Some((Some(window), button));
+                    window.raise_priv(self, Event::mouse_down(point, button, mods), false, app);
+                    window.raise_priv(self, Event::DoubleClick(point, button, mods), false, app);
+                }
+            },
+            Some(screen_Event::MouseMove(point, button, mods)) => {
+                if let Some((window, click_button)) = self.click.filter(|_| button.is_some()) {
+                    window.map(|x| x.raise_priv(self, Event::Drag(point, click_button, mods), false, app));
+                } else if let Some(window) = self.hit_test(point) {
+                    window.raise_priv(self, Event::MouseMove(point, mods), false, app);
+                }
+            },
+            Some(screen_Event::Wheel(point, delta, mods)) => {
+                if let Some(window) = self.hit_test(point) {
+                    window.raise_priv(self, Event::Wheel(point, delta, mods), false, app);
+                }
+            },
+            Some(screen_Event::Paste(text)) => {
+                self.pasted_text = text;
+                self.primary_focused.map(|x| x.raise_priv(self, Event::Paste, false, app));
+                self.pasted_text.clear();
+            },
             _ => { }
         }
-        Ok(())
+        Ok(event_received)
+    }
+
+    fn open_context_menu(&mut self, window: Window, point: Point, app: &mut dyn App) -> Result<bool, Error> {
+        let mut owner = window;
+        let template = loop {
+            if let Some(template) = owner.context_menu(self) { break template; }
+            let Some(parent) = owner.parent(self) else { return Ok(false); };
+            owner = parent;
+        };
+        if !owner.actual_is_enabled(self) { return Ok(false); }
+        let Some(widget) = template.widget_extension::<dyn ContextMenuWidgetExtension>(self) else {
+            return Ok(false);
+        };
+        widget.open_context_menu(self, template, owner, point, app)?;
+        Ok(true)
+    }
+
+    fn accepts_input(&self, window: Window) -> bool {
+        let Some(modal) = self.modal() else { return true; };
+        let mut root = window;
+        loop {
+            if root == modal { return true; }
+            let Some(parent) = root.parent(self) else { break; };
+            root = parent;
+        }
+        let mut modal_root = modal;
+        while let Some(parent) = modal_root.parent(self) {
+            modal_root = parent;
+        }
+        let first_child = self.first_child.unwrap();
+        let mut child = modal_root.next(self);
+        while child != first_child {
+            if child == root { return true; }
+            child = child.next(self);
+        }
+        false
     }
 
     fn hit_test(&self, point: Point) -> Option<Window> {
         if let Some(first_child) = self.first_child {
-            let mut child = first_child;
+            let last_child = first_child.prev(self);
+            let mut child = last_child;
             loop {
                 let hit_test = child.hit_test(point, self);
-                if hit_test.is_some() { return hit_test; }
-                child = child.next(self);
-                if child == first_child { break; }
+                if hit_test.is_some() { return hit_test.filter(|&x| self.accepts_input(x)); }
+                child = child.prev(self);
+                if child == last_child { break; }
             }
         }
         None
@@ -1855,6 +2303,7 @@ impl<'clock> WindowTree<'clock> {
     ) {
         let old_focused = self.primary_focused;
         if window == old_focused { return; }
+        if window.is_some_and(|x| !self.accepts_input(x)) { return; }
         window.map(|x| x.raise(self, Event::Cmd(CMD_GOT_PRIMARY_FOCUS), app));
 
         if let Some(mut window) = self.primary_focused {
@@ -1903,9 +2352,183 @@ impl<'clock> WindowTree<'clock> {
         if window == old_focused { return; }
         let focusable = window.map_or(true, |x| self.arena[x.0].widget.secondary_focusable());
         if !focusable { return; }
+        if window.is_some_and(|x| !self.accepts_input(x)) { return; }
         window.map(|x| x.raise(self, Event::Cmd(CMD_GOT_SECONDARY_FOCUS), app));
         self.secondary_focused = window;
         old_focused.map(|x| x.raise(self, Event::Cmd(CMD_LOST_SECONDARY_FOCUS), app));
         window.map(|x| x.bring_into_view(self));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use dynamic_cast::impl_supports_interfaces;
+    use tuifw_screen_test::Screen as TestScreen;
+
+    struct TestApp;
+
+    impl App for TestApp { }
+
+    struct Label {
+        text: String,
+    }
+
+    impl WidgetData for Label { }
+
+    #[derive(Clone)]
+    struct LabelWidget;
+
+    impl_supports_interfaces!(LabelWidget);
+
+    impl Widget for LabelWidget {
+        fn new(&self) -> Box<dyn WidgetData> {
+            Box::new(Label { text: String::new() })
+        }
+
+        fn clone_data(
+            &self,
+            _tree: &mut WindowTree,
+            _source: Window,
+            _dest: Window,
+            _clone_window: Box<dyn Fn(&WindowTree, Window) -> Window>,
+        ) { }
+
+        fn render(
+            &self,
+            tree: &WindowTree,
+            window: Window,
+            rp: &mut RenderPort,
+            _app: &mut dyn App,
+        ) {
+            let color = window.color(tree, 0);
+            rp.fill_bg(color);
+            rp.label(Point { x: 0, y: 0 }, color, window.color(tree, 1), &window.data::<Label>(tree).text);
+        }
+
+        fn measure(
+            &self,
+            tree: &mut WindowTree,
+            window: Window,
+            available_width: Option<i16>,
+            available_height: Option<i16>,
+            app: &mut dyn App,
+        ) -> Vector {
+            let text = &window.data::<Label>(tree).text;
+            let mut size = Vector { x: text_width(&text.replace('~', "")), y: 1 };
+            if let Some(first_child) = window.first_child(tree) {
+                let mut child = first_child;
+                loop {
+                    child.measure(tree, available_width, available_height, app);
+                    size = size.max(child.desired_size(tree));
+                    child = child.next(tree);
+                    if child == first_child { break; }
+                }
+            }
+            size
+        }
+
+        fn arrange(
+            &self,
+            tree: &mut WindowTree,
+            window: Window,
+            final_inner_bounds: Rect,
+            app: &mut dyn App,
+        ) -> Vector {
+            if let Some(first_child) = window.first_child(tree) {
+                let mut child = first_child;
+                loop {
+                    child.arrange(tree, final_inner_bounds, app);
+                    child = child.next(tree);
+                    if child == first_child { break; }
+                }
+            }
+            final_inner_bounds.size
+        }
+
+        fn update(
+            &self,
+            _tree: &mut WindowTree,
+            _window: Window,
+            _event: Event,
+            _event_source: Window,
+            _app: &mut dyn App,
+        ) -> bool {
+            false
+        }
+    }
+
+    fn label(
+        tree: &mut WindowTree,
+        parent: Option<Window>,
+        prev: Option<Window>,
+        text: &str
+    ) -> Window {
+        let window = Window::new(tree, Box::new(LabelWidget), parent, prev).unwrap();
+        window.data_mut::<Label>(tree).text = text.to_string();
+        window
+    }
+
+    #[test]
+    fn render_golden() {
+        let screen = TestScreen::new(Vector { x: 16, y: 5 });
+        let handle = screen.handle();
+        let clock = FakeClock::new();
+        let tree = &mut WindowTree::new_with_clock(Box::new(screen), &clock).unwrap();
+        let root = label(tree, None, None, "");
+        root.set_color(tree, 0, (Fg::LightGray, Bg::Blue));
+        root.set_color(tree, 1, (Fg::Yellow, Bg::Blue));
+        let title = label(tree, Some(root), None, "~T~itle");
+        title.set_h_align(tree, Some(HAlign::Center));
+        title.set_v_align(tree, Some(VAlign::Top));
+        let text = label(tree, Some(root), Some(title), "Text");
+        text.set_h_align(tree, Some(HAlign::Left));
+        text.set_v_align(tree, Some(VAlign::Top));
+        text.set_margin(tree, Thickness::new(2, 2, 0, 0));
+        text.set_color(tree, 0, (Fg::Black, Bg::Green));
+        let hidden = label(tree, Some(root), Some(text), "Hidden");
+        hidden.set_visibility(tree, Visibility::Hidden);
+        let app = &mut TestApp;
+        tree.run_until_idle(app).unwrap();
+        handle.assert_golden(concat!(env!("CARGO_MANIFEST_DIR"), "/golden/render.txt"));
+    }
+
+    struct TimerApp {
+        fired: Vec<&'static str>,
+    }
+
+    impl App for TimerApp { }
+
+    fn timer(tree: &mut WindowTree, span_ms: u16, name: &'static str) -> Timer {
+        Timer::new(tree, span_ms, Box::new(move |_, app| app.downcast_mut::<TimerApp>().unwrap().fired.push(name)))
+    }
+
+    #[test]
+    fn timers_fire_in_deadline_order() {
+        let clock = FakeClock::new();
+        let tree = &mut WindowTree::new_with_clock(Box::new(TestScreen::new(Vector { x: 8, y: 2 })), &clock).unwrap();
+        let app = &mut TimerApp { fired: Vec::new() };
+        timer(tree, 30, "a");
+        timer(tree, 10, "b");
+        timer(tree, 20, "c");
+        timer(tree, 25, "dropped").drop_timer(tree);
+        Timer::new(tree, 40, Box::new(|tree, _| { timer(tree, 5, "rearmed"); }));
+        assert!(!tree.step(app).unwrap());
+        clock.advance_ms(9);
+        assert!(!tree.step(app).unwrap());
+        assert!(app.fired.is_empty());
+        clock.advance_ms(1);
+        assert!(tree.step(app).unwrap());
+        assert_eq!(app.fired, ["b"]);
+        clock.advance_ms(30);
+        assert!(tree.step(app).unwrap());
+        assert_eq!(app.fired, ["b", "c", "a"]);
+        clock.advance_ms(4);
+        tree.run_until_idle(app).unwrap();
+        assert_eq!(app.fired, ["b", "c", "a"]);
+        clock.advance_ms(1);
+        tree.run_until_idle(app).unwrap();
+        assert_eq!(app.fired, ["b", "c", "a", "rearmed"]);
+    }
+}
@@ -12,7 +12,7 @@ pub mod preprocessor;
 use indent::indent_all_by;
 use indoc::indoc;
 use std::str::FromStr;
-use tuifw_screen_base::{Bg, Fg};
+use tuifw_screen_base::{Attr, ExtBg, ExtFg};
 use xaml::*;
 
 pub const XMLNS: &str = "https://a1-triard.github.io/tuifw/2023/xaml";
@@ -23,9 +23,20 @@ pub fn set_widget_ctor(
     widget_name: &'static str,
     widget_children: XamlProperty,
 ) {
+    set_child_widget_ctor(xaml, widget, widget_name, &[widget_children]);
+}
+
+/// Like [`set_widget_ctor`], but the widget can be a child through any of the `children` properties.
+pub fn set_child_widget_ctor(
+    xaml: &mut Xaml,
+    widget: XamlStruct,
+    widget_name: &'static str,
+    children: &[XamlProperty],
+) {
+    let children = children.to_vec();
     widget.set_ctor(xaml, Some(Box::new(move |obj, parent, prev| {
         if let Some((parent, parent_property)) = parent {
-            if parent_property == widget_children {
+            if children.contains(&parent_property) {
                 if let Some(prev) = prev {
                     indent_all_by(4, format!(indoc! { "
                         #[allow(unused_variables)]
@@ -52,6 +63,74 @@ pub fn set_widget_ctor(
     })));
 }
 
+fn accelerator_key(s: &str) -> Option<(String, String)> {
+    let mut parts = s.split('+').collect::<Vec<_>>();
+    let key = parts.pop()?;
+    let mut ctrl = false;
+    let mut alt = false;
+    let mut shift = false;
+    for part in parts {
+        match part {
+            "Ctrl" if !ctrl => ctrl = true,
+            "Alt" if !alt => alt = true,
+            "Shift" if !shift => shift = true,
+            _ => return None,
+        }
+    }
+    let mut chars = key.chars();
+    let key = if let (Some(c), None) = (chars.next(), chars.next()) {
+        if ctrl {
+            const CTRL: &str = "ABCDEFGJKLNOPQRSTUVWXYZ";
+            let c = c.to_ascii_uppercase();
+            if !CTRL.contains(c) { return None; }
+            ctrl = false;
+            format!("tuifw_screen_base::Key::Ctrl(tuifw_screen_base::Ctrl::{c})")
+        } else if alt {
+            alt = false;
+            format!("tuifw_screen_base::Key::Alt({:?})", c.to_ascii_lowercase())
+        } else {
+            format!("tuifw_screen_base::Key::Char({c:?})")
+        }
+    } else {
+        match key {
+            "Enter" | "Escape" | "Tab" | "Backspace" | "Delete" | "Insert" | "Home" | "End" |
+            "PageUp" | "PageDown" | "Up" | "Down" | "Left" | "Right" => { },
+            _ => {
+                let n = key.strip_prefix('F').and_then(|x| u8::from_str(x).ok())?;
+                if !(1 ..= 24).contains(&n) { return None; }
+            },
+        }
+        format!("tuifw_screen_base::Key::{key}")
+    };
+    let mut mods = Vec::new();
+    if shift { mods.push("tuifw_screen_base::Mods::SHIFT"); }
+    if alt { mods.push("tuifw_screen_base::Mods::ALT"); }
+    if ctrl { mods.push("tuifw_screen_base::Mods::CTRL"); }
+    let mods = if mods.is_empty() { "tuifw_screen_base::Mods::NONE".to_string() } else { mods.join(" | ") };
+    Some((key, mods))
+}
+
+/// Splits a `StringList` literal into items separated by `;`. A doubled `;;` stands for a literal semicolon.
+/// An empty (or blank) literal is an empty list.
+fn string_list_items(s: &str) -> Vec<String> {
+    let mut items = Vec::new();
+    if s.trim().is_empty() { return items; }
+    let mut item = String::new();
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != ';' {
+            item.push(c);
+        } else if chars.next_if_eq(&';').is_some() {
+            item.push(';');
+        } else {
+            items.push(item.trim().to_string());
+            item.clear();
+        }
+    }
+    items.push(item.trim().to_string());
+    items
+}
+
 pub struct Registered {
     pub boolean: XamlLiteral,
     pub string: XamlLiteral,
@@ -65,9 +144,14 @@ pub struct Registered {
     pub h_align: XamlLiteral,
     pub v_align: XamlLiteral,
     pub dock: XamlLiteral,
+    pub grid_lengths: XamlLiteral,
+    pub line_align: XamlLiteral,
+    pub text_wrap: XamlLiteral,
+    pub accelerator: XamlLiteral,
     pub focus: XamlLiteral,
     pub visibility: XamlLiteral,
     pub color: XamlLiteral,
+    pub string_list: XamlLiteral,
 
     pub validator: XamlStruct,
 
@@ -83,11 +167,13 @@ pub struct Registered {
     pub widget_children: XamlProperty,
     pub widget_name: XamlProperty,
     pub widget_focus_tab: XamlProperty,
+    pub widget_focus_back_tab: XamlProperty,
     pub widget_focus_right: XamlProperty,
     pub widget_focus_left: XamlProperty,
     pub widget_focus_up: XamlProperty,
     pub widget_focus_down: XamlProperty,
     pub widget_focus_click: XamlProperty,
+    pub widget_context_menu: XamlProperty,
     pub widget_focused_primary: XamlProperty,
     pub widget_focused_secondary: XamlProperty,
     pub widget_h_align: XamlProperty,
@@ -126,6 +212,21 @@ pub struct Registered {
     pub widget_color_button_focused_disabled: XamlProperty,
     pub widget_color_button_pressed: XamlProperty,
     pub widget_color_frame: XamlProperty,
+    pub widget_color_menu: XamlProperty,
+    pub widget_color_menu_hotkey: XamlProperty,
+    pub widget_color_menu_disabled: XamlProperty,
+    pub widget_color_menu_selected: XamlProperty,
+    pub widget_color_menu_selected_hotkey: XamlProperty,
+    pub widget_color_menu_selected_disabled: XamlProperty,
+    pub widget_color_list_box: XamlProperty,
+    pub widget_color_list_box_selected: XamlProperty,
+    pub widget_color_list_box_focused: XamlProperty,
+    pub widget_color_list_box_focused_selected: XamlProperty,
+    pub widget_color_data_grid_header: XamlProperty,
+    pub widget_color_data_grid_header_focused: XamlProperty,
+    pub widget_color_progress_bar: XamlProperty,
+    pub widget_color_text_selection: XamlProperty,
+    pub widget_color_text_match: XamlProperty,
 
     pub background: XamlStruct,
     pub background_show_pattern: XamlProperty,
@@ -145,6 +246,20 @@ pub struct Registered {
     pub canvas: XamlStruct,
     pub widget_tl: XamlProperty,
 
+    pub grid: XamlStruct,
+    pub grid_rows: XamlProperty,
+    pub grid_columns: XamlProperty,
+    pub widget_row: XamlProperty,
+    pub widget_column: XamlProperty,
+    pub widget_row_span: XamlProperty,
+    pub widget_column_span: XamlProperty,
+
+    pub wrap_panel: XamlStruct,
+    pub wrap_panel_vertical: XamlProperty,
+    pub wrap_panel_item_spacing: XamlProperty,
+    pub wrap_panel_line_spacing: XamlProperty,
+    pub wrap_panel_line_align: XamlProperty,
+
     pub static_text: XamlStruct,
     pub static_text_text: XamlProperty,
 
@@ -154,10 +269,20 @@ pub struct Registered {
     pub input_line: XamlStruct,
     pub input_line_text: XamlProperty,
     pub input_line_validator: XamlProperty,
+    pub input_line_undo_depth: XamlProperty,
 
     pub text_edit: XamlStruct,
     pub text_edit_text: XamlProperty,
     pub text_edit_line_break: XamlProperty,
+    pub text_edit_undo_depth: XamlProperty,
+    pub text_edit_wrap: XamlProperty,
+    pub text_edit_search: XamlProperty,
+    pub text_edit_search_case_sensitive: XamlProperty,
+    pub text_edit_search_whole_word: XamlProperty,
+    pub text_edit_search_regex: XamlProperty,
+
+    pub find_bar: XamlStruct,
+    pub find_bar_text_edit: XamlProperty,
 
     pub frame: XamlStruct,
     pub frame_double: XamlProperty,
@@ -195,6 +320,61 @@ pub struct Registered {
     pub virt_items_presenter_item_template: XamlProperty,
     pub virt_items_presenter_tab_navigation: XamlProperty,
     pub virt_items_presenter_up_down_navigation: XamlProperty,
+
+    pub menu_bar: XamlStruct,
+
+    pub menu: XamlStruct,
+    pub menu_text: XamlProperty,
+
+    pub menu_item: XamlStruct,
+    pub menu_item_text: XamlProperty,
+    pub menu_item_shortcut: XamlProperty,
+    pub menu_item_accelerator: XamlProperty,
+    pub menu_item_is_checked: XamlProperty,
+    pub menu_item_cmd: XamlProperty,
+
+    pub menu_separator: XamlStruct,
+
+    pub list_box: XamlStruct,
+    pub list_box_multiple: XamlProperty,
+
+    pub combo_box: XamlStruct,
+    pub combo_box_items: XamlProperty,
+    pub combo_box_is_editable: XamlProperty,
+    pub combo_box_validator: XamlProperty,
+
+    pub tab_control: XamlStruct,
+    pub tab_control_children: XamlProperty,
+
+    pub tab_item: XamlStruct,
+    pub tab_item_header: XamlProperty,
+
+    pub progress_bar: XamlStruct,
+    pub progress_bar_value: XamlProperty,
+    pub progress_bar_min: XamlProperty,
+    pub progress_bar_max: XamlProperty,
+    pub progress_bar_show_percentage: XamlProperty,
+    pub progress_bar_is_indeterminate: XamlProperty,
+
+    pub spinner: XamlStruct,
+    pub spinner_is_active: XamlProperty,
+
+    pub slider: XamlStruct,
+    pub slider_vertical: XamlProperty,
+    pub slider_min: XamlProperty,
+    pub slider_max: XamlProperty,
+    pub slider_step: XamlProperty,
+    pub slider_page_step: XamlProperty,
+    pub slider_value: XamlProperty,
+
+    pub spin_box: XamlStruct,
+    pub spin_box_validator: XamlProperty,
+    pub spin_box_step: XamlProperty,
+    pub spin_box_page_step: XamlProperty,
+    pub spin_box_value: XamlProperty,
+
+    pub scroll_bar: XamlStruct,
+    pub scroll_bar_vertical: XamlProperty,
 }
 
 pub fn reg_widgets(xaml: &mut Xaml) -> Registered {
@@ -210,9 +390,14 @@ pub fn reg_widgets(xaml: &mut Xaml) -> Registered {
     let h_align = XamlLiteral::new(xaml, XMLNS, "HAlign");
     let v_align = XamlLiteral::new(xaml, XMLNS, "VAlign");
     let dock = XamlLiteral::new(xaml, XMLNS, "Dock");
+    let grid_lengths = XamlLiteral::new(xaml, XMLNS, "GridLengths");
+    let line_align = XamlLiteral::new(xaml, XMLNS, "LineAlign");
+    let text_wrap = XamlLiteral::new(xaml, XMLNS, "TextWrap");
+    let accelerator = XamlLiteral::new(xaml, XMLNS, "Accelerator");
     let focus = XamlLiteral::new(xaml, XMLNS, "Focus");
     let visibility = XamlLiteral::new(xaml, XMLNS, "Visibility");
     let color = XamlLiteral::new(xaml, XMLNS, "Color");
+    let string_list = XamlLiteral::new(xaml, XMLNS, "StringList");
 
     let validator = XamlStruct::new(xaml, None, XMLNS, "Validator");
 
@@ -236,6 +421,7 @@ pub fn reg_widgets(xaml: &mut Xaml) -> Registered {
     let widget_children = XamlProperty::new(xaml, widget, "Children", XamlType::Struct(widget), true, false);
     let widget_name = XamlProperty::new(xaml, widget, "Name", XamlType::Literal(string), false, true);
     let widget_focus_tab = XamlProperty::new(xaml, widget, "FocusTab", XamlType::Ref, false, false);
+    let widget_focus_back_tab = XamlProperty::new(xaml, widget, "FocusBackTab", XamlType::Ref, false, false);
     let widget_focus_right = XamlProperty::new(xaml, widget, "FocusRight", XamlType::Ref, false, false);
     let widget_focus_left = XamlProperty::new(xaml, widget, "FocusLeft", XamlType::Ref, false, false);
     let widget_focus_up = XamlProperty::new(xaml, widget, "FocusUp", XamlType::Ref, false, false);
@@ -243,6 +429,9 @@ pub fn reg_widgets(xaml: &mut Xaml) -> Registered {
     let widget_focus_click = XamlProperty::new(
         xaml, widget, "FocusClick", XamlType::Literal(focus), false, false
     );
+    let widget_context_menu = XamlProperty::new(
+        xaml, widget, "ContextMenu", XamlType::Struct(widget), false, false
+    );
     let widget_focused_primary = XamlProperty::new(
         xaml, widget, "FocusedPrimary", XamlType::Literal(boolean), false, false
     );
@@ -343,6 +532,51 @@ pub fn reg_widgets(xaml: &mut Xaml) -> Registered {
     let widget_color_frame = XamlProperty::new(
         xaml, widget, "ColorFrame", XamlType::Literal(color), false, false
     );
+    let widget_color_menu = XamlProperty::new(
+        xaml, widget, "ColorMenu", XamlType::Literal(color), false, false
+    );
+    let widget_color_menu_hotkey = XamlProperty::new(
+        xaml, widget, "ColorMenuHotkey", XamlType::Literal(color), false, false
+    );
+    let widget_color_menu_disabled = XamlProperty::new(
+        xaml, widget, "ColorMenuDisabled", XamlType::Literal(color), false, false
+    );
+    let widget_color_menu_selected = XamlProperty::new(
+        xaml, widget, "ColorMenuSelected", XamlType::Literal(color), false, false
+    );
+    let widget_color_menu_selected_hotkey = XamlProperty::new(
+        xaml, widget, "ColorMenuSelectedHotkey", XamlType::Literal(color), false, false
+    );
+    let widget_color_menu_selected_disabled = XamlProperty::new(
+        xaml, widget, "ColorMenuSelectedDisabled", XamlType::Literal(color), false, false
+    );
+    let widget_color_list_box = XamlProperty::new(
+        xaml, widget, "ColorListBox", XamlType::Literal(color), false, false
+    );
+    let widget_color_list_box_selected = XamlProperty::new(
+        xaml, widget, "ColorListBoxSelected", XamlType::Literal(color), false, false
+    );
+    let widget_color_list_box_focused = XamlProperty::new(
+        xaml, widget, "ColorListBoxFocused", XamlType::Literal(color), false, false
+    );
+    let widget_color_list_box_focused_selected = XamlProperty::new(
+        xaml, widget, "ColorListBoxFocusedSelected", XamlType::Literal(color), false, false
+    );
+    let widget_color_data_grid_header = XamlProperty::new(
+        xaml, widget, "ColorDataGridHeader", XamlType::Literal(color), false, false
+    );
+    let widget_color_data_grid_header_focused = XamlProperty::new(
+        xaml, widget, "ColorDataGridHeaderFocused", XamlType::Literal(color), false, false
+    );
+    let widget_color_progress_bar = XamlProperty::new(
+        xaml, widget, "ColorProgressBar", XamlType::Literal(color), false, false
+    );
+    let widget_color_text_selection = XamlProperty::new(
+        xaml, widget, "ColorTextSelection", XamlType::Literal(color), false, false
+    );
+    let widget_color_text_match = XamlProperty::new(
+        xaml, widget, "ColorTextMatch", XamlType::Literal(color), false, false
+    );
 
     let background = XamlStruct::new(xaml, Some(widget), XMLNS, "Background");
     let background_show_pattern = XamlProperty::new(
@@ -372,6 +606,30 @@ pub fn reg_widgets(xaml: &mut Xaml) -> Registered {
     let canvas = XamlStruct::new(xaml, Some(widget), XMLNS, "Canvas");
     let widget_tl = XamlProperty::new(xaml, widget, "Tl", XamlType::Literal(point), false, false);
 
+    let grid = XamlStruct::new(xaml, Some(widget), XMLNS, "Grid");
+    let grid_rows = XamlProperty::new(xaml, grid, "Rows", XamlType::Literal(grid_lengths), false, false);
+    let grid_columns = XamlProperty::new(xaml, grid, "Columns", XamlType::Literal(grid_lengths), false, false);
+    let widget_row = XamlProperty::new(xaml, widget, "Row", XamlType::Literal(uint_16), false, false);
+    let widget_column = XamlProperty::new(xaml, widget, "Column", XamlType::Literal(uint_16), false, false);
+    let widget_row_span = XamlProperty::new(xaml, widget, "RowSpan", XamlType::Literal(uint_16), false, false);
+    let widget_column_span = XamlProperty::new(
+        xaml, widget, "ColumnSpan", XamlType::Literal(uint_16), false, false
+    );
+
+    let wrap_panel = XamlStruct::new(xaml, Some(widget), XMLNS, "WrapPanel");
+    let wrap_panel_vertical = XamlProperty::new(
+        xaml, wrap_panel, "Vertical", XamlType::Literal(boolean), false, false
+    );
+    let wrap_panel_item_spacing = XamlProperty::new(
+        xaml, wrap_panel, "ItemSpacing", XamlType::Literal(int_16), false, false
+    );
+    let wrap_panel_line_spacing = XamlProperty::new(
+        xaml, wrap_panel, "LineSpacing", XamlType::Literal(int_16), false, false
+    );
+    let wrap_panel_line_align = XamlProperty::new(
+        xaml, wrap_panel, "LineAlign", XamlType::Literal(line_align), false, false
+    );
+
     let static_text = XamlStruct::new(xaml, Some(widget), XMLNS, "StaticText");
     let static_text_text = XamlProperty::new(
         xaml, static_text, "Text", XamlType::Literal(string), false, false
@@ -385,12 +643,34 @@ pub fn reg_widgets(xaml: &mut Xaml) -> Registered {
     let input_line_validator = XamlProperty::new(
         xaml, input_line, "Validator", XamlType::Struct(validator), false, false
     );
+    let input_line_undo_depth = XamlProperty::new(
+        xaml, input_line, "UndoDepth", XamlType::Literal(uint_16), false, false
+    );
 
     let text_edit = XamlStruct::new(xaml, Some(widget), XMLNS, "TextEdit");
     let text_edit_text = XamlProperty::new(xaml, text_edit, "Text", XamlType::Literal(string), false, false);
     let text_edit_line_break = XamlProperty::new(
         xaml, text_edit, "LineBreak", XamlType::Literal(string), false, false
     );
+    let text_edit_undo_depth = XamlProperty::new(
+        xaml, text_edit, "UndoDepth", XamlType::Literal(uint_16), false, false
+    );
+    let text_edit_wrap = XamlProperty::new(
+        xaml, text_edit, "Wrap", XamlType::Literal(text_wrap), false, false
+    );
+    let text_edit_search = XamlProperty::new(xaml, text_edit, "Search", XamlType::Literal(string), false, false);
+    let text_edit_search_case_sensitive = XamlProperty::new(
+        xaml, text_edit, "SearchCaseSensitive", XamlType::Literal(boolean), false, false
+    );
+    let text_edit_search_whole_word = XamlProperty::new(
+        xaml, text_edit, "SearchWholeWord", XamlType::Literal(boolean), false, false
+    );
+    let text_edit_search_regex = XamlProperty::new(
+        xaml, text_edit, "SearchRegex", XamlType::Literal(boolean), false, false
+    );
+
+    let find_bar = XamlStruct::new(xaml, Some(widget), XMLNS, "FindBar");
+    let find_bar_text_edit = XamlProperty::new(xaml, find_bar, "TextEdit", XamlType::Ref, false, false);
 
     let frame = XamlStruct::new(xaml, Some(widget), XMLNS, "Frame");
     let frame_double = XamlProperty::new(xaml, frame, "Double", XamlType::Literal(boolean), false, false);
@@ -459,6 +739,111 @@ pub fn reg_widgets(xaml: &mut Xaml) -> Registered {
         xaml, virt_items_presenter, "UpDownNavigation", XamlType::Literal(boolean), false, false
     );
 
+    let menu_bar = XamlStruct::new(xaml, Some(widget), XMLNS, "MenuBar");
+
+    let menu = XamlStruct::new(xaml, Some(widget), XMLNS, "Menu");
+    let menu_text = XamlProperty::new(xaml, menu, "Text", XamlType::Literal(string), false, false);
+
+    let menu_item = XamlStruct::new(xaml, Some(widget), XMLNS, "MenuItem");
+    let menu_item_text = XamlProperty::new(xaml, menu_item, "Text", XamlType::Literal(string), false, false);
+    let menu_item_shortcut = XamlProperty::new(
+        xaml, menu_item, "Shortcut", XamlType::Literal(string), false, false
+    );
+    let menu_item_accelerator = XamlProperty::new(
+        xaml, menu_item, "Accelerator", XamlType::Literal(accelerator), false, false
+    );
+    let menu_item_is_checked = XamlProperty::new(
+        xaml, menu_item, "IsChecked", XamlType::Literal(boolean), false, false
+    );
+    let menu_item_cmd = XamlProperty::new(xaml, menu_item, "Cmd", XamlType::Literal(uint_16), false, false);
+
+    let menu_separator = XamlStruct::new(xaml, Some(widget), XMLNS, "MenuSeparator");
+
+    let list_box = XamlStruct::new(xaml, Some(widget), XMLNS, "ListBox");
+    let list_box_multiple = XamlProperty::new(
+        xaml, list_box, "Multiple", XamlType::Literal(boolean), false, false
+    );
+
+    let combo_box = XamlStruct::new(xaml, Some(widget), XMLNS, "ComboBox");
+    let combo_box_items = XamlProperty::new(
+        xaml, combo_box, "Items", XamlType::Literal(string_list), false, false
+    );
+    let combo_box_is_editable = XamlProperty::new(
+        xaml, combo_box, "IsEditable", XamlType::Literal(boolean), false, false
+    );
+    let combo_box_validator = XamlProperty::new(
+        xaml, combo_box, "Validator", XamlType::Struct(validator), false, false
+    );
+
+    let tab_control = XamlStruct::new(xaml, Some(widget), XMLNS, "TabControl");
+
+    let tab_item = XamlStruct::new(xaml, Some(widget), XMLNS, "TabItem");
+    let tab_item_header = XamlProperty::new(xaml, tab_item, "Header", XamlType::Literal(string), false, false);
+    let tab_control_children = XamlProperty::new(
+        xaml, tab_control, "Children", XamlType::Struct(tab_item), true, false
+    );
+
+    let progress_bar = XamlStruct::new(xaml, Some(widget), XMLNS, "ProgressBar");
+    let progress_bar_value = XamlProperty::new(
+        xaml, progress_bar, "Value", XamlType::Literal(float_64), false, false
+    );
+    let progress_bar_min = XamlProperty::new(
+        xaml, progress_bar, "Min", XamlType::Literal(float_64), false, false
+    );
+    let progress_bar_max = XamlProperty::new(
+        xaml, progress_bar, "Max", XamlType::Literal(float_64), false, false
+    );
+    let progress_bar_show_percentage = XamlProperty::new(
+        xaml, progress_bar, "ShowPercentage", XamlType::Literal(boolean), false, false
+    );
+    let progress_bar_is_indeterminate = XamlProperty::new(
+        xaml, progress_bar, "IsIndeterminate", XamlType::Literal(boolean), false, false
+    );
+
+    let spinner = XamlStruct::new(xaml, Some(widget), XMLNS, "Spinner");
+    let spinner_is_active = XamlProperty::new(
+        xaml, spinner, "IsActive", XamlType::Literal(boolean), false, false
+    );
+
+    let slider = XamlStruct::new(xaml, Some(widget), XMLNS, "Slider");
+    let slider_vertical = XamlProperty::new(
+        xaml, slider, "Vertical", XamlType::Literal(boolean), false, false
+    );
+    let slider_min = XamlProperty::new(
+        xaml, slider, "Min", XamlType::Literal(float_64), false, false
+    );
+    let slider_max = XamlProperty::new(
+        xaml, slider, "Max", XamlType::Literal(float_64), false, false
+    );
+    let slider_step = XamlProperty::new(
+        xaml, slider, "Step", XamlType::Literal(float_64), false, false
+    );
+    let slider_page_step = XamlProperty::new(
+        xaml, slider, "PageStep", XamlType::Literal(float_64), false, false
+    );
+    let slider_value = XamlProperty::new(
+        xaml, slider, "Value", XamlType::Literal(float_64), false, false
+    );
+
+    let spin_box = XamlStruct::new(xaml, Some(widget), XMLNS, "SpinBox");
+    let spin_box_validator = XamlProperty::new(
+        xaml, spin_box, "Validator", XamlType::Struct(validator), false, false
+    );
+    let spin_box_step = XamlProperty::new(
+        xaml, spin_box, "Step", XamlType::Literal(float_64), false, false
+    );
+    let spin_box_page_step = XamlProperty::new(
+        xaml, spin_box, "PageStep", XamlType::Literal(float_64), false, false
+    );
+    let spin_box_value = XamlProperty::new(
+        xaml, spin_box, "Value", XamlType::Literal(float_64), false, false
+    );
+
+    let scroll_bar = XamlStruct::new(xaml, Some(widget), XMLNS, "ScrollBar");
+    let scroll_bar_vertical = XamlProperty::new(
+        xaml, scroll_bar, "Vertical", XamlType::Literal(boolean), false, false
+    );
+
     boolean.set_ctor(xaml, Some(Box::new(|x| match x {
         "True" => Some("true".to_string()),
         "False" => Some("false".to_string()),
@@ -509,6 +894,45 @@ pub fn reg_widgets(xaml: &mut Xaml) -> Registered {
         "Bottom" => Some("tuifw::Dock::Bottom".to_string()),
         _ => None,
     })));
+    grid_lengths.set_ctor(xaml, Some(Box::new(|x| {
+        let mut lengths = Vec::new();
+        for part in x.split(',').map(|x| x.trim()) {
+            let length = if part == "Auto" {
+                "tuifw::GridLength::Auto".to_string()
+            } else if let Some(weight) = part.strip_suffix('*') {
+                let weight = if weight.is_empty() { 1.0 } else { f32::from_str(weight).ok()? };
+                if !weight.is_finite() || weight < 0.0 { return None; }
+                format!("tuifw::GridLength::Star({weight:?})")
+            } else {
+                format!("tuifw::GridLength::Fixed({})", i16::from_str(part).ok()?)
+            };
+            lengths.push(length);
+        }
+        Some(format!("alloc::vec![{}]", lengths.join(", ")))
+    })));
+    string_list.set_ctor(xaml, Some(Box::new(|x| {
+        let items = string_list_items(x).into_iter()
+            .map(|x| format!("alloc::string::String::from(\"{}\")", x.escape_debug()))
+            .collect::<Vec<_>>()
+        ;
+        Some(format!("alloc::vec![{}]", items.join(", ")))
+    })));
+    line_align.set_ctor(xaml, Some(Box::new(|x| match x {
+        "Start" => Some("tuifw::LineAlign::Start".to_string()),
+        "Center" => Some("tuifw::LineAlign::Center".to_string()),
+        "End" => Some("tuifw::LineAlign::End".to_string()),
+        _ => None,
+    })));
+    text_wrap.set_ctor(xaml, Some(Box::new(|x| match x {
+        "None" => Some("tuifw::TextWrap::None".to_string()),
+        "Char" => Some("tuifw::TextWrap::Char".to_string()),
+        "Word" => Some("tuifw::TextWrap::Word".to_string()),
+        _ => None,
+    })));
+    accelerator.set_ctor(xaml, Some(Box::new(|x| {
+        let (key, mods) = accelerator_key(x)?;
+        Some(format!("Some(({key}, {mods}))"))
+    })));
     focus.set_ctor(xaml, Some(Box::new(|x| match x {
         "Primary" => Some("tuifw_window::Focus::Primary".to_string()),
         "Secondary" => Some("tuifw_window::Focus::Secondary".to_string()),
@@ -532,10 +956,31 @@ pub fn reg_widgets(xaml: &mut Xaml) -> Registered {
     })));
     color.set_ctor(xaml, Some(Box::new(|x| {
         let parts = x.split('/').collect::<Vec<_>>();
-        if parts.len() == 2 {
-            let fg = Fg::from_str(parts[0]).ok()?;
-            let bg = Bg::from_str(parts[1]).ok()?;
-            Some(format!("(tuifw_screen_base::Fg::{fg}, tuifw_screen_base::Bg::{bg})"))
+        if parts.len() == 2 || parts.len() == 3 {
+            let fg = match ExtFg::from_str(parts[0]).ok()? {
+                ExtFg::Cga(fg) => format!("tuifw_screen_base::ExtFg::Cga(tuifw_screen_base::Fg::{fg})"),
+                ExtFg::Indexed(i) => format!("tuifw_screen_base::ExtFg::Indexed({i})"),
+                ExtFg::Rgb(r, g, b) => format!("tuifw_screen_base::ExtFg::Rgb({r}, {g}, {b})"),
+            };
+            let bg = match ExtBg::from_str(parts[1]).ok()? {
+                ExtBg::Cga(bg) => format!("tuifw_screen_base::ExtBg::Cga(tuifw_screen_base::Bg::{bg})"),
+                ExtBg::Indexed(i) => format!("tuifw_screen_base::ExtBg::Indexed({i})"),
+                ExtBg::Rgb(r, g, b) => format!("tuifw_screen_base::ExtBg::Rgb({r}, {g}, {b})"),
+            };
+            if let Some(&attr) = parts.get(2) {
+                let attr = Attr::from_str(attr).ok()?;
+                let attr = if attr.is_empty() {
+                    "tuifw_screen_base::Attr::NONE".to_string()
+                } else {
+                    attr.to_string().split('+')
+                        .map(|x| format!("tuifw_screen_base::Attr::{}", x.to_uppercase()))
+                        .collect::<Vec<_>>()
+                        .join(" | ")
+                };
+                Some(format!("({fg}, {bg}, {attr})"))
+            } else {
+                Some(format!("({fg}, {bg})"))
+            }
         } else {
             None
         }
@@ -617,6 +1062,9 @@ pub fn reg_widgets(xaml: &mut Xaml) -> Registered {
     widget_focus_tab.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
         {}.set_focus_tab(tree, {});
     " }, obj, value))));
+    widget_focus_back_tab.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        {}.set_focus_back_tab(tree, {});
+    " }, obj, value))));
     widget_focus_right.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
         {}.set_focus_right(tree, {});
     " }, obj, value))));
@@ -632,6 +1080,9 @@ pub fn reg_widgets(xaml: &mut Xaml) -> Registered {
     widget_focus_click.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
         {}.set_focus_click(tree, Some({}));
     " }, obj, value))));
+    widget_context_menu.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        {}.set_context_menu(tree, Some({}));
+    " }, obj, value))));
     widget_focused_primary.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
         {}.set_focused_primary(tree, {});
     " }, obj, value))));
@@ -740,6 +1191,51 @@ pub fn reg_widgets(xaml: &mut Xaml) -> Registered {
     widget_color_frame.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
         {}.set_color(tree, tuifw_window::COLOR_FRAME, {});
     " }, obj, value))));
+    widget_color_menu.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        {}.set_color(tree, tuifw_window::COLOR_MENU, {});
+    " }, obj, value))));
+    widget_color_menu_hotkey.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        {}.set_color(tree, tuifw_window::COLOR_MENU_HOTKEY, {});
+    " }, obj, value))));
+    widget_color_menu_disabled.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        {}.set_color(tree, tuifw_window::COLOR_MENU_DISABLED, {});
+    " }, obj, value))));
+    widget_color_menu_selected.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        {}.set_color(tree, tuifw_window::COLOR_MENU_SELECTED, {});
+    " }, obj, value))));
+    widget_color_menu_selected_hotkey.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        {}.set_color(tree, tuifw_window::COLOR_MENU_SELECTED_HOTKEY, {});
+    " }, obj, value))));
+    widget_color_menu_selected_disabled.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        {}.set_color(tree, tuifw_window::COLOR_MENU_SELECTED_DISABLED, {});
+    " }, obj, value))));
+    widget_color_list_box.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        {}.set_color(tree, tuifw_window::COLOR_LIST_BOX, {});
+    " }, obj, value))));
+    widget_color_list_box_selected.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        {}.set_color(tree, tuifw_window::COLOR_LIST_BOX_SELECTED, {});
+    " }, obj, value))));
+    widget_color_list_box_focused.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        {}.set_color(tree, tuifw_window::COLOR_LIST_BOX_FOCUSED, {});
+    " }, obj, value))));
+    widget_color_list_box_focused_selected.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        {}.set_color(tree, tuifw_window::COLOR_LIST_BOX_FOCUSED_SELECTED, {});
+    " }, obj, value))));
+    widget_color_data_grid_header.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        {}.set_color(tree, tuifw_window::COLOR_DATA_GRID_HEADER, {});
+    " }, obj, value))));
+    widget_color_data_grid_header_focused.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        {}.set_color(tree, tuifw_window::COLOR_DATA_GRID_HEADER_FOCUSED, {});
+    " }, obj, value))));
+    widget_color_progress_bar.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        {}.set_color(tree, tuifw_window::COLOR_PROGRESS_BAR, {});
+    " }, obj, value))));
+    widget_color_text_selection.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        {}.set_color(tree, tuifw_window::COLOR_TEXT_SELECTION, {});
+    " }, obj, value))));
+    widget_color_text_match.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        {}.set_color(tree, tuifw_window::COLOR_TEXT_MATCH, {});
+    " }, obj, value))));
 
     set_widget_ctor(xaml, background, "tuifw::Background", widget_children);
     background_show_pattern.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
@@ -775,6 +1271,40 @@ pub fn reg_widgets(xaml: &mut Xaml) -> Registered {
         tuifw::Canvas::set_tl(tree, {}, {});
     " }, obj, value))));
 
+    set_widget_ctor(xaml, grid, "tuifw::Grid", widget_children);
+    grid_rows.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        tuifw::Grid::set_rows(tree, {}, {});
+    " }, obj, value))));
+    grid_columns.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        tuifw::Grid::set_columns(tree, {}, {});
+    " }, obj, value))));
+    widget_row.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        tuifw::Grid::set_row(tree, {}, {});
+    " }, obj, value))));
+    widget_column.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        tuifw::Grid::set_column(tree, {}, {});
+    " }, obj, value))));
+    widget_row_span.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        tuifw::Grid::set_row_span(tree, {}, {});
+    " }, obj, value))));
+    widget_column_span.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        tuifw::Grid::set_column_span(tree, {}, {});
+    " }, obj, value))));
+
+    set_widget_ctor(xaml, wrap_panel, "tuifw::WrapPanel", widget_children);
+    wrap_panel_vertical.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        tuifw::WrapPanel::set_vertical(tree, {}, {});
+    " }, obj, value))));
+    wrap_panel_item_spacing.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        tuifw::WrapPanel::set_item_spacing(tree, {}, {});
+    " }, obj, value))));
+    wrap_panel_line_spacing.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        tuifw::WrapPanel::set_line_spacing(tree, {}, {});
+    " }, obj, value))));
+    wrap_panel_line_align.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        tuifw::WrapPanel::set_line_align(tree, {}, {});
+    " }, obj, value))));
+
     set_widget_ctor(xaml, static_text, "tuifw::StaticText", widget_children);
     static_text_text.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
         tuifw::StaticText::set_text(tree, {}, {});
@@ -792,6 +1322,9 @@ pub fn reg_widgets(xaml: &mut Xaml) -> Registered {
     input_line_validator.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
         tuifw::InputLine::set_validator(tree, {}, Some(alloc::boxed::Box::new({})));
     " }, obj, value))));
+    input_line_undo_depth.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        tuifw::InputLine::set_undo_depth(tree, {}, {});
+    " }, obj, value))));
 
     set_widget_ctor(xaml, text_edit, "tuifw::TextEdit", widget_children);
     text_edit_text.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
@@ -800,6 +1333,29 @@ pub fn reg_widgets(xaml: &mut Xaml) -> Registered {
     text_edit_line_break.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
         tuifw::TextEdit::set_line_break(tree, {}, {});
     " }, obj, value))));
+    text_edit_undo_depth.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        tuifw::TextEdit::set_undo_depth(tree, {}, {});
+    " }, obj, value))));
+    text_edit_wrap.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        tuifw::TextEdit::set_wrap(tree, {}, {});
+    " }, obj, value))));
+    text_edit_search.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        tuifw::TextEdit::set_search(tree, {}, {});
+    " }, obj, value))));
+    text_edit_search_case_sensitive.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        tuifw::TextEdit::set_search_case_sensitive(tree, {}, {});
+    " }, obj, value))));
+    text_edit_search_whole_word.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        tuifw::TextEdit::set_search_whole_word(tree, {}, {});
+    " }, obj, value))));
+    text_edit_search_regex.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        tuifw::TextEdit::set_search_regex(tree, {}, {});
+    " }, obj, value))));
+
+    set_widget_ctor(xaml, find_bar, "tuifw::FindBar", widget_children);
+    find_bar_text_edit.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        tuifw::FindBar::set_text_edit(tree, {}, Some({}));
+    " }, obj, value))));
 
     set_widget_ctor(xaml, frame, "tuifw::Frame", widget_children);
     frame_double.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
@@ -904,6 +1460,118 @@ pub fn reg_widgets(xaml: &mut Xaml) -> Registered {
         " }, obj, value)))
     );
 
+
+    set_widget_ctor(xaml, menu_bar, "tuifw::MenuBar", widget_children);
+
+    set_widget_ctor(xaml, menu, "tuifw::Menu", widget_children);
+    menu_text.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        tuifw::Menu::set_text(tree, {}, {});
+    " }, obj, value))));
+
+    set_widget_ctor(xaml, menu_item, "tuifw::MenuItem", widget_children);
+    menu_item_text.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        tuifw::MenuItem::set_text(tree, {}, {});
+    " }, obj, value))));
+    menu_item_shortcut.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        tuifw::MenuItem::set_shortcut(tree, {}, {});
+    " }, obj, value))));
+    menu_item_accelerator.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        tuifw::MenuItem::set_accel(tree, {}, {});
+    " }, obj, value))));
+    menu_item_is_checked.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        tuifw::MenuItem::set_is_checked(tree, {}, {});
+    " }, obj, value))));
+    menu_item_cmd.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        tuifw::MenuItem::set_cmd(tree, {}, {});
+    " }, obj, value))));
+
+    set_widget_ctor(xaml, menu_separator, "tuifw::MenuSeparator", widget_children);
+
+    set_widget_ctor(xaml, list_box, "tuifw::ListBox", widget_children);
+    list_box_multiple.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        tuifw::ListBox::set_multiple(tree, {}, {});
+    " }, obj, value))));
+
+    set_widget_ctor(xaml, combo_box, "tuifw::ComboBox", widget_children);
+    combo_box_items.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        tuifw::ComboBox::set_items(tree, {}, {});
+    " }, obj, value))));
+    combo_box_is_editable.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        tuifw::ComboBox::set_is_editable(tree, {}, {});
+    " }, obj, value))));
+    combo_box_validator.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        tuifw::ComboBox::set_validator(tree, {}, Some(alloc::boxed::Box::new({})));
+    " }, obj, value))));
+
+    set_widget_ctor(xaml, tab_control, "tuifw::TabControl", widget_children);
+    tab_control_children.set_setter(xaml, Box::new(|_obj, _value| String::new()));
+
+    set_child_widget_ctor(xaml, tab_item, "tuifw::TabItem", &[widget_children, tab_control_children]);
+    tab_item_header.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        tuifw::TabItem::set_header(tree, {}, {});
+    " }, obj, value))));
+
+    set_widget_ctor(xaml, progress_bar, "tuifw::ProgressBar", widget_children);
+    progress_bar_value.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        tuifw::ProgressBar::set_value(tree, {}, {});
+    " }, obj, value))));
+    progress_bar_min.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        tuifw::ProgressBar::set_min(tree, {}, {});
+    " }, obj, value))));
+    progress_bar_max.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        tuifw::ProgressBar::set_max(tree, {}, {});
+    " }, obj, value))));
+    progress_bar_show_percentage.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        tuifw::ProgressBar::set_show_percentage(tree, {}, {});
+    " }, obj, value))));
+    progress_bar_is_indeterminate.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        tuifw::ProgressBar::set_is_indeterminate(tree, {}, {});
+    " }, obj, value))));
+
+    set_widget_ctor(xaml, spinner, "tuifw::Spinner", widget_children);
+    spinner_is_active.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        tuifw::Spinner::set_is_active(tree, {}, {});
+    " }, obj, value))));
+
+    set_widget_ctor(xaml, slider, "tuifw::Slider", widget_children);
+    slider_vertical.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        tuifw::Slider::set_vertical(tree, {}, {});
+    " }, obj, value))));
+    slider_min.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        tuifw::Slider::set_min(tree, {}, {});
+    " }, obj, value))));
+    slider_max.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        tuifw::Slider::set_max(tree, {}, {});
+    " }, obj, value))));
+    slider_step.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        tuifw::Slider::set_step(tree, {}, {});
+    " }, obj, value))));
+    slider_page_step.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        tuifw::Slider::set_page_step(tree, {}, {});
+    " }, obj, value))));
+    slider_value.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        tuifw::Slider::set_value(tree, {}, {});
+    " }, obj, value))));
+
+    set_widget_ctor(xaml, spin_box, "tuifw::SpinBox", widget_children);
+    spin_box_validator.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        tuifw::SpinBox::set_validator(tree, {}, Some(alloc::boxed::Box::new({})));
+    " }, obj, value))));
+    spin_box_step.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        tuifw::SpinBox::set_step(tree, {}, {});
+    " }, obj, value))));
+    spin_box_page_step.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        tuifw::SpinBox::set_page_step(tree, {}, {});
+    " }, obj, value))));
+    spin_box_value.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        tuifw::SpinBox::set_value(tree, {}, {});
+    " }, obj, value))));
+
+    set_widget_ctor(xaml, scroll_bar, "tuifw::ScrollBar", widget_children);
+    scroll_bar_vertical.set_setter(xaml, Box::new(|obj, value| indent_all_by(4, format!(indoc! { "
+        tuifw::ScrollBar::set_vertical(tree, {}, {});
+    " }, obj, value))));
+
     Registered {
         boolean,
         string,
@@ -917,9 +1585,14 @@ pub fn reg_widgets(xaml: &mut Xaml) -> Registered {
         h_align,
         v_align,
         dock,
+        grid_lengths,
+        line_align,
+        text_wrap,
+        accelerator,
         focus,
         visibility,
         color,
+        string_list,
 
         validator,
 
@@ -935,11 +1608,13 @@ pub fn reg_widgets(xaml: &mut Xaml) -> Registered {
         widget_children,
         widget_name,
         widget_focus_tab,
+        widget_focus_back_tab,
         widget_focus_right,
         widget_focus_left,
         widget_focus_up,
         widget_focus_down,
         widget_focus_click,
+        widget_context_menu,
         widget_focused_primary,
         widget_focused_secondary,
         widget_h_align,
@@ -978,6 +1653,21 @@ pub fn reg_widgets(xaml: &mut Xaml) -> Registered {
         widget_color_button_focused_disabled,
         widget_color_button_pressed,
         widget_color_frame,
+        widget_color_menu,
+        widget_color_menu_hotkey,
+        widget_color_menu_disabled,
+        widget_color_menu_selected,
+        widget_color_menu_selected_hotkey,
+        widget_color_menu_selected_disabled,
+        widget_color_list_box,
+        widget_color_list_box_selected,
+        widget_color_list_box_focused,
+        widget_color_list_box_focused_selected,
+        widget_color_data_grid_header,
+        widget_color_data_grid_header_focused,
+        widget_color_progress_bar,
+        widget_color_text_selection,
+        widget_color_text_match,
 
         background,
         background_show_pattern,
@@ -997,6 +1687,20 @@ pub fn reg_widgets(xaml: &mut Xaml) -> Registered {
         canvas,
         widget_tl,
 
+        grid,
+        grid_rows,
+        grid_columns,
+        widget_row,
+        widget_column,
+        widget_row_span,
+        widget_column_span,
+
+        wrap_panel,
+        wrap_panel_vertical,
+        wrap_panel_item_spacing,
+        wrap_panel_line_spacing,
+        wrap_panel_line_align,
+
         static_text,
         static_text_text,
 
@@ -1006,10 +1710,20 @@ pub fn reg_widgets(xaml: &mut Xaml) -> Registered {
         input_line,
         input_line_text,
         input_line_validator,
+        input_line_undo_depth,
 
         text_edit,
         text_edit_text,
         text_edit_line_break,
+        text_edit_undo_depth,
+        text_edit_wrap,
+        text_edit_search,
+        text_edit_search_case_sensitive,
+        text_edit_search_whole_word,
+        text_edit_search_regex,
+
+        find_bar,
+        find_bar_text_edit,
 
         frame,
         frame_double,
@@ -1047,5 +1761,72 @@ pub fn reg_widgets(xaml: &mut Xaml) -> Registered {
         virt_items_presenter_item_template,
         virt_items_presenter_tab_navigation,
         virt_items_presenter_up_down_navigation,
+
+        menu_bar,
+
+        menu,
+        menu_text,
+
+        menu_item,
+        menu_item_text,
+        menu_item_shortcut,
+        menu_item_accelerator,
+        menu_item_is_checked,
+        menu_item_cmd,
+
+        menu_separator,
+
+        list_box,
+        list_box_multiple,
+
+        combo_box,
+        combo_box_items,
+        combo_box_is_editable,
+        combo_box_validator,
+
+        tab_control,
+        tab_control_children,
+
+        tab_item,
+        tab_item_header,
+
+        progress_bar,
+        progress_bar_value,
+        progress_bar_min,
+        progress_bar_max,
+        progress_bar_show_percentage,
+        progress_bar_is_indeterminate,
+
+        spinner,
+        spinner_is_active,
+
+        slider,
+        slider_vertical,
+        slider_min,
+        slider_max,
+        slider_step,
+        slider_page_step,
+        slider_value,
+
+        spin_box,
+        spin_box_validator,
+        spin_box_step,
+        spin_box_page_step,
+        spin_box_value,
+
+        scroll_bar,
+        scroll_bar_vertical,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn string_list() {
+        assert!(string_list_items("").is_empty());
+        assert!(string_list_items("  ").is_empty());
+        assert_eq!(string_list_items("a; b;;c ;"), ["a", "b;c", ""]);
     }
 }
@@ -114,6 +114,13 @@ impl<'a, R: Read, W: Write> XamlProcessor<'a, R, W> {
         let Some(ty) = self.xaml.ty(&name) else {
             return self.error(format!("unknown type '{}'", name));
         };
+        if let Some((_, property)) = parent {
+            if let (XamlType::Struct(property_ty), XamlType::Struct(ty)) = (property.ty(self.xaml), ty) {
+                if !ty.is_derived_from(self.xaml, property_ty) {
+                    return self.error(format!("invalid '{}' property value", property.name(self.xaml)));
+                }
+            }
+        }
         match ty {
             XamlType::Ref => self.process_literal(None, attributes),
             XamlType::Literal(ty) => self.process_literal(Some(ty), attributes),
@@ -392,4 +399,33 @@ mod tests {
             obj_1\
         ");
     }
+
+    #[test]
+    fn reject_struct_of_wrong_type() {
+        let mut xaml = Xaml::new();
+        let widget = XamlStruct::new(&mut xaml, None, "https://a1-triard.github.io/tuifw/2023/xaml", "Widget");
+        let tabs = XamlStruct::new(&mut xaml, Some(widget), "https://a1-triard.github.io/tuifw/2023/xaml", "Tabs");
+        let tab = XamlStruct::new(&mut xaml, Some(widget), "https://a1-triard.github.io/tuifw/2023/xaml", "Tab");
+        let tabs_pages = XamlProperty::new(&mut xaml, tabs, "Pages", XamlType::Struct(tab), true, false);
+        xaml.set_result(Box::new(|x, _| x.to_string()));
+        widget.set_ctor(&mut xaml, Some(Box::new(|x, _, _| format!("let mut {x} = Widget::new();\n"))));
+        tabs.set_ctor(&mut xaml, Some(Box::new(|x, _, _| format!("let mut {x} = Tabs::new();\n"))));
+        tab.set_ctor(&mut xaml, Some(Box::new(|x, _, _| format!("let mut {x} = Tab::new();\n"))));
+        tabs_pages.set_setter(&mut xaml, Box::new(|o, x| format!("Tabs::add_page({o}, {x});\n")));
+        let source = "
+            <Tabs xmlns='https://a1-triard.github.io/tuifw/2023/xaml'>
+                <Tab/>
+            </Tabs>
+        ";
+        let mut dest = Vec::new();
+        preprocess_xaml(&xaml, || Ok(source.as_bytes()), &mut dest).unwrap();
+        let source = "
+            <Tabs xmlns='https://a1-triard.github.io/tuifw/2023/xaml'>
+                <Widget/>
+            </Tabs>
+        ";
+        let mut dest = Vec::new();
+        let err = preprocess_xaml(&xaml, || Ok(source.as_bytes()), &mut dest).unwrap_err();
+        assert!(err.to_string().contains("invalid 'Pages' property value"));
+    }
 }
@@ -43,6 +43,15 @@ impl XamlStruct {
         None
     }
 
+    pub fn is_derived_from(self, xaml: &Xaml, base: XamlStruct) -> bool {
+        let mut ty = self;
+        loop {
+            if ty == base { return true; }
+            let Some(parent) = ty.parent(xaml) else { return false; };
+            ty = parent;
+        }
+    }
+
     pub fn self_content_property(self, xaml: &Xaml) -> Option<XamlProperty> {
         xaml.structs[self.0].content_property
     }